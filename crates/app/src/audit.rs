@@ -0,0 +1,487 @@
+//! Audit log module
+//!
+//! Contains the audit log data structures, the server-side helper used by the
+//! ingredient, recipe and food log server functions to record every change,
+//! and the admin page for browsing and filtering entries.
+
+use leptos::prelude::*;
+use serde::{Deserialize, Serialize};
+use server_fn::ServerFnError;
+
+use crate::auth::AdminAuth;
+use crate::components::{INPUT_CLASS, LABEL_CLASS};
+
+// ============================================================================
+// Data Types
+// ============================================================================
+
+/// Maximum number of entries returned by a single audit log query
+pub const AUDIT_LOG_PAGE_SIZE: i64 = 200;
+
+/// The kind of record an audit entry refers to
+#[derive(Clone, Copy, Debug, Serialize, Deserialize, PartialEq, Eq)]
+pub enum AuditEntity {
+    Ingredient,
+    Recipe,
+    FoodLog,
+}
+
+impl AuditEntity {
+    pub const ALL: [AuditEntity; 3] = [
+        AuditEntity::Ingredient,
+        AuditEntity::Recipe,
+        AuditEntity::FoodLog,
+    ];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            AuditEntity::Ingredient => "ingredient",
+            AuditEntity::Recipe => "recipe",
+            AuditEntity::FoodLog => "food_log",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|e| e.as_str() == value)
+    }
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            AuditEntity::Ingredient => "Ingredient",
+            AuditEntity::Recipe => "Recipe",
+            AuditEntity::FoodLog => "Food log",
+        }
+    }
+}
+
+/// What happened to the record
+#[derive(Clone, Copy, Debug, Serialize, Deserialize, PartialEq, Eq)]
+pub enum AuditAction {
+    Create,
+    Update,
+    /// Moved to the trash
+    Delete,
+    /// Brought back from the trash
+    Restore,
+    /// Permanently removed from the trash
+    Purge,
+    /// Merged into another record
+    Merge,
+}
+
+impl AuditAction {
+    pub const ALL: [AuditAction; 6] = [
+        AuditAction::Create,
+        AuditAction::Update,
+        AuditAction::Delete,
+        AuditAction::Restore,
+        AuditAction::Purge,
+        AuditAction::Merge,
+    ];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            AuditAction::Create => "create",
+            AuditAction::Update => "update",
+            AuditAction::Delete => "delete",
+            AuditAction::Restore => "restore",
+            AuditAction::Purge => "purge",
+            AuditAction::Merge => "merge",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|a| a.as_str() == value)
+    }
+
+    fn badge_class(&self) -> &'static str {
+        match self {
+            AuditAction::Create => {
+                "bg-green-100 text-green-800 dark:bg-green-900 dark:text-green-200"
+            }
+            AuditAction::Update => "bg-blue-100 text-blue-800 dark:bg-blue-900 dark:text-blue-200",
+            AuditAction::Delete => {
+                "bg-amber-100 text-amber-800 dark:bg-amber-900 dark:text-amber-200"
+            }
+            AuditAction::Restore => {
+                "bg-purple-100 text-purple-800 dark:bg-purple-900 dark:text-purple-200"
+            }
+            AuditAction::Purge => "bg-red-100 text-red-800 dark:bg-red-900 dark:text-red-200",
+            AuditAction::Merge => "bg-teal-100 text-teal-800 dark:bg-teal-900 dark:text-teal-200",
+        }
+    }
+}
+
+/// A single recorded change
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+pub struct AuditEntry {
+    pub id: i64,
+    pub entity: AuditEntity,
+    pub entity_id: i64,
+    pub action: AuditAction,
+    /// Pretty-printed JSON of the record before the change
+    pub before: Option<String>,
+    /// Pretty-printed JSON of the record after the change
+    pub after: Option<String>,
+    pub actor: String,
+    pub created_at: String,
+}
+
+/// Filters for the audit log query. Empty fields match everything.
+#[derive(Clone, Debug, Default, Serialize, Deserialize, PartialEq)]
+pub struct AuditFilter {
+    pub entity: Option<AuditEntity>,
+    pub entity_id: Option<i64>,
+    pub action: Option<AuditAction>,
+    pub actor: Option<String>,
+}
+
+// ============================================================================
+// Server Functions
+// ============================================================================
+
+#[cfg(feature = "ssr")]
+use crate::ingredients::SendD1Database;
+
+/// Build the insert recording a change in the audit log, e.g. to run in a batch.
+/// `before` is None for creates and `after` is None for deletes.
+#[cfg(feature = "ssr")]
+pub(crate) fn audit_statement<T: Serialize>(
+    db: &worker::d1::D1Database,
+    entity: AuditEntity,
+    entity_id: i64,
+    action: AuditAction,
+    before: Option<&T>,
+    after: Option<&T>,
+    actor: &str,
+) -> Result<worker::d1::D1PreparedStatement, worker::Error> {
+    use wasm_bindgen::JsValue;
+
+    let to_json = |value: Option<&T>| -> Result<JsValue, worker::Error> {
+        Ok(value
+            .map(serde_json::to_string)
+            .transpose()?
+            .map(JsValue::from)
+            .unwrap_or(JsValue::NULL))
+    };
+
+    let stmt = db.prepare(
+        "INSERT INTO audit_log (entity, entity_id, action, before_json, after_json, actor) VALUES (?, ?, ?, ?, ?, ?)",
+    );
+    stmt.bind(&[
+        entity.as_str().into(),
+        (entity_id as f64).into(),
+        action.as_str().into(),
+        to_json(before)?,
+        to_json(after)?,
+        actor.into(),
+    ])
+}
+
+/// Record a change in the audit log.
+/// `before` is None for creates and `after` is None for deletes.
+#[cfg(feature = "ssr")]
+pub(crate) async fn record_audit<T: Serialize>(
+    db: &SendD1Database,
+    entity: AuditEntity,
+    entity_id: i64,
+    action: AuditAction,
+    before: Option<&T>,
+    after: Option<&T>,
+    actor: &str,
+) -> Result<(), ServerFnError> {
+    use send_wrapper::SendWrapper;
+
+    SendWrapper::new(async {
+        audit_statement(db.inner(), entity, entity_id, action, before, after, actor)?
+            .run()
+            .await
+    })
+    .await
+    .map_err(|e| ServerFnError::new(format!("D1 insert audit error: {:?}", e)))?;
+
+    Ok(())
+}
+
+/// Re-indent a stored JSON snapshot for display
+#[cfg(feature = "ssr")]
+fn pretty_json(raw: &str) -> String {
+    serde_json::from_str::<serde_json::Value>(raw)
+        .ok()
+        .and_then(|v| serde_json::to_string_pretty(&v).ok())
+        .unwrap_or_else(|| raw.to_string())
+}
+
+/// Fetch the most recent audit log entries matching a filter (admin only)
+#[server]
+pub async fn get_audit_log(
+    filter: AuditFilter,
+    auth_token: Option<String>,
+) -> Result<Vec<AuditEntry>, ServerFnError> {
+    use send_wrapper::SendWrapper;
+    use wasm_bindgen::JsValue;
+
+    use crate::auth::require_admin;
+
+    require_admin(auth_token.as_deref()).await?;
+
+    let db = expect_context::<SendD1Database>();
+
+    let rows = SendWrapper::new(async {
+        let mut conditions = Vec::new();
+        let mut params: Vec<JsValue> = Vec::new();
+        if let Some(entity) = filter.entity {
+            conditions.push("entity = ?");
+            params.push(entity.as_str().into());
+        }
+        if let Some(entity_id) = filter.entity_id {
+            conditions.push("entity_id = ?");
+            params.push((entity_id as f64).into());
+        }
+        if let Some(action) = filter.action {
+            conditions.push("action = ?");
+            params.push(action.as_str().into());
+        }
+        if let Some(actor) = filter.actor.as_deref().filter(|a| !a.is_empty()) {
+            conditions.push("actor LIKE ?");
+            params.push(format!("%{}%", actor).into());
+        }
+        let where_clause = if conditions.is_empty() {
+            String::new()
+        } else {
+            format!("WHERE {}", conditions.join(" AND "))
+        };
+        params.push((AUDIT_LOG_PAGE_SIZE as f64).into());
+
+        let stmt = db.inner().prepare(format!(
+            "SELECT id, entity, entity_id, action, before_json, after_json, actor, created_at
+             FROM audit_log {} ORDER BY id DESC LIMIT ?",
+            where_clause
+        ));
+        let stmt = stmt.bind(&params)?;
+        let results = stmt.all().await?;
+        results.results::<serde_json::Value>()
+    })
+    .await
+    .map_err(|e| ServerFnError::new(format!("D1 query error: {:?}", e)))?;
+
+    let entries = rows
+        .into_iter()
+        .filter_map(|row| {
+            Some(AuditEntry {
+                id: row.get("id")?.as_i64()?,
+                entity: AuditEntity::parse(row.get("entity")?.as_str()?)?,
+                entity_id: row.get("entity_id")?.as_i64()?,
+                action: AuditAction::parse(row.get("action")?.as_str()?)?,
+                before: row
+                    .get("before_json")
+                    .and_then(|v| v.as_str())
+                    .map(pretty_json),
+                after: row
+                    .get("after_json")
+                    .and_then(|v| v.as_str())
+                    .map(pretty_json),
+                actor: row
+                    .get("actor")
+                    .and_then(|v| v.as_str())
+                    .unwrap_or("")
+                    .to_string(),
+                created_at: row
+                    .get("created_at")
+                    .and_then(|v| v.as_str())
+                    .unwrap_or("")
+                    .to_string(),
+            })
+        })
+        .collect();
+
+    Ok(entries)
+}
+
+// ============================================================================
+// Components
+// ============================================================================
+
+/// Collapsible JSON snapshot of a record
+#[component]
+fn JsonSnapshot(label: &'static str, json: Option<String>) -> impl IntoView {
+    json.map(|json| {
+        view! {
+          <details class="mt-1">
+            <summary class="cursor-pointer text-xs font-medium text-slate-600 dark:text-slate-400">{label}</summary>
+            <pre class="mt-1 max-h-64 overflow-auto rounded bg-slate-50 dark:bg-slate-900 p-2 text-xs text-slate-700 dark:text-slate-300">
+              {json}
+            </pre>
+          </details>
+        }
+    })
+}
+
+/// A single row of the audit log table
+#[component]
+fn AuditRow(entry: AuditEntry) -> impl IntoView {
+    let cell_class = "px-3 py-2 align-top text-slate-700 dark:text-slate-300";
+
+    view! {
+      <tr class="hover:bg-slate-50 dark:hover:bg-slate-700">
+        <td class=format!("{} whitespace-nowrap", cell_class)>{format!("{} UTC", entry.created_at)}</td>
+        <td class=format!("{} whitespace-nowrap font-mono text-xs", cell_class)>{entry.actor.clone()}</td>
+        <td class=format!("{} whitespace-nowrap", cell_class)>
+          {format!("{} #{}", entry.entity.label(), entry.entity_id)}
+        </td>
+        <td class=cell_class>
+          <span class=format!(
+            "inline-flex px-2 py-0.5 rounded-full text-xs font-medium {}",
+            entry.action.badge_class(),
+          )>{entry.action.as_str()}</span>
+        </td>
+        <td class=cell_class>
+          <JsonSnapshot label="Before" json=entry.before.clone() />
+          <JsonSnapshot label="After" json=entry.after.clone() />
+        </td>
+      </tr>
+    }
+}
+
+/// Admin page listing recorded data changes
+#[component]
+pub fn AuditLog() -> impl IntoView {
+    let auth = expect_context::<AdminAuth>();
+    let is_authenticated = auth.is_authenticated;
+    let auth_token = auth.token;
+
+    let entity = RwSignal::new(Option::<AuditEntity>::None);
+    let action = RwSignal::new(Option::<AuditAction>::None);
+    let entity_id = RwSignal::new(String::new());
+    let actor = RwSignal::new(String::new());
+
+    let entries = Resource::new(
+        move || {
+            let filter = AuditFilter {
+                entity: entity.get(),
+                entity_id: entity_id.get().trim().parse().ok(),
+                action: action.get(),
+                actor: Some(actor.get().trim().to_string()).filter(|a| !a.is_empty()),
+            };
+            (filter, auth_token.get())
+        },
+        |(filter, token)| async move {
+            if token.is_none() {
+                return Ok(Vec::new());
+            }
+            get_audit_log(filter, token).await
+        },
+    );
+
+    view! {
+      <div class="mx-auto max-w-7xl py-6">
+        <h2 class="mb-6 text-3xl font-bold text-slate-900 dark:text-slate-100">"Audit Log"</h2>
+
+        <Show
+          when=move || is_authenticated.get()
+          fallback=|| {
+            view! { <p class="text-slate-600 dark:text-slate-400">"Unlock admin access to view the audit log."</p> }
+          }
+        >
+          <div class="mb-4 grid grid-cols-2 gap-4 rounded-lg bg-white dark:bg-slate-800 p-4 shadow-sm md:grid-cols-4">
+            <div>
+              <label class=LABEL_CLASS>"Entity"</label>
+              <select class=INPUT_CLASS on:change=move |ev| entity.set(AuditEntity::parse(&event_target_value(&ev)))>
+                <option value="">"All"</option>
+                {AuditEntity::ALL
+                  .into_iter()
+                  .map(|e| view! { <option value=e.as_str()>{e.label()}</option> })
+                  .collect_view()}
+              </select>
+            </div>
+            <div>
+              <label class=LABEL_CLASS>"Action"</label>
+              <select class=INPUT_CLASS on:change=move |ev| action.set(AuditAction::parse(&event_target_value(&ev)))>
+                <option value="">"All"</option>
+                {AuditAction::ALL
+                  .into_iter()
+                  .map(|a| view! { <option value=a.as_str()>{a.as_str()}</option> })
+                  .collect_view()}
+              </select>
+            </div>
+            <div>
+              <label class=LABEL_CLASS>"ID"</label>
+              <input
+                type="number"
+                class=INPUT_CLASS
+                prop:value=move || entity_id.get()
+                on:input=move |ev| entity_id.set(event_target_value(&ev))
+                placeholder="Any"
+              />
+            </div>
+            <div>
+              <label class=LABEL_CLASS>"Actor"</label>
+              <input
+                type="text"
+                class=INPUT_CLASS
+                prop:value=move || actor.get()
+                on:input=move |ev| actor.set(event_target_value(&ev))
+                placeholder="e.g., admin:1a2b"
+              />
+            </div>
+          </div>
+
+          <Suspense fallback=move || {
+            view! { <p class="text-slate-600 dark:text-slate-400">"Loading audit log..."</p> }
+          }>
+            {move || {
+              entries
+                .get()
+                .map(|result| match result {
+                  Ok(list) if list.is_empty() => {
+                    view! { <p class="text-slate-600 dark:text-slate-400">"No matching changes recorded."</p> }
+                      .into_any()
+                  }
+                  Ok(list) => {
+                    let truncated = list.len() as i64 >= AUDIT_LOG_PAGE_SIZE;
+                    view! {
+                      <div class="rounded-lg bg-white shadow-md overflow-hidden overflow-x-auto dark:bg-slate-800">
+                        <table class="w-full divide-y divide-slate-200 dark:divide-slate-700 text-sm">
+                          <thead class="bg-slate-50 dark:bg-slate-700">
+                            <tr>
+                              {["When", "Actor", "Record", "Action", "Changes"]
+                                .into_iter()
+                                .map(|h| {
+                                  view! {
+                                    <th class="px-3 py-3 text-left text-xs font-medium text-slate-500 dark:text-slate-400 uppercase tracking-wider">
+                                      {h}
+                                    </th>
+                                  }
+                                })
+                                .collect_view()}
+                            </tr>
+                          </thead>
+                          <tbody class="divide-y divide-slate-200 dark:divide-slate-700">
+                            {list.into_iter().map(|entry| view! { <AuditRow entry=entry /> }).collect_view()}
+                          </tbody>
+                        </table>
+                      </div>
+                      <Show when=move || truncated>
+                        <p class="mt-2 text-xs text-slate-500 dark:text-slate-400">
+                          {format!("Showing the latest {} entries. Narrow the filters to see older ones.", AUDIT_LOG_PAGE_SIZE)}
+                        </p>
+                      </Show>
+                    }
+                      .into_any()
+                  }
+                  Err(e) => {
+                    view! {
+                      <div class="rounded bg-red-100 px-4 py-3 text-red-700">
+                        <p class="font-medium">"Failed to load audit log"</p>
+                        <p class="text-sm">{e.to_string()}</p>
+                      </div>
+                    }
+                      .into_any()
+                  }
+                })
+            }}
+          </Suspense>
+        </Show>
+      </div>
+    }
+}
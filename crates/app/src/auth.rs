@@ -206,6 +206,39 @@ pub async fn admin_logout(token: String) -> Result<bool, ServerFnError> {
     }
 }
 
+/// Resolve a short, non-secret identifier for the admin behind a token.
+/// The identifier is derived from a SHA-256 hash of the token, so it is stable for
+/// a login session but reveals nothing about the token itself.
+#[cfg(feature = "ssr")]
+pub(crate) async fn resolve_actor(auth_token: Option<&str>) -> String {
+    use sha2::{Digest, Sha256};
+
+    let Some(token) = auth_token else {
+        return "anonymous".to_string();
+    };
+    match admin_validate(token.to_string()).await {
+        Ok(result) if result.valid => {
+            let digest = Sha256::digest(token.as_bytes());
+            format!("admin:{}", hex::encode(&digest[..8]))
+        }
+        _ => "invalid-token".to_string(),
+    }
+}
+
+/// Fail unless the token belongs to a logged-in admin
+#[cfg(feature = "ssr")]
+pub(crate) async fn require_admin(auth_token: Option<&str>) -> Result<(), ServerFnError> {
+    let valid = match auth_token {
+        Some(token) => admin_validate(token.to_string()).await?.valid,
+        None => false,
+    };
+    if valid {
+        Ok(())
+    } else {
+        Err(ServerFnError::new("Admin access required"))
+    }
+}
+
 // ============================================================================
 // Admin Authentication - Client-side State
 // ============================================================================
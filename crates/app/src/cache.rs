@@ -12,7 +12,7 @@ pub const INGREDIENTS_CACHE_KEY: &str = "food_ingredients_cache";
 /// Cache key for recipes list
 pub const RECIPES_CACHE_KEY: &str = "food_recipes_cache";
 
-/// Cache key for food logs list
+/// Cache key for the first page of the unfiltered food log
 pub const FOOD_LOGS_CACHE_KEY: &str = "food_logs_cache";
 
 /// Store data in localStorage
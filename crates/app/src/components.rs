@@ -140,6 +140,46 @@ pub fn ImageIcon(#[prop(optional, into)] class: String) -> impl IntoView {
     }
 }
 
+/// History (clock) icon - used for version history buttons
+#[component]
+pub fn HistoryIcon(#[prop(optional, into)] class: String) -> impl IntoView {
+    let class = if class.is_empty() {
+        "h-5 w-5".to_string()
+    } else {
+        class
+    };
+    view! {
+      <svg class=class fill="none" stroke="currentColor" viewBox="0 0 24 24">
+        <path
+          stroke-linecap="round"
+          stroke-linejoin="round"
+          stroke-width="2"
+          d="M12 8v4l3 3m6-3a9 9 0 11-18 0 9 9 0 0118 0z"
+        />
+      </svg>
+    }
+}
+
+/// Merge icon - two branches joining into one
+#[component]
+pub fn MergeIcon(#[prop(optional, into)] class: String) -> impl IntoView {
+    let class = if class.is_empty() {
+        "h-4 w-4".to_string()
+    } else {
+        class
+    };
+    view! {
+      <svg class=class fill="none" stroke="currentColor" viewBox="0 0 24 24">
+        <path
+          stroke-linecap="round"
+          stroke-linejoin="round"
+          stroke-width="2"
+          d="M7 4v5a5 5 0 005 5m5-10v5a5 5 0 01-5 5m0 0v6"
+        />
+      </svg>
+    }
+}
+
 // ============================================================================
 // Modal Backdrop Helper
 // ============================================================================
@@ -317,26 +357,3 @@ pub const INPUT_CLASS: &str = "w-full rounded border border-slate-300 dark:borde
 
 /// Standard label CSS classes
 pub const LABEL_CLASS: &str = "block text-sm font-medium text-slate-700 dark:text-slate-300 mb-1";
-
-// ============================================================================
-// Content Type Detection
-// ============================================================================
-
-/// Detect content type from a data URL prefix
-///
-/// # Arguments
-/// * `data_url_prefix` - The part before the comma in a data URL (e.g., "data:image/png;base64")
-///
-/// # Returns
-/// The detected MIME type, defaulting to "image/jpeg" if unknown
-pub fn detect_content_type(data_url_prefix: &str) -> &'static str {
-    if data_url_prefix.contains("png") {
-        "image/png"
-    } else if data_url_prefix.contains("gif") {
-        "image/gif"
-    } else if data_url_prefix.contains("webp") {
-        "image/webp"
-    } else {
-        "image/jpeg"
-    }
-}
@@ -0,0 +1,333 @@
+//! Container labels module
+//!
+//! Printable A4 sheets of labels for meal-prep containers. Each label shows the recipe,
+//! when it was cooked, its use-by date and the macros of the portion inside, plus a QR
+//! code that opens the food log with the recipe and portion filled in.
+
+use std::fmt::Write;
+
+use leptos::prelude::*;
+
+use crate::components::{INPUT_CLASS, LABEL_CLASS};
+use crate::nutrition_label::{escape_xml, query_param};
+use crate::pantry::local_date_in_days;
+use crate::qr::QrCode;
+use crate::recipes::Recipe;
+
+#[cfg(feature = "ssr")]
+use crate::ingredients::SendD1Database;
+#[cfg(feature = "ssr")]
+use server_fn::ServerFnError;
+
+// ============================================================================
+// Constants
+// ============================================================================
+
+// A4 sheet with a 2 x 6 grid of labels, all in millimetres
+const SHEET_WIDTH: f32 = 210.0;
+const SHEET_HEIGHT: f32 = 297.0;
+const COLUMNS: u32 = 2;
+const ROWS: u32 = 6;
+const MARGIN: f32 = 10.0;
+const GAP: f32 = 4.0;
+const LABEL_PAD: f32 = 4.0;
+
+/// Labels that fit on one sheet
+pub const MAX_LABELS: u32 = COLUMNS * ROWS;
+
+/// Days a cooked meal keeps in the fridge, used as the default use-by date
+const DEFAULT_KEEPS_DAYS: u32 = 4;
+
+/// Quiet zone around each QR code, in modules
+const QR_BORDER: usize = 4;
+
+/// Longest recipe name that fits next to the QR code
+const MAX_NAME_CHARS: usize = 22;
+
+// ============================================================================
+// Data Types
+// ============================================================================
+
+/// What to print on a sheet of container labels
+#[derive(Clone, Debug, PartialEq)]
+pub struct ContainerLabelOptions {
+    pub count: u32,
+    /// Servings of the recipe in each container
+    pub portion: f32,
+    /// YYYY-MM-DD, or empty to leave out
+    pub cooked_on: String,
+    /// YYYY-MM-DD, or empty to leave out
+    pub use_by: String,
+}
+
+impl ContainerLabelOptions {
+    /// One label per portion the recipe makes, up to a full sheet
+    pub fn for_recipe(recipe: &Recipe, portion: f32) -> Self {
+        let portion = if portion > 0.0 { portion } else { 1.0 };
+        Self {
+            count: ((recipe.servings.max(1) as f32 / portion).round() as u32).clamp(1, MAX_LABELS),
+            portion,
+            cooked_on: String::new(),
+            use_by: String::new(),
+        }
+    }
+
+    /// Encode as query parameters for the label sheet endpoint
+    pub fn to_query(&self) -> String {
+        format!(
+            "count={}&portion={}&cooked={}&use_by={}",
+            self.count, self.portion, self.cooked_on, self.use_by
+        )
+    }
+
+    /// Decode from query parameters. Dates that are not plain YYYY-MM-DD are dropped.
+    pub fn from_query(query: &str) -> Self {
+        let date = |key: &str| {
+            query_param(query, key)
+                .filter(|v| v.len() == 10 && v.chars().all(|c| c.is_ascii_digit() || c == '-'))
+                .unwrap_or_default()
+                .to_string()
+        };
+        Self {
+            count: query_param(query, "count")
+                .and_then(|v| v.parse::<u32>().ok())
+                .unwrap_or(1)
+                .clamp(1, MAX_LABELS),
+            portion: query_param(query, "portion")
+                .and_then(|v| v.parse::<f32>().ok())
+                .filter(|p| *p > 0.0)
+                .unwrap_or(1.0),
+            cooked_on: date("cooked"),
+            use_by: date("use_by"),
+        }
+    }
+}
+
+/// Food log link that opens a new entry for `portion` servings of the recipe
+pub fn food_log_url(origin: &str, recipe_id: i64, portion: f32) -> String {
+    format!(
+        "{}/?log_recipe={}&portion={}",
+        origin.trim_end_matches('/'),
+        recipe_id,
+        portion
+    )
+}
+
+// ============================================================================
+// SVG Rendering
+// ============================================================================
+
+/// A sheet of identical container labels for a recipe. `origin` is the site's base URL
+/// for the QR code links.
+pub fn container_labels_svg(
+    recipe: &Recipe,
+    options: &ContainerLabelOptions,
+    origin: &str,
+) -> String {
+    let label_width = (SHEET_WIDTH - 2.0 * MARGIN - (COLUMNS - 1) as f32 * GAP) / COLUMNS as f32;
+    let label_height = (SHEET_HEIGHT - 2.0 * MARGIN - (ROWS - 1) as f32 * GAP) / ROWS as f32;
+    let qr_size = label_height - 2.0 * LABEL_PAD;
+
+    let nutrition = recipe.nutrition_per_serving().scaled(options.portion);
+    let name = if recipe.name.chars().count() > MAX_NAME_CHARS {
+        format!(
+            "{}…",
+            recipe
+                .name
+                .chars()
+                .take(MAX_NAME_CHARS - 1)
+                .collect::<String>()
+        )
+    } else {
+        recipe.name.clone()
+    };
+    let portion_text = if options.portion == 1.0 {
+        "1 serving".to_string()
+    } else {
+        format!("{} servings", options.portion)
+    };
+
+    // Every label links to the same URL, so encode the QR code once
+    let qr = recipe
+        .id
+        .and_then(|id| QrCode::encode(food_log_url(origin, id, options.portion).as_bytes()));
+    let qr_svg = qr
+        .map(|qr| {
+            let modules = qr.size() + 2 * QR_BORDER;
+            format!(
+                r#"<svg width="{qr_size}" height="{qr_size}" viewBox="0 0 {modules} {modules}" shape-rendering="crispEdges"><path d="{}" fill="black"/></svg>"#,
+                qr.svg_path(QR_BORDER)
+            )
+        })
+        .unwrap_or_default();
+
+    let mut body = String::new();
+    for i in 0..options.count.min(MAX_LABELS) {
+        let x = MARGIN + (i % COLUMNS) as f32 * (label_width + GAP);
+        let y = MARGIN + (i / COLUMNS) as f32 * (label_height + GAP);
+        let text_x = x + LABEL_PAD;
+
+        let _ = write!(
+            body,
+            r##"<g><rect x="{x}" y="{y}" width="{label_width}" height="{label_height}" rx="2" fill="white" stroke="#999" stroke-width="0.3" stroke-dasharray="2 1"/>"##
+        );
+        let _ = write!(
+            body,
+            r#"<text x="{text_x}" y="{}" font-size="4.5" font-weight="bold">{}</text>"#,
+            y + LABEL_PAD + 4.0,
+            escape_xml(&name)
+        );
+        let mut line_y = y + LABEL_PAD + 10.0;
+        if !options.cooked_on.is_empty() {
+            let _ = write!(
+                body,
+                r#"<text x="{text_x}" y="{line_y}" font-size="3.2">Cooked: {}</text>"#,
+                options.cooked_on
+            );
+            line_y += 4.5;
+        }
+        if !options.use_by.is_empty() {
+            let _ = write!(
+                body,
+                r#"<text x="{text_x}" y="{line_y}" font-size="3.5" font-weight="bold">Use by: {}</text>"#,
+                options.use_by
+            );
+            line_y += 5.5;
+        }
+        let _ = write!(
+            body,
+            r#"<text x="{text_x}" y="{line_y}" font-size="3.2">{} · {:.0} kcal</text>"#,
+            portion_text, nutrition.calories
+        );
+        line_y += 4.5;
+        let _ = write!(
+            body,
+            r#"<text x="{text_x}" y="{line_y}" font-size="3.2">P {:.0}g · C {:.0}g · F {:.0}g</text>"#,
+            nutrition.protein, nutrition.carbs, nutrition.fat
+        );
+
+        if !qr_svg.is_empty() {
+            let qr_x = x + label_width - LABEL_PAD - qr_size;
+            let qr_y = y + LABEL_PAD;
+            let _ = write!(
+                body,
+                r#"<g transform="translate({qr_x} {qr_y})">{qr_svg}</g><text x="{}" y="{}" font-size="2.2" text-anchor="middle">Scan to log</text>"#,
+                qr_x + qr_size / 2.0,
+                qr_y + qr_size + 1.5
+            );
+        }
+        body.push_str("</g>");
+    }
+
+    format!(
+        r#"<svg xmlns="http://www.w3.org/2000/svg" width="{SHEET_WIDTH}mm" height="{SHEET_HEIGHT}mm" viewBox="0 0 {SHEET_WIDTH} {SHEET_HEIGHT}" font-family="Helvetica, Arial, sans-serif">{body}</svg>"#
+    )
+}
+
+/// Render a label sheet for a stored recipe, for the worker's container label endpoint.
+/// Returns `None` if there is no such recipe.
+#[cfg(feature = "ssr")]
+pub async fn recipe_container_labels_svg(
+    db: &SendD1Database,
+    recipe_id: i64,
+    query: &str,
+    origin: &str,
+) -> Result<Option<String>, ServerFnError> {
+    let Some(recipe) = crate::recipes::fetch_recipe(db, recipe_id).await? else {
+        return Ok(None);
+    };
+    let options = ContainerLabelOptions::from_query(query);
+    Ok(Some(container_labels_svg(&recipe, &options, origin)))
+}
+
+// ============================================================================
+// Components
+// ============================================================================
+
+/// Options for printing container labels, opening the sheet in a new tab
+#[component]
+pub fn ContainerLabelsForm(recipe: Recipe) -> impl IntoView {
+    let defaults = ContainerLabelOptions::for_recipe(&recipe, 1.0);
+    let count = RwSignal::new(defaults.count.to_string());
+    let portion = RwSignal::new(String::from("1"));
+    let cooked_on = RwSignal::new(String::new());
+    let use_by = RwSignal::new(String::new());
+
+    // Dates come from the browser's clock, so fill them in after hydration
+    Effect::new(move || {
+        cooked_on.set(local_date_in_days(0));
+        use_by.set(local_date_in_days(DEFAULT_KEEPS_DAYS));
+    });
+
+    let recipe_id = recipe.id;
+    let sheet_url = move || {
+        let options = ContainerLabelOptions {
+            count: count.get().parse::<u32>().unwrap_or(1).clamp(1, MAX_LABELS),
+            portion: portion
+                .get()
+                .parse::<f32>()
+                .ok()
+                .filter(|p| *p > 0.0)
+                .unwrap_or(1.0),
+            cooked_on: cooked_on.get(),
+            use_by: use_by.get(),
+        };
+        recipe_id
+            .map(|id| format!("/api/recipe-containers/{}?{}", id, options.to_query()))
+            .unwrap_or_default()
+    };
+
+    view! {
+      <div class="mt-3 grid grid-cols-2 gap-2 text-sm">
+        <div>
+          <label class=LABEL_CLASS>"Containers"</label>
+          <input
+            type="number"
+            min="1"
+            max=MAX_LABELS.to_string()
+            class=INPUT_CLASS
+            prop:value=move || count.get()
+            on:input=move |ev| count.set(event_target_value(&ev))
+          />
+        </div>
+        <div>
+          <label class=LABEL_CLASS>"Servings each"</label>
+          <input
+            type="number"
+            min="0.25"
+            step="0.25"
+            class=INPUT_CLASS
+            prop:value=move || portion.get()
+            on:input=move |ev| portion.set(event_target_value(&ev))
+          />
+        </div>
+        <div>
+          <label class=LABEL_CLASS>"Cooked"</label>
+          <input
+            type="date"
+            class=INPUT_CLASS
+            prop:value=move || cooked_on.get()
+            on:input=move |ev| cooked_on.set(event_target_value(&ev))
+          />
+        </div>
+        <div>
+          <label class=LABEL_CLASS>"Use by"</label>
+          <input
+            type="date"
+            class=INPUT_CLASS
+            prop:value=move || use_by.get()
+            on:input=move |ev| use_by.set(event_target_value(&ev))
+          />
+        </div>
+        <div class="col-span-2 flex justify-end">
+          <a
+            class="rounded bg-blue-600 px-4 py-2 font-medium text-white hover:bg-blue-700"
+            target="_blank"
+            href=sheet_url
+          >
+            "Print Labels"
+          </a>
+        </div>
+      </div>
+    }
+}
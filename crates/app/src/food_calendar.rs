@@ -0,0 +1,346 @@
+//! Food log calendar module
+//!
+//! A month view of the food log with a photo thumbnail and the calories eaten on each
+//! day, coloured against the daily calorie goal from Settings. Clicking a day lists its
+//! entries below the calendar.
+
+use std::collections::HashMap;
+
+use leptos::prelude::*;
+use server_fn::ServerFnError;
+
+use crate::food_log::{
+    days_from_civil, get_food_logs, local_now, weekday, FoodLog, FoodLogDays, FoodLogFilter, MONTHS,
+};
+use crate::image_pipeline::ImageVariant;
+use crate::recipes::Recipe;
+use crate::settings::load_settings;
+
+// ============================================================================
+// Constants
+// ============================================================================
+
+/// Share of the calorie goal either side of it that still counts as on target
+const GOAL_TOLERANCE: f32 = 0.1;
+
+const WEEKDAY_LABELS: [&str; 7] = ["Mon", "Tue", "Wed", "Thu", "Fri", "Sat", "Sun"];
+
+// ============================================================================
+// Data Types
+// ============================================================================
+
+/// How a day's calories compare with the daily goal
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum GoalStatus {
+    Under,
+    OnTarget,
+    Over,
+}
+
+impl GoalStatus {
+    /// `None` when there is no goal to compare against
+    pub fn for_calories(calories: f32, goal: f32) -> Option<Self> {
+        if goal <= 0.0 {
+            return None;
+        }
+        let ratio = calories / goal;
+        Some(if ratio < 1.0 - GOAL_TOLERANCE {
+            GoalStatus::Under
+        } else if ratio > 1.0 + GOAL_TOLERANCE {
+            GoalStatus::Over
+        } else {
+            GoalStatus::OnTarget
+        })
+    }
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            GoalStatus::Under => "Under goal",
+            GoalStatus::OnTarget => "Within 10% of goal",
+            GoalStatus::Over => "Over goal",
+        }
+    }
+
+    fn cell_class(&self) -> &'static str {
+        match self {
+            GoalStatus::Under => {
+                "border-amber-300 bg-amber-50 dark:border-amber-700 dark:bg-amber-900/20"
+            }
+            GoalStatus::OnTarget => {
+                "border-green-300 bg-green-50 dark:border-green-700 dark:bg-green-900/20"
+            }
+            GoalStatus::Over => "border-red-300 bg-red-50 dark:border-red-700 dark:bg-red-900/20",
+        }
+    }
+}
+
+/// A calendar month, with `month` from 1 to 12
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct CalendarMonth {
+    pub year: i64,
+    pub month: i64,
+}
+
+impl CalendarMonth {
+    /// The month containing a `YYYY-MM-DD...` date
+    pub fn containing(date: &str) -> Option<Self> {
+        let year = date.get(..4)?.parse().ok()?;
+        let month = date.get(5..7)?.parse().ok()?;
+        (1..=12)
+            .contains(&month)
+            .then_some(CalendarMonth { year, month })
+    }
+
+    pub fn next(self) -> Self {
+        if self.month == 12 {
+            CalendarMonth {
+                year: self.year + 1,
+                month: 1,
+            }
+        } else {
+            CalendarMonth {
+                month: self.month + 1,
+                ..self
+            }
+        }
+    }
+
+    pub fn previous(self) -> Self {
+        if self.month == 1 {
+            CalendarMonth {
+                year: self.year - 1,
+                month: 12,
+            }
+        } else {
+            CalendarMonth {
+                month: self.month - 1,
+                ..self
+            }
+        }
+    }
+
+    pub fn days(&self) -> i64 {
+        let next = self.next();
+        days_from_civil(next.year, next.month, 1) - days_from_civil(self.year, self.month, 1)
+    }
+
+    /// `YYYY-MM-DD` for a day of this month
+    pub fn date(&self, day: i64) -> String {
+        format!("{:04}-{:02}-{:02}", self.year, self.month, day)
+    }
+
+    pub fn title(&self) -> String {
+        format!("{} {}", MONTHS[self.month as usize - 1], self.year)
+    }
+}
+
+/// Calories eaten in an entry, from its recipe's per-serving nutrition and the portion
+pub fn entry_calories(log: &FoodLog, calories_per_serving: &HashMap<i64, f32>) -> f32 {
+    log.recipe_id
+        .and_then(|id| calories_per_serving.get(&id))
+        .map(|calories| calories * log.portion)
+        .unwrap_or(0.0)
+}
+
+/// Fetch every entry in a month, following the cursor through all pages
+async fn fetch_month(month: CalendarMonth) -> Result<Vec<FoodLog>, ServerFnError> {
+    let filter = FoodLogFilter {
+        from: Some(month.date(1)),
+        to: Some(month.date(month.days())),
+        ..FoodLogFilter::default()
+    };
+    let mut logs = Vec::new();
+    let mut cursor = None;
+    loop {
+        let page = get_food_logs(filter.clone(), cursor).await?;
+        logs.extend(page.logs);
+        match page.next_cursor {
+            Some(next) => cursor = Some(next),
+            None => return Ok(logs),
+        }
+    }
+}
+
+// ============================================================================
+// Components
+// ============================================================================
+
+/// Month calendar of food log entries
+#[component]
+pub fn FoodCalendar(
+    recipes: ReadSignal<Vec<Recipe>>,
+    on_edit: impl Fn(FoodLog) + Clone + Send + Sync + 'static,
+    is_authenticated: ReadSignal<bool>,
+    /// Bumped by the page whenever entries are saved or deleted
+    reload: ReadSignal<u32>,
+) -> impl IntoView {
+    let month = RwSignal::new(Option::<CalendarMonth>::None);
+    let selected = RwSignal::new(Option::<String>::None);
+    let calorie_goal = RwSignal::new(0.0_f32);
+
+    // The current month and the goal come from the browser, so read them after hydration
+    Effect::new(move || {
+        month.set(CalendarMonth::containing(&local_now()));
+        calorie_goal.set(load_settings().daily_calories as f32);
+    });
+
+    let logs_resource = Resource::new(
+        move || (month.get(), reload.get()),
+        |(month, _)| async move {
+            match month {
+                Some(month) => fetch_month(month).await,
+                None => Ok(Vec::new()),
+            }
+        },
+    );
+
+    let calories_per_serving = Memo::new(move |_| {
+        recipes
+            .get()
+            .iter()
+            .filter_map(|r| Some((r.id?, r.nutrition_per_serving().calories)))
+            .collect::<HashMap<i64, f32>>()
+    });
+
+    let change_month = move |change: fn(CalendarMonth) -> CalendarMonth| {
+        month.update(|m| *m = m.map(change));
+        selected.set(None);
+    };
+
+    let nav_button_class = "rounded px-3 py-1 text-slate-700 dark:text-slate-300 hover:bg-slate-100 dark:hover:bg-slate-700";
+
+    view! {
+      <div class="rounded-lg bg-white dark:bg-slate-800 p-6 shadow-md">
+        <div class="mb-4 flex items-center justify-between">
+          <button class=nav_button_class title="Previous month" on:click=move |_| change_month(CalendarMonth::previous)>
+            "‹"
+          </button>
+          <h3 class="text-xl font-semibold text-slate-900 dark:text-slate-100">
+            {move || month.get().map(|m| m.title()).unwrap_or_default()}
+          </h3>
+          <button class=nav_button_class title="Next month" on:click=move |_| change_month(CalendarMonth::next)>
+            "›"
+          </button>
+        </div>
+
+        <div class="grid grid-cols-7 gap-1 text-center text-xs font-medium uppercase text-slate-500 dark:text-slate-400">
+          {WEEKDAY_LABELS.into_iter().map(|label| view! { <div class="py-1">{label}</div> }).collect_view()}
+        </div>
+
+        <Suspense fallback=move || {
+          view! { <p class="py-8 text-center text-slate-600 dark:text-slate-400">"Loading calendar..."</p> }
+        }>
+          {move || {
+            let month = month.get()?;
+            let logs = match logs_resource.get()? {
+              Ok(logs) => logs,
+              Err(e) => {
+                return Some(
+                  view! {
+                    <div class="rounded bg-red-100 px-4 py-3 text-red-700">
+                      <p class="font-medium">"Failed to load food log"</p>
+                      <p class="text-sm">{e.to_string()}</p>
+                    </div>
+                  }
+                    .into_any(),
+                );
+              }
+            };
+            let calories_per_serving = calories_per_serving.get();
+            let goal = calorie_goal.get();
+            let mut by_day: HashMap<String, Vec<FoodLog>> = HashMap::new();
+            for log in logs {
+              by_day.entry(log.date().to_string()).or_default().push(log);
+            }
+            let leading_blanks = weekday(month.year, month.month, 1);
+            Some(
+              view! {
+                <div class="grid grid-cols-7 gap-1">
+                  {(0..leading_blanks).map(|_| view! { <div></div> }).collect_view()}
+                  {(1..=month.days())
+                    .map(|day| {
+                      let date = month.date(day);
+                      let entries = by_day.get(&date).cloned().unwrap_or_default();
+                      let calories: f32 = entries.iter().map(|log| entry_calories(log, &calories_per_serving)).sum();
+                      let status = if entries.is_empty() { None } else { GoalStatus::for_calories(calories, goal) };
+                      let thumbnail = entries
+                        .iter()
+                        .find_map(|log| log.cover_image())
+                        .map(|image| (image.variant_url(ImageVariant::Thumb), image.image_style(ImageVariant::Thumb)));
+                      let has_entries = !entries.is_empty();
+                      let date_for_click = date.clone();
+                      let cell_class = move || {
+                        format!(
+                          "flex h-28 flex-col overflow-hidden rounded border p-1 text-left {} {}",
+                          status
+                            .map(|s| s.cell_class())
+                            .unwrap_or("border-slate-200 dark:border-slate-700"),
+                          if selected.get().as_deref() == Some(date.as_str()) { "ring-2 ring-blue-500" } else { "" },
+                        )
+                      };
+                      view! {
+                        <button
+                          class=cell_class
+                          disabled=!has_entries
+                          on:click=move |_| selected.set(Some(date_for_click.clone()))
+                        >
+                          <span class="text-xs font-medium text-slate-700 dark:text-slate-300">{day}</span>
+                          {thumbnail
+                            .map(|(url, style)| {
+                              view! {
+                                <div class="mt-1 h-12 overflow-hidden rounded bg-slate-200 dark:bg-slate-700 flex items-center justify-center">
+                                  <img src=url class="min-w-full min-h-full object-cover" style=style />
+                                </div>
+                              }
+                            })}
+                          <Show when=move || has_entries>
+                            <span class="mt-auto text-xs text-slate-600 dark:text-slate-400">
+                              {format!("{:.0} kcal", calories)}
+                            </span>
+                          </Show>
+                        </button>
+                      }
+                    })
+                    .collect_view()}
+                </div>
+              }
+                .into_any(),
+            )
+          }}
+        </Suspense>
+
+        <div class="mt-4 flex flex-wrap gap-4 text-xs text-slate-600 dark:text-slate-400">
+          {[GoalStatus::Under, GoalStatus::OnTarget, GoalStatus::Over]
+            .into_iter()
+            .map(|status| {
+              view! {
+                <span class="flex items-center gap-1">
+                  <span class=format!("inline-block h-3 w-3 rounded border {}", status.cell_class())></span>
+                  {status.label()}
+                </span>
+              }
+            })
+            .collect_view()}
+        </div>
+      </div>
+
+      {move || {
+        let date = selected.get()?;
+        let entries: Vec<FoodLog> = logs_resource
+          .get()
+          .and_then(Result::ok)
+          .unwrap_or_default()
+          .into_iter()
+          .filter(|log| log.date() == date)
+          .collect();
+        let on_edit = on_edit.clone();
+        Some(
+          view! {
+            <div class="mt-6">
+              <FoodLogDays logs=entries on_edit=on_edit is_authenticated=is_authenticated />
+            </div>
+          },
+        )
+      }}
+    }
+}
@@ -4,6 +4,8 @@
 //! and the Food Log page components with image cropping functionality.
 
 use leptos::prelude::*;
+use leptos_router::hooks::{use_navigate, use_query_map};
+use leptos_router::NavigateOptions;
 use serde::{Deserialize, Serialize};
 use server_fn::ServerFnError;
 use wasm_bindgen::JsCast;
@@ -12,10 +14,15 @@ use crate::auth::AdminAuth;
 #[cfg(not(feature = "ssr"))]
 use crate::cache::{get_cache, set_cache, FOOD_LOGS_CACHE_KEY, RECIPES_CACHE_KEY};
 use crate::components::{
-    detect_content_type, CloseIcon, EditIcon, ImageIcon, PlusIcon, StarIcon, INPUT_CLASS,
-    LABEL_CLASS,
+    CloseIcon, EditIcon, ImageIcon, PlusIcon, StarIcon, INPUT_CLASS, LABEL_CLASS,
+};
+use crate::food_calendar::FoodCalendar;
+use crate::image_pipeline::ImageVariant;
+use crate::image_upload::{
+    check_upload_size, prepare_photo, random_key, upload_photo, ImageFormat, UploadError,
 };
 use crate::recipes::{get_recipes, Recipe};
+use crate::settings::load_settings;
 
 // ============================================================================
 // Data Types
@@ -42,9 +49,27 @@ impl Default for ImageCrop {
     }
 }
 
-/// The aspect ratio used for displaying food log images (width/height)
-/// This matches the card display which uses a wide rectangle
-pub const CROP_ASPECT_RATIO: f32 = 2.5; // width is 2.5x height (roughly matching h-48 on typical card width)
+/// Smallest crop side, as a percentage of the photo
+const MIN_CROP_SIZE: f32 = 5.0;
+
+/// Aspect ratios offered by the cropper (width over height in pixels); `None` is free-form
+pub const CROP_ASPECTS: [(&str, Option<f32>); 6] = [
+    ("Free", None),
+    ("2.5:1", Some(2.5)),
+    ("16:9", Some(16.0 / 9.0)),
+    ("4:3", Some(4.0 / 3.0)),
+    ("1:1", Some(1.0)),
+    ("4:5", Some(0.8)),
+];
+
+/// A corner of the crop box
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum CropCorner {
+    TopLeft,
+    TopRight,
+    BottomLeft,
+    BottomRight,
+}
 
 impl ImageCrop {
     pub fn new() -> Self {
@@ -71,6 +96,22 @@ impl ImageCrop {
         }
     }
 
+    /// Short identifier that changes whenever the crop does (FNV-1a of the fields)
+    pub fn fingerprint(&self) -> String {
+        let mut hash: u32 = 0x811c_9dc5;
+        let fields = [
+            self.x.to_bits(),
+            self.y.to_bits(),
+            self.width.to_bits(),
+            self.height.to_bits(),
+            self.rotation as u32,
+        ];
+        for byte in fields.iter().flat_map(|field| field.to_le_bytes()) {
+            hash = (hash ^ byte as u32).wrapping_mul(0x0100_0193);
+        }
+        format!("{:08x}", hash)
+    }
+
     /// Rotate the crop by 90 degrees clockwise
     pub fn rotate_cw(&mut self) {
         self.rotation = (self.rotation + 90) % 360;
@@ -83,36 +124,313 @@ impl ImageCrop {
 
     /// Zoom in (decrease crop area while maintaining aspect ratio)
     pub fn zoom_in(&mut self) {
-        let new_width = (self.width * 0.9).max(20.0); // minimum 20% width
-        let new_height = new_width / CROP_ASPECT_RATIO;
-        // Adjust position to keep centered
-        let dx = (self.width - new_width) / 2.0;
-        let dy = (self.height - new_height) / 2.0;
-        self.x = (self.x + dx).clamp(0.0, 100.0 - new_width);
-        self.y = (self.y + dy).clamp(0.0, 100.0 - new_height);
-        self.width = new_width;
-        self.height = new_height;
+        self.zoom(0.9);
     }
 
     /// Zoom out (increase crop area while maintaining aspect ratio)
     pub fn zoom_out(&mut self) {
-        let new_width = (self.width * 1.1).min(100.0);
-        let new_height = (new_width / CROP_ASPECT_RATIO).min(100.0);
-        // Adjust width if height would exceed 100%
-        let new_width = if new_height >= 100.0 {
-            100.0 * CROP_ASPECT_RATIO
+        self.zoom(1.1);
+    }
+
+    /// Scale the crop about its center, keeping its shape and staying inside the photo
+    fn zoom(&mut self, factor: f32) {
+        let largest = (100.0 / self.width).min(100.0 / self.height);
+        let smallest = (MIN_CROP_SIZE / self.width).max(MIN_CROP_SIZE / self.height);
+        let factor = factor.min(largest).max(smallest);
+        self.set_size_around_center(self.width * factor, self.height * factor);
+    }
+
+    /// Reshape the crop to `aspect` (width over height in pixels), keeping its center and
+    /// width where they fit. `image_aspect` is the photo's, after rotation; the crop's
+    /// percentages are of its width and height.
+    pub fn fit_aspect(&mut self, aspect: f32, image_aspect: f32) {
+        let mut width = self.width;
+        let mut height = width * image_aspect / aspect;
+        if height > 100.0 {
+            height = 100.0;
+            width = height * aspect / image_aspect;
+        }
+        self.set_size_around_center(width.min(100.0), height);
+    }
+
+    /// The crop with `corner` dragged by `dx`, `dy` percent, keeping the opposite corner
+    /// in place. With an `aspect`, the shape is kept too and the width leads.
+    pub fn resized_from(
+        &self,
+        corner: CropCorner,
+        dx: f32,
+        dy: f32,
+        aspect: Option<f32>,
+        image_aspect: f32,
+    ) -> Self {
+        let moves_left = matches!(corner, CropCorner::TopLeft | CropCorner::BottomLeft);
+        let moves_top = matches!(corner, CropCorner::TopLeft | CropCorner::TopRight);
+        let anchor_x = if moves_left {
+            self.x + self.width
+        } else {
+            self.x
+        };
+        let anchor_y = if moves_top {
+            self.y + self.height
+        } else {
+            self.y
+        };
+        // Room between the fixed corner and the edges the crop grows towards
+        let room_x = if moves_left {
+            anchor_x
+        } else {
+            100.0 - anchor_x
+        };
+        let room_y = if moves_top {
+            anchor_y
+        } else {
+            100.0 - anchor_y
+        };
+
+        let grow_x = if moves_left { -dx } else { dx };
+        let grow_y = if moves_top { -dy } else { dy };
+        let mut width = (self.width + grow_x).max(MIN_CROP_SIZE).min(room_x);
+        let mut height = (self.height + grow_y).max(MIN_CROP_SIZE).min(room_y);
+        if let Some(aspect) = aspect {
+            height = width * image_aspect / aspect;
+            if height > room_y {
+                height = room_y;
+                width = height * aspect / image_aspect;
+            }
+        }
+
+        Self {
+            x: if moves_left {
+                anchor_x - width
+            } else {
+                anchor_x
+            },
+            y: if moves_top {
+                anchor_y - height
+            } else {
+                anchor_y
+            },
+            width,
+            height,
+            rotation: self.rotation,
+        }
+    }
+
+    fn set_size_around_center(&mut self, width: f32, height: f32) {
+        let center_x = self.x + self.width / 2.0;
+        let center_y = self.y + self.height / 2.0;
+        self.width = width;
+        self.height = height;
+        self.x = (center_x - width / 2.0).clamp(0.0, 100.0 - width);
+        self.y = (center_y - height / 2.0).clamp(0.0, 100.0 - height);
+    }
+}
+
+/// A place a photo is shown, each with its own crop
+#[derive(Clone, Copy, Debug, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum CropView {
+    /// Food log cards
+    Card,
+    /// Calendar thumbnails
+    Thumb,
+    /// A page showing a single photo
+    Detail,
+}
+
+impl CropView {
+    pub const ALL: [CropView; 3] = [CropView::Card, CropView::Thumb, CropView::Detail];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            CropView::Card => "card",
+            CropView::Thumb => "thumb",
+            CropView::Detail => "detail",
+        }
+    }
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            CropView::Card => "Card",
+            CropView::Thumb => "Thumbnail",
+            CropView::Detail => "Detail",
+        }
+    }
+
+    pub fn parse(s: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|v| v.as_str() == s)
+    }
+
+    /// The aspect ratio (width over height) the view shows photos at
+    pub fn aspect_ratio(&self) -> f32 {
+        match self {
+            CropView::Card => 2.5,
+            CropView::Thumb => 1.0,
+            CropView::Detail => 0.8,
+        }
+    }
+}
+
+/// The crop of a photo for a view other than the card
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+pub struct ViewCrop {
+    pub view: CropView,
+    pub crop: ImageCrop,
+}
+
+/// Which meal of the day a food log entry was
+#[derive(Clone, Copy, Debug, Default, Serialize, Deserialize, PartialEq, Eq, PartialOrd, Ord)]
+#[serde(rename_all = "lowercase")]
+pub enum MealType {
+    Breakfast,
+    Lunch,
+    Dinner,
+    #[default]
+    Snack,
+}
+
+impl MealType {
+    /// All meal types in the order they happen during a day
+    pub const ALL: [MealType; 4] = [
+        MealType::Breakfast,
+        MealType::Lunch,
+        MealType::Dinner,
+        MealType::Snack,
+    ];
+
+    /// Value stored in the database
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            MealType::Breakfast => "breakfast",
+            MealType::Lunch => "lunch",
+            MealType::Dinner => "dinner",
+            MealType::Snack => "snack",
+        }
+    }
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            MealType::Breakfast => "Breakfast",
+            MealType::Lunch => "Lunch",
+            MealType::Dinner => "Dinner",
+            MealType::Snack => "Snack",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "breakfast" => Some(MealType::Breakfast),
+            "lunch" => Some(MealType::Lunch),
+            "dinner" => Some(MealType::Dinner),
+            "snack" => Some(MealType::Snack),
+            _ => None,
+        }
+    }
+
+    /// Best guess at the meal for a local time of day in `HH:MM` form
+    pub fn for_time(time: &str) -> Self {
+        let minutes = time
+            .split_once(':')
+            .and_then(|(h, m)| Some(h.parse::<u32>().ok()? * 60 + m.parse::<u32>().ok()?));
+        match minutes {
+            Some(m) if (300..630).contains(&m) => MealType::Breakfast,
+            Some(m) if (690..900).contains(&m) => MealType::Lunch,
+            Some(m) if (1020..1290).contains(&m) => MealType::Dinner,
+            _ => MealType::Snack,
+        }
+    }
+}
+
+/// A photo of a food log entry
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+pub struct FoodLogImage {
+    pub image_key: String,
+    /// Crop for cards. Its rotation applies to the whole photo, and so to every crop.
+    pub crop: ImageCrop,
+    /// Crops for the other views; a view without one uses the card crop
+    #[serde(default)]
+    pub view_crops: Vec<ViewCrop>,
+    /// Whether the pipeline has stored cropped and resized variants of the image. Without
+    /// them the original is shown with the crop applied in CSS.
+    #[serde(default)]
+    pub variants: bool,
+    /// Signed query granting access to the image for a while, added by the server when it
+    /// sends the entry. Images can't be fetched without one.
+    #[serde(default)]
+    pub signed_query: Option<String>,
+}
+
+impl FoodLogImage {
+    /// A photo just uploaded as `image_key`, uncropped. The server adds its variants and
+    /// signs its URL once the entry is saved.
+    pub fn new(image_key: String) -> Self {
+        Self {
+            image_key,
+            crop: ImageCrop::new(),
+            view_crops: Vec::new(),
+            variants: false,
+            signed_query: None,
+        }
+    }
+
+    /// The crop for `view`, with the photo's rotation
+    pub fn crop_for(&self, view: CropView) -> ImageCrop {
+        let crop = self
+            .view_crops
+            .iter()
+            .find(|c| c.view == view && view != CropView::Card)
+            .map_or(&self.crop, |c| &c.crop);
+        ImageCrop {
+            rotation: self.crop.rotation,
+            ..crop.clone()
+        }
+    }
+
+    /// The crop a display variant is rendered with. The full variant is only rotated, so
+    /// it goes by the card crop, which carries the rotation.
+    pub fn variant_crop(&self, variant: ImageVariant) -> ImageCrop {
+        self.crop_for(variant.crop_view().unwrap_or(CropView::Card))
+    }
+
+    /// URL of the original upload
+    pub fn image_url(&self) -> String {
+        match &self.signed_query {
+            Some(query) => format!("/api/food-image/{}?{}", self.image_key, query),
+            None => format!("/api/food-image/{}", self.image_key),
+        }
+    }
+
+    /// URL of the cropped image at a display size, falling back to the original when no
+    /// variants have been generated. Show it with `image_style`.
+    pub fn variant_url(&self, variant: ImageVariant) -> String {
+        let url = self.image_url();
+        // The crop picks the variants rendered for it, and keeps a new crop from being
+        // hidden behind the browser's cache
+        if self.variants {
+            format!(
+                "{}{}size={}&crop={}",
+                url,
+                if self.signed_query.is_some() {
+                    '&'
+                } else {
+                    '?'
+                },
+                variant.as_str(),
+                self.variant_crop(variant).fingerprint()
+            )
+        } else {
+            url
+        }
+    }
+
+    /// Inline style for an `object-cover` image from `variant_url`. Variants are already
+    /// cropped, so only the original needs the crop applied in CSS.
+    pub fn image_style(&self, variant: ImageVariant) -> String {
+        if self.variants {
+            String::new()
         } else {
-            new_width
+            crop_style(&self.variant_crop(variant))
         }
-        .min(100.0);
-        let new_height = new_width / CROP_ASPECT_RATIO;
-        // Adjust position to keep centered and within bounds
-        let dx = (self.width - new_width) / 2.0;
-        let dy = (self.height - new_height) / 2.0;
-        self.x = (self.x + dx).clamp(0.0, 100.0 - new_width);
-        self.y = (self.y + dy).clamp(0.0, 100.0 - new_height);
-        self.width = new_width;
-        self.height = new_height;
     }
 }
 
@@ -122,11 +440,23 @@ pub struct FoodLog {
     pub id: Option<i64>,
     pub recipe_id: Option<i64>,
     pub recipe_name: Option<String>,
-    pub image_key: Option<String>,
+    /// Photos in display order; the first is the cover shown in the calendar
+    #[serde(default)]
+    pub images: Vec<FoodLogImage>,
+    /// Local wall-clock time the meal was eaten, `YYYY-MM-DDTHH:MM`. Older entries
+    /// only have the date.
     pub logged_at: String,
     pub rating: Option<i32>,
     pub notes: String,
-    pub crop: ImageCrop,
+    /// Servings of the recipe eaten
+    #[serde(default = "default_portion")]
+    pub portion: f32,
+    #[serde(default)]
+    pub meal_type: MealType,
+}
+
+fn default_portion() -> f32 {
+    1.0
 }
 
 impl FoodLog {
@@ -135,19 +465,33 @@ impl FoodLog {
             id: None,
             recipe_id: None,
             recipe_name: None,
-            image_key: None,
+            images: Vec::new(),
             logged_at: String::new(),
             rating: None,
             notes: String::new(),
-            crop: ImageCrop::new(),
+            portion: default_portion(),
+            meal_type: MealType::default(),
         }
     }
 
-    /// Get the image URL for display (if image exists)
-    pub fn image_url(&self) -> Option<String> {
-        self.image_key
-            .as_ref()
-            .map(|key| format!("/api/food-image/{}", key))
+    /// Local date the meal was eaten, `YYYY-MM-DD`
+    pub fn date(&self) -> &str {
+        self.logged_at.get(..10).unwrap_or(&self.logged_at)
+    }
+
+    /// Local time the meal was eaten, `HH:MM`, if one was recorded
+    pub fn time(&self) -> Option<&str> {
+        self.logged_at.get(11..16)
+    }
+
+    /// The photo shown where there is only room for one
+    pub fn cover_image(&self) -> Option<&FoodLogImage> {
+        self.images.first()
+    }
+
+    /// Recipe and servings this entry takes out of the pantry
+    pub fn pantry_usage(&self) -> Option<(i64, f32)> {
+        self.recipe_id.map(|recipe_id| (recipe_id, self.portion))
     }
 }
 
@@ -157,8 +501,173 @@ impl Default for FoodLog {
     }
 }
 
+/// Number of food log entries fetched per page
+pub const FOOD_LOGS_PAGE_SIZE: i64 = 30;
+
+/// Filters for the food log query. Empty fields match everything.
+#[derive(Clone, Debug, Default, Serialize, Deserialize, PartialEq)]
+pub struct FoodLogFilter {
+    /// First local date to include, `YYYY-MM-DD`
+    pub from: Option<String>,
+    /// Last local date to include, `YYYY-MM-DD`
+    pub to: Option<String>,
+    pub min_rating: Option<i32>,
+    pub recipe_id: Option<i64>,
+}
+
+impl FoodLogFilter {
+    pub fn is_empty(&self) -> bool {
+        *self == Self::default()
+    }
+}
+
+/// Position after the last entry of a page. Entries are ordered newest first by
+/// `logged_at`, with the id breaking ties.
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+pub struct FoodLogCursor {
+    pub logged_at: String,
+    pub id: i64,
+}
+
+/// One page of food log entries
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+pub struct FoodLogPage {
+    pub logs: Vec<FoodLog>,
+    /// Where the next page starts, or `None` if this was the last one
+    pub next_cursor: Option<FoodLogCursor>,
+}
+
+/// The browser's current local date and time as `YYYY-MM-DDTHH:MM`. Food log entries
+/// store wall-clock time rather than UTC so that a late-night meal stays on the day it
+/// was eaten.
+pub(crate) fn local_now() -> String {
+    let now = js_sys::Date::new_0();
+    format!(
+        "{:04}-{:02}-{:02}T{:02}:{:02}",
+        now.get_full_year(),
+        now.get_month() + 1,
+        now.get_date(),
+        now.get_hours(),
+        now.get_minutes()
+    )
+}
+
+/// English month names, January first
+pub(crate) const MONTHS: [&str; 12] = [
+    "January",
+    "February",
+    "March",
+    "April",
+    "May",
+    "June",
+    "July",
+    "August",
+    "September",
+    "October",
+    "November",
+    "December",
+];
+
+/// Days since 1970-01-01, from Howard Hinnant's civil calendar algorithm
+pub(crate) fn days_from_civil(year: i64, month: i64, day: i64) -> i64 {
+    let y = if month <= 2 { year - 1 } else { year };
+    let era = y.div_euclid(400);
+    let yoe = y - era * 400;
+    let doy = (153 * (month + if month > 2 { -3 } else { 9 }) + 2) / 5 + day - 1;
+    let doe = yoe * 365 + yoe / 4 - yoe / 100 + doy;
+    era * 146097 + doe - 719468
+}
+
+/// `YYYY-MM-DD` for a day number from [`days_from_civil`]
+pub(crate) fn date_from_days(days: i64) -> String {
+    let z = days + 719468;
+    let era = z.div_euclid(146097);
+    let doe = z - era * 146097;
+    let yoe = (doe - doe / 1460 + doe / 36524 - doe / 146096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = yoe + era * 400 + if month <= 2 { 1 } else { 0 };
+    format!("{:04}-{:02}-{:02}", year, month, day)
+}
+
+/// Day number of a date starting with `YYYY-MM-DD`
+pub(crate) fn days_from_date(date: &str) -> Option<i64> {
+    let year = date.get(..4)?.parse().ok()?;
+    let month = date.get(5..7)?.parse().ok()?;
+    let day = date.get(8..10)?.parse().ok()?;
+    Some(days_from_civil(year, month, day))
+}
+
+/// Day of the week, 0 for Monday through 6 for Sunday
+pub(crate) fn weekday(year: i64, month: i64, day: i64) -> i64 {
+    // 1970-01-01 was a Thursday
+    (days_from_civil(year, month, day) + 3).rem_euclid(7)
+}
+
+/// Human-readable heading for a `YYYY-MM-DD` date, e.g. "Sunday, 18 October 2026"
+pub fn day_heading(date: &str) -> String {
+    const WEEKDAYS: [&str; 7] = [
+        "Monday",
+        "Tuesday",
+        "Wednesday",
+        "Thursday",
+        "Friday",
+        "Saturday",
+        "Sunday",
+    ];
+    let mut parts = date.splitn(3, '-').map(|p| p.parse::<i64>().ok());
+    let (Some(Some(year)), Some(Some(month)), Some(Some(day))) =
+        (parts.next(), parts.next(), parts.next())
+    else {
+        return date.to_string();
+    };
+    if !(1..=12).contains(&month) {
+        return date.to_string();
+    }
+    let weekday = weekday(year, month, day) as usize;
+    format!(
+        "{}, {} {} {}",
+        WEEKDAYS[weekday],
+        day,
+        MONTHS[month as usize - 1],
+        year
+    )
+}
+
+/// A day's entries, grouped by meal
+pub type DayMeals = Vec<(MealType, Vec<FoodLog>)>;
+
+/// Group entries by local day (newest first), and within a day by meal in the order
+/// they are eaten
+pub fn group_by_day(logs: &[FoodLog]) -> Vec<(String, DayMeals)> {
+    let mut days: std::collections::BTreeMap<String, Vec<FoodLog>> = Default::default();
+    for log in logs {
+        days.entry(log.date().to_string())
+            .or_default()
+            .push(log.clone());
+    }
+    days.into_iter()
+        .rev()
+        .map(|(date, mut entries)| {
+            entries.sort_by(|a, b| {
+                (a.meal_type, a.time().unwrap_or("")).cmp(&(b.meal_type, b.time().unwrap_or("")))
+            });
+            let mut meals: DayMeals = Vec::new();
+            for entry in entries {
+                match meals.last_mut() {
+                    Some((meal, group)) if *meal == entry.meal_type => group.push(entry),
+                    _ => meals.push((entry.meal_type, vec![entry])),
+                }
+            }
+            (date, meals)
+        })
+        .collect()
+}
+
 // ============================================================================
-// R2 Bucket Wrapper (SSR only)
+// R2 Bucket and Context Wrappers (SSR only)
 // ============================================================================
 
 /// Wrapper around CloudFlare R2 bucket that implements Send + Sync
@@ -177,6 +686,24 @@ impl SendR2Bucket {
     }
 }
 
+/// Wrapper around the request's execution context that implements Send + Sync, for work
+/// that outlives the response
+#[cfg(feature = "ssr")]
+#[derive(Clone)]
+pub struct SendContext(std::sync::Arc<send_wrapper::SendWrapper<worker::Context>>);
+
+#[cfg(feature = "ssr")]
+impl SendContext {
+    pub fn new(ctx: worker::Context) -> Self {
+        Self(std::sync::Arc::new(send_wrapper::SendWrapper::new(ctx)))
+    }
+
+    /// Keep the worker alive until `future` completes, after the response is sent
+    pub fn wait_until(&self, future: impl std::future::Future<Output = ()> + 'static) {
+        self.0.wait_until(future)
+    }
+}
+
 // ============================================================================
 // Server Functions
 // ============================================================================
@@ -184,87 +711,384 @@ impl SendR2Bucket {
 #[cfg(feature = "ssr")]
 use crate::ingredients::SendD1Database;
 
-/// Fetch all food logs from D1 database
-#[server]
-pub async fn get_food_logs() -> Result<Vec<FoodLog>, ServerFnError> {
+/// Build a FoodLog from a food_logs row joined with the recipe name. Its images are
+/// loaded separately by `attach_images`.
+#[cfg(feature = "ssr")]
+fn food_log_from_row(row: &serde_json::Value) -> FoodLog {
+    FoodLog {
+        id: row.get("id").and_then(|v| v.as_i64()),
+        recipe_id: row.get("recipe_id").and_then(|v| v.as_i64()),
+        recipe_name: row
+            .get("recipe_name")
+            .and_then(|v| v.as_str())
+            .map(|s| s.to_string()),
+        images: Vec::new(),
+        logged_at: row
+            .get("logged_at")
+            .and_then(|v| v.as_str())
+            .unwrap_or("")
+            .to_string(),
+        rating: row.get("rating").and_then(|v| v.as_i64()).map(|r| r as i32),
+        notes: row
+            .get("notes")
+            .and_then(|v| v.as_str())
+            .unwrap_or("")
+            .to_string(),
+        portion: row.get("portion").and_then(|v| v.as_f64()).unwrap_or(1.0) as f32,
+        meal_type: row
+            .get("meal_type")
+            .and_then(|v| v.as_str())
+            .and_then(MealType::parse)
+            .unwrap_or_default(),
+    }
+}
+
+/// Build a FoodLogImage from a food_log_images row
+#[cfg(feature = "ssr")]
+fn food_log_image_from_row(row: &serde_json::Value) -> Option<FoodLogImage> {
+    Some(FoodLogImage {
+        image_key: row.get("image_key")?.as_str()?.to_string(),
+        crop: ImageCrop {
+            x: row.get("crop_x").and_then(|v| v.as_f64()).unwrap_or(0.0) as f32,
+            y: row.get("crop_y").and_then(|v| v.as_f64()).unwrap_or(0.0) as f32,
+            width: row
+                .get("crop_width")
+                .and_then(|v| v.as_f64())
+                .unwrap_or(100.0) as f32,
+            height: row
+                .get("crop_height")
+                .and_then(|v| v.as_f64())
+                .unwrap_or(100.0) as f32,
+            rotation: row
+                .get("crop_rotation")
+                .and_then(|v| v.as_i64())
+                .unwrap_or(0) as i32,
+        },
+        view_crops: row
+            .get("view_crops")
+            .and_then(|v| v.as_str())
+            .and_then(|json| serde_json::from_str(json).ok())
+            .unwrap_or_default(),
+        variants: row
+            .get("image_variants")
+            .and_then(|v| v.as_i64())
+            .unwrap_or(0)
+            != 0,
+        signed_query: None,
+    })
+}
+
+/// Load the images of each entry, in order
+#[cfg(feature = "ssr")]
+async fn attach_images(db: &SendD1Database, logs: &mut [FoodLog]) -> Result<(), ServerFnError> {
     use send_wrapper::SendWrapper;
+    use wasm_bindgen::JsValue;
 
-    let db = expect_context::<SendD1Database>();
+    let ids: Vec<i64> = logs.iter().filter_map(|log| log.id).collect();
+    // Stay well inside D1's limit on bound parameters
+    for chunk in ids.chunks(90) {
+        let placeholders = vec!["?"; chunk.len()].join(", ");
+        let rows = SendWrapper::new(async {
+            let params: Vec<JsValue> = chunk.iter().map(|&id| (id as f64).into()).collect();
+            let stmt = db.inner().prepare(format!(
+                "SELECT food_log_id, image_key, crop_x, crop_y, crop_width, crop_height, crop_rotation, view_crops, image_variants
+                 FROM food_log_images
+                 WHERE food_log_id IN ({})
+                 ORDER BY food_log_id, position",
+                placeholders
+            ));
+            let stmt = stmt.bind(&params)?;
+            stmt.all().await?.results::<serde_json::Value>()
+        })
+        .await
+        .map_err(|e| ServerFnError::new(format!("D1 query error: {:?}", e)))?;
 
-    let logs = SendWrapper::new(async {
+        for row in &rows {
+            let food_log_id = row.get("food_log_id").and_then(|v| v.as_i64());
+            let log = logs.iter_mut().find(|log| log.id == food_log_id);
+            if let (Some(log), Some(image)) = (log, food_log_image_from_row(row)) {
+                log.images.push(image);
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Sign the URLs of the entries' images so the client can show them. Whoever can read an
+/// entry can therefore fetch its photos until the signatures expire.
+#[cfg(feature = "ssr")]
+fn sign_images(logs: &mut [FoodLog]) {
+    use crate::image_signing::ImageSigner;
+
+    let signer = expect_context::<ImageSigner>();
+    let now_secs = worker::Date::now().as_millis() / 1000;
+    for image in logs.iter_mut().flat_map(|log| log.images.iter_mut()) {
+        image.signed_query = Some(signer.sign(&image.image_key, now_secs));
+    }
+}
+
+/// Replace the stored images of an entry with `images`, in order
+#[cfg(feature = "ssr")]
+async fn save_images(
+    db: &SendD1Database,
+    food_log_id: i64,
+    images: &[FoodLogImage],
+) -> Result<(), ServerFnError> {
+    use send_wrapper::SendWrapper;
+
+    SendWrapper::new(async {
+        let stmt = db
+            .inner()
+            .prepare("DELETE FROM food_log_images WHERE food_log_id = ?");
+        let stmt = stmt.bind(&[(food_log_id as f64).into()])?;
+        stmt.run().await
+    })
+    .await
+    .map_err(|e| ServerFnError::new(format!("D1 delete images error: {:?}", e)))?;
+
+    for (position, image) in images.iter().enumerate() {
+        SendWrapper::new(async {
+            let stmt = db.inner().prepare(
+                "INSERT INTO food_log_images (food_log_id, image_key, position, crop_x, crop_y, crop_width, crop_height, crop_rotation, view_crops, image_variants)
+                 VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?)",
+            );
+            let stmt = stmt.bind(&[
+                (food_log_id as f64).into(),
+                image.image_key.clone().into(),
+                (position as f64).into(),
+                (image.crop.x as f64).into(),
+                (image.crop.y as f64).into(),
+                (image.crop.width as f64).into(),
+                (image.crop.height as f64).into(),
+                (image.crop.rotation as f64).into(),
+                serde_json::to_string(&image.view_crops)
+                    .unwrap_or_else(|_| "[]".to_string())
+                    .into(),
+                (if image.variants { 1.0 } else { 0.0 }).into(),
+            ])?;
+            stmt.run().await
+        })
+        .await
+        .map_err(|e| ServerFnError::new(format!("D1 insert image error: {:?}", e)))?;
+    }
+    Ok(())
+}
+
+/// Load a single food log entry, used to snapshot state for the audit log
+#[cfg(feature = "ssr")]
+pub(crate) async fn fetch_food_log(
+    db: &SendD1Database,
+    id: i64,
+) -> Result<Option<FoodLog>, ServerFnError> {
+    use send_wrapper::SendWrapper;
+
+    let row = SendWrapper::new(async {
         let stmt = db.inner().prepare(
-            "SELECT fl.id, fl.recipe_id, r.name as recipe_name, fl.image_key, fl.logged_at, 
-                    fl.rating, fl.notes, fl.crop_x, fl.crop_y, fl.crop_width, fl.crop_height, fl.crop_rotation
+            "SELECT fl.id, fl.recipe_id, r.name as recipe_name, fl.logged_at, fl.rating, fl.notes,
+                    fl.portion, fl.meal_type
              FROM food_logs fl
              LEFT JOIN recipes r ON fl.recipe_id = r.id
-             ORDER BY fl.logged_at DESC",
+             WHERE fl.id = ?",
         );
+        let stmt = stmt.bind(&[(id as f64).into()])?;
+        stmt.first::<serde_json::Value>(None).await
+    })
+    .await
+    .map_err(|e| ServerFnError::new(format!("D1 query error: {:?}", e)))?;
+
+    let Some(row) = row else {
+        return Ok(None);
+    };
+    let mut logs = [food_log_from_row(&row)];
+    attach_images(db, &mut logs).await?;
+    let [log] = logs;
+    Ok(Some(log))
+}
+
+/// Fetch a page of food logs matching a filter, newest first, starting after `cursor`
+#[server]
+pub async fn get_food_logs(
+    filter: FoodLogFilter,
+    cursor: Option<FoodLogCursor>,
+) -> Result<FoodLogPage, ServerFnError> {
+    use send_wrapper::SendWrapper;
+    use wasm_bindgen::JsValue;
+
+    let db = expect_context::<SendD1Database>();
+
+    let mut logs = SendWrapper::new(async {
+        let mut conditions = vec!["fl.deleted_at IS NULL"];
+        let mut params: Vec<JsValue> = Vec::new();
+        if let Some(from) = filter.from.filter(|d| !d.is_empty()) {
+            conditions.push("fl.logged_at >= ?");
+            params.push(from.into());
+        }
+        if let Some(to) = filter.to.filter(|d| !d.is_empty()) {
+            // logged_at may carry a time after the date, so compare the date part only
+            conditions.push("substr(fl.logged_at, 1, 10) <= ?");
+            params.push(to.into());
+        }
+        if let Some(min_rating) = filter.min_rating {
+            conditions.push("fl.rating >= ?");
+            params.push((min_rating as f64).into());
+        }
+        if let Some(recipe_id) = filter.recipe_id {
+            conditions.push("fl.recipe_id = ?");
+            params.push((recipe_id as f64).into());
+        }
+        if let Some(cursor) = &cursor {
+            conditions.push("(fl.logged_at < ? OR (fl.logged_at = ? AND fl.id < ?))");
+            params.push(cursor.logged_at.clone().into());
+            params.push(cursor.logged_at.clone().into());
+            params.push((cursor.id as f64).into());
+        }
+        // Fetch one extra row to find out whether there is another page
+        params.push(((FOOD_LOGS_PAGE_SIZE + 1) as f64).into());
+
+        let stmt = db.inner().prepare(format!(
+            "SELECT fl.id, fl.recipe_id, r.name as recipe_name, fl.logged_at, fl.rating, fl.notes,
+                    fl.portion, fl.meal_type
+             FROM food_logs fl
+             LEFT JOIN recipes r ON fl.recipe_id = r.id
+             WHERE {}
+             ORDER BY fl.logged_at DESC, fl.id DESC
+             LIMIT ?",
+            conditions.join(" AND ")
+        ));
+        let stmt = stmt.bind(&params)?;
         let results = stmt.all().await?;
         let rows: Vec<serde_json::Value> = results.results::<serde_json::Value>()?;
 
-        let logs: Vec<FoodLog> = rows
-            .into_iter()
-            .map(|row| FoodLog {
-                id: row.get("id").and_then(|v| v.as_i64()),
-                recipe_id: row.get("recipe_id").and_then(|v| v.as_i64()),
-                recipe_name: row
-                    .get("recipe_name")
-                    .and_then(|v| v.as_str())
-                    .map(|s| s.to_string()),
-                image_key: row
-                    .get("image_key")
-                    .and_then(|v| v.as_str())
-                    .map(|s| s.to_string()),
-                logged_at: row
-                    .get("logged_at")
-                    .and_then(|v| v.as_str())
-                    .unwrap_or("")
-                    .to_string(),
-                rating: row.get("rating").and_then(|v| v.as_i64()).map(|r| r as i32),
-                notes: row
-                    .get("notes")
-                    .and_then(|v| v.as_str())
-                    .unwrap_or("")
-                    .to_string(),
-                crop: ImageCrop {
-                    x: row.get("crop_x").and_then(|v| v.as_f64()).unwrap_or(0.0) as f32,
-                    y: row.get("crop_y").and_then(|v| v.as_f64()).unwrap_or(0.0) as f32,
-                    width: row
-                        .get("crop_width")
-                        .and_then(|v| v.as_f64())
-                        .unwrap_or(100.0) as f32,
-                    height: row
-                        .get("crop_height")
-                        .and_then(|v| v.as_f64())
-                        .unwrap_or(100.0) as f32,
-                    rotation: row
-                        .get("crop_rotation")
-                        .and_then(|v| v.as_i64())
-                        .unwrap_or(0) as i32,
-                },
-            })
-            .collect();
+        let logs: Vec<FoodLog> = rows.iter().map(food_log_from_row).collect();
 
         Ok::<_, worker::Error>(logs)
     })
     .await
     .map_err(|e| ServerFnError::new(format!("D1 query error: {:?}", e)))?;
 
-    Ok(logs)
+    let next_cursor = if logs.len() as i64 > FOOD_LOGS_PAGE_SIZE {
+        logs.truncate(FOOD_LOGS_PAGE_SIZE as usize);
+        logs.last().and_then(|log| {
+            Some(FoodLogCursor {
+                logged_at: log.logged_at.clone(),
+                id: log.id?,
+            })
+        })
+    } else {
+        None
+    };
+    attach_images(&db, &mut logs).await?;
+    sign_images(&mut logs);
+
+    Ok(FoodLogPage { logs, next_cursor })
+}
+
+/// Record which photos already have display variants: those made earlier for the same
+/// photo and crops, as listed in `before`. The rest get theirs from
+/// `generate_variants_later`. Signatures sent back by the client are dropped rather than
+/// saved with the entry.
+#[cfg(feature = "ssr")]
+fn reuse_variants(images: Vec<FoodLogImage>, before: &[FoodLogImage]) -> Vec<FoodLogImage> {
+    images
+        .into_iter()
+        .map(|image| {
+            let variants = before.iter().any(|b| {
+                b.variants
+                    && b.image_key == image.image_key
+                    && b.crop == image.crop
+                    && b.view_crops == image.view_crops
+            });
+            FoodLogImage {
+                variants,
+                signed_query: None,
+                ..image
+            }
+        })
+        .collect()
+}
+
+/// Generate the display variants of an entry's photos that lack them once the response
+/// has been sent, as decoding and encoding photos takes too long for a request. A photo is
+/// marked as having variants when they're stored, unless its crops changed in the meantime,
+/// and the variants of its earlier crops are then removed. One the pipeline can't handle
+/// is still shown, from its original.
+#[cfg(feature = "ssr")]
+fn generate_variants_later(food_log_id: i64, images: &[FoodLogImage]) {
+    use crate::image_pipeline::{delete_variants, generate_variants};
+
+    let pending: Vec<FoodLogImage> = images.iter().filter(|i| !i.variants).cloned().collect();
+    if pending.is_empty() {
+        return;
+    }
+    let db = expect_context::<SendD1Database>();
+    let bucket = expect_context::<SendR2Bucket>();
+    expect_context::<SendContext>().wait_until(async move {
+        for image in pending {
+            let key = &image.image_key;
+            if let Err(e) = generate_variants(&bucket, &image).await {
+                log::warn!("Failed to process image {}: {}", key, e);
+                continue;
+            }
+            let marked = async {
+                let stmt = db.inner().prepare(
+                    "UPDATE food_log_images SET image_variants = 1
+                     WHERE food_log_id = ? AND image_key = ? AND crop_x = ? AND crop_y = ? AND crop_width = ? AND crop_height = ? AND crop_rotation = ? AND view_crops = ?
+                     RETURNING id",
+                );
+                let stmt = stmt.bind(&[
+                    (food_log_id as f64).into(),
+                    key.clone().into(),
+                    (image.crop.x as f64).into(),
+                    (image.crop.y as f64).into(),
+                    (image.crop.width as f64).into(),
+                    (image.crop.height as f64).into(),
+                    (image.crop.rotation as f64).into(),
+                    serde_json::to_string(&image.view_crops)
+                        .unwrap_or_else(|_| "[]".to_string())
+                        .into(),
+                ])?;
+                stmt.first::<serde_json::Value>(None).await
+            }
+            .await;
+            let crops: Vec<String> = ImageVariant::ALL
+                .into_iter()
+                .map(|variant| image.variant_crop(variant).fingerprint())
+                .collect();
+            match marked {
+                Ok(Some(_)) => delete_variants(&bucket, key, &crops).await,
+                // A later save has its own variants made
+                Ok(None) => {}
+                Err(e) => log::warn!("Failed to record variants of image {}: {:?}", key, e),
+            }
+        }
+    });
 }
 
 /// Create a new food log entry
 #[server]
-pub async fn create_food_log(log: FoodLog) -> Result<FoodLog, ServerFnError> {
+pub async fn create_food_log(
+    log: FoodLog,
+    auth_token: Option<String>,
+) -> Result<FoodLog, ServerFnError> {
     use send_wrapper::SendWrapper;
     use wasm_bindgen::JsValue;
 
+    use crate::audit::{record_audit, AuditAction, AuditEntity};
+    use crate::auth::resolve_actor;
+    use crate::pantry::sync_pantry_for_log;
+
     let db = expect_context::<SendD1Database>();
+    let actor = resolve_actor(auth_token.as_deref()).await;
+
+    let log = FoodLog {
+        images: reuse_variants(log.images, &[]),
+        ..log
+    };
 
     let result = SendWrapper::new(async {
         let stmt = db.inner().prepare(
-            "INSERT INTO food_logs (recipe_id, image_key, logged_at, rating, notes, crop_x, crop_y, crop_width, crop_height, crop_rotation) 
-             VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?) RETURNING id",
+            "INSERT INTO food_logs (recipe_id, logged_at, rating, notes, portion, meal_type) 
+             VALUES (?, ?, ?, ?, ?, ?) RETURNING id",
         );
 
         // Convert Option values to JsValue (NULL for None)
@@ -272,11 +1096,6 @@ pub async fn create_food_log(log: FoodLog) -> Result<FoodLog, ServerFnError> {
             .recipe_id
             .map(|id| (id as f64).into())
             .unwrap_or(JsValue::NULL);
-        let image_key_val: JsValue = log
-            .image_key
-            .as_ref()
-            .map(|k| k.clone().into())
-            .unwrap_or(JsValue::NULL);
         let rating_val: JsValue = log
             .rating
             .map(|r| (r as f64).into())
@@ -284,15 +1103,11 @@ pub async fn create_food_log(log: FoodLog) -> Result<FoodLog, ServerFnError> {
 
         let stmt = stmt.bind(&[
             recipe_id_val,
-            image_key_val,
             log.logged_at.clone().into(),
             rating_val,
             log.notes.clone().into(),
-            (log.crop.x as f64).into(),
-            (log.crop.y as f64).into(),
-            (log.crop.width as f64).into(),
-            (log.crop.height as f64).into(),
-            (log.crop.rotation as f64).into(),
+            (log.portion as f64).into(),
+            log.meal_type.as_str().into(),
         ])?;
 
         stmt.first::<serde_json::Value>(None).await
@@ -303,31 +1118,68 @@ pub async fn create_food_log(log: FoodLog) -> Result<FoodLog, ServerFnError> {
     let id = result
         .and_then(|v| v.get("id").and_then(|id| id.as_i64()))
         .ok_or_else(|| ServerFnError::new("Failed to get inserted ID"))?;
+    save_images(&db, id, &log.images).await?;
+    generate_variants_later(id, &log.images);
 
     log::info!("Created food log entry: id={}", id);
 
-    Ok(FoodLog {
+    // Eating a recipe uses up its ingredients. A pantry failure shouldn't lose the log entry.
+    if let Err(e) = sync_pantry_for_log(&db, id, log.pantry_usage()).await {
+        log::warn!("Failed to update pantry for food log {}: {}", id, e);
+    }
+
+    let mut log = FoodLog {
         id: Some(id),
         ..log
-    })
+    };
+
+    record_audit(
+        &db,
+        AuditEntity::FoodLog,
+        id,
+        AuditAction::Create,
+        None,
+        Some(&log),
+        &actor,
+    )
+    .await?;
+
+    sign_images(std::slice::from_mut(&mut log));
+    Ok(log)
 }
 
-/// Update an existing food log entry
+/// Update an existing food log entry, returning it as saved
 #[server]
-pub async fn update_food_log(log: FoodLog) -> Result<(), ServerFnError> {
+pub async fn update_food_log(
+    log: FoodLog,
+    auth_token: Option<String>,
+) -> Result<FoodLog, ServerFnError> {
     use send_wrapper::SendWrapper;
     use wasm_bindgen::JsValue;
 
+    use crate::audit::{record_audit, AuditAction, AuditEntity};
+    use crate::auth::resolve_actor;
+    use crate::pantry::sync_pantry_for_log;
+
     let db = expect_context::<SendD1Database>();
+    let actor = resolve_actor(auth_token.as_deref()).await;
 
     let id = log
         .id
         .ok_or_else(|| ServerFnError::new("Food log ID is required for update"))?;
 
+    let before = fetch_food_log(&db, id).await?;
+
+    // Only a new photo or a new crop needs the variants regenerated
+    let before_images = before.as_ref().map_or(&[][..], |b| &b.images);
+    let mut log = FoodLog {
+        images: reuse_variants(log.images, before_images),
+        ..log
+    };
+
     SendWrapper::new(async {
         let stmt = db.inner().prepare(
-            "UPDATE food_logs SET recipe_id = ?, image_key = ?, logged_at = ?, rating = ?, notes = ?, 
-             crop_x = ?, crop_y = ?, crop_width = ?, crop_height = ?, crop_rotation = ?, updated_at = datetime('now') 
+            "UPDATE food_logs SET recipe_id = ?, logged_at = ?, rating = ?, notes = ?, portion = ?, meal_type = ?, updated_at = datetime('now') 
              WHERE id = ?",
         );
 
@@ -336,11 +1188,6 @@ pub async fn update_food_log(log: FoodLog) -> Result<(), ServerFnError> {
             .recipe_id
             .map(|rid| (rid as f64).into())
             .unwrap_or(JsValue::NULL);
-        let image_key_val: JsValue = log
-            .image_key
-            .as_ref()
-            .map(|k| k.clone().into())
-            .unwrap_or(JsValue::NULL);
         let rating_val: JsValue = log
             .rating
             .map(|r| (r as f64).into())
@@ -348,15 +1195,11 @@ pub async fn update_food_log(log: FoodLog) -> Result<(), ServerFnError> {
 
         let stmt = stmt.bind(&[
             recipe_id_val,
-            image_key_val,
             log.logged_at.clone().into(),
             rating_val,
             log.notes.clone().into(),
-            (log.crop.x as f64).into(),
-            (log.crop.y as f64).into(),
-            (log.crop.width as f64).into(),
-            (log.crop.height as f64).into(),
-            (log.crop.rotation as f64).into(),
+            (log.portion as f64).into(),
+            log.meal_type.as_str().into(),
             (id as f64).into(),
         ])?;
 
@@ -364,113 +1207,109 @@ pub async fn update_food_log(log: FoodLog) -> Result<(), ServerFnError> {
     })
     .await
     .map_err(|e| ServerFnError::new(format!("D1 update error: {:?}", e)))?;
+    save_images(&db, id, &log.images).await?;
+    generate_variants_later(id, &log.images);
+
+    if let Err(e) = sync_pantry_for_log(&db, id, log.pantry_usage()).await {
+        log::warn!("Failed to update pantry for food log {}: {}", id, e);
+    }
+
+    record_audit(
+        &db,
+        AuditEntity::FoodLog,
+        id,
+        AuditAction::Update,
+        before.as_ref(),
+        Some(&log),
+        &actor,
+    )
+    .await?;
 
     log::info!("Updated food log entry: id={}", id);
-    Ok(())
+    sign_images(std::slice::from_mut(&mut log));
+    Ok(log)
 }
 
-/// Delete a food log entry
+/// Move a food log entry to the trash. The images are kept until the entry is purged.
 #[server]
-pub async fn delete_food_log(id: i64) -> Result<(), ServerFnError> {
+pub async fn delete_food_log(id: i64, auth_token: Option<String>) -> Result<(), ServerFnError> {
     use send_wrapper::SendWrapper;
 
+    use crate::audit::{record_audit, AuditAction, AuditEntity};
+    use crate::auth::resolve_actor;
+    use crate::pantry::sync_pantry_for_log;
+
     let db = expect_context::<SendD1Database>();
-    let bucket = expect_context::<SendR2Bucket>();
+    let actor = resolve_actor(auth_token.as_deref()).await;
 
-    // First get the image key to delete from R2
-    let image_key: Option<String> = SendWrapper::new(async {
-        let stmt = db
-            .inner()
-            .prepare("SELECT image_key FROM food_logs WHERE id = ?");
+    let before = fetch_food_log(&db, id).await?;
+
+    let trashed = SendWrapper::new(async {
+        let stmt = db.inner().prepare(
+            "UPDATE food_logs SET deleted_at = datetime('now') WHERE id = ? AND deleted_at IS NULL RETURNING id",
+        );
         let stmt = stmt.bind(&[(id as f64).into()])?;
-        let result = stmt.first::<serde_json::Value>(None).await?;
-        Ok::<_, worker::Error>(result.and_then(|v| {
-            v.get("image_key")
-                .and_then(|k| k.as_str().map(|s| s.to_string()))
-        }))
+        stmt.first::<serde_json::Value>(None).await
     })
     .await
-    .map_err(|e| ServerFnError::new(format!("D1 query error: {:?}", e)))?;
+    .map_err(|e| ServerFnError::new(format!("D1 delete error: {:?}", e)))?
+    .is_some();
 
-    // Delete from R2 if there's an image
-    if let Some(key) = image_key {
-        let _ = SendWrapper::new(bucket.inner().delete(&key)).await;
+    // A trashed entry no longer counts as eaten, so its ingredients go back in the pantry
+    if trashed {
+        if let Err(e) = sync_pantry_for_log(&db, id, None).await {
+            log::warn!("Failed to update pantry for food log {}: {}", id, e);
+        }
     }
 
-    // Delete from D1
-    SendWrapper::new(async {
-        let stmt = db.inner().prepare("DELETE FROM food_logs WHERE id = ?");
-        let stmt = stmt.bind(&[(id as f64).into()])?;
-        stmt.run().await
-    })
-    .await
-    .map_err(|e| ServerFnError::new(format!("D1 delete error: {:?}", e)))?;
+    if before.is_some() {
+        record_audit(
+            &db,
+            AuditEntity::FoodLog,
+            id,
+            AuditAction::Delete,
+            before.as_ref(),
+            None,
+            &actor,
+        )
+        .await?;
+    }
 
-    log::info!("Deleted food log entry: id={}", id);
+    log::info!("Moved food log entry to trash: id={}", id);
     Ok(())
 }
 
-/// Upload an image to R2 using base64 encoded data and return the key
-#[server]
-pub async fn upload_food_image(
-    image_base64: String,
-    content_type: String,
-) -> Result<String, ServerFnError> {
-    use base64::Engine;
-    use send_wrapper::SendWrapper;
-
-    let bucket = expect_context::<SendR2Bucket>();
-
-    // Decode base64 data
-    let data = base64::engine::general_purpose::STANDARD
-        .decode(&image_base64)
-        .map_err(|e| ServerFnError::new(format!("Failed to decode image: {}", e)))?;
-
-    // Generate a unique key
-    let mut key_bytes = [0u8; 16];
-    getrandom::fill(&mut key_bytes).expect("Failed to generate random bytes");
-    let key = hex::encode(key_bytes);
-
-    // Upload to R2
-    SendWrapper::new(async {
-        bucket
-            .inner()
-            .put(&key, data)
-            .http_metadata(worker::HttpMetadata {
-                content_type: Some(content_type),
-                ..Default::default()
-            })
-            .execute()
-            .await
-    })
-    .await
-    .map_err(|e| ServerFnError::new(format!("R2 upload error: {:?}", e)))?;
-
-    log::info!("Uploaded image to R2: key={}", key);
-    Ok(key)
-}
-
 /// Delete an image from R2
 #[server]
 pub async fn delete_food_image(key: String) -> Result<(), ServerFnError> {
     use send_wrapper::SendWrapper;
 
+    use crate::image_pipeline::delete_variants;
+
     let bucket = expect_context::<SendR2Bucket>();
 
     SendWrapper::new(bucket.inner().delete(&key))
         .await
         .map_err(|e| ServerFnError::new(format!("R2 delete error: {:?}", e)))?;
+    delete_variants(&bucket, &key, &[]).await;
 
     log::info!("Deleted image from R2: key={}", key);
     Ok(())
 }
 
-/// Get an image from R2 (returns base64 encoded data with content type)
+/// Get an image from R2 (returns base64 encoded data with content type). Admin only,
+/// since it bypasses the signed image URLs.
 #[server]
-pub async fn get_food_image(key: String) -> Result<(String, String), ServerFnError> {
+pub async fn get_food_image(
+    key: String,
+    auth_token: Option<String>,
+) -> Result<(String, String), ServerFnError> {
+    use crate::auth::require_admin;
     use base64::Engine;
     use send_wrapper::SendWrapper;
 
+    require_admin(auth_token.as_deref()).await?;
+
     let bucket = expect_context::<SendR2Bucket>();
 
     // Wrap the entire operation in SendWrapper to handle non-Send types
@@ -550,53 +1389,137 @@ fn StarRating(rating: RwSignal<Option<i32>>, #[prop(optional)] readonly: bool) -
     }
 }
 
-/// Image cropper component with fixed aspect ratio crop box, rotation, and zoom
+/// A drag in progress in the cropper
+#[derive(Clone)]
+struct CropDrag {
+    /// The corner being dragged, or `None` when moving the whole box
+    corner: Option<CropCorner>,
+    start: (f32, f32),
+    crop: ImageCrop,
+}
+
+/// Image cropper component with a crop per view, each with a selectable aspect ratio,
+/// moved by dragging and resized from its corners by mouse or touch, plus rotation and
+/// zoom. Crop percentages are of the photo after rotation.
 #[component]
 fn ImageCropper(
     image_data: ReadSignal<Option<String>>,
+    /// The card crop, which also holds the photo's rotation
     crop: RwSignal<ImageCrop>,
+    view_crops: RwSignal<Vec<ViewCrop>>,
 ) -> impl IntoView {
     let container_ref = NodeRef::<leptos::html::Div>::new();
-    let is_dragging = RwSignal::new(false);
-    let drag_start = RwSignal::new((0.0_f32, 0.0_f32));
-    let crop_start = RwSignal::new(ImageCrop::new());
+    let view = RwSignal::new(CropView::Card);
+    let aspect = RwSignal::new(Some(CropView::Card.aspect_ratio()));
+    let natural_size = RwSignal::new(Option::<(f32, f32)>::None);
+    let drag = RwSignal::new(Option::<CropDrag>::None);
+
+    // Width over height of the photo as shown, after rotation
+    let image_aspect = move || {
+        let (width, height) = natural_size.get().unwrap_or((4.0, 3.0));
+        if crop.get().rotation % 180 == 0 {
+            width / height
+        } else {
+            height / width
+        }
+    };
+    // The crop of the view being edited. A view without its own crop starts from the card
+    // crop reshaped to the view.
+    let current = move || match view.get() {
+        CropView::Card => crop.get(),
+        v => view_crops
+            .with(|crops| crops.iter().find(|c| c.view == v).map(|c| c.crop.clone()))
+            .unwrap_or_else(|| {
+                let mut fitted = crop.get();
+                fitted.fit_aspect(v.aspect_ratio(), image_aspect());
+                fitted
+            }),
+    };
+    let set_current = move |new_crop: ImageCrop| match view.get_untracked() {
+        CropView::Card => crop.set(new_crop),
+        v => view_crops.update(|crops| {
+            crops.retain(|c| c.view != v);
+            crops.push(ViewCrop {
+                view: v,
+                crop: new_crop,
+            });
+        }),
+    };
 
-    let on_mouse_down = move |ev: web_sys::MouseEvent| {
+    let select_view = move |v: CropView| {
+        view.set(v);
+        aspect.set(Some(v.aspect_ratio()));
+    };
+    let on_aspect_change = move |ev| {
+        let label = event_target_value(&ev);
+        let new_aspect = CROP_ASPECTS
+            .iter()
+            .find(|(l, _)| *l == label)
+            .and_then(|(_, a)| *a);
+        aspect.set(new_aspect);
+        if let Some(new_aspect) = new_aspect {
+            let mut reshaped = current();
+            reshaped.fit_aspect(new_aspect, image_aspect());
+            set_current(reshaped);
+        }
+    };
+
+    let start_drag = move |ev: web_sys::PointerEvent, corner: Option<CropCorner>| {
         ev.prevent_default();
-        is_dragging.set(true);
-        drag_start.set((ev.client_x() as f32, ev.client_y() as f32));
-        crop_start.set(crop.get());
+        ev.stop_propagation();
+        // Keep receiving the pointer's events when it leaves the box or the photo
+        if let Some(container) = container_ref.get() {
+            let _ = container.set_pointer_capture(ev.pointer_id());
+        }
+        drag.set(Some(CropDrag {
+            corner,
+            start: (ev.client_x() as f32, ev.client_y() as f32),
+            crop: current(),
+        }));
     };
 
-    let on_mouse_move = move |ev: web_sys::MouseEvent| {
-        if !is_dragging.get() {
+    let on_pointer_move = move |ev: web_sys::PointerEvent| {
+        let Some(active) = drag.get_untracked() else {
             return;
-        }
+        };
         let Some(container) = container_ref.get() else {
             return;
         };
         let rect = container.get_bounding_client_rect();
-        let container_width = rect.width() as f32;
-        let container_height = rect.height() as f32;
-
-        let (start_x, start_y) = drag_start.get();
-        let dx = ((ev.client_x() as f32 - start_x) / container_width) * 100.0;
-        let dy = ((ev.client_y() as f32 - start_y) / container_height) * 100.0;
+        let dx = (ev.client_x() as f32 - active.start.0) / rect.width() as f32 * 100.0;
+        let dy = (ev.client_y() as f32 - active.start.1) / rect.height() as f32 * 100.0;
 
-        let start_crop = crop_start.get();
-        let new_x = (start_crop.x + dx).clamp(0.0, 100.0 - start_crop.width);
-        let new_y = (start_crop.y + dy).clamp(0.0, 100.0 - start_crop.height);
+        let start = active.crop;
+        let moved = match active.corner {
+            Some(corner) => {
+                start.resized_from(corner, dx, dy, aspect.get_untracked(), image_aspect())
+            }
+            None => ImageCrop {
+                x: (start.x + dx).clamp(0.0, 100.0 - start.width),
+                y: (start.y + dy).clamp(0.0, 100.0 - start.height),
+                ..start
+            },
+        };
+        set_current(moved);
+    };
 
-        crop.update(|c| {
-            c.x = new_x;
-            c.y = new_y;
-        });
+    let on_pointer_up = move |_: web_sys::PointerEvent| {
+        drag.set(None);
     };
 
-    let on_mouse_up = move |_: web_sys::MouseEvent| {
-        is_dragging.set(false);
+    let on_image_load = move |ev: web_sys::Event| {
+        if let Some(img) = ev
+            .target()
+            .and_then(|t| t.dyn_into::<web_sys::HtmlImageElement>().ok())
+        {
+            natural_size.set(Some((
+                img.natural_width() as f32,
+                img.natural_height() as f32,
+            )));
+        }
     };
 
+    // Rotation turns the whole photo, so it is kept on the card crop
     let on_rotate_ccw = move |_| {
         crop.update(|c| c.rotate_ccw());
     };
@@ -606,19 +1529,77 @@ fn ImageCropper(
     };
 
     let on_zoom_in = move |_| {
-        crop.update(|c| c.zoom_in());
+        let mut zoomed = current();
+        zoomed.zoom_in();
+        set_current(zoomed);
     };
 
     let on_zoom_out = move |_| {
-        crop.update(|c| c.zoom_out());
+        let mut zoomed = current();
+        zoomed.zoom_out();
+        set_current(zoomed);
+    };
+
+    let corner_handle = move |corner: CropCorner, class: &'static str| {
+        view! {
+          <div
+            class=format!("absolute w-4 h-4 bg-white rounded-sm touch-none {}", class)
+            on:pointerdown=move |ev: web_sys::PointerEvent| start_drag(ev, Some(corner))
+          />
+        }
     };
 
     view! {
       <Show when=move || image_data.get().is_some()>
         <div class="space-y-3">
+          // Crop views and aspect ratio
+          <div class="flex flex-wrap items-center justify-between gap-2">
+            <div class="flex rounded bg-slate-200 dark:bg-slate-700 p-0.5 text-xs">
+              {CropView::ALL
+                .into_iter()
+                .map(|v| {
+                  view! {
+                    <button
+                      type="button"
+                      class=move || {
+                        format!(
+                          "rounded px-2 py-1 {}",
+                          if view.get() == v {
+                            "bg-white dark:bg-slate-900 text-slate-900 dark:text-slate-100 shadow"
+                          } else {
+                            "text-slate-600 dark:text-slate-300"
+                          },
+                        )
+                      }
+                      on:click=move |_| select_view(v)
+                    >
+                      {v.label()}
+                    </button>
+                  }
+                })
+                .collect_view()}
+            </div>
+            <select
+              class="rounded border border-slate-300 dark:border-slate-600 bg-white dark:bg-slate-700 px-2 py-1 text-xs text-slate-700 dark:text-slate-300"
+              title="Aspect ratio"
+              on:change=on_aspect_change
+            >
+              {CROP_ASPECTS
+                .iter()
+                .map(|(label, value)| {
+                  let value = *value;
+                  view! {
+                    <option value=*label selected=move || aspect.get() == value>
+                      {*label}
+                    </option>
+                  }
+                })
+                .collect_view()}
+            </select>
+          </div>
           // Control buttons
           <div class="flex items-center justify-between">
-            <p class="text-xs text-slate-500 dark:text-slate-400">"Drag box to pan, use buttons to rotate/zoom"</p>
+            <p class="text-xs text-slate-500 dark:text-slate-400">"Drag the box to move it and its corners to resize it"</p>
             <div class="flex items-center gap-1">
               // Rotate counter-clockwise
               <button
@@ -687,32 +1668,47 @@ fn ImageCropper(
               </button>
             </div>
           </div>
-          // Image container
+          // Image container, shaped like the rotated photo so crop percentages are of it
           <div
             node_ref=container_ref
-            class="relative w-full bg-slate-900 rounded overflow-hidden select-none"
-            style="aspect-ratio: 4/3;"
-            on:mousemove=on_mouse_move
-            on:mouseup=on_mouse_up
-            on:mouseleave=on_mouse_up
+            class="relative mx-auto bg-slate-900 rounded overflow-hidden select-none touch-none"
+            style=move || {
+              let a = image_aspect();
+              format!("aspect-ratio: {}; width: min(100%, calc(60vh * {}));", a, a)
+            }
+            on:pointermove=on_pointer_move
+            on:pointerup=on_pointer_up
+            on:pointercancel=on_pointer_up
           >
-            // The image fills the container with rotation applied
+            // The image fills the container with rotation applied. Turned sideways, its box
+            // is the container's height wide and width tall.
             <img
               src=move || image_data.get().unwrap_or_default()
-              class="absolute inset-0 w-full h-full object-contain transition-transform duration-200"
-              style=move || format!("transform: rotate({}deg);", crop.get().rotation)
+              class="absolute left-1/2 top-1/2 max-w-none object-contain transition-transform duration-200"
+              style=move || {
+                let rotation = crop.get().rotation;
+                let a = image_aspect();
+                let (width, height) = if rotation % 180 == 0 { (100.0, 100.0) } else { (100.0 / a, 100.0 * a) };
+                format!(
+                  "width: {}%; height: {}%; transform: translate(-50%, -50%) rotate({}deg);",
+                  width,
+                  height,
+                  rotation,
+                )
+              }
               draggable="false"
+              on:load=on_image_load
             />
             // Top dark band
             <div
               class="absolute left-0 right-0 top-0 bg-black/60 pointer-events-none"
-              style=move || format!("height: {}%;", crop.get().y)
+              style=move || format!("height: {}%;", current().y)
             />
             // Bottom dark band
             <div
               class="absolute left-0 right-0 bottom-0 bg-black/60 pointer-events-none"
               style=move || {
-                let c = crop.get();
+                let c = current();
                 format!("height: {}%;", 100.0 - c.y - c.height)
               }
             />
@@ -720,7 +1716,7 @@ fn ImageCropper(
             <div
               class="absolute left-0 bg-black/60 pointer-events-none"
               style=move || {
-                let c = crop.get();
+                let c = current();
                 format!("top: {}%; height: {}%; width: {}%;", c.y, c.height, c.x)
               }
             />
@@ -728,24 +1724,24 @@ fn ImageCropper(
             <div
               class="absolute right-0 bg-black/60 pointer-events-none"
               style=move || {
-                let c = crop.get();
+                let c = current();
                 format!("top: {}%; height: {}%; width: {}%;", c.y, c.height, 100.0 - c.x - c.width)
               }
             />
             // Crop box border (draggable)
             <div
-              class="absolute border-2 border-white shadow-lg cursor-move"
+              class="absolute border-2 border-white shadow-lg cursor-move touch-none"
               style=move || {
-                let c = crop.get();
+                let c = current();
                 format!("left: {}%; top: {}%; width: {}%; height: {}%;", c.x, c.y, c.width, c.height)
               }
-              on:mousedown=on_mouse_down
+              on:pointerdown=move |ev: web_sys::PointerEvent| start_drag(ev, None)
             >
-              // Corner indicators
-              <div class="absolute -top-1 -left-1 w-3 h-3 bg-white rounded-sm" />
-              <div class="absolute -top-1 -right-1 w-3 h-3 bg-white rounded-sm" />
-              <div class="absolute -bottom-1 -left-1 w-3 h-3 bg-white rounded-sm" />
-              <div class="absolute -bottom-1 -right-1 w-3 h-3 bg-white rounded-sm" />
+              // Corner handles
+              {corner_handle(CropCorner::TopLeft, "-top-2 -left-2 cursor-nwse-resize")}
+              {corner_handle(CropCorner::TopRight, "-top-2 -right-2 cursor-nesw-resize")}
+              {corner_handle(CropCorner::BottomLeft, "-bottom-2 -left-2 cursor-nesw-resize")}
+              {corner_handle(CropCorner::BottomRight, "-bottom-2 -right-2 cursor-nwse-resize")}
             </div>
           </div>
         </div>
@@ -753,6 +1749,77 @@ fn ImageCropper(
     }
 }
 
+/// A newly chosen photo waiting to be uploaded
+#[derive(Clone)]
+struct PendingPhoto {
+    blob: web_sys::Blob,
+    /// Kept across save attempts so a failed upload resumes
+    upload_id: String,
+    /// Whether the photo has already been shrunk for upload
+    prepared: bool,
+}
+
+/// A photo in the modal's gallery editor
+#[derive(Clone)]
+struct GalleryPhoto {
+    /// URL the photo is shown from: the stored original or a local object URL
+    src: String,
+    /// Set once the photo is stored
+    image_key: Option<String>,
+    crop: ImageCrop,
+    view_crops: Vec<ViewCrop>,
+    pending: Option<PendingPhoto>,
+}
+
+impl GalleryPhoto {
+    fn stored(image: &FoodLogImage) -> Self {
+        Self {
+            src: image.image_url(),
+            image_key: Some(image.image_key.clone()),
+            crop: image.crop.clone(),
+            view_crops: image.view_crops.clone(),
+            pending: None,
+        }
+    }
+
+    /// Shrink a newly chosen photo for upload, baking in its rotation if the settings ask
+    /// for it. Does nothing for stored photos or if it was already done.
+    async fn prepare(&mut self) {
+        let Some(pending) = self.pending.as_mut() else {
+            return;
+        };
+        if pending.prepared {
+            return;
+        }
+        pending.prepared = true;
+        // Animated GIFs would lose their animation on a canvas
+        if pending.blob.type_() == ImageFormat::Gif.content_type() {
+            return;
+        }
+        let settings = load_settings().photo;
+        let rotation = if settings.bake_rotation {
+            self.crop.rotation
+        } else {
+            0
+        };
+        match prepare_photo(&self.src, rotation, &settings).await {
+            // Keep the original if it is smaller and there's no rotation to bake in
+            Ok(blob) if rotation != 0 || blob.size() < pending.blob.size() => {
+                pending.blob = blob;
+                if rotation != 0 {
+                    self.crop.rotation = 0;
+                    if let Ok(url) = web_sys::Url::create_object_url_with_blob(&pending.blob) {
+                        self.src = url;
+                    }
+                }
+            }
+            Ok(_) => {}
+            // The original can still be uploaded, just more slowly
+            Err(e) => log::warn!("Failed to shrink photo before upload: {}", e),
+        }
+    }
+}
+
 /// Food log entry modal
 #[component]
 fn FoodLogModal(
@@ -762,13 +1829,21 @@ fn FoodLogModal(
     on_save: impl Fn() + Clone + Send + Sync + 'static,
     on_delete: impl Fn(i64) + Clone + Send + Sync + 'static,
 ) -> impl IntoView {
+    let auth_token = expect_context::<AdminAuth>().token;
     let recipe_id = RwSignal::new(Option::<i64>::None);
-    let logged_at = RwSignal::new(String::new());
+    let logged_date = RwSignal::new(String::new());
+    let logged_time = RwSignal::new(String::new());
+    let meal_type = RwSignal::new(MealType::default());
     let rating = RwSignal::new(Option::<i32>::None);
     let notes = RwSignal::new(String::new());
-    let image_key = RwSignal::new(Option::<String>::None);
+    let portion = RwSignal::new(String::from("1"));
+    let photos = RwSignal::new_local(Vec::<GalleryPhoto>::new());
+    let selected = RwSignal::new(Option::<usize>::None);
+    // The selected photo, as shown in the cropper
     let image_data = RwSignal::new(Option::<String>::None);
     let crop = RwSignal::new(ImageCrop::new());
+    let view_crops = RwSignal::new(Vec::<ViewCrop>::new());
+    let upload_progress = RwSignal::new(Option::<f64>::None);
     let error = RwSignal::new(Option::<String>::None);
     let saving = RwSignal::new(false);
     let show_delete_confirm = RwSignal::new(false);
@@ -776,6 +1851,79 @@ fn FoodLogModal(
 
     let file_input_ref = NodeRef::<leptos::html::Input>::new();
 
+    // Show a photo in the cropper without saving the current crop
+    let load = move |index: Option<usize>| {
+        let photo = index.and_then(|i| {
+            photos.with_untracked(|p| {
+                p.get(i).map(|photo| {
+                    (
+                        photo.src.clone(),
+                        photo.crop.clone(),
+                        photo.view_crops.clone(),
+                    )
+                })
+            })
+        });
+        selected.set(index.filter(|_| photo.is_some()));
+        match photo {
+            Some((src, photo_crop, photo_view_crops)) => {
+                image_data.set(Some(src));
+                crop.set(photo_crop);
+                view_crops.set(photo_view_crops);
+            }
+            None => {
+                image_data.set(None);
+                crop.set(ImageCrop::new());
+                view_crops.set(Vec::new());
+            }
+        }
+    };
+    // Save the cropper's crops into the selected photo
+    let commit_crop = move || {
+        if let Some(i) = selected.get_untracked() {
+            let current = crop.get_untracked();
+            let current_view_crops = view_crops.get_untracked();
+            photos.update(|p| {
+                if let Some(photo) = p.get_mut(i) {
+                    photo.crop = current;
+                    photo.view_crops = current_view_crops;
+                }
+            });
+        }
+    };
+    let select = move |index: usize| {
+        commit_crop();
+        load(Some(index));
+    };
+    let move_photo = move |from: usize, to: usize| {
+        if from == to || to >= photos.with_untracked(|p| p.len()) {
+            return;
+        }
+        commit_crop();
+        photos.update(|p| p.swap(from, to));
+        selected.update(|s| {
+            if *s == Some(from) {
+                *s = Some(to);
+            } else if *s == Some(to) {
+                *s = Some(from);
+            }
+        });
+    };
+    let remove_photo = move |index: usize| {
+        commit_crop();
+        photos.update(|p| {
+            if index < p.len() {
+                p.remove(index);
+            }
+        });
+        let remaining = photos.with_untracked(|p| p.len());
+        match selected.get_untracked() {
+            Some(s) if s == index => load(Some(index.min(remaining.saturating_sub(1)))),
+            Some(s) if s > index => selected.set(Some(s - 1)),
+            _ => {}
+        }
+    };
+
     {
         let on_delete = on_delete.clone();
         Effect::new(move || {
@@ -785,8 +1933,9 @@ fn FoodLogModal(
                     if let Some(id) = log.id {
                         saving.set(true);
                         let on_delete = on_delete.clone();
+                        let token = auth_token.get_untracked();
                         wasm_bindgen_futures::spawn_local(async move {
-                            match delete_food_log(id).await {
+                            match delete_food_log(id, token).await {
                                 Ok(()) => {
                                     show.set(false);
                                     editing.set(None);
@@ -807,28 +1956,28 @@ fn FoodLogModal(
     Effect::new(move || {
         if let Some(log) = editing.get() {
             recipe_id.set(log.recipe_id);
-            logged_at.set(log.logged_at.clone());
+            logged_date.set(log.date().to_string());
+            logged_time.set(log.time().unwrap_or_default().to_string());
+            meal_type.set(log.meal_type);
             rating.set(log.rating);
             notes.set(log.notes.clone());
-            image_key.set(log.image_key.clone());
-            crop.set(log.crop.clone());
-            if let Some(url) = log.image_url() {
-                image_data.set(Some(url));
-            } else {
-                image_data.set(None);
-            }
+            portion.set(log.portion.to_string());
+            photos.set(log.images.iter().map(GalleryPhoto::stored).collect());
+            load(Some(0));
         } else {
             recipe_id.set(None);
-            let today = js_sys::Date::new_0();
-            let year = today.get_full_year();
-            let month = today.get_month() + 1;
-            let day = today.get_date();
-            logged_at.set(format!("{:04}-{:02}-{:02}", year, month, day));
+            let now = FoodLog {
+                logged_at: local_now(),
+                ..FoodLog::new_empty()
+            };
+            logged_date.set(now.date().to_string());
+            logged_time.set(now.time().unwrap_or_default().to_string());
+            meal_type.set(MealType::for_time(now.time().unwrap_or_default()));
             rating.set(None);
             notes.set(String::new());
-            image_key.set(None);
-            image_data.set(None);
-            crop.set(ImageCrop::new());
+            portion.set(String::from("1"));
+            photos.set(Vec::new());
+            load(None);
         }
         error.set(None);
         show_delete_confirm.set(false);
@@ -844,88 +1993,140 @@ fn FoodLogModal(
             return;
         };
         let Some(files) = input.files() else { return };
-        if files.length() == 0 {
-            return;
-        }
-
-        let file = files.get(0).unwrap();
-        let reader = web_sys::FileReader::new().unwrap();
 
-        let reader_clone = reader.clone();
-        let onload = wasm_bindgen::closure::Closure::wrap(Box::new(move |_: web_sys::Event| {
-            if let Ok(result) = reader_clone.result() {
-                if let Some(data_url) = result.as_string() {
-                    image_data.set(Some(data_url));
-                    crop.set(ImageCrop::new());
-                    image_key.set(None);
-                }
+        let first_new = photos.with_untracked(|p| p.len());
+        let mut rejected = None;
+        for file in (0..files.length()).filter_map(|i| files.get(i)) {
+            // The server checks each photo properly; this catches the obvious cases before
+            // the upload. Some browsers don't report a type, so an empty one is let through.
+            let content_type = file.type_();
+            let check = if content_type.is_empty()
+                || ImageFormat::from_content_type(&content_type).is_some()
+            {
+                check_upload_size(file.size() as usize)
+            } else {
+                Err(UploadError::UnsupportedFormat)
+            };
+            if let Err(e) = check {
+                rejected = Some(format!("{}: {}", file.name(), e));
+                continue;
             }
-        }) as Box<dyn FnMut(_)>);
+            let Ok(src) = web_sys::Url::create_object_url_with_blob(&file) else {
+                continue;
+            };
+            photos.update(|p| {
+                p.push(GalleryPhoto {
+                    src,
+                    image_key: None,
+                    crop: ImageCrop::new(),
+                    view_crops: Vec::new(),
+                    pending: Some(PendingPhoto {
+                        blob: file.clone().into(),
+                        upload_id: random_key(),
+                        prepared: false,
+                    }),
+                })
+            });
+        }
+        error.set(rejected);
+        // Let the same file be chosen again after it is removed
+        input.set_value("");
 
-        reader.set_onload(Some(onload.as_ref().unchecked_ref()));
-        onload.forget();
-        let _ = reader.read_as_data_url(&file);
+        if photos.with_untracked(|p| p.len()) > first_new {
+            select(first_new);
+        }
     };
 
     let handle_save = {
         let on_save = on_save.clone();
         move || {
-            let logged_at_val = logged_at.get();
-            if logged_at_val.trim().is_empty() {
+            if logged_date.get().trim().is_empty() {
                 error.set(Some("Date is required".to_string()));
                 return;
             }
 
             saving.set(true);
+            commit_crop();
             let on_save = on_save.clone();
-            let current_image_data = image_data.get();
-            let current_image_key = image_key.get();
-            let needs_upload = current_image_data.is_some()
-                && current_image_key.is_none()
-                && current_image_data
-                    .as_ref()
-                    .map(|d| d.starts_with("data:"))
-                    .unwrap_or(false);
+            let token = auth_token.get_untracked();
 
             wasm_bindgen_futures::spawn_local(async move {
-                let final_image_key = if needs_upload {
-                    let data_url = image_data.get().unwrap();
-                    let parts: Vec<&str> = data_url.splitn(2, ',').collect();
-                    if parts.len() != 2 {
-                        error.set(Some("Invalid image data".to_string()));
-                        saving.set(false);
-                        return;
+                // Upload new photos one at a time, keeping each key as it arrives so saving
+                // again after a failure doesn't upload a photo twice
+                let count = photos.with_untracked(|p| p.len());
+                for i in 0..count {
+                    commit_crop();
+                    let Some(mut photo) = photos.with_untracked(|p| p.get(i).cloned()) else {
+                        break;
+                    };
+                    if photo.pending.as_ref().is_some_and(|p| !p.prepared) {
+                        photo.prepare().await;
+                        photos.update(|p| p[i] = photo.clone());
+                        if selected.get_untracked() == Some(i) {
+                            load(Some(i));
+                        }
                     }
-                    let base64_data = parts[1].to_string();
-                    let content_type = detect_content_type(parts[0]);
-
-                    match upload_food_image(base64_data, content_type.to_string()).await {
-                        Ok(key) => Some(key),
-                        Err(e) => {
-                            error.set(Some(format!("Failed to upload image: {}", e)));
+                    let Some(pending) = photo.pending.clone() else {
+                        continue;
+                    };
+
+                    upload_progress.set(Some(i as f64 / count as f64));
+                    let uploaded = upload_photo(&pending.blob, &pending.upload_id, |fraction| {
+                        upload_progress.set(Some((i as f64 + fraction) / count as f64))
+                    })
+                    .await;
+                    match uploaded {
+                        Ok(key) => photos.update(|p| {
+                            p[i].image_key = Some(key);
+                            p[i].pending = None;
+                        }),
+                        Err(message) => {
+                            upload_progress.set(None);
+                            error.set(Some(format!("Failed to upload photo: {}", message)));
                             saving.set(false);
                             return;
                         }
                     }
-                } else {
-                    current_image_key
-                };
+                }
+                upload_progress.set(None);
+                commit_crop();
 
                 let log = FoodLog {
                     id: editing.get().and_then(|e| e.id),
                     recipe_id: recipe_id.get(),
                     recipe_name: None,
-                    image_key: final_image_key,
-                    logged_at: logged_at.get(),
+                    images: photos.with_untracked(|p| {
+                        p.iter()
+                            .filter_map(|photo| {
+                                Some(FoodLogImage {
+                                    image_key: photo.image_key.clone()?,
+                                    crop: photo.crop.clone(),
+                                    view_crops: photo.view_crops.clone(),
+                                    variants: false,
+                                    signed_query: None,
+                                })
+                            })
+                            .collect()
+                    }),
+                    logged_at: match logged_time.get() {
+                        time if time.is_empty() => logged_date.get(),
+                        time => format!("{}T{}", logged_date.get(), time),
+                    },
                     rating: rating.get(),
                     notes: notes.get(),
-                    crop: crop.get(),
+                    portion: portion
+                        .get()
+                        .parse::<f32>()
+                        .ok()
+                        .filter(|p| *p > 0.0)
+                        .unwrap_or(1.0),
+                    meal_type: meal_type.get(),
                 };
 
                 let result = if log.id.is_some() {
-                    update_food_log(log).await.map(|_| ())
+                    update_food_log(log, token).await.map(|_| ())
                 } else {
-                    create_food_log(log).await.map(|_| ())
+                    create_food_log(log, token).await.map(|_| ())
                 };
 
                 saving.set(false);
@@ -961,7 +2162,7 @@ fn FoodLogModal(
           <div class="w-full max-w-2xl rounded-lg bg-white dark:bg-slate-800 p-6 shadow-xl mx-4 my-auto">
             <div class="mb-4 flex items-center justify-between">
               <h2 class="text-xl font-bold text-slate-900 dark:text-slate-100">
-                {move || if editing.get().is_some() { "Edit Food Log Entry" } else { "New Food Log Entry" }}
+                {move || if editing.get().and_then(|l| l.id).is_some() { "Edit Food Log Entry" } else { "New Food Log Entry" }}
               </h2>
               <button
                 class="text-slate-500 hover:text-slate-700 dark:text-slate-400 dark:hover:text-slate-200"
@@ -979,15 +2180,86 @@ fn FoodLogModal(
 
             <div class="space-y-4 max-h-[70vh] overflow-y-auto pr-2">
               <div>
-                <label class=LABEL_CLASS>"Photo"</label>
+                <label class=LABEL_CLASS>"Photos"</label>
                 <div class="space-y-2">
                   <input
                     node_ref=file_input_ref
                     type="file"
-                    accept="image/*"
+                    accept="image/jpeg,image/png,image/webp,image/gif"
+                    multiple=true
                     class="hidden"
                     on:change=handle_file_select
                   />
+                  <Show when=move || photos.with(|p| !p.is_empty())>
+                    <div class="flex gap-2 overflow-x-auto pb-1">
+                      {move || {
+                        photos
+                          .with(|p| {
+                            let last = p.len() - 1;
+                            p.iter()
+                              .enumerate()
+                              .map(|(i, photo)| {
+                                let src = photo.src.clone();
+                                view! {
+                                  <div class="shrink-0">
+                                    <button
+                                      type="button"
+                                      class=move || {
+                                        format!(
+                                          "relative block h-16 w-16 overflow-hidden rounded border-2 {}",
+                                          if selected.get() == Some(i) { "border-blue-500" } else { "border-transparent" },
+                                        )
+                                      }
+                                      title=format!("Photo {}", i + 1)
+                                      on:click=move |_| select(i)
+                                    >
+                                      <img src=src alt="" class="h-full w-full object-cover" />
+                                      {(i == 0)
+                                        .then(|| {
+                                          view! {
+                                            <span class="absolute left-0 top-0 rounded-br bg-blue-600 px-1 text-[10px] text-white">
+                                              "Cover"
+                                            </span>
+                                          }
+                                        })}
+                                    </button>
+                                    <div class="mt-1 flex justify-between text-xs text-slate-500 dark:text-slate-400">
+                                      <button
+                                        type="button"
+                                        class="px-1 hover:text-blue-500 disabled:opacity-30"
+                                        title="Move left"
+                                        disabled=move || i == 0 || saving.get()
+                                        on:click=move |_| move_photo(i, i.saturating_sub(1))
+                                      >
+                                        "←"
+                                      </button>
+                                      <button
+                                        type="button"
+                                        class="px-1 hover:text-red-500 disabled:opacity-30"
+                                        title="Remove photo"
+                                        disabled=move || saving.get()
+                                        on:click=move |_| remove_photo(i)
+                                      >
+                                        "×"
+                                      </button>
+                                      <button
+                                        type="button"
+                                        class="px-1 hover:text-blue-500 disabled:opacity-30"
+                                        title="Move right"
+                                        disabled=move || i == last || saving.get()
+                                        on:click=move |_| move_photo(i, i + 1)
+                                      >
+                                        "→"
+                                      </button>
+                                    </div>
+                                  </div>
+                                }
+                              })
+                              .collect_view()
+                          })
+                      }}
+                    </div>
+                  </Show>
                   <button
                     type="button"
                     class="w-full rounded border-2 border-dashed border-slate-300 dark:border-slate-600 px-4 py-8 text-center text-slate-500 dark:text-slate-400 hover:border-blue-500 hover:text-blue-500 transition-colors"
@@ -998,18 +2270,18 @@ fn FoodLogModal(
                     }
                   >
                     <Show
-                      when=move || image_data.get().is_some()
+                      when=move || photos.with(|p| !p.is_empty())
                       fallback=move || {
                         view! {
                           <ImageIcon class="mx-auto mb-2" />
-                          <span>"Click to upload a photo"</span>
+                          <span>"Click to upload photos"</span>
                         }
                       }
                     >
-                      <span>"Click to change photo"</span>
+                      <span>"Click to add more photos"</span>
                     </Show>
                   </button>
-                  <ImageCropper image_data=image_data.read_only() crop=crop />
+                  <ImageCropper image_data=image_data.read_only() crop=crop view_crops=view_crops />
                 </div>
               </div>
 
@@ -1044,14 +2316,65 @@ fn FoodLogModal(
                 </select>
               </div>
 
+              <Show when=move || recipe_id.get().is_some()>
+                <div>
+                  <label class=LABEL_CLASS>"Portion (servings)"</label>
+                  <input
+                    type="number"
+                    min="0.25"
+                    step="0.25"
+                    class=INPUT_CLASS
+                    prop:value=move || portion.get()
+                    on:input=move |ev| portion.set(event_target_value(&ev))
+                  />
+                </div>
+              </Show>
+
+              <div class="grid grid-cols-2 gap-4">
+                <div>
+                  <label class=LABEL_CLASS>"Date"</label>
+                  <input
+                    type="date"
+                    class=INPUT_CLASS
+                    prop:value=move || logged_date.get()
+                    on:input=move |ev| logged_date.set(event_target_value(&ev))
+                  />
+                </div>
+                <div>
+                  <label class=LABEL_CLASS>"Time"</label>
+                  <input
+                    type="time"
+                    class=INPUT_CLASS
+                    prop:value=move || logged_time.get()
+                    on:input=move |ev| logged_time.set(event_target_value(&ev))
+                  />
+                </div>
+              </div>
+
               <div>
-                <label class=LABEL_CLASS>"Date"</label>
-                <input
-                  type="date"
-                  class=INPUT_CLASS
-                  prop:value=move || logged_at.get()
-                  on:input=move |ev| logged_at.set(event_target_value(&ev))
-                />
+                <label class=LABEL_CLASS>"Meal"</label>
+                <div class="inline-flex rounded border border-slate-300 dark:border-slate-600 overflow-hidden text-sm">
+                  {MealType::ALL
+                    .into_iter()
+                    .map(|meal| {
+                      view! {
+                        <button
+                          type="button"
+                          class=move || {
+                            if meal_type.get() == meal {
+                              "px-3 py-1 bg-blue-600 text-white"
+                            } else {
+                              "px-3 py-1 bg-white dark:bg-slate-800 text-slate-700 dark:text-slate-300 hover:bg-slate-100 dark:hover:bg-slate-700"
+                            }
+                          }
+                          on:click=move |_| meal_type.set(meal)
+                        >
+                          {meal.label()}
+                        </button>
+                      }
+                    })
+                    .collect_view()}
+                </div>
               </div>
 
               <div>
@@ -1120,7 +2443,11 @@ fn FoodLogModal(
                     move |_| handle_save()
                   }
                 >
-                  {move || if saving.get() { "Saving..." } else { "Save" }}
+                  {move || match upload_progress.get() {
+                    Some(fraction) => format!("Uploading {:.0}%...", fraction * 100.0),
+                    None if saving.get() => "Saving...".to_string(),
+                    None => "Save".to_string(),
+                  }}
                 </button>
               </div>
             </div>
@@ -1138,37 +2465,75 @@ fn FoodLogCard(
     is_authenticated: ReadSignal<bool>,
 ) -> impl IntoView {
     let log_for_edit = log.clone();
-    let has_image = log.image_key.is_some();
-    let image_url = log.image_url().unwrap_or_default();
-    let crop_x = log.crop.x;
-    let crop_y = log.crop.y;
-    let crop_width = log.crop.width;
-    let crop_height = log.crop.height;
-    let rotation = log.crop.rotation;
+    let slides: Vec<(String, String)> = log
+        .images
+        .iter()
+        .map(|image| {
+            (
+                image.variant_url(ImageVariant::Card),
+                image.image_style(ImageVariant::Card),
+            )
+        })
+        .collect();
+    let slide_count = slides.len();
+    let current = RwSignal::new(0usize);
     let recipe_name = log.recipe_name.clone();
     let has_recipe = recipe_name.is_some();
-    let logged_at = log.logged_at.clone();
+    let portion = log.portion;
+    let when = match log.time() {
+        Some(time) => format!("{} · {}", log.meal_type.label(), time),
+        None => log.meal_type.label().to_string(),
+    };
     let rating = log.rating;
     let has_rating = rating.is_some();
     let notes = log.notes.clone();
     let has_notes = !notes.is_empty();
 
-    // For displaying the cropped image, we use object-position
-    // The position tells CSS where in the image to focus
-    // crop_x=0, crop_width=80 means show left 80%, so position should be towards left
-    // crop_x=20, crop_width=80 means crop starts at 20%, so center of crop is at 20 + 40 = 60%
-    let center_x = crop_x + crop_width / 2.0;
-    let center_y = crop_y + crop_height / 2.0;
-
     view! {
       <div class="rounded-lg bg-white dark:bg-slate-800 shadow-md overflow-hidden">
-        <Show when=move || has_image>
-          <div class="h-48 bg-slate-200 dark:bg-slate-700 overflow-hidden flex items-center justify-center">
-            <img
-              src=image_url.clone()
-              class="min-w-full min-h-full object-cover"
-              style=format!("transform: rotate({}deg); object-position: {}% {}%;", rotation, center_x, center_y)
-            />
+        <Show when=move || { slide_count > 0 }>
+          <div class="relative h-48 bg-slate-200 dark:bg-slate-700 overflow-hidden flex items-center justify-center">
+            {
+              let slides = slides.clone();
+              move || {
+                let (url, style) = slides[current.get().min(slide_count - 1)].clone();
+                view! { <img src=url class="min-w-full min-h-full object-cover" style=style /> }
+              }
+            }
+            <Show when=move || { slide_count > 1 }>
+              <button
+                class="absolute left-1 top-1/2 -translate-y-1/2 rounded-full bg-black/40 px-2 py-1 text-white hover:bg-black/60"
+                title="Previous photo"
+                on:click=move |_| current.update(|c| *c = (*c + slide_count - 1) % slide_count)
+              >
+                "‹"
+              </button>
+              <button
+                class="absolute right-1 top-1/2 -translate-y-1/2 rounded-full bg-black/40 px-2 py-1 text-white hover:bg-black/60"
+                title="Next photo"
+                on:click=move |_| current.update(|c| *c = (*c + 1) % slide_count)
+              >
+                "›"
+              </button>
+              <div class="absolute bottom-2 left-0 right-0 flex justify-center gap-1">
+                {(0..slide_count)
+                  .map(|i| {
+                    view! {
+                      <button
+                        class=move || {
+                          format!(
+                            "h-2 w-2 rounded-full {}",
+                            if current.get() == i { "bg-white" } else { "bg-white/50" },
+                          )
+                        }
+                        title=format!("Photo {}", i + 1)
+                        on:click=move |_| current.set(i)
+                      ></button>
+                    }
+                  })
+                  .collect_view()}
+              </div>
+            </Show>
           </div>
         </Show>
 
@@ -1178,9 +2543,14 @@ fn FoodLogCard(
               <Show when=move || has_recipe>
                 <h3 class="text-lg font-semibold text-slate-900 dark:text-slate-100">
                   {recipe_name.clone().unwrap_or_default()}
+                  <Show when=move || { portion != 1.0 }>
+                    <span class="ml-1 text-sm font-normal text-slate-500 dark:text-slate-400">
+                      {format!("× {}", portion)}
+                    </span>
+                  </Show>
                 </h3>
               </Show>
-              <p class="text-sm text-slate-500 dark:text-slate-400">{logged_at.clone()}</p>
+              <p class="text-sm text-slate-500 dark:text-slate-400">{when.clone()}</p>
             </div>
             <Show when=move || is_authenticated.get()>
               <button
@@ -1216,6 +2586,59 @@ fn FoodLogCard(
     }
 }
 
+/// Inline style showing the cropped part of a food photo in an `object-cover` image
+fn crop_style(crop: &ImageCrop) -> String {
+    // For displaying the cropped image, we use object-position
+    // The position tells CSS where in the image to focus
+    // crop_x=0, crop_width=80 means show left 80%, so position should be towards left
+    // crop_x=20, crop_width=80 means crop starts at 20%, so center of crop is at 20 + 40 = 60%
+    let center_x = crop.x + crop.width / 2.0;
+    let center_y = crop.y + crop.height / 2.0;
+    format!(
+        "transform: rotate({}deg); object-position: {}% {}%;",
+        crop.rotation, center_x, center_y
+    )
+}
+
+/// Food log entries grouped under a heading per day, and per meal within each day
+#[component]
+pub(crate) fn FoodLogDays(
+    logs: Vec<FoodLog>,
+    on_edit: impl Fn(FoodLog) + Clone + Send + Sync + 'static,
+    is_authenticated: ReadSignal<bool>,
+) -> impl IntoView {
+    group_by_day(&logs)
+        .into_iter()
+        .map(|(date, meals)| {
+            let on_edit = on_edit.clone();
+            view! {
+              <section class="mb-8">
+                <h3 class="mb-4 text-xl font-semibold text-slate-900 dark:text-slate-100">{day_heading(&date)}</h3>
+                {meals
+                  .into_iter()
+                  .map(|(meal, entries)| {
+                    let on_edit = on_edit.clone();
+                    view! {
+                      <h4 class="mb-2 text-sm font-medium uppercase tracking-wide text-slate-500 dark:text-slate-400">
+                        {meal.label()}
+                      </h4>
+                      <div class="mb-6 grid gap-6 md:grid-cols-2 lg:grid-cols-3">
+                        {entries
+                          .into_iter()
+                          .map(|log| {
+                            view! { <FoodLogCard log=log on_edit=on_edit.clone() is_authenticated=is_authenticated /> }
+                          })
+                          .collect_view()}
+                      </div>
+                    }
+                  })
+                  .collect_view()}
+              </section>
+            }
+        })
+        .collect_view()
+}
+
 /// Main Food Log page component
 #[component]
 pub fn FoodLogs() -> impl IntoView {
@@ -1237,13 +2660,38 @@ pub fn FoodLogs() -> impl IntoView {
         }
     }
 
-    let logs_resource = Resource::new(|| (), |_| get_food_logs());
+    let filter = RwSignal::new(FoodLogFilter::default());
+    // Entries from the pages loaded after the first one, and where the next page starts
+    let more_logs = RwSignal::new(Vec::<FoodLog>::new());
+    let next_cursor = RwSignal::new(Option::<FoodLogCursor>::None);
+    let loading_more = RwSignal::new(false);
+    let recipe_options = RwSignal::new(Vec::<Recipe>::new());
+
+    let logs_resource = Resource::new(move || filter.get(), |filter| get_food_logs(filter, None));
     let recipes_resource = Resource::new(|| (), |_| get_recipes());
 
+    // A fresh first page replaces everything loaded after it
+    Effect::new(move || {
+        if let Some(Ok(page)) = logs_resource.get() {
+            more_logs.set(Vec::new());
+            next_cursor.set(page.next_cursor);
+        }
+    });
+
+    // Only the first unfiltered page is cached, so the cache stays small however long
+    // the history gets
     #[cfg(not(feature = "ssr"))]
     Effect::new(move || {
-        if let Some(Ok(logs)) = logs_resource.get() {
-            set_cache(FOOD_LOGS_CACHE_KEY, &logs);
+        if let Some(Ok(page)) = logs_resource.get() {
+            if filter.get_untracked().is_empty() {
+                set_cache(FOOD_LOGS_CACHE_KEY, &page.logs);
+            }
+        }
+    });
+
+    Effect::new(move || {
+        if let Some(Ok(recipes)) = recipes_resource.get() {
+            recipe_options.set(recipes);
         }
     });
 
@@ -1254,12 +2702,123 @@ pub fn FoodLogs() -> impl IntoView {
         }
     });
 
+    let load_more = move || {
+        let Some(cursor) = next_cursor.get_untracked() else {
+            return;
+        };
+        if loading_more.get_untracked() {
+            return;
+        }
+        loading_more.set(true);
+        let page_filter = filter.get_untracked();
+        wasm_bindgen_futures::spawn_local(async move {
+            let result = get_food_logs(page_filter.clone(), Some(cursor)).await;
+            // Drop the page if the filters changed while it was loading
+            if filter.get_untracked() == page_filter {
+                match result {
+                    Ok(page) => {
+                        more_logs.update(|logs| logs.extend(page.logs));
+                        next_cursor.set(page.next_cursor);
+                    }
+                    Err(e) => log::error!("Failed to load more food logs: {}", e),
+                }
+            }
+            loading_more.set(false);
+        });
+    };
+
+    // Load the next page as the bottom of the list scrolls into view
+    let sentinel = NodeRef::<leptos::html::Div>::new();
+    #[cfg(not(feature = "ssr"))]
+    Effect::new(move || {
+        let Some(element) = sentinel.get() else {
+            return;
+        };
+        let callback = wasm_bindgen::closure::Closure::wrap(Box::new(
+            move |entries: js_sys::Array, _: web_sys::IntersectionObserver| {
+                let visible = entries.iter().any(|entry| {
+                    entry
+                        .unchecked_into::<web_sys::IntersectionObserverEntry>()
+                        .is_intersecting()
+                });
+                if visible {
+                    load_more();
+                }
+            },
+        )
+            as Box<dyn FnMut(js_sys::Array, web_sys::IntersectionObserver)>);
+        let options = web_sys::IntersectionObserverInit::new();
+        options.set_root_margin("400px");
+        if let Ok(observer) = web_sys::IntersectionObserver::new_with_options(
+            callback.as_ref().unchecked_ref(),
+            &options,
+        ) {
+            observer.observe(&element);
+        }
+        callback.forget();
+    });
+
+    // Container label QR codes link to `/?log_recipe=<id>&portion=<servings>`, which opens
+    // a new entry for that recipe so eating a container is a single scan
+    let query = use_query_map();
+    let navigate = use_navigate();
+    Effect::new(move || {
+        let params = query.get();
+        let Some(recipe_id) = params.get("log_recipe").and_then(|v| v.parse::<i64>().ok()) else {
+            return;
+        };
+        let portion = params
+            .get("portion")
+            .and_then(|v| v.parse::<f32>().ok())
+            .filter(|p| *p > 0.0)
+            .unwrap_or(1.0);
+        let logged_at = local_now();
+        editing_log.set(Some(FoodLog {
+            recipe_id: Some(recipe_id),
+            portion,
+            meal_type: MealType::for_time(logged_at.get(11..16).unwrap_or_default()),
+            logged_at,
+            ..FoodLog::new_empty()
+        }));
+        show_modal.set(true);
+        navigate(
+            "/",
+            NavigateOptions {
+                replace: true,
+                ..Default::default()
+            },
+        );
+    });
+
+    // The calendar fetches its own entries, so tell it when they change
+    let show_calendar = RwSignal::new(false);
+    let calendar_reload = RwSignal::new(0_u32);
+
     let refetch = move || {
         logs_resource.refetch();
+        calendar_reload.update(|n| *n += 1);
     };
 
     let handle_delete = move |_id: i64| {
         logs_resource.refetch();
+        calendar_reload.update(|n| *n += 1);
+    };
+
+    let view_button = move |calendar: bool, label: &'static str| {
+        view! {
+          <button
+            class=move || {
+              if show_calendar.get() == calendar {
+                "px-3 py-1 bg-blue-600 text-white"
+              } else {
+                "px-3 py-1 bg-white dark:bg-slate-800 text-slate-700 dark:text-slate-300 hover:bg-slate-100 dark:hover:bg-slate-700"
+              }
+            }
+            on:click=move |_| show_calendar.set(calendar)
+          >
+            {label}
+          </button>
+        }
     };
 
     let handle_new = move |_| {
@@ -1276,64 +2835,168 @@ pub fn FoodLogs() -> impl IntoView {
       <div class="mx-auto max-w-7xl py-6">
         <div class="mb-6 flex items-center justify-between flex-wrap gap-4">
           <h2 class="text-3xl font-bold text-slate-900 dark:text-slate-100">"Food Log"</h2>
-          <Show when=move || auth.is_authenticated.get()>
-            <button
-              class="flex items-center gap-2 rounded bg-green-600 px-4 py-2 text-sm font-medium text-white hover:bg-green-700"
-              on:click=handle_new
-            >
-              <PlusIcon />
-              "Log Meal"
-            </button>
-          </Show>
+          <div class="flex items-center gap-3">
+            <div class="flex rounded border border-slate-300 dark:border-slate-600 overflow-hidden text-sm">
+              {view_button(false, "List")}
+              {view_button(true, "Calendar")}
+            </div>
+            <Show when=move || auth.is_authenticated.get()>
+              <button
+                class="flex items-center gap-2 rounded bg-green-600 px-4 py-2 text-sm font-medium text-white hover:bg-green-700"
+                on:click=handle_new
+              >
+                <PlusIcon />
+                "Log Meal"
+              </button>
+            </Show>
+          </div>
         </div>
 
-        <Suspense fallback=move || {
-          match (cached_logs.get(), cached_recipes.get()) {
-            (Some(logs), Some(recipes)) if !logs.is_empty() => {
-              let (recipes_signal, _) = signal(recipes);
-              let is_auth = auth.is_authenticated;
-              view! {
-                <div class="grid gap-6 md:grid-cols-2 lg:grid-cols-3">
-                  <For
-                    each=move || logs.clone()
-                    key=|log| log.id.unwrap_or(0)
-                    children=move |log: FoodLog| {
-                      let is_auth_signal = is_auth.read_only();
-                      view! { <FoodLogCard log=log on_edit=handle_edit is_authenticated=is_auth_signal /> }
-                    }
-                  />
-                </div>
-                <FoodLogModal
-                  show=show_modal
-                  editing=editing_log
-                  available_recipes=recipes_signal
-                  on_save=refetch
-                  on_delete=handle_delete
-                />
-              }
-                .into_any()
+        <Show
+          when=move || !show_calendar.get()
+          fallback=move || {
+            view! {
+              <FoodCalendar
+                recipes=recipe_options.read_only()
+                on_edit=handle_edit
+                is_authenticated=auth.is_authenticated.read_only()
+                reload=calendar_reload.read_only()
+              />
+              <FoodLogModal
+                show=show_modal
+                editing=editing_log
+                available_recipes=recipe_options.read_only()
+                on_save=refetch
+                on_delete=handle_delete
+              />
             }
-            _ => view! { <p class="text-slate-600 dark:text-slate-400">"Loading food log..."</p> }.into_any(),
           }
-        }>
-          {move || {
-            let logs_result = logs_resource.get();
-            let recipes_result = recipes_resource.get();
-            match (logs_result, recipes_result) {
-              (Some(Ok(logs)), Some(Ok(recipes))) => {
+        >
+          <div class="mb-6 grid grid-cols-2 gap-4 rounded-lg bg-white dark:bg-slate-800 p-4 shadow-sm md:grid-cols-4">
+            <div>
+              <label class=LABEL_CLASS>"From"</label>
+              <input
+                type="date"
+                class=INPUT_CLASS
+                prop:value=move || filter.get().from.unwrap_or_default()
+                on:input=move |ev| {
+                  let value = event_target_value(&ev);
+                  filter.update(|f| f.from = Some(value).filter(|v| !v.is_empty()));
+                }
+              />
+            </div>
+            <div>
+              <label class=LABEL_CLASS>"To"</label>
+              <input
+                type="date"
+                class=INPUT_CLASS
+                prop:value=move || filter.get().to.unwrap_or_default()
+                on:input=move |ev| {
+                  let value = event_target_value(&ev);
+                  filter.update(|f| f.to = Some(value).filter(|v| !v.is_empty()));
+                }
+              />
+            </div>
+            <div>
+              <label class=LABEL_CLASS>"Rating"</label>
+              <select
+                class=INPUT_CLASS
+                on:change=move |ev| {
+                  let value = event_target_value(&ev).parse().ok();
+                  filter.update(|f| f.min_rating = value);
+                }
+              >
+                <option value="">"Any"</option>
+                {(1..=5)
+                  .map(|stars| {
+                    view! { <option value=stars.to_string()>{format!("{}+ stars", stars)}</option> }
+                  })
+                  .collect_view()}
+              </select>
+            </div>
+            <div>
+              <label class=LABEL_CLASS>"Recipe"</label>
+              <select
+                class=INPUT_CLASS
+                on:change=move |ev| {
+                  let value = event_target_value(&ev).parse().ok();
+                  filter.update(|f| f.recipe_id = value);
+                }
+              >
+                <option value="">"Any"</option>
+                {move || {
+                  recipe_options
+                    .get()
+                    .into_iter()
+                    .filter_map(|recipe| {
+                      let id = recipe.id?;
+                      Some(view! { <option value=id.to_string()>{recipe.name}</option> })
+                    })
+                    .collect_view()
+                }}
+              </select>
+            </div>
+          </div>
+
+          <Suspense fallback=move || {
+            match (cached_logs.get(), cached_recipes.get()) {
+              (Some(logs), Some(recipes)) if !logs.is_empty() => {
                 let (recipes_signal, _) = signal(recipes);
                 let is_auth = auth.is_authenticated;
-                Some(
-                  if logs.is_empty() {
+                view! {
+                  <FoodLogDays logs=logs on_edit=handle_edit is_authenticated=is_auth.read_only() />
+                  <FoodLogModal
+                    show=show_modal
+                    editing=editing_log
+                    available_recipes=recipes_signal
+                    on_save=refetch
+                    on_delete=handle_delete
+                  />
+                }
+                  .into_any()
+              }
+              _ => view! { <p class="text-slate-600 dark:text-slate-400">"Loading food log..."</p> }.into_any(),
+            }
+          }>
+            {move || {
+              let logs_result = logs_resource.get();
+              let recipes_result = recipes_resource.get();
+              match (logs_result, recipes_result) {
+                (Some(Ok(page)), Some(Ok(recipes))) => {
+                  let (recipes_signal, _) = signal(recipes);
+                  let is_auth = auth.is_authenticated;
+                  let first_page = page.logs;
+                  Some(
                     view! {
-                      <div class="text-center py-12">
-                        <p class="text-slate-600 dark:text-slate-400 mb-4">"No food log entries yet."</p>
-                        <Show when=move || auth.is_authenticated.get()>
-                          <p class="text-slate-500 dark:text-slate-500 text-sm">
-                            "Click \"Log Meal\" to add your first entry."
-                          </p>
-                        </Show>
-                      </div>
+                      {move || {
+                        let mut logs = first_page.clone();
+                        logs.extend(more_logs.get());
+                        if logs.is_empty() {
+                          if filter.get_untracked().is_empty() {
+                            view! {
+                              <div class="text-center py-12">
+                                <p class="text-slate-600 dark:text-slate-400 mb-4">"No food log entries yet."</p>
+                                <Show when=move || auth.is_authenticated.get()>
+                                  <p class="text-slate-500 dark:text-slate-500 text-sm">
+                                    "Click \"Log Meal\" to add your first entry."
+                                  </p>
+                                </Show>
+                              </div>
+                            }
+                              .into_any()
+                          } else {
+                            view! {
+                              <p class="text-center py-12 text-slate-600 dark:text-slate-400">
+                                "No entries match these filters."
+                              </p>
+                            }
+                              .into_any()
+                          }
+                        } else {
+                          view! { <FoodLogDays logs=logs on_edit=handle_edit is_authenticated=is_auth.read_only() /> }
+                            .into_any()
+                        }
+                      }}
                       <FoodLogModal
                         show=show_modal
                         editing=editing_log
@@ -1342,46 +3005,37 @@ pub fn FoodLogs() -> impl IntoView {
                         on_delete=handle_delete
                       />
                     }
-                      .into_any()
-                  } else {
+                      .into_any(),
+                  )
+                }
+                (Some(Err(e)), _) | (_, Some(Err(e))) => {
+                  Some(
                     view! {
-                      <div class="grid gap-6 md:grid-cols-2 lg:grid-cols-3">
-                        <For
-                          each=move || logs.clone()
-                          key=|log| log.id.unwrap_or(0)
-                          children=move |log: FoodLog| {
-                            let is_auth_signal = is_auth.read_only();
-                            view! { <FoodLogCard log=log on_edit=handle_edit is_authenticated=is_auth_signal /> }
-                          }
-                        />
+                      <div class="rounded bg-red-100 px-4 py-3 text-red-700">
+                        <p class="font-medium">"Failed to load data"</p>
+                        <p class="text-sm">{e.to_string()}</p>
                       </div>
-                      <FoodLogModal
-                        show=show_modal
-                        editing=editing_log
-                        available_recipes=recipes_signal
-                        on_save=refetch
-                        on_delete=handle_delete
-                      />
                     }
-                      .into_any()
-                  },
-                )
-              }
-              (Some(Err(e)), _) | (_, Some(Err(e))) => {
-                Some(
-                  view! {
-                    <div class="rounded bg-red-100 px-4 py-3 text-red-700">
-                      <p class="font-medium">"Failed to load data"</p>
-                      <p class="text-sm">{e.to_string()}</p>
-                    </div>
-                  }
-                    .into_any(),
-                )
+                      .into_any(),
+                  )
+                }
+                _ => None,
               }
-              _ => None,
-            }
-          }}
-        </Suspense>
+            }}
+          </Suspense>
+
+          <div node_ref=sentinel class="py-4 text-center">
+            <Show when=move || next_cursor.get().is_some()>
+              <button
+                class="rounded bg-slate-200 dark:bg-slate-600 px-4 py-2 text-sm font-medium text-slate-700 dark:text-slate-200 hover:bg-slate-300 dark:hover:bg-slate-500 disabled:opacity-50"
+                disabled=move || loading_more.get()
+                on:click=move |_| load_more()
+              >
+                {move || if loading_more.get() { "Loading..." } else { "Load more" }}
+              </button>
+            </Show>
+          </div>
+        </Show>
       </div>
     }
 }
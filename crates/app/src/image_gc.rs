@@ -0,0 +1,211 @@
+//! Image garbage collection module
+//!
+//! Photos stay in the `FOOD_IMAGES` bucket when an upload is abandoned or a food log's
+//! photo is replaced. A daily cron run in the worker lists the bucket, keeps every object
+//! belonging to a photo some food log refers to (trashed logs included, since they can
+//! still be restored), and deletes the rest once they are older than
+//! [`GRACE_PERIOD_HOURS`]. Admins can fetch the same report as a dry run.
+
+use leptos::prelude::*;
+use serde::{Deserialize, Serialize};
+use server_fn::ServerFnError;
+
+// ============================================================================
+// Constants
+// ============================================================================
+
+/// How old an unreferenced object must be before it is deleted, so photos uploaded for a
+/// food log that hasn't been saved yet are left alone
+pub const GRACE_PERIOD_HOURS: u64 = 24;
+
+/// Most objects deleted in one run, to stay inside the worker's subrequest limit. The
+/// rest are deleted on later runs.
+#[cfg(feature = "ssr")]
+const MAX_DELETES_PER_RUN: usize = 500;
+
+// ============================================================================
+// Data Types
+// ============================================================================
+
+/// What an object in the image bucket is
+#[derive(Clone, Copy, Debug, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum ImageObjectKind {
+    /// An uploaded photo
+    Original,
+    /// A resized copy made by the image pipeline
+    Variant,
+    /// Part of an upload that was never completed
+    UploadPart,
+}
+
+impl ImageObjectKind {
+    /// The kind of the object stored under `key`, and the photo key it belongs to.
+    /// Upload parts belong to no photo.
+    pub fn classify(key: &str) -> (Self, Option<&str>) {
+        if let Some(rest) = key.strip_prefix("variants/") {
+            let owner = rest.split_once('/').map_or(rest, |(owner, _)| owner);
+            (ImageObjectKind::Variant, Some(owner))
+        } else if key.starts_with("uploads/") {
+            (ImageObjectKind::UploadPart, None)
+        } else {
+            (ImageObjectKind::Original, Some(key))
+        }
+    }
+}
+
+/// An object no food log needs
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+pub struct OrphanedImage {
+    pub key: String,
+    pub kind: ImageObjectKind,
+    pub size: u64,
+    pub age_hours: u64,
+}
+
+/// Outcome of a garbage collection run
+#[derive(Clone, Debug, Default, Serialize, Deserialize, PartialEq)]
+pub struct ImageGcReport {
+    /// Objects in the bucket
+    pub scanned: usize,
+    /// Unreferenced objects older than the grace period
+    pub orphans: Vec<OrphanedImage>,
+    /// Unreferenced objects still inside the grace period
+    pub recent: usize,
+    /// Orphans deleted; always zero for a dry run
+    pub deleted: usize,
+}
+
+impl ImageGcReport {
+    pub fn orphaned_bytes(&self) -> u64 {
+        self.orphans.iter().map(|orphan| orphan.size).sum()
+    }
+}
+
+// ============================================================================
+// Collection
+// ============================================================================
+
+/// Keys of every photo a food log refers to, including trashed logs
+#[cfg(feature = "ssr")]
+async fn referenced_image_keys(
+    db: &crate::ingredients::SendD1Database,
+) -> Result<std::collections::HashSet<String>, ServerFnError> {
+    use send_wrapper::SendWrapper;
+
+    let rows = SendWrapper::new(async {
+        let stmt = db
+            .inner()
+            .prepare("SELECT DISTINCT image_key FROM food_log_images");
+        stmt.all().await?.results::<serde_json::Value>()
+    })
+    .await
+    .map_err(|e| ServerFnError::new(format!("D1 query error: {:?}", e)))?;
+
+    Ok(rows
+        .iter()
+        .filter_map(|row| Some(row.get("image_key")?.as_str()?.to_string()))
+        .collect())
+}
+
+/// Find the objects in the image bucket that no food log needs and, unless `dry_run`,
+/// delete those older than the grace period
+#[cfg(feature = "ssr")]
+pub async fn collect_orphaned_images(
+    db: &crate::ingredients::SendD1Database,
+    bucket: &crate::food_log::SendR2Bucket,
+    dry_run: bool,
+) -> Result<ImageGcReport, ServerFnError> {
+    use send_wrapper::SendWrapper;
+
+    let referenced = referenced_image_keys(db).await?;
+
+    // List the whole bucket as (key, size, uploaded millis) in one block, since R2 objects
+    // can't be held across awaits in a server function
+    let (objects, now) = SendWrapper::new(async {
+        let mut objects = Vec::new();
+        let mut cursor: Option<String> = None;
+        loop {
+            let mut list = bucket.inner().list();
+            if let Some(cursor) = cursor.take() {
+                list = list.cursor(cursor);
+            }
+            let page = list.execute().await?;
+            objects.extend(
+                page.objects()
+                    .iter()
+                    .map(|object| (object.key(), object.size(), object.uploaded().as_millis())),
+            );
+            match page.cursor() {
+                Some(next) if page.truncated() => cursor = Some(next),
+                _ => break,
+            }
+        }
+        Ok::<_, worker::Error>((objects, worker::Date::now().as_millis()))
+    })
+    .await
+    .map_err(|e| ServerFnError::new(format!("R2 list error: {:?}", e)))?;
+
+    let mut report = ImageGcReport {
+        scanned: objects.len(),
+        ..Default::default()
+    };
+    for (key, size, uploaded) in objects {
+        let (kind, owner) = ImageObjectKind::classify(&key);
+        if owner.is_some_and(|owner| referenced.contains(owner)) {
+            continue;
+        }
+        let age_hours = now.saturating_sub(uploaded) / (60 * 60 * 1000);
+        if age_hours < GRACE_PERIOD_HOURS {
+            report.recent += 1;
+            continue;
+        }
+        report.orphans.push(OrphanedImage {
+            key,
+            kind,
+            size,
+            age_hours,
+        });
+    }
+
+    if !dry_run {
+        for orphan in report.orphans.iter().take(MAX_DELETES_PER_RUN) {
+            match SendWrapper::new(bucket.inner().delete(&orphan.key)).await {
+                Ok(()) => report.deleted += 1,
+                Err(e) => log::warn!("Failed to delete orphaned image {}: {:?}", orphan.key, e),
+            }
+        }
+    }
+
+    log::info!(
+        "Image GC{}: scanned {} objects, {} orphans ({} bytes), {} deleted, {} within the grace period",
+        if dry_run { " (dry run)" } else { "" },
+        report.scanned,
+        report.orphans.len(),
+        report.orphaned_bytes(),
+        report.deleted,
+        report.recent
+    );
+    Ok(report)
+}
+
+// ============================================================================
+// Server Functions
+// ============================================================================
+
+/// Report what the next garbage collection run would delete, without deleting anything
+/// (admin only). Served at `/api/image_gc_report`.
+#[server(endpoint = "image_gc_report")]
+pub async fn get_image_gc_report(
+    auth_token: Option<String>,
+) -> Result<ImageGcReport, ServerFnError> {
+    use crate::auth::require_admin;
+    use crate::food_log::SendR2Bucket;
+    use crate::ingredients::SendD1Database;
+
+    require_admin(auth_token.as_deref()).await?;
+
+    let db = expect_context::<SendD1Database>();
+    let bucket = expect_context::<SendR2Bucket>();
+    collect_orphaned_images(&db, &bucket, true).await
+}
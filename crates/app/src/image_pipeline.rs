@@ -0,0 +1,404 @@
+//! Image pipeline module
+//!
+//! Turns food photo uploads into the resized JPEG variants the app displays. The stored
+//! crop and rotation are applied here rather than with CSS: the photo is turned upright
+//! from its EXIF orientation, rotated by the crop's rotation, and each view's crop
+//! percentages are taken of the rotated photo. Variants live in R2 next to the original
+//! upload, under the fingerprint of the crop they were rendered with.
+
+use std::fmt;
+
+use crate::food_log::{CropView, FoodLogImage, ImageCrop};
+use crate::jpeg::{self, JpegError};
+use crate::png::{self, PngError};
+
+// ============================================================================
+// Constants
+// ============================================================================
+
+/// Largest photo the pipeline will decode: the app's default upload size of 2048 pixels
+/// on the long side, square. Bigger JPEGs are scaled down while decoding, and bigger PNGs
+/// keep only their original, since decoding them could exhaust the worker's 128 MB of
+/// memory.
+pub const MAX_PIXELS: usize = 2048 * 2048;
+
+/// JPEG quality for the generated variants
+const VARIANT_QUALITY: u8 = 82;
+
+// ============================================================================
+// Data Types
+// ============================================================================
+
+/// A resized copy of a food photo
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ImageVariant {
+    /// The crop, small enough for calendar cells
+    Thumb,
+    /// The crop, sized for food log cards
+    Card,
+    /// The crop, sized for a page showing a single photo
+    Detail,
+    /// The whole rotated photo
+    Full,
+}
+
+impl ImageVariant {
+    pub const ALL: [ImageVariant; 4] = [
+        ImageVariant::Thumb,
+        ImageVariant::Card,
+        ImageVariant::Detail,
+        ImageVariant::Full,
+    ];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ImageVariant::Thumb => "thumb",
+            ImageVariant::Card => "card",
+            ImageVariant::Detail => "detail",
+            ImageVariant::Full => "full",
+        }
+    }
+
+    pub fn parse(s: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|v| v.as_str() == s)
+    }
+
+    /// Longest side in pixels
+    fn max_side(&self) -> u32 {
+        match self {
+            ImageVariant::Thumb => 320,
+            ImageVariant::Card => 1000,
+            ImageVariant::Detail => 1200,
+            ImageVariant::Full => 2048,
+        }
+    }
+
+    /// The view whose crop the variant shows; the full variant isn't cropped
+    pub fn crop_view(&self) -> Option<CropView> {
+        match self {
+            ImageVariant::Thumb => Some(CropView::Thumb),
+            ImageVariant::Card => Some(CropView::Card),
+            ImageVariant::Detail => Some(CropView::Detail),
+            ImageVariant::Full => None,
+        }
+    }
+}
+
+/// R2 key of a variant of the image uploaded as `key`, rendered with the crop whose
+/// fingerprint is `crop`. Each crop gets its own keys, so variants still being rendered
+/// for an earlier crop can't overwrite those of the current one.
+pub fn variant_key(key: &str, crop: &str, variant: ImageVariant) -> String {
+    format!("variants/{}/{}/{}.jpg", key, crop, variant.as_str())
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum ImageError {
+    /// Not a JPEG or PNG
+    UnsupportedFormat,
+    Jpeg(JpegError),
+    Png(PngError),
+}
+
+impl fmt::Display for ImageError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ImageError::UnsupportedFormat => write!(f, "only JPEG and PNG photos are processed"),
+            ImageError::Jpeg(e) => e.fmt(f),
+            ImageError::Png(e) => e.fmt(f),
+        }
+    }
+}
+
+impl std::error::Error for ImageError {}
+
+impl From<JpegError> for ImageError {
+    fn from(e: JpegError) -> Self {
+        ImageError::Jpeg(e)
+    }
+}
+
+impl From<PngError> for ImageError {
+    fn from(e: PngError) -> Self {
+        ImageError::Png(e)
+    }
+}
+
+/// An 8-bit RGB image, row by row
+#[derive(Clone, Debug, PartialEq)]
+pub struct RgbImage {
+    pub width: u32,
+    pub height: u32,
+    pub pixels: Vec<u8>,
+}
+
+impl RgbImage {
+    pub fn new(width: u32, height: u32) -> Self {
+        RgbImage {
+            width,
+            height,
+            pixels: vec![0; width as usize * height as usize * 3],
+        }
+    }
+
+    fn offset(&self, x: u32, y: u32) -> usize {
+        (y as usize * self.width as usize + x as usize) * 3
+    }
+
+    pub fn get(&self, x: u32, y: u32) -> [u8; 3] {
+        let i = self.offset(x, y);
+        [self.pixels[i], self.pixels[i + 1], self.pixels[i + 2]]
+    }
+
+    pub fn set(&mut self, x: u32, y: u32, pixel: [u8; 3]) {
+        let i = self.offset(x, y);
+        self.pixels[i..i + 3].copy_from_slice(&pixel);
+    }
+
+    /// A `width`×`height` image whose pixel (x, y) is this image's pixel `source(x, y)`
+    fn remap(&self, width: u32, height: u32, source: impl Fn(u32, u32) -> (u32, u32)) -> Self {
+        let mut out = RgbImage::new(width, height);
+        for y in 0..height {
+            for x in 0..width {
+                let (sx, sy) = source(x, y);
+                out.set(x, y, self.get(sx, sy));
+            }
+        }
+        out
+    }
+
+    /// Rotate clockwise by a number of quarter turns
+    pub fn rotate_clockwise(self, quarter_turns: i32) -> Self {
+        let (w, h) = (self.width, self.height);
+        match quarter_turns.rem_euclid(4) {
+            0 => self,
+            1 => self.remap(h, w, |x, y| (y, h - 1 - x)),
+            2 => self.remap(w, h, |x, y| (w - 1 - x, h - 1 - y)),
+            _ => self.remap(h, w, |x, y| (w - 1 - y, x)),
+        }
+    }
+
+    pub fn flip_horizontal(self) -> Self {
+        let w = self.width;
+        self.remap(w, self.height, |x, y| (w - 1 - x, y))
+    }
+
+    /// Turn the image upright according to an EXIF orientation (1-8)
+    pub fn apply_orientation(self, orientation: u8) -> Self {
+        match orientation {
+            2 => self.flip_horizontal(),
+            3 => self.rotate_clockwise(2),
+            4 => self.rotate_clockwise(2).flip_horizontal(),
+            5 => self.rotate_clockwise(1).flip_horizontal(),
+            6 => self.rotate_clockwise(1),
+            7 => self.rotate_clockwise(3).flip_horizontal(),
+            8 => self.rotate_clockwise(3),
+            _ => self,
+        }
+    }
+
+    pub fn crop(&self, x: u32, y: u32, width: u32, height: u32) -> Self {
+        self.remap(width, height, |cx, cy| (x + cx, y + cy))
+    }
+
+    /// The part of the image inside `crop`, whose percentages are of this image's size.
+    /// The crop's rotation is not applied here.
+    pub fn crop_percent(&self, crop: &ImageCrop) -> Self {
+        let to_pixels = |percent: f32, size: u32| {
+            ((percent.clamp(0.0, 100.0) / 100.0 * size as f32).round() as u32).min(size)
+        };
+        let x = to_pixels(crop.x, self.width).min(self.width - 1);
+        let y = to_pixels(crop.y, self.height).min(self.height - 1);
+        let width = to_pixels(crop.width, self.width).clamp(1, self.width - x);
+        let height = to_pixels(crop.height, self.height).clamp(1, self.height - y);
+        self.crop(x, y, width, height)
+    }
+
+    /// Shrink so the longest side is at most `max_side`, keeping the aspect ratio.
+    /// Never enlarges.
+    pub fn resize_to_fit(&self, max_side: u32) -> Self {
+        let longest = self.width.max(self.height);
+        if longest <= max_side {
+            return self.clone();
+        }
+        let scale = max_side as f64 / longest as f64;
+        let width = ((self.width as f64 * scale).round() as u32).max(1);
+        let height = ((self.height as f64 * scale).round() as u32).max(1);
+        self.downscale(width, height)
+    }
+
+    /// Area-averaging downscale, one axis at a time
+    fn downscale(&self, width: u32, height: u32) -> Self {
+        let columns = box_ranges(self.width, width);
+        let mut narrow = RgbImage::new(width, self.height);
+        for y in 0..self.height {
+            for (x, &(start, end)) in columns.iter().enumerate() {
+                narrow.set(x as u32, y, average((start..end).map(|sx| self.get(sx, y))));
+            }
+        }
+
+        let rows = box_ranges(self.height, height);
+        let mut out = RgbImage::new(width, height);
+        for (y, &(start, end)) in rows.iter().enumerate() {
+            for x in 0..width {
+                out.set(
+                    x,
+                    y as u32,
+                    average((start..end).map(|sy| narrow.get(x, sy))),
+                );
+            }
+        }
+        out
+    }
+}
+
+/// The source pixels `[start, end)` each of `to` output pixels covers
+fn box_ranges(from: u32, to: u32) -> Vec<(u32, u32)> {
+    (0..to as u64)
+        .map(|i| {
+            let start = (i * from as u64 / to as u64) as u32;
+            let end = ((i + 1) * from as u64 / to as u64) as u32;
+            (start, end.max(start + 1))
+        })
+        .collect()
+}
+
+fn average(pixels: impl Iterator<Item = [u8; 3]>) -> [u8; 3] {
+    let mut sum = [0u32; 3];
+    let mut count = 0u32;
+    for pixel in pixels {
+        for (total, value) in sum.iter_mut().zip(pixel) {
+            *total += value as u32;
+        }
+        count += 1;
+    }
+    sum.map(|total| ((total + count / 2) / count.max(1)) as u8)
+}
+
+// ============================================================================
+// Pipeline
+// ============================================================================
+
+/// Decode a JPEG or PNG upload, turned upright from any EXIF orientation
+pub fn decode_image(data: &[u8]) -> Result<RgbImage, ImageError> {
+    if data.starts_with(&[0xFF, 0xD8]) {
+        let decoded = jpeg::decode(data)?;
+        Ok(decoded.image.apply_orientation(decoded.orientation))
+    } else if data.starts_with(b"\x89PNG") {
+        Ok(png::decode(data)?)
+    } else {
+        Err(ImageError::UnsupportedFormat)
+    }
+}
+
+/// Encode every variant of an upright photo as JPEG, each cropped for its view
+pub fn render_variants(image: RgbImage, photo: &FoodLogImage) -> Vec<(ImageVariant, Vec<u8>)> {
+    let rotated = image.rotate_clockwise(photo.crop.rotation / 90);
+    ImageVariant::ALL
+        .into_iter()
+        .map(|variant| {
+            let resized = match variant.crop_view() {
+                Some(view) => rotated
+                    .crop_percent(&photo.crop_for(view))
+                    .resize_to_fit(variant.max_side()),
+                None => rotated.resize_to_fit(variant.max_side()),
+            };
+            (variant, jpeg::encode(&resized, VARIANT_QUALITY))
+        })
+        .collect()
+}
+
+/// Generate the variants of a photo and store them in R2
+#[cfg(feature = "ssr")]
+pub async fn generate_variants(
+    bucket: &crate::food_log::SendR2Bucket,
+    photo: &FoodLogImage,
+) -> Result<(), server_fn::ServerFnError> {
+    use send_wrapper::SendWrapper;
+    use server_fn::ServerFnError;
+
+    let key = &photo.image_key;
+
+    let original = SendWrapper::new(async {
+        let object = bucket
+            .inner()
+            .get(key)
+            .execute()
+            .await?
+            .ok_or_else(|| worker::Error::JsError("Image not found".to_string()))?;
+        object
+            .body()
+            .ok_or_else(|| worker::Error::JsError("No body".to_string()))?
+            .bytes()
+            .await
+    })
+    .await
+    .map_err(|e| ServerFnError::new(format!("R2 get error: {:?}", e)))?;
+
+    let image = decode_image(&original)
+        .map_err(|e| ServerFnError::new(format!("Failed to decode image {}: {}", key, e)))?;
+    drop(original);
+
+    for (variant, data) in render_variants(image, photo) {
+        let variant_key = variant_key(key, &photo.variant_crop(variant).fingerprint(), variant);
+        SendWrapper::new(async {
+            bucket
+                .inner()
+                .put(&variant_key, data)
+                .http_metadata(worker::HttpMetadata {
+                    content_type: Some("image/jpeg".to_string()),
+                    ..Default::default()
+                })
+                .execute()
+                .await
+        })
+        .await
+        .map_err(|e| ServerFnError::new(format!("R2 upload error: {:?}", e)))?;
+    }
+
+    log::info!("Generated image variants: key={}", key);
+    Ok(())
+}
+
+/// Remove the variants of the photo uploaded as `key`, except those rendered with a crop
+/// whose fingerprint is in `keep`
+#[cfg(feature = "ssr")]
+pub async fn delete_variants(bucket: &crate::food_log::SendR2Bucket, key: &str, keep: &[String]) {
+    use send_wrapper::SendWrapper;
+
+    let prefix = format!("variants/{}/", key);
+    let listed = SendWrapper::new(async {
+        let page = bucket
+            .inner()
+            .list()
+            .prefix(prefix.clone())
+            .execute()
+            .await?;
+        Ok::<_, worker::Error>(
+            page.objects()
+                .iter()
+                .map(|object| object.key())
+                .collect::<Vec<_>>(),
+        )
+    })
+    .await;
+    let variant_keys = match listed {
+        Ok(keys) => keys,
+        Err(e) => {
+            log::warn!("Failed to list variants of image {}: {:?}", key, e);
+            return;
+        }
+    };
+
+    for variant_key in variant_keys {
+        let crop = variant_key[prefix.len()..]
+            .split('/')
+            .next()
+            .unwrap_or_default();
+        if keep.iter().any(|kept| kept == crop) {
+            continue;
+        }
+        if let Err(e) = SendWrapper::new(bucket.inner().delete(&variant_key)).await {
+            log::warn!("Failed to delete image variant {}: {:?}", variant_key, e);
+        }
+    }
+}
@@ -0,0 +1,167 @@
+//! Image signing module
+//!
+//! Food photos are only served from URLs the server has signed. A signature is an
+//! HMAC-SHA256 over the image key and an expiry time, keyed with the worker's
+//! `IMAGE_SIGNING_KEY` secret, and it covers every display size of the image. Expiry
+//! times are rounded up to whole days so an image keeps the same URL, and stays in the
+//! browser's cache, for a day at a time.
+//!
+//! Every food log entry sent to the client carries signed URLs for its photos, so the
+//! photos are exactly as visible as the food log itself. Signing keeps image keys from
+//! being used as permanent links, not photos from the log's readers.
+
+#[cfg(feature = "ssr")]
+use hmac::Mac;
+
+// ============================================================================
+// Constants
+// ============================================================================
+
+/// Shortest time a signed image URL stays valid
+pub const IMAGE_URL_LIFETIME_SECS: u64 = 7 * 24 * 60 * 60;
+
+/// Expiry times are rounded up to a multiple of this
+#[cfg(feature = "ssr")]
+const EXPIRY_GRANULARITY_SECS: u64 = 24 * 60 * 60;
+
+#[cfg(feature = "ssr")]
+type HmacSha256 = hmac::Hmac<sha2::Sha256>;
+
+// ============================================================================
+// Signing
+// ============================================================================
+
+/// Signs and verifies image URLs with the worker's signing key
+#[cfg(feature = "ssr")]
+#[derive(Clone)]
+pub struct ImageSigner {
+    key: Vec<u8>,
+}
+
+#[cfg(feature = "ssr")]
+impl ImageSigner {
+    pub fn new(secret: &str) -> Self {
+        Self {
+            key: secret.as_bytes().to_vec(),
+        }
+    }
+
+    /// Query string (`expires=..&sig=..`) granting access to the image under `image_key`
+    /// until at least `IMAGE_URL_LIFETIME_SECS` after `now_secs`
+    pub fn sign(&self, image_key: &str, now_secs: u64) -> String {
+        let expires = (now_secs + IMAGE_URL_LIFETIME_SECS).div_ceil(EXPIRY_GRANULARITY_SECS)
+            * EXPIRY_GRANULARITY_SECS;
+        format!(
+            "expires={}&sig={}",
+            expires,
+            hex::encode(self.mac(image_key, expires).finalize().into_bytes())
+        )
+    }
+
+    /// The expiry time of the signature in the query string of a request for `image_key`,
+    /// if it is valid and hasn't expired. Nothing verifies without a signing key configured.
+    pub fn verify(&self, image_key: &str, query: &str, now_secs: u64) -> Option<u64> {
+        if self.key.is_empty() {
+            return None;
+        }
+        let param = |name: &str| {
+            query
+                .split('&')
+                .find_map(|pair| pair.strip_prefix(name)?.strip_prefix('='))
+        };
+        let expires = param("expires")?.parse::<u64>().ok()?;
+        let sig = hex::decode(param("sig")?).ok()?;
+        // Compared in constant time, so the signature can't be guessed byte by byte
+        self.mac(image_key, expires).verify_slice(&sig).ok()?;
+        (now_secs < expires).then_some(expires)
+    }
+
+    /// HMAC-SHA256 of the image key and expiry time, ready to finalize or verify
+    fn mac(&self, image_key: &str, expires: u64) -> HmacSha256 {
+        let mut mac =
+            HmacSha256::new_from_slice(&self.key).expect("HMAC accepts keys of any length");
+        mac.update(format!("{}:{}", image_key, expires).as_bytes());
+        mac
+    }
+}
+
+#[cfg(all(test, feature = "ssr"))]
+mod tests {
+    use super::*;
+
+    /// HMAC-SHA256 test cases 1, 2 and 6 from RFC 4231: a short key, a key shorter than
+    /// the output, and a key longer than the block size
+    #[test]
+    fn hmac_sha256_matches_rfc_4231() {
+        let cases: [(&[u8], &[u8], &str); 3] = [
+            (
+                &[0x0b; 20],
+                b"Hi There",
+                "b0344c61d8db38535ca8afceaf0bf12b881dc200c9833da726e9376c2e32cff7",
+            ),
+            (
+                b"Jefe",
+                b"what do ya want for nothing?",
+                "5bdcc146bf60754e6a042426089575c75a003f089d2739839dec58b964ec3843",
+            ),
+            (
+                &[0xaa; 131],
+                b"Test Using Larger Than Block-Size Key - Hash Key First",
+                "60e431591ee0b67f0d8a26aacbf5b77f8e0bc6213728c5140546040f0ee37f54",
+            ),
+        ];
+        for (key, message, expected) in cases {
+            let mut mac = HmacSha256::new_from_slice(key).unwrap();
+            mac.update(message);
+            assert_eq!(hex::encode(mac.finalize().into_bytes()), expected);
+        }
+    }
+
+    #[test]
+    fn signed_queries_verify_until_they_expire() {
+        let signer = ImageSigner::new("secret");
+        let now = 1_700_000_000;
+        let query = signer.sign("photo.jpg", now);
+        let expires: u64 = query
+            .strip_prefix("expires=")
+            .and_then(|rest| rest.split('&').next())
+            .unwrap()
+            .parse()
+            .unwrap();
+        assert!(expires >= now + IMAGE_URL_LIFETIME_SECS);
+        assert_eq!(expires % EXPIRY_GRANULARITY_SECS, 0);
+
+        assert_eq!(signer.verify("photo.jpg", &query, now), Some(expires));
+        assert_eq!(
+            signer.verify("photo.jpg", &format!("size=thumb&{}", query), now),
+            Some(expires)
+        );
+        assert_eq!(signer.verify("photo.jpg", &query, expires), None);
+    }
+
+    #[test]
+    fn rejects_tampered_or_foreign_signatures() {
+        let signer = ImageSigner::new("secret");
+        let now = 1_700_000_000;
+        let query = signer.sign("photo.jpg", now);
+
+        assert!(signer.verify("other.jpg", &query, now).is_none());
+        assert!(ImageSigner::new("another secret")
+            .verify("photo.jpg", &query, now)
+            .is_none());
+        let later = query.replacen("expires=", "expires=9", 1);
+        assert!(signer.verify("photo.jpg", &later, now).is_none());
+        let truncated = &query[..query.len() - 2];
+        assert!(signer.verify("photo.jpg", truncated, now).is_none());
+        assert!(signer
+            .verify("photo.jpg", "expires=9999999999", now)
+            .is_none());
+    }
+
+    #[test]
+    fn nothing_verifies_without_a_key() {
+        let signer = ImageSigner::new("");
+        let query = signer.sign("photo.jpg", 0);
+        assert!(signer.verify("photo.jpg", &query, 0).is_none());
+    }
+}
@@ -0,0 +1,744 @@
+//! Image upload module
+//!
+//! Checks food photos before they reach R2. The format comes from the file's magic bytes
+//! rather than the type the browser reports, uploads over the size limit are refused,
+//! photos taken sideways are turned upright from their EXIF orientation, and EXIF
+//! (including GPS position), XMP and comment metadata is removed.
+//!
+//! Before uploading, the browser redraws the photo on a canvas no larger than the
+//! [`PhotoSettings`] allow, optionally turned by the crop's rotation. Photos are sent as
+//! raw bytes in parts of [`UPLOAD_PART_BYTES`] rather than as base64 in a server function,
+//! which keeps each request small and lets an interrupted upload resume. Parts wait in R2
+//! under `uploads/{upload_id}/` until the upload is completed.
+
+use std::fmt;
+
+use serde::{Deserialize, Serialize};
+
+use crate::jpeg::{self, JpegError};
+use crate::png::{self, PngError};
+
+// ============================================================================
+// Constants
+// ============================================================================
+
+/// Largest photo that can be uploaded
+pub const MAX_UPLOAD_BYTES: usize = 10 * 1024 * 1024;
+
+/// JPEG quality when a photo has to be re-encoded to turn it upright
+const UPRIGHT_QUALITY: u8 = 90;
+
+/// Size of each part of an upload; the last part may be shorter
+pub const UPLOAD_PART_BYTES: usize = 512 * 1024;
+
+/// Most parts an upload of [`MAX_UPLOAD_BYTES`] can need
+pub const MAX_UPLOAD_PARTS: u32 = MAX_UPLOAD_BYTES.div_ceil(UPLOAD_PART_BYTES) as u32;
+
+/// Attempts at sending a part before the upload is given up
+const PART_ATTEMPTS: u32 = 4;
+
+// ============================================================================
+// Data Types
+// ============================================================================
+
+/// A photo format accepted for upload
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ImageFormat {
+    Jpeg,
+    Png,
+    WebP,
+    Gif,
+}
+
+impl ImageFormat {
+    pub const ALL: [ImageFormat; 4] = [
+        ImageFormat::Jpeg,
+        ImageFormat::Png,
+        ImageFormat::WebP,
+        ImageFormat::Gif,
+    ];
+
+    /// The format the data's magic bytes identify
+    pub fn sniff(data: &[u8]) -> Option<Self> {
+        if data.starts_with(&[0xFF, 0xD8, 0xFF]) {
+            Some(ImageFormat::Jpeg)
+        } else if data.starts_with(b"\x89PNG\r\n\x1a\n") {
+            Some(ImageFormat::Png)
+        } else if data.starts_with(b"RIFF") && data.get(8..12) == Some(b"WEBP") {
+            Some(ImageFormat::WebP)
+        } else if data.starts_with(b"GIF87a") || data.starts_with(b"GIF89a") {
+            Some(ImageFormat::Gif)
+        } else {
+            None
+        }
+    }
+
+    pub fn content_type(&self) -> &'static str {
+        match self {
+            ImageFormat::Jpeg => "image/jpeg",
+            ImageFormat::Png => "image/png",
+            ImageFormat::WebP => "image/webp",
+            ImageFormat::Gif => "image/gif",
+        }
+    }
+
+    pub fn from_content_type(content_type: &str) -> Option<Self> {
+        let content_type = match content_type {
+            "image/jpg" | "image/pjpeg" => "image/jpeg",
+            other => other,
+        };
+        Self::ALL
+            .into_iter()
+            .find(|f| f.content_type() == content_type)
+    }
+}
+
+/// Format photos are re-encoded to in the browser before upload
+#[derive(Clone, Copy, Debug, Default, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum PhotoFormat {
+    /// Works everywhere, and the server makes the display sizes from it
+    #[default]
+    Jpeg,
+    /// Smaller, but the server can't resize it so it is always shown full size
+    WebP,
+}
+
+impl PhotoFormat {
+    pub const ALL: [PhotoFormat; 2] = [PhotoFormat::Jpeg, PhotoFormat::WebP];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            PhotoFormat::Jpeg => "jpeg",
+            PhotoFormat::WebP => "webp",
+        }
+    }
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            PhotoFormat::Jpeg => "JPEG",
+            PhotoFormat::WebP => "WebP",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|f| f.as_str() == value)
+    }
+
+    pub fn content_type(&self) -> &'static str {
+        match self {
+            PhotoFormat::Jpeg => ImageFormat::Jpeg.content_type(),
+            PhotoFormat::WebP => ImageFormat::WebP.content_type(),
+        }
+    }
+}
+
+/// How photos are shrunk in the browser before upload, kept with the other settings
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+#[serde(default)]
+pub struct PhotoSettings {
+    /// Longest side in pixels
+    pub max_side: u32,
+    /// Encoder quality, 1-100
+    pub quality: u8,
+    pub format: PhotoFormat,
+    /// Turn the photo by the crop's rotation before upload, rather than storing the rotation
+    pub bake_rotation: bool,
+}
+
+impl Default for PhotoSettings {
+    fn default() -> Self {
+        Self {
+            max_side: 2048,
+            quality: 85,
+            format: PhotoFormat::Jpeg,
+            bake_rotation: true,
+        }
+    }
+}
+
+/// Why an upload was refused, worded for the person uploading
+#[derive(Clone, Debug, PartialEq)]
+pub enum UploadError {
+    TooLarge {
+        bytes: usize,
+    },
+    UnsupportedFormat,
+    Invalid(String),
+    /// Some parts of the upload never arrived
+    Incomplete,
+    /// R2 failed; the detail is logged rather than shown
+    Storage(String),
+}
+
+impl fmt::Display for UploadError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            UploadError::TooLarge { bytes } => write!(
+                f,
+                "The photo is {:.1} MB, over the {} MB limit",
+                *bytes as f64 / (1024.0 * 1024.0),
+                MAX_UPLOAD_BYTES / (1024 * 1024)
+            ),
+            UploadError::UnsupportedFormat => {
+                write!(f, "Only JPEG, PNG, WebP and GIF photos can be uploaded")
+            }
+            UploadError::Invalid(reason) => {
+                write!(
+                    f,
+                    "The photo couldn't be read, it may be damaged ({})",
+                    reason
+                )
+            }
+            UploadError::Incomplete => {
+                write!(f, "Part of the photo didn't arrive, please try again")
+            }
+            UploadError::Storage(_) => write!(f, "The photo couldn't be stored, please try again"),
+        }
+    }
+}
+
+impl std::error::Error for UploadError {}
+
+impl From<JpegError> for UploadError {
+    fn from(e: JpegError) -> Self {
+        UploadError::Invalid(e.to_string())
+    }
+}
+
+impl From<PngError> for UploadError {
+    fn from(e: PngError) -> Self {
+        UploadError::Invalid(e.to_string())
+    }
+}
+
+// ============================================================================
+// Validation
+// ============================================================================
+
+pub fn check_upload_size(bytes: usize) -> Result<(), UploadError> {
+    if bytes > MAX_UPLOAD_BYTES {
+        return Err(UploadError::TooLarge { bytes });
+    }
+    Ok(())
+}
+
+/// Validate an upload and return it upright and without metadata, along with the format
+/// it turned out to be
+pub fn normalize_upload(data: &[u8]) -> Result<(Vec<u8>, ImageFormat), UploadError> {
+    check_upload_size(data.len())?;
+    let format = ImageFormat::sniff(data).ok_or(UploadError::UnsupportedFormat)?;
+    match format {
+        ImageFormat::Jpeg => normalize_jpeg(data),
+        ImageFormat::Png => normalize_png(data),
+        ImageFormat::WebP => Ok((strip_webp_metadata(data)?, format)),
+        ImageFormat::Gif => Ok((strip_gif_metadata(data)?, format)),
+    }
+}
+
+fn normalize_jpeg(data: &[u8]) -> Result<(Vec<u8>, ImageFormat), UploadError> {
+    let orientation = jpeg::read_orientation(data);
+    if orientation != 1 {
+        if let Ok(decoded) = jpeg::decode(data) {
+            let upright = decoded.image.apply_orientation(orientation);
+            return Ok((jpeg::encode(&upright, UPRIGHT_QUALITY), ImageFormat::Jpeg));
+        }
+        // One that can't be decoded keeps just the orientation tag for browsers to apply
+    }
+    Ok((jpeg::strip_metadata(data, orientation)?, ImageFormat::Jpeg))
+}
+
+fn normalize_png(data: &[u8]) -> Result<(Vec<u8>, ImageFormat), UploadError> {
+    let stripped = png::strip_metadata(data)?;
+    let orientation = png::read_orientation(data);
+    if orientation != 1 {
+        // A turned PNG is almost always a screenshot of a photo, so it becomes a JPEG
+        if let Ok(image) = png::decode(data) {
+            let upright = image.apply_orientation(orientation);
+            return Ok((jpeg::encode(&upright, UPRIGHT_QUALITY), ImageFormat::Jpeg));
+        }
+    }
+    Ok((stripped, ImageFormat::Png))
+}
+
+/// Copy a WebP without its EXIF and XMP chunks, clearing their flags in the extended header
+fn strip_webp_metadata(data: &[u8]) -> Result<Vec<u8>, UploadError> {
+    let invalid = |reason: &str| UploadError::Invalid(format!("invalid WebP: {}", reason));
+    let riff_size = data
+        .get(4..8)
+        .map(|b| u32::from_le_bytes([b[0], b[1], b[2], b[3]]) as usize)
+        .ok_or_else(|| invalid("truncated"))?;
+    let body = data
+        .get(12..8 + riff_size)
+        .ok_or_else(|| invalid("truncated"))?;
+
+    let mut chunks = Vec::with_capacity(body.len());
+    let mut has_image = false;
+    let mut pos = 0;
+    while pos < body.len() {
+        let header = body
+            .get(pos..pos + 8)
+            .ok_or_else(|| invalid("truncated chunk"))?;
+        let kind = &header[..4];
+        let size = u32::from_le_bytes([header[4], header[5], header[6], header[7]]) as usize;
+        // Chunks are padded to an even length
+        let end = (pos + 8 + size + (size & 1)).min(body.len());
+        let chunk = body
+            .get(pos..end)
+            .filter(|chunk| chunk.len() >= 8 + size)
+            .ok_or_else(|| invalid("truncated chunk"))?;
+        match kind {
+            b"EXIF" | b"XMP " => {}
+            b"VP8X" => {
+                let mut chunk = chunk.to_vec();
+                let flags = chunk.get_mut(8).ok_or_else(|| invalid("short header"))?;
+                *flags &= !(0x08 | 0x04);
+                chunks.extend_from_slice(&chunk);
+            }
+            _ => {
+                has_image |= matches!(kind, b"VP8 " | b"VP8L" | b"ANMF");
+                chunks.extend_from_slice(chunk);
+            }
+        }
+        pos = end;
+    }
+    if !has_image {
+        return Err(invalid("no image data"));
+    }
+
+    let mut out = Vec::with_capacity(chunks.len() + 12);
+    out.extend_from_slice(b"RIFF");
+    out.extend_from_slice(&(chunks.len() as u32 + 4).to_le_bytes());
+    out.extend_from_slice(b"WEBP");
+    out.extend_from_slice(&chunks);
+    Ok(out)
+}
+
+/// Copy a GIF without its comment and application extensions, except the looping ones
+/// animations need
+fn strip_gif_metadata(data: &[u8]) -> Result<Vec<u8>, UploadError> {
+    let invalid = || UploadError::Invalid("invalid GIF: truncated".to_string());
+    // Skip a run of data sub-blocks, returning the position after the terminator
+    let skip_sub_blocks = |mut pos: usize| -> Result<usize, UploadError> {
+        loop {
+            let size = *data.get(pos).ok_or_else(invalid)? as usize;
+            pos += 1 + size;
+            if size == 0 {
+                return Ok(pos);
+            }
+        }
+    };
+    let colour_table_size = |packed: u8| {
+        if packed & 0x80 != 0 {
+            3 << ((packed & 0x07) + 1)
+        } else {
+            0
+        }
+    };
+
+    let packed = *data.get(10).ok_or_else(invalid)?;
+    let mut pos = 13 + colour_table_size(packed);
+    let mut out = data.get(..pos).ok_or_else(invalid)?.to_vec();
+    loop {
+        let start = pos;
+        match *data.get(pos).ok_or_else(invalid)? {
+            // Trailer
+            0x3B => {
+                out.push(0x3B);
+                return Ok(out);
+            }
+            // Image descriptor, local colour table, LZW code size and image data
+            0x2C => {
+                let packed = *data.get(pos + 9).ok_or_else(invalid)?;
+                pos = skip_sub_blocks(pos + 10 + colour_table_size(packed) + 1)?;
+                out.extend_from_slice(data.get(start..pos).ok_or_else(invalid)?);
+            }
+            0x21 => {
+                let label = *data.get(pos + 1).ok_or_else(invalid)?;
+                pos = skip_sub_blocks(pos + 2)?;
+                let keep = match label {
+                    0xFE => false,
+                    0xFF => matches!(
+                        data.get(start + 3..start + 14),
+                        Some(b"NETSCAPE2.0") | Some(b"ANIMEXTS1.0")
+                    ),
+                    _ => true,
+                };
+                if keep {
+                    out.extend_from_slice(data.get(start..pos).ok_or_else(invalid)?);
+                }
+            }
+            _ => {
+                return Err(UploadError::Invalid(
+                    "invalid GIF: unknown block".to_string(),
+                ))
+            }
+        }
+    }
+}
+
+// ============================================================================
+// Upload Storage
+// ============================================================================
+
+/// Whether `id` looks like an upload id from [`random_key`]
+#[cfg(feature = "ssr")]
+fn is_upload_id(id: &str) -> bool {
+    id.len() == 32 && id.bytes().all(|b| matches!(b, b'0'..=b'9' | b'a'..=b'f'))
+}
+
+/// A random 128-bit key in hex, used for upload ids and for stored photos
+pub fn random_key() -> String {
+    let mut bytes = [0u8; 16];
+    getrandom::fill(&mut bytes).expect("Failed to generate random bytes");
+    hex::encode(bytes)
+}
+
+#[cfg(feature = "ssr")]
+fn upload_prefix(upload_id: &str) -> String {
+    format!("uploads/{}/", upload_id)
+}
+
+/// R2 key of one part of an upload
+#[cfg(feature = "ssr")]
+fn upload_part_key(upload_id: &str, part: u32) -> String {
+    format!("{}{:04}", upload_prefix(upload_id), part)
+}
+
+#[cfg(feature = "ssr")]
+fn check_part(upload_id: &str, part: u32) -> Result<(), UploadError> {
+    if !is_upload_id(upload_id) || part >= MAX_UPLOAD_PARTS {
+        return Err(UploadError::Invalid("unknown upload part".to_string()));
+    }
+    Ok(())
+}
+
+/// Store one part of an upload, replacing any earlier copy of it
+#[cfg(feature = "ssr")]
+pub async fn store_upload_part(
+    bucket: &crate::food_log::SendR2Bucket,
+    upload_id: &str,
+    part: u32,
+    data: Vec<u8>,
+) -> Result<(), UploadError> {
+    use send_wrapper::SendWrapper;
+
+    check_part(upload_id, part)?;
+    if data.is_empty() || data.len() > UPLOAD_PART_BYTES {
+        return Err(UploadError::Invalid(format!(
+            "upload parts must be 1 to {} bytes",
+            UPLOAD_PART_BYTES
+        )));
+    }
+
+    let key = upload_part_key(upload_id, part);
+    SendWrapper::new(bucket.inner().put(&key, data).execute())
+        .await
+        .map_err(|e| UploadError::Storage(format!("R2 upload error: {:?}", e)))?;
+    Ok(())
+}
+
+/// The parts of an upload that have been stored so far, in order
+#[cfg(feature = "ssr")]
+pub async fn uploaded_parts(
+    bucket: &crate::food_log::SendR2Bucket,
+    upload_id: &str,
+) -> Result<Vec<u32>, UploadError> {
+    use send_wrapper::SendWrapper;
+
+    if !is_upload_id(upload_id) {
+        return Err(UploadError::Invalid("unknown upload".to_string()));
+    }
+    let prefix = upload_prefix(upload_id);
+    let objects = SendWrapper::new(bucket.inner().list().prefix(prefix.clone()).execute())
+        .await
+        .map_err(|e| UploadError::Storage(format!("R2 list error: {:?}", e)))?;
+
+    let mut parts: Vec<u32> = objects
+        .objects()
+        .iter()
+        .filter_map(|object| object.key().strip_prefix(&prefix)?.parse().ok())
+        .collect();
+    parts.sort_unstable();
+    Ok(parts)
+}
+
+/// Join the `parts` of an upload, check and clean the photo, and store it under a new
+/// key, which is returned. The parts are removed once the photo is stored.
+#[cfg(feature = "ssr")]
+pub async fn complete_upload(
+    bucket: &crate::food_log::SendR2Bucket,
+    upload_id: &str,
+    parts: u32,
+) -> Result<String, UploadError> {
+    use send_wrapper::SendWrapper;
+
+    if parts == 0 {
+        return Err(UploadError::Invalid("empty upload".to_string()));
+    }
+    check_part(upload_id, parts - 1)?;
+
+    let mut data = Vec::new();
+    for part in 0..parts {
+        let key = upload_part_key(upload_id, part);
+        let bytes = SendWrapper::new(async {
+            match bucket.inner().get(&key).execute().await? {
+                Some(object) => match object.body() {
+                    Some(body) => body.bytes().await.map(Some),
+                    None => Ok(None),
+                },
+                None => Ok(None),
+            }
+        })
+        .await
+        .map_err(|e: worker::Error| UploadError::Storage(format!("R2 get error: {:?}", e)))?
+        .ok_or(UploadError::Incomplete)?;
+        data.extend_from_slice(&bytes);
+        check_upload_size(data.len())?;
+    }
+
+    let (data, format) = normalize_upload(&data)?;
+
+    let key = random_key();
+    SendWrapper::new(async {
+        bucket
+            .inner()
+            .put(&key, data)
+            .http_metadata(worker::HttpMetadata {
+                content_type: Some(format.content_type().to_string()),
+                ..Default::default()
+            })
+            .execute()
+            .await
+    })
+    .await
+    .map_err(|e| UploadError::Storage(format!("R2 upload error: {:?}", e)))?;
+
+    for part in 0..parts {
+        let part_key = upload_part_key(upload_id, part);
+        if let Err(e) = SendWrapper::new(bucket.inner().delete(&part_key)).await {
+            log::warn!("Failed to delete upload part {}: {:?}", part_key, e);
+        }
+    }
+
+    log::info!("Uploaded image to R2: key={}", key);
+    Ok(key)
+}
+
+// ============================================================================
+// Client Preparation
+// ============================================================================
+
+/// Redraw the photo at `src` (a data or object URL) no larger than `settings.max_side`,
+/// turned `rotation` degrees clockwise, and encode it in the configured format.
+/// Browsers apply the EXIF orientation when drawing, so the result is upright.
+pub async fn prepare_photo(
+    src: &str,
+    rotation: i32,
+    settings: &PhotoSettings,
+) -> Result<web_sys::Blob, String> {
+    use wasm_bindgen::JsCast;
+    use wasm_bindgen_futures::JsFuture;
+
+    // Failed casts give back the original element or object rather than a `JsValue`
+    fn js_error(e: impl fmt::Debug) -> String {
+        format!("{:?}", e)
+    }
+
+    let image = web_sys::HtmlImageElement::new().map_err(js_error)?;
+    image.set_src(src);
+    JsFuture::from(image.decode()).await.map_err(js_error)?;
+
+    let (width, height) = (image.natural_width(), image.natural_height());
+    if width == 0 || height == 0 {
+        return Err("the photo has no pixels".to_string());
+    }
+    let scale = (settings.max_side.max(1) as f64 / width.max(height) as f64).min(1.0);
+    let scaled_width = (width as f64 * scale).round().max(1.0);
+    let scaled_height = (height as f64 * scale).round().max(1.0);
+    let turned = rotation.rem_euclid(180) == 90;
+    let (canvas_width, canvas_height) = if turned {
+        (scaled_height, scaled_width)
+    } else {
+        (scaled_width, scaled_height)
+    };
+
+    let canvas: web_sys::HtmlCanvasElement = web_sys::window()
+        .and_then(|w| w.document())
+        .ok_or("no document")?
+        .create_element("canvas")
+        .map_err(js_error)?
+        .dyn_into()
+        .map_err(js_error)?;
+    canvas.set_width(canvas_width as u32);
+    canvas.set_height(canvas_height as u32);
+    let context: web_sys::CanvasRenderingContext2d = canvas
+        .get_context("2d")
+        .map_err(js_error)?
+        .ok_or("no canvas context")?
+        .dyn_into()
+        .map_err(js_error)?;
+    context
+        .translate(canvas_width / 2.0, canvas_height / 2.0)
+        .map_err(js_error)?;
+    context
+        .rotate((rotation as f64).to_radians())
+        .map_err(js_error)?;
+    context
+        .draw_image_with_html_image_element_and_dw_and_dh(
+            &image,
+            -scaled_width / 2.0,
+            -scaled_height / 2.0,
+            scaled_width,
+            scaled_height,
+        )
+        .map_err(js_error)?;
+
+    let quality = settings.quality.clamp(1, 100) as f64 / 100.0;
+    let blob = canvas_to_blob(&canvas, settings.format.content_type(), quality).await?;
+    // Browsers that can't encode WebP quietly produce a PNG instead
+    if blob.type_() != settings.format.content_type() {
+        return canvas_to_blob(&canvas, PhotoFormat::Jpeg.content_type(), quality).await;
+    }
+    Ok(blob)
+}
+
+async fn canvas_to_blob(
+    canvas: &web_sys::HtmlCanvasElement,
+    content_type: &str,
+    quality: f64,
+) -> Result<web_sys::Blob, String> {
+    use wasm_bindgen::{closure::Closure, JsCast, JsValue};
+
+    let promise = js_sys::Promise::new(&mut |resolve, reject| {
+        let callback = Closure::once_into_js(move |blob: JsValue| {
+            let _ = resolve.call1(&JsValue::NULL, &blob);
+        });
+        if let Err(e) = canvas.to_blob_with_type_and_encoder_options(
+            callback.unchecked_ref(),
+            content_type,
+            &JsValue::from_f64(quality),
+        ) {
+            let _ = reject.call1(&JsValue::NULL, &e);
+        }
+    });
+    wasm_bindgen_futures::JsFuture::from(promise)
+        .await
+        .map_err(|e| format!("{:?}", e))?
+        .dyn_into()
+        .map_err(|_| "the photo couldn't be encoded".to_string())
+}
+
+// ============================================================================
+// Client Upload
+// ============================================================================
+
+/// Why a request to the upload routes failed
+enum RequestError {
+    /// The server refused the request; the message is for the user
+    Rejected(String),
+    /// The request didn't get through or the server failed, so it may work if retried
+    Failed(String),
+}
+
+/// Upload a photo in parts and return its R2 key. Parts the server already has from an
+/// earlier attempt with the same `upload_id` are skipped. `on_progress` is called with
+/// the fraction uploaded as parts complete.
+pub async fn upload_photo(
+    photo: &web_sys::Blob,
+    upload_id: &str,
+    on_progress: impl Fn(f64),
+) -> Result<String, String> {
+    let size = photo.size() as usize;
+    check_upload_size(size).map_err(|e| e.to_string())?;
+    let parts = size.div_ceil(UPLOAD_PART_BYTES).max(1) as u32;
+    let url = format!("/api/food-image-upload/{}", upload_id);
+
+    // Nothing is known about a new upload, so a failed check just means sending every part
+    let received: Vec<u32> = match request("GET", &url, None).await {
+        Ok(text) => text.split(',').filter_map(|p| p.parse().ok()).collect(),
+        Err(_) => Vec::new(),
+    };
+
+    let mut done = received.iter().filter(|&&part| part < parts).count();
+    on_progress(done as f64 / parts as f64);
+    for part in (0..parts).filter(|part| !received.contains(part)) {
+        let start = part as usize * UPLOAD_PART_BYTES;
+        let end = (start + UPLOAD_PART_BYTES).min(size);
+        let blob = photo
+            .slice_with_f64_and_f64(start as f64, end as f64)
+            .map_err(|e| format!("{:?}", e))?;
+        let part_url = format!("{}/{}", url, part);
+
+        let mut attempt = 1;
+        loop {
+            match request("PUT", &part_url, Some(&blob)).await {
+                Ok(_) => break,
+                Err(RequestError::Rejected(message)) => return Err(message),
+                Err(RequestError::Failed(message)) => {
+                    if attempt == PART_ATTEMPTS {
+                        log::warn!("Giving up on upload part {}: {}", part, message);
+                        return Err(UploadError::Incomplete.to_string());
+                    }
+                    sleep(500 * (1 << attempt)).await;
+                    attempt += 1;
+                }
+            }
+        }
+        done += 1;
+        on_progress(done as f64 / parts as f64);
+    }
+
+    match request("POST", &format!("{}?parts={}", url, parts), None).await {
+        Ok(key) => Ok(key),
+        Err(RequestError::Rejected(message)) => Err(message),
+        Err(RequestError::Failed(message)) => {
+            log::warn!("Failed to complete upload {}: {}", upload_id, message);
+            Err(UploadError::Storage(message).to_string())
+        }
+    }
+}
+
+async fn request(
+    method: &str,
+    url: &str,
+    body: Option<&web_sys::Blob>,
+) -> Result<String, RequestError> {
+    use wasm_bindgen::JsCast;
+    use wasm_bindgen_futures::JsFuture;
+
+    let failed = |e: wasm_bindgen::JsValue| RequestError::Failed(format!("{:?}", e));
+    let window = web_sys::window().ok_or_else(|| RequestError::Failed("no window".to_string()))?;
+
+    let init = web_sys::RequestInit::new();
+    init.set_method(method);
+    if let Some(body) = body {
+        init.set_body(body);
+    }
+    let response: web_sys::Response = JsFuture::from(window.fetch_with_str_and_init(url, &init))
+        .await
+        .map_err(failed)?
+        .dyn_into()
+        .map_err(failed)?;
+    let text = JsFuture::from(response.text().map_err(failed)?)
+        .await
+        .map_err(failed)?
+        .as_string()
+        .unwrap_or_default();
+
+    match response.status() {
+        200..=299 => Ok(text),
+        400..=499 => Err(RequestError::Rejected(text)),
+        _ => Err(RequestError::Failed(text)),
+    }
+}
+
+/// Wait before retrying a part
+async fn sleep(millis: i32) {
+    let promise = js_sys::Promise::new(&mut |resolve, _| {
+        if let Some(window) = web_sys::window() {
+            let _ = window.set_timeout_with_callback_and_timeout_and_arguments_0(&resolve, millis);
+        }
+    });
+    let _ = wasm_bindgen_futures::JsFuture::from(promise).await;
+}
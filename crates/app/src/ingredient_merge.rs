@@ -0,0 +1,687 @@
+//! Ingredient merge module
+//!
+//! Contains the admin tool for merging duplicate ingredients: a preview of the
+//! recipes and nutrition affected, and the merge itself, which repoints recipe
+//! ingredients and price history to the surviving ingredient, adds up pantry stock,
+//! unions labels and trashes the duplicates.
+
+use leptos::prelude::*;
+use serde::{Deserialize, Serialize};
+use server_fn::ServerFnError;
+
+use crate::auth::AdminAuth;
+use crate::components::{is_backdrop_click, CloseIcon, INPUT_CLASS, LABEL_CLASS};
+use crate::ingredients::{get_ingredients, Ingredient};
+use crate::recipe_history::{RecipeDiff, RevisionDiff};
+use crate::recipes::Recipe;
+
+// ============================================================================
+// Data Types
+// ============================================================================
+
+/// A recipe as it is now and as it will be after the merge
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+pub struct MergedRecipe {
+    pub before: Recipe,
+    pub after: Recipe,
+}
+
+/// Everything a merge will change, computed without writing anything
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+pub struct MergePreview {
+    pub survivor: Ingredient,
+    pub duplicates: Vec<Ingredient>,
+    /// Union of the survivor's and duplicates' labels
+    pub merged_labels: Vec<String>,
+    pub affected_recipes: Vec<MergedRecipe>,
+}
+
+/// Rewrite a recipe so that every duplicate ingredient points at the survivor.
+/// Nutrition values are taken from the survivor, so the recipe totals change
+/// wherever the duplicates' values differed.
+pub fn repoint_recipe(recipe: &Recipe, survivor: &Ingredient, duplicate_ids: &[i64]) -> Recipe {
+    let Some(survivor_id) = survivor.id else {
+        return recipe.clone();
+    };
+
+    let mut merged = recipe.clone();
+    for ing in merged
+        .ingredients
+        .iter_mut()
+        .filter(|ing| duplicate_ids.contains(&ing.ingredient_id))
+    {
+        ing.ingredient_id = survivor_id;
+        ing.ingredient_name = survivor.name.clone();
+        ing.package_size_g = survivor.package_size_g;
+        ing.calories_per_100g = survivor.calories;
+        ing.protein_per_100g = survivor.protein;
+        ing.fat_per_100g = survivor.fat;
+        ing.saturated_fat_per_100g = survivor.saturated_fat;
+        ing.carbs_per_100g = survivor.carbs;
+        ing.sugar_per_100g = survivor.sugar;
+        ing.fiber_per_100g = survivor.fiber;
+        ing.salt_per_100g = survivor.salt;
+        ing.package_price = survivor.package_price;
+        ing.cheapest_price_per_g = None;
+        ing.cheapest_store = None;
+    }
+    merged
+}
+
+// ============================================================================
+// Server Functions
+// ============================================================================
+
+#[cfg(feature = "ssr")]
+use crate::ingredients::SendD1Database;
+
+/// Load the ingredients and recipes involved in a merge and compute the result
+#[cfg(feature = "ssr")]
+async fn build_merge_preview(
+    db: &SendD1Database,
+    survivor_id: i64,
+    duplicate_ids: &[i64],
+) -> Result<MergePreview, ServerFnError> {
+    use send_wrapper::SendWrapper;
+    use wasm_bindgen::JsValue;
+
+    use crate::ingredients::fetch_ingredient;
+    use crate::recipes::fetch_recipe;
+
+    if duplicate_ids.is_empty() {
+        return Err(ServerFnError::new("Select at least one duplicate to merge"));
+    }
+    if duplicate_ids.contains(&survivor_id) {
+        return Err(ServerFnError::new(
+            "The surviving ingredient can't also be merged away",
+        ));
+    }
+
+    // Only ingredients that aren't in the trash can take part in a merge
+    let active_ids = SendWrapper::new(async {
+        let stmt = db
+            .inner()
+            .prepare("SELECT id FROM ingredients WHERE deleted_at IS NULL");
+        let rows = stmt.all().await?.results::<serde_json::Value>()?;
+        Ok::<_, worker::Error>(
+            rows.iter()
+                .filter_map(|r| r.get("id").and_then(|v| v.as_i64()))
+                .collect::<Vec<i64>>(),
+        )
+    })
+    .await
+    .map_err(|e| ServerFnError::new(format!("D1 query error: {:?}", e)))?;
+
+    let load = |id: i64| {
+        let active = active_ids.contains(&id);
+        async move {
+            match fetch_ingredient(db, id).await? {
+                Some(ing) if active => Ok(ing),
+                _ => Err(ServerFnError::new(format!("Ingredient #{} not found", id))),
+            }
+        }
+    };
+
+    let survivor = load(survivor_id).await?;
+    let mut duplicates = Vec::new();
+    for &id in duplicate_ids {
+        duplicates.push(load(id).await?);
+    }
+
+    let mut merged_labels: Vec<String> = survivor
+        .labels
+        .iter()
+        .chain(duplicates.iter().flat_map(|d| d.labels.iter()))
+        .cloned()
+        .collect();
+    merged_labels.sort();
+    merged_labels.dedup();
+
+    // Every recipe using a duplicate, including trashed ones, so duplicates can be purged later
+    let placeholders = vec!["?"; duplicate_ids.len()].join(", ");
+    let recipe_ids = SendWrapper::new(async {
+        let params: Vec<JsValue> = duplicate_ids.iter().map(|&id| (id as f64).into()).collect();
+        let stmt = db.inner().prepare(format!(
+            "SELECT DISTINCT recipe_id FROM recipe_ingredients WHERE ingredient_id IN ({}) ORDER BY recipe_id",
+            placeholders
+        ));
+        let stmt = stmt.bind(&params)?;
+        let rows = stmt.all().await?.results::<serde_json::Value>()?;
+        Ok::<_, worker::Error>(
+            rows.iter()
+                .filter_map(|r| r.get("recipe_id").and_then(|v| v.as_i64()))
+                .collect::<Vec<i64>>(),
+        )
+    })
+    .await
+    .map_err(|e| ServerFnError::new(format!("D1 query error: {:?}", e)))?;
+
+    let mut affected_recipes = Vec::new();
+    for recipe_id in recipe_ids {
+        if let Some(before) = fetch_recipe(db, recipe_id).await? {
+            let after = repoint_recipe(&before, &survivor, duplicate_ids);
+            affected_recipes.push(MergedRecipe { before, after });
+        }
+    }
+
+    Ok(MergePreview {
+        survivor,
+        duplicates,
+        merged_labels,
+        affected_recipes,
+    })
+}
+
+/// Preview merging `duplicate_ids` into `survivor_id` (admin only)
+#[server]
+pub async fn preview_ingredient_merge(
+    survivor_id: i64,
+    duplicate_ids: Vec<i64>,
+    auth_token: Option<String>,
+) -> Result<MergePreview, ServerFnError> {
+    use crate::auth::require_admin;
+
+    require_admin(auth_token.as_deref()).await?;
+
+    let db = expect_context::<SendD1Database>();
+    build_merge_preview(&db, survivor_id, &duplicate_ids).await
+}
+
+/// Merge duplicate ingredients into a surviving one (admin only).
+/// Recipe ingredients and prices are repointed, pantry stock is summed, labels are unioned
+/// and the duplicates are moved to the trash.
+#[server]
+pub async fn merge_ingredients(
+    survivor_id: i64,
+    duplicate_ids: Vec<i64>,
+    auth_token: Option<String>,
+) -> Result<Ingredient, ServerFnError> {
+    use send_wrapper::SendWrapper;
+
+    use crate::audit::{audit_statement, AuditAction, AuditEntity};
+    use crate::auth::{require_admin, resolve_actor};
+    use crate::ingredient_prices::{current_price_statement, latest_price};
+    use crate::recipe_history::{baseline_revision_statement, revision_statement};
+
+    require_admin(auth_token.as_deref()).await?;
+    let actor = resolve_actor(auth_token.as_deref()).await;
+
+    let db = expect_context::<SendD1Database>();
+    let preview = build_merge_preview(&db, survivor_id, &duplicate_ids).await?;
+
+    let mut survivor = Ingredient {
+        labels: preview.merged_labels.clone(),
+        ..preview.survivor.clone()
+    };
+
+    // The duplicates' price history moves to the survivor, so its current price
+    // becomes the latest observation among all of them
+    let price_ids: Vec<i64> = std::iter::once(survivor_id)
+        .chain(duplicate_ids.iter().copied())
+        .collect();
+    let latest = latest_price(&db, &price_ids).await?;
+    if let Some(latest) = &latest {
+        survivor.package_price = latest.price;
+        survivor.package_size_g = latest.package_size_g;
+        survivor.store = latest.store.clone();
+    }
+
+    // All writes run as one batch, so a failed merge leaves nothing half-done
+    SendWrapper::new(async {
+        let db = db.inner();
+        let mut statements = Vec::new();
+        for duplicate in &preview.duplicates {
+            let Some(duplicate_id) = duplicate.id else {
+                continue;
+            };
+
+            let stmt = db
+                .prepare("UPDATE recipe_ingredients SET ingredient_id = ? WHERE ingredient_id = ?");
+            statements
+                .push(stmt.bind(&[(survivor_id as f64).into(), (duplicate_id as f64).into()])?);
+
+            let stmt = db
+                .prepare("UPDATE ingredient_prices SET ingredient_id = ? WHERE ingredient_id = ?");
+            statements
+                .push(stmt.bind(&[(survivor_id as f64).into(), (duplicate_id as f64).into()])?);
+
+            // Pantry stock of the duplicate is added to the survivor's, keeping the earlier expiry
+            let stmt = db.prepare(
+                "INSERT INTO pantry_items (ingredient_id, grams_on_hand, low_stock_g, expires_on)
+                 SELECT ?, grams_on_hand, low_stock_g, expires_on FROM pantry_items WHERE ingredient_id = ?
+                 ON CONFLICT(ingredient_id) DO UPDATE SET
+                     grams_on_hand = grams_on_hand + excluded.grams_on_hand,
+                     low_stock_g = MAX(low_stock_g, excluded.low_stock_g),
+                     expires_on = COALESCE(MIN(expires_on, excluded.expires_on), expires_on, excluded.expires_on),
+                     updated_at = datetime('now')",
+            );
+            statements
+                .push(stmt.bind(&[(survivor_id as f64).into(), (duplicate_id as f64).into()])?);
+
+            let stmt = db.prepare("DELETE FROM pantry_items WHERE ingredient_id = ?");
+            statements.push(stmt.bind(&[(duplicate_id as f64).into()])?);
+
+            let stmt = db.prepare(
+                "UPDATE pantry_consumption SET ingredient_id = ? WHERE ingredient_id = ?",
+            );
+            statements
+                .push(stmt.bind(&[(survivor_id as f64).into(), (duplicate_id as f64).into()])?);
+
+            let stmt = db.prepare(
+                "INSERT OR IGNORE INTO ingredient_labels (ingredient_id, label)
+                 SELECT ?, label FROM ingredient_labels WHERE ingredient_id = ?",
+            );
+            statements
+                .push(stmt.bind(&[(survivor_id as f64).into(), (duplicate_id as f64).into()])?);
+
+            let stmt = db.prepare(
+                "UPDATE ingredients SET deleted_at = datetime('now') WHERE id = ? AND deleted_at IS NULL",
+            );
+            statements.push(stmt.bind(&[(duplicate_id as f64).into()])?);
+
+            statements.push(audit_statement(
+                db,
+                AuditEntity::Ingredient,
+                duplicate_id,
+                AuditAction::Merge,
+                Some(duplicate),
+                Some(&survivor),
+                &actor,
+            )?);
+        }
+
+        if let Some(latest) = &latest {
+            statements.push(current_price_statement(db, survivor_id, latest)?);
+        }
+        statements.push(audit_statement(
+            db,
+            AuditEntity::Ingredient,
+            survivor_id,
+            AuditAction::Update,
+            Some(&preview.survivor),
+            Some(&survivor),
+            &actor,
+        )?);
+
+        for recipe in &preview.affected_recipes {
+            if let Some(recipe_id) = recipe.after.id {
+                statements.push(baseline_revision_statement(db, &recipe.before)?);
+                statements.push(revision_statement(db, &recipe.after)?);
+                statements.push(audit_statement(
+                    db,
+                    AuditEntity::Recipe,
+                    recipe_id,
+                    AuditAction::Update,
+                    Some(&recipe.before),
+                    Some(&recipe.after),
+                    &actor,
+                )?);
+            }
+        }
+
+        db.batch(statements).await
+    })
+    .await
+    .map_err(|e| ServerFnError::new(format!("D1 merge error: {:?}", e)))?;
+
+    log::info!(
+        "Merged ingredients {:?} into id: {} ({} recipes updated)",
+        duplicate_ids,
+        survivor_id,
+        preview.affected_recipes.len()
+    );
+
+    Ok(survivor)
+}
+
+// ============================================================================
+// Components
+// ============================================================================
+
+/// Whether two ingredient names look like variants of each other,
+/// e.g. "Chicken breast" and "Chicken Breast (Costco)"
+fn names_look_similar(a: &str, b: &str) -> bool {
+    let normalize = |s: &str| -> String {
+        s.split(['(', ','])
+            .next()
+            .unwrap_or("")
+            .trim()
+            .to_lowercase()
+    };
+    let (a, b) = (normalize(a), normalize(b));
+    !a.is_empty() && !b.is_empty() && (a.contains(&b) || b.contains(&a))
+}
+
+/// Per-100g nutrient comparison of a duplicate against the survivor
+fn nutrient_comparison(duplicate: &Ingredient, survivor: &Ingredient) -> String {
+    format!(
+        "{:.0} \u{2192} {:.0} kcal, {:.1} \u{2192} {:.1}g protein, {:.1} \u{2192} {:.1}g carbs, {:.1} \u{2192} {:.1}g fat per 100g",
+        duplicate.calories,
+        survivor.calories,
+        duplicate.protein,
+        survivor.protein,
+        duplicate.carbs,
+        survivor.carbs,
+        duplicate.fat,
+        survivor.fat,
+    )
+}
+
+/// Admin modal for merging duplicate ingredients
+#[component]
+pub fn IngredientMergeModal(
+    show: RwSignal<bool>,
+    on_merge: impl Fn() + Clone + Send + Sync + 'static,
+) -> impl IntoView {
+    let auth_token = expect_context::<AdminAuth>().token;
+
+    let survivor_id = RwSignal::new(Option::<i64>::None);
+    let duplicate_ids = RwSignal::new(Vec::<i64>::new());
+    let search = RwSignal::new(String::new());
+    let preview = RwSignal::new(Option::<MergePreview>::None);
+    let error = RwSignal::new(Option::<String>::None);
+    let working = RwSignal::new(false);
+
+    let ingredients = Resource::new(move || show.get(), |_| get_ingredients());
+
+    // Any change to the selection invalidates the preview
+    Effect::new(move || {
+        survivor_id.track();
+        duplicate_ids.track();
+        preview.set(None);
+    });
+
+    let close = move || {
+        show.set(false);
+        survivor_id.set(None);
+        duplicate_ids.set(Vec::new());
+        search.set(String::new());
+        preview.set(None);
+        error.set(None);
+    };
+
+    let handle_preview = move || {
+        let Some(survivor) = survivor_id.get() else {
+            error.set(Some("Choose the ingredient to keep".to_string()));
+            return;
+        };
+        let duplicates = duplicate_ids.get();
+        working.set(true);
+        error.set(None);
+        let token = auth_token.get_untracked();
+        wasm_bindgen_futures::spawn_local(async move {
+            match preview_ingredient_merge(survivor, duplicates, token).await {
+                Ok(result) => preview.set(Some(result)),
+                Err(e) => error.set(Some(format!("Failed to preview merge: {}", e))),
+            }
+            working.set(false);
+        });
+    };
+
+    let on_merge = StoredValue::new(on_merge);
+    let handle_merge = move || {
+        let Some(current) = preview.get() else {
+            return;
+        };
+        let Some(survivor) = current.survivor.id else {
+            return;
+        };
+        let duplicates: Vec<i64> = current.duplicates.iter().filter_map(|d| d.id).collect();
+        working.set(true);
+        error.set(None);
+        let token = auth_token.get_untracked();
+        wasm_bindgen_futures::spawn_local(async move {
+            match merge_ingredients(survivor, duplicates, token).await {
+                Ok(_) => {
+                    close();
+                    on_merge.with_value(|on_merge| on_merge());
+                }
+                Err(e) => error.set(Some(format!("Failed to merge: {}", e))),
+            }
+            working.set(false);
+        });
+    };
+
+    let toggle_duplicate = move |id: i64| {
+        duplicate_ids.update(|ids| {
+            if let Some(pos) = ids.iter().position(|&d| d == id) {
+                ids.remove(pos);
+            } else {
+                ids.push(id);
+            }
+        });
+    };
+
+    view! {
+      <Show when=move || show.get()>
+        <div
+          id="ingredient-merge-backdrop"
+          class="fixed inset-0 z-50 flex items-center justify-center bg-black/50 overflow-y-auto py-4"
+          on:click=move |ev: web_sys::MouseEvent| {
+            if is_backdrop_click(&ev, "ingredient-merge-backdrop") {
+              close();
+            }
+          }
+        >
+          <div class="w-full max-w-3xl rounded-lg bg-white dark:bg-slate-800 p-6 shadow-xl mx-4">
+            <div class="mb-4 flex items-center justify-between">
+              <h2 class="text-xl font-bold text-slate-900 dark:text-slate-100">"Merge Duplicate Ingredients"</h2>
+              <button
+                class="text-slate-500 hover:text-slate-700 dark:text-slate-400 dark:hover:text-slate-200"
+                on:click=move |_| close()
+              >
+                <CloseIcon />
+              </button>
+            </div>
+
+            <Show when=move || error.get().is_some()>
+              <div class="mb-4 rounded bg-red-100 px-4 py-3 text-red-700">{move || error.get().unwrap_or_default()}</div>
+            </Show>
+
+            <div class="grid gap-4 md:grid-cols-2">
+              <div>
+                <label class=LABEL_CLASS>"Keep this ingredient"</label>
+                <Suspense fallback=move || {
+                  view! { <p class="text-sm text-slate-600 dark:text-slate-400">"Loading ingredients..."</p> }
+                }>
+                  {move || {
+                    ingredients
+                      .get()
+                      .map(|result| {
+                        let options = result.unwrap_or_default();
+                        view! {
+                          <select
+                            class=INPUT_CLASS
+                            on:change=move |ev| {
+                              let id = event_target_value(&ev).parse::<i64>().ok();
+                              duplicate_ids.update(|ids| ids.retain(|d| Some(*d) != id));
+                              survivor_id.set(id);
+                            }
+                          >
+                            <option value="" selected=move || survivor_id.get().is_none()>
+                              "Select..."
+                            </option>
+                            {options
+                              .into_iter()
+                              .filter_map(|ing| {
+                                let id = ing.id?;
+                                Some(
+                                  view! {
+                                    <option value=id.to_string() selected=move || survivor_id.get() == Some(id)>
+                                      {ing.name}
+                                    </option>
+                                  },
+                                )
+                              })
+                              .collect_view()}
+                          </select>
+                        }
+                      })
+                  }}
+                </Suspense>
+              </div>
+              <div>
+                <label class=LABEL_CLASS>"Find duplicates"</label>
+                <input
+                  type="text"
+                  class=INPUT_CLASS
+                  placeholder="Search by name"
+                  prop:value=move || search.get()
+                  on:input=move |ev| search.set(event_target_value(&ev))
+                />
+              </div>
+            </div>
+
+            <div class="mt-4">
+              <p class=LABEL_CLASS>"Merge these into it"</p>
+              <div class="max-h-56 overflow-y-auto rounded border border-slate-200 dark:border-slate-600 divide-y divide-slate-100 dark:divide-slate-700">
+                <Suspense fallback=|| ()>
+                  {move || {
+                    ingredients
+                      .get()
+                      .map(|result| {
+                        let all = result.unwrap_or_default();
+                        let survivor = survivor_id.get().and_then(|id| all.iter().find(|i| i.id == Some(id)).cloned());
+                        let query = search.get().trim().to_lowercase();
+                        let mut candidates: Vec<Ingredient> = all
+                          .into_iter()
+                          .filter(|i| i.id.is_some() && i.id != survivor_id.get())
+                          .filter(|i| query.is_empty() || i.name.to_lowercase().contains(&query))
+                          .collect();
+                        // Likely duplicates of the survivor first
+                        if let Some(s) = &survivor {
+                          candidates.sort_by_key(|i| !names_look_similar(&i.name, &s.name));
+                        }
+                        candidates
+                          .into_iter()
+                          .filter_map(|ing| {
+                            let id = ing.id?;
+                            let similar = survivor.as_ref().is_some_and(|s| names_look_similar(&ing.name, &s.name));
+                            Some(
+                              view! {
+                                <label class="flex items-center gap-2 px-3 py-2 text-sm text-slate-700 dark:text-slate-300 hover:bg-slate-50 dark:hover:bg-slate-700 cursor-pointer">
+                                  <input
+                                    type="checkbox"
+                                    prop:checked=move || duplicate_ids.get().contains(&id)
+                                    on:change=move |_| toggle_duplicate(id)
+                                  />
+                                  <span>{ing.name}</span>
+                                  <Show when=move || similar>
+                                    <span class="rounded-full bg-amber-100 dark:bg-amber-900 px-2 py-0.5 text-xs text-amber-800 dark:text-amber-200">
+                                      "likely duplicate"
+                                    </span>
+                                  </Show>
+                                </label>
+                              },
+                            )
+                          })
+                          .collect_view()
+                      })
+                  }}
+                </Suspense>
+              </div>
+            </div>
+
+            {move || {
+              preview
+                .get()
+                .map(|p| {
+                  let survivor = p.survivor.clone();
+                  let has_recipes = !p.affected_recipes.is_empty();
+                  view! {
+                    <div class="mt-6 space-y-4 border-t border-slate-200 dark:border-slate-600 pt-4">
+                      <div>
+                        <h3 class="font-semibold text-slate-900 dark:text-slate-100">"Ingredients"</h3>
+                        <ul class="mt-1 space-y-1 text-sm text-slate-700 dark:text-slate-300">
+                          {p
+                            .duplicates
+                            .iter()
+                            .map(|d| {
+                              view! {
+                                <li>
+                                  <span class="font-medium">{format!("{} \u{2192} {}", d.name, survivor.name)}</span>
+                                  <span class="block text-xs text-slate-500 dark:text-slate-400">
+                                    {nutrient_comparison(d, &survivor)}
+                                  </span>
+                                </li>
+                              }
+                            })
+                            .collect_view()}
+                        </ul>
+                        <p class="mt-2 text-sm text-slate-600 dark:text-slate-400">
+                          {format!(
+                            "Labels after merge: {}",
+                            if p.merged_labels.is_empty() { "none".to_string() } else { p.merged_labels.join(", ") },
+                          )}
+                        </p>
+                      </div>
+                      <div>
+                        <h3 class="font-semibold text-slate-900 dark:text-slate-100">
+                          {format!("Affected recipes ({})", p.affected_recipes.len())}
+                        </h3>
+                        <Show
+                          when=move || has_recipes
+                          fallback=|| {
+                            view! { <p class="text-sm text-slate-500 dark:text-slate-400">"No recipes use the duplicates."</p> }
+                          }
+                        >
+                          <ul class="mt-1 space-y-3">
+                            {p
+                              .affected_recipes
+                              .iter()
+                              .map(|r| {
+                                let diff = RecipeDiff::between(&r.before, &r.after);
+                                view! {
+                                  <li class="rounded bg-slate-50 dark:bg-slate-700 p-3">
+                                    <p class="font-medium text-slate-900 dark:text-slate-100">{r.before.name.clone()}</p>
+                                    <RevisionDiff diff=diff />
+                                  </li>
+                                }
+                              })
+                              .collect_view()}
+                          </ul>
+                        </Show>
+                      </div>
+                      <p class="text-xs text-slate-500 dark:text-slate-400">
+                        "The duplicates will be moved to the trash."
+                      </p>
+                    </div>
+                  }
+                })
+            }}
+
+            <div class="mt-6 flex justify-end gap-3 border-t border-slate-200 dark:border-slate-600 pt-4">
+              <button
+                class="rounded bg-slate-200 dark:bg-slate-600 px-4 py-2 font-medium text-slate-700 dark:text-slate-200 hover:bg-slate-300 dark:hover:bg-slate-500"
+                on:click=move |_| close()
+              >
+                "Cancel"
+              </button>
+              <Show
+                when=move || preview.get().is_some()
+                fallback=move || {
+                  view! {
+                    <button
+                      class="rounded bg-blue-600 px-4 py-2 font-medium text-white hover:bg-blue-700 disabled:bg-blue-300 dark:disabled:bg-blue-800"
+                      disabled=move || working.get() || survivor_id.get().is_none() || duplicate_ids.get().is_empty()
+                      on:click=move |_| handle_preview()
+                    >
+                      {move || if working.get() { "Loading..." } else { "Preview" }}
+                    </button>
+                  }
+                }
+              >
+                <button
+                  class="rounded bg-red-600 px-4 py-2 font-medium text-white hover:bg-red-700 disabled:bg-red-300"
+                  disabled=move || working.get()
+                  on:click=move |_| handle_merge()
+                >
+                  {move || if working.get() { "Merging..." } else { "Merge" }}
+                </button>
+              </Show>
+            </div>
+          </div>
+        </div>
+      </Show>
+    }
+}
@@ -0,0 +1,766 @@
+//! Ingredient prices module
+//!
+//! Keeps every observed price of an ingredient (price, package size, store, date).
+//! The latest observation is mirrored into the ingredient's `package_price`,
+//! `package_size_g` and `store` so existing code keeps working with the current price.
+
+use leptos::prelude::*;
+use serde::{Deserialize, Serialize};
+use server_fn::ServerFnError;
+
+use crate::auth::AdminAuth;
+use crate::components::{INPUT_CLASS, LABEL_CLASS};
+
+/// How far back to look when picking the cheapest store for recipe costs
+pub const RECENT_PRICE_DAYS: i64 = 90;
+
+// ============================================================================
+// Data Types
+// ============================================================================
+
+/// A single price observation of an ingredient
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+pub struct IngredientPrice {
+    pub id: Option<i64>,
+    pub ingredient_id: i64,
+    pub price: f32,
+    pub package_size_g: f32,
+    pub store: String,
+    /// Date the price was seen, YYYY-MM-DD. Empty means today.
+    pub observed_on: String,
+}
+
+impl IngredientPrice {
+    /// Price normalized to one kilogram, if the package size is known
+    pub fn price_per_kg(&self) -> Option<f32> {
+        if self.package_size_g > 0.0 {
+            Some(self.price / self.package_size_g * 1000.0)
+        } else {
+            None
+        }
+    }
+
+    /// Store name for display
+    pub fn store_label(&self) -> &str {
+        if self.store.is_empty() {
+            "Unknown store"
+        } else {
+            &self.store
+        }
+    }
+}
+
+// ============================================================================
+// Server Functions
+// ============================================================================
+
+#[cfg(feature = "ssr")]
+use crate::ingredients::{fetch_ingredient, Ingredient, SendD1Database};
+
+#[cfg(feature = "ssr")]
+fn ingredient_price_from_row(row: &serde_json::Value) -> Option<IngredientPrice> {
+    Some(IngredientPrice {
+        id: row.get("id")?.as_i64(),
+        ingredient_id: row.get("ingredient_id")?.as_i64()?,
+        price: row.get("price")?.as_f64()? as f32,
+        package_size_g: row
+            .get("package_size_g")
+            .and_then(|v| v.as_f64())
+            .unwrap_or(0.0) as f32,
+        store: row
+            .get("store")
+            .and_then(|v| v.as_str())
+            .unwrap_or("")
+            .to_string(),
+        observed_on: row
+            .get("observed_on")
+            .and_then(|v| v.as_str())
+            .unwrap_or("")
+            .to_string(),
+    })
+}
+
+/// Append a price observation when a saved ingredient's price, size or store changed
+#[cfg(feature = "ssr")]
+pub(crate) async fn record_price_if_changed(
+    db: &SendD1Database,
+    ingredient_id: i64,
+    before: Option<&Ingredient>,
+    after: &Ingredient,
+) -> Result<(), ServerFnError> {
+    use send_wrapper::SendWrapper;
+
+    if after.package_price <= 0.0 {
+        return Ok(());
+    }
+    let unchanged = before.is_some_and(|b| {
+        b.package_price == after.package_price
+            && b.package_size_g == after.package_size_g
+            && b.store == after.store
+    });
+    if unchanged {
+        return Ok(());
+    }
+
+    SendWrapper::new(async {
+        let stmt = db.inner().prepare(
+            "INSERT INTO ingredient_prices (ingredient_id, price, package_size_g, store) VALUES (?, ?, ?, ?)",
+        );
+        let stmt = stmt.bind(&[
+            (ingredient_id as f64).into(),
+            after.package_price.into(),
+            after.package_size_g.into(),
+            after.store.clone().into(),
+        ])?;
+        stmt.run().await
+    })
+    .await
+    .map_err(|e| ServerFnError::new(format!("D1 insert price error: {:?}", e)))?;
+
+    Ok(())
+}
+
+/// Fetch the most recent price observation among the given ingredients
+#[cfg(feature = "ssr")]
+pub(crate) async fn latest_price(
+    db: &SendD1Database,
+    ingredient_ids: &[i64],
+) -> Result<Option<IngredientPrice>, ServerFnError> {
+    use send_wrapper::SendWrapper;
+    use wasm_bindgen::JsValue;
+
+    if ingredient_ids.is_empty() {
+        return Ok(None);
+    }
+    let placeholders = vec!["?"; ingredient_ids.len()].join(", ");
+
+    let row = SendWrapper::new(async {
+        let params: Vec<JsValue> = ingredient_ids
+            .iter()
+            .map(|&id| (id as f64).into())
+            .collect();
+        let stmt = db.inner().prepare(format!(
+            "SELECT id, ingredient_id, price, package_size_g, store, observed_on FROM ingredient_prices
+             WHERE ingredient_id IN ({})
+             ORDER BY observed_on DESC, id DESC
+             LIMIT 1",
+            placeholders
+        ));
+        let stmt = stmt.bind(&params)?;
+        stmt.first::<serde_json::Value>(None).await
+    })
+    .await
+    .map_err(|e| ServerFnError::new(format!("D1 query error: {:?}", e)))?;
+
+    Ok(row.as_ref().and_then(ingredient_price_from_row))
+}
+
+/// Build the update mirroring a price observation into an ingredient row
+#[cfg(feature = "ssr")]
+pub(crate) fn current_price_statement(
+    db: &worker::d1::D1Database,
+    ingredient_id: i64,
+    price: &IngredientPrice,
+) -> Result<worker::d1::D1PreparedStatement, worker::Error> {
+    let stmt = db.prepare(
+        "UPDATE ingredients SET package_price = ?, package_size_g = ?, store = ?, updated_at = datetime('now') WHERE id = ?",
+    );
+    stmt.bind(&[
+        price.price.into(),
+        price.package_size_g.into(),
+        price.store.clone().into(),
+        (ingredient_id as f64).into(),
+    ])
+}
+
+/// Copy the latest price observation into the ingredient row
+#[cfg(feature = "ssr")]
+pub(crate) async fn sync_current_price(
+    db: &SendD1Database,
+    ingredient_id: i64,
+) -> Result<(), ServerFnError> {
+    use send_wrapper::SendWrapper;
+
+    let Some(latest) = latest_price(db, &[ingredient_id]).await? else {
+        return Ok(());
+    };
+
+    SendWrapper::new(async {
+        current_price_statement(db.inner(), ingredient_id, &latest)?
+            .run()
+            .await
+    })
+    .await
+    .map_err(|e| ServerFnError::new(format!("D1 price sync error: {:?}", e)))?;
+
+    Ok(())
+}
+
+/// Record an Update audit entry if syncing the price changed the ingredient
+#[cfg(feature = "ssr")]
+async fn audit_price_change(
+    db: &SendD1Database,
+    ingredient_id: i64,
+    before: Option<Ingredient>,
+    actor: &str,
+) -> Result<(), ServerFnError> {
+    use crate::audit::{record_audit, AuditAction, AuditEntity};
+
+    let after = fetch_ingredient(db, ingredient_id).await?;
+    if before != after {
+        record_audit(
+            db,
+            AuditEntity::Ingredient,
+            ingredient_id,
+            AuditAction::Update,
+            before.as_ref(),
+            after.as_ref(),
+            actor,
+        )
+        .await?;
+    }
+    Ok(())
+}
+
+/// Fetch the price history of an ingredient, oldest first
+#[server]
+pub async fn get_ingredient_prices(
+    ingredient_id: i64,
+) -> Result<Vec<IngredientPrice>, ServerFnError> {
+    use send_wrapper::SendWrapper;
+
+    let db = expect_context::<SendD1Database>();
+
+    let rows = SendWrapper::new(async {
+        let stmt = db.inner().prepare(
+            "SELECT id, ingredient_id, price, package_size_g, store, observed_on FROM ingredient_prices
+             WHERE ingredient_id = ?
+             ORDER BY observed_on, id",
+        );
+        let stmt = stmt.bind(&[(ingredient_id as f64).into()])?;
+        let results = stmt.all().await?;
+        results.results::<serde_json::Value>()
+    })
+    .await
+    .map_err(|e| ServerFnError::new(format!("D1 query error: {:?}", e)))?;
+
+    Ok(rows.iter().filter_map(ingredient_price_from_row).collect())
+}
+
+/// Check that an observation date is a real `YYYY-MM-DD` date and not in the future.
+/// One day of slack is allowed for users whose local date is ahead of UTC.
+#[cfg(feature = "ssr")]
+fn validate_observed_on(date: &str) -> Result<(), ServerFnError> {
+    let invalid = || ServerFnError::new(format!("Invalid date \"{}\", expected YYYY-MM-DD", date));
+
+    let well_formed = date.len() == 10
+        && date.bytes().enumerate().all(|(i, b)| match i {
+            4 | 7 => b == b'-',
+            _ => b.is_ascii_digit(),
+        });
+    if !well_formed {
+        return Err(invalid());
+    }
+    let year: i64 = date[..4].parse().map_err(|_| invalid())?;
+    let month: i64 = date[5..7].parse().map_err(|_| invalid())?;
+    let day: i64 = date[8..10].parse().map_err(|_| invalid())?;
+    let leap = (year % 4 == 0 && year % 100 != 0) || year % 400 == 0;
+    let days_in_month = match month {
+        1 | 3 | 5 | 7 | 8 | 10 | 12 => 31,
+        4 | 6 | 9 | 11 => 30,
+        2 if leap => 29,
+        2 => 28,
+        _ => return Err(invalid()),
+    };
+    if !(1..=days_in_month).contains(&day) {
+        return Err(invalid());
+    }
+
+    let today = (worker::Date::now().as_millis() / 86_400_000) as i64;
+    if day_number(date).ok_or_else(invalid)? > today + 1 {
+        return Err(ServerFnError::new("Price date can't be in the future"));
+    }
+    Ok(())
+}
+
+/// Record a price observation. If it is the latest one it becomes the ingredient's current price.
+#[server]
+pub async fn add_ingredient_price(
+    price: IngredientPrice,
+    auth_token: Option<String>,
+) -> Result<IngredientPrice, ServerFnError> {
+    use send_wrapper::SendWrapper;
+    use wasm_bindgen::JsValue;
+
+    use crate::auth::resolve_actor;
+
+    if price.price <= 0.0 {
+        return Err(ServerFnError::new("Price must be greater than zero"));
+    }
+
+    let db = expect_context::<SendD1Database>();
+    let actor = resolve_actor(auth_token.as_deref()).await;
+
+    let before = fetch_ingredient(&db, price.ingredient_id).await?;
+    if before.is_none() {
+        return Err(ServerFnError::new("Ingredient not found"));
+    }
+
+    let observed_on = price.observed_on.trim();
+    if !observed_on.is_empty() {
+        validate_observed_on(observed_on)?;
+    }
+
+    let row = SendWrapper::new(async {
+        let stmt = db.inner().prepare(
+            "INSERT INTO ingredient_prices (ingredient_id, price, package_size_g, store, observed_on)
+             VALUES (?, ?, ?, ?, COALESCE(?, date('now')))
+             RETURNING id, ingredient_id, price, package_size_g, store, observed_on",
+        );
+        let stmt = stmt.bind(&[
+            (price.ingredient_id as f64).into(),
+            price.price.into(),
+            price.package_size_g.into(),
+            price.store.trim().into(),
+            if observed_on.is_empty() {
+                JsValue::NULL
+            } else {
+                observed_on.into()
+            },
+        ])?;
+        stmt.first::<serde_json::Value>(None).await
+    })
+    .await
+    .map_err(|e| ServerFnError::new(format!("D1 insert price error: {:?}", e)))?;
+
+    let saved = row
+        .as_ref()
+        .and_then(ingredient_price_from_row)
+        .ok_or_else(|| ServerFnError::new("Failed to read inserted price"))?;
+
+    sync_current_price(&db, saved.ingredient_id).await?;
+    audit_price_change(&db, saved.ingredient_id, before, &actor).await?;
+
+    log::info!(
+        "Recorded price {} for ingredient id: {}",
+        saved.price,
+        saved.ingredient_id
+    );
+    Ok(saved)
+}
+
+/// Delete a price observation and fall back to the previous one as the current price
+#[server]
+pub async fn delete_ingredient_price(
+    id: i64,
+    auth_token: Option<String>,
+) -> Result<(), ServerFnError> {
+    use send_wrapper::SendWrapper;
+
+    use crate::auth::resolve_actor;
+
+    let db = expect_context::<SendD1Database>();
+    let actor = resolve_actor(auth_token.as_deref()).await;
+
+    let ingredient_id = SendWrapper::new(async {
+        let stmt = db
+            .inner()
+            .prepare("DELETE FROM ingredient_prices WHERE id = ? RETURNING ingredient_id");
+        let stmt = stmt.bind(&[(id as f64).into()])?;
+        stmt.first::<serde_json::Value>(None).await
+    })
+    .await
+    .map_err(|e| ServerFnError::new(format!("D1 delete price error: {:?}", e)))?
+    .and_then(|row| row.get("ingredient_id").and_then(|v| v.as_i64()));
+
+    let Some(ingredient_id) = ingredient_id else {
+        return Ok(());
+    };
+
+    let before = fetch_ingredient(&db, ingredient_id).await?;
+    sync_current_price(&db, ingredient_id).await?;
+    audit_price_change(&db, ingredient_id, before, &actor).await?;
+
+    log::info!(
+        "Deleted price id: {} of ingredient id: {}",
+        id,
+        ingredient_id
+    );
+    Ok(())
+}
+
+// ============================================================================
+// Components
+// ============================================================================
+
+const CHART_WIDTH: f64 = 480.0;
+const CHART_HEIGHT: f64 = 180.0;
+const CHART_PAD_LEFT: f64 = 48.0;
+const CHART_PAD_RIGHT: f64 = 12.0;
+const CHART_PAD_TOP: f64 = 12.0;
+const CHART_PAD_BOTTOM: f64 = 24.0;
+const CHART_COLORS: [&str; 6] = [
+    "#2563eb", "#16a34a", "#dc2626", "#d97706", "#9333ea", "#0d9488",
+];
+
+/// Days since 1970-01-01 for a YYYY-MM-DD date, used as the chart's x axis
+fn day_number(date: &str) -> Option<i64> {
+    let mut parts = date.get(..10)?.split('-');
+    let y: i64 = parts.next()?.parse().ok()?;
+    let m: i64 = parts.next()?.parse().ok()?;
+    let d: i64 = parts.next()?.parse().ok()?;
+
+    // Days-from-civil algorithm (proleptic Gregorian calendar)
+    let y = if m <= 2 { y - 1 } else { y };
+    let era = y.div_euclid(400);
+    let yoe = y - era * 400;
+    let mp = (m + 9) % 12;
+    let doy = (153 * mp + 2) / 5 + d - 1;
+    let doe = yoe * 365 + yoe / 4 - yoe / 100 + doy;
+    Some(era * 146097 + doe - 719468)
+}
+
+/// Line chart of price per kg over time, one line per store
+#[component]
+pub fn PriceChart(prices: Vec<IngredientPrice>) -> impl IntoView {
+    let points: Vec<(i64, f64, String)> = prices
+        .iter()
+        .filter_map(|p| {
+            Some((
+                day_number(&p.observed_on)?,
+                p.price_per_kg()? as f64,
+                p.store_label().to_string(),
+            ))
+        })
+        .collect();
+
+    if points.len() < 2 {
+        return view! {
+          <p class="text-sm text-slate-500 dark:text-slate-400">
+            "Record at least two prices with a package size to see a chart."
+          </p>
+        }
+        .into_any();
+    }
+
+    let min_day = points.iter().map(|p| p.0).min().unwrap_or(0);
+    let max_day = points.iter().map(|p| p.0).max().unwrap_or(0);
+    let max_price = points.iter().map(|p| p.1).fold(0.0, f64::max) * 1.1;
+    let day_span = (max_day - min_day).max(1) as f64;
+    let plot_w = CHART_WIDTH - CHART_PAD_LEFT - CHART_PAD_RIGHT;
+    let plot_h = CHART_HEIGHT - CHART_PAD_TOP - CHART_PAD_BOTTOM;
+
+    let x = move |day: i64| CHART_PAD_LEFT + (day - min_day) as f64 / day_span * plot_w;
+    let y = move |price: f64| CHART_PAD_TOP + plot_h - price / max_price * plot_h;
+
+    let mut stores: Vec<String> = points.iter().map(|p| p.2.clone()).collect();
+    stores.sort();
+    stores.dedup();
+
+    let series = stores
+        .iter()
+        .enumerate()
+        .map(|(idx, store)| {
+            let color = CHART_COLORS[idx % CHART_COLORS.len()];
+            let store_points: Vec<(f64, f64)> = points
+                .iter()
+                .filter(|p| &p.2 == store)
+                .map(|p| (x(p.0), y(p.1)))
+                .collect();
+            let path = store_points
+                .iter()
+                .map(|(px, py)| format!("{:.1},{:.1}", px, py))
+                .collect::<Vec<_>>()
+                .join(" ");
+            view! {
+              <g>
+                <polyline points=path fill="none" stroke=color stroke-width="2" />
+                {store_points
+                  .into_iter()
+                  .map(|(px, py)| {
+                    view! { <circle cx=format!("{:.1}", px) cy=format!("{:.1}", py) r="3" fill=color /> }
+                  })
+                  .collect_view()}
+              </g>
+            }
+        })
+        .collect_view();
+
+    let legend = stores
+        .iter()
+        .enumerate()
+        .map(|(idx, store)| {
+            let color = CHART_COLORS[idx % CHART_COLORS.len()];
+            view! {
+              <span class="flex items-center gap-1">
+                <span class="inline-block h-2 w-3 rounded" style=format!("background-color: {}", color)></span>
+                {store.clone()}
+              </span>
+            }
+        })
+        .collect_view();
+
+    let first_date = prices
+        .first()
+        .map(|p| p.observed_on.clone())
+        .unwrap_or_default();
+    let last_date = prices
+        .last()
+        .map(|p| p.observed_on.clone())
+        .unwrap_or_default();
+    let axis_y = CHART_PAD_TOP + plot_h;
+
+    view! {
+      <div>
+        <svg
+          viewBox=format!("0 0 {} {}", CHART_WIDTH, CHART_HEIGHT)
+          class="w-full text-slate-400 dark:text-slate-500"
+        >
+          <line
+            x1=CHART_PAD_LEFT
+            y1=CHART_PAD_TOP
+            x2=CHART_PAD_LEFT
+            y2=axis_y
+            stroke="currentColor"
+          />
+          <line
+            x1=CHART_PAD_LEFT
+            y1=axis_y
+            x2={CHART_WIDTH - CHART_PAD_RIGHT}
+            y2=axis_y
+            stroke="currentColor"
+          />
+          <text x={CHART_PAD_LEFT - 4.0} y={CHART_PAD_TOP + 8.0} text-anchor="end" font-size="10" fill="currentColor">
+            {format!("${:.2}", max_price)}
+          </text>
+          <text x={CHART_PAD_LEFT - 4.0} y=axis_y text-anchor="end" font-size="10" fill="currentColor">
+            "$0"
+          </text>
+          <text x=CHART_PAD_LEFT y={CHART_HEIGHT - 6.0} font-size="10" fill="currentColor">
+            {first_date}
+          </text>
+          <text x={CHART_WIDTH - CHART_PAD_RIGHT} y={CHART_HEIGHT - 6.0} text-anchor="end" font-size="10" fill="currentColor">
+            {last_date}
+          </text>
+          {series}
+        </svg>
+        <div class="mt-1 flex flex-wrap gap-3 text-xs text-slate-600 dark:text-slate-400">
+          <span>"Price per kg:"</span>
+          {legend}
+        </div>
+      </div>
+    }
+    .into_any()
+}
+
+/// Price history of an ingredient with a chart, the list of observations and a form to add one.
+/// `on_current_change` receives the latest observation whenever the history changes.
+#[component]
+pub fn IngredientPriceHistory(
+    ingredient_id: i64,
+    on_current_change: impl Fn(IngredientPrice) + Clone + Send + Sync + 'static,
+) -> impl IntoView {
+    let auth_token = expect_context::<AdminAuth>().token;
+    let prices = Resource::new(|| (), move |_| get_ingredient_prices(ingredient_id));
+
+    let price = RwSignal::new(String::new());
+    let package_size = RwSignal::new(String::new());
+    let store = RwSignal::new(String::new());
+    let observed_on = RwSignal::new(String::new());
+    let error = RwSignal::new(Option::<String>::None);
+    let saving = RwSignal::new(false);
+
+    // Keep the parent form in sync with the latest observation after a change
+    let refresh = move || {
+        let on_current_change = on_current_change.clone();
+        wasm_bindgen_futures::spawn_local(async move {
+            if let Ok(list) = get_ingredient_prices(ingredient_id).await {
+                if let Some(latest) = list.last().cloned() {
+                    on_current_change(latest);
+                }
+            }
+            prices.refetch();
+        });
+    };
+
+    let handle_add = {
+        let refresh = refresh.clone();
+        move || {
+            let price_val: f32 = price.get().parse().unwrap_or(0.0);
+            if price_val <= 0.0 {
+                error.set(Some("Enter a price".to_string()));
+                return;
+            }
+            let observation = IngredientPrice {
+                id: None,
+                ingredient_id,
+                price: price_val,
+                package_size_g: package_size.get().parse().unwrap_or(0.0),
+                store: store.get(),
+                observed_on: observed_on.get(),
+            };
+
+            saving.set(true);
+            error.set(None);
+            let refresh = refresh.clone();
+            let token = auth_token.get_untracked();
+            wasm_bindgen_futures::spawn_local(async move {
+                match add_ingredient_price(observation, token).await {
+                    Ok(_) => {
+                        price.set(String::new());
+                        observed_on.set(String::new());
+                        refresh();
+                    }
+                    Err(e) => error.set(Some(format!("Failed to add price: {}", e))),
+                }
+                saving.set(false);
+            });
+        }
+    };
+
+    let handle_delete = move |id: i64| {
+        saving.set(true);
+        error.set(None);
+        let refresh = refresh.clone();
+        let token = auth_token.get_untracked();
+        wasm_bindgen_futures::spawn_local(async move {
+            match delete_ingredient_price(id, token).await {
+                Ok(()) => refresh(),
+                Err(e) => error.set(Some(format!("Failed to delete price: {}", e))),
+            }
+            saving.set(false);
+        });
+    };
+
+    view! {
+      <div class="mt-6 border-t border-slate-200 dark:border-slate-600 pt-4">
+        <h3 class="text-sm font-semibold text-slate-600 dark:text-slate-400 mb-2">"Price History"</h3>
+
+        <Show when=move || error.get().is_some()>
+          <div class="mb-3 rounded bg-red-100 dark:bg-red-900/30 px-4 py-2 text-sm text-red-700 dark:text-red-400">
+            {move || error.get().unwrap_or_default()}
+          </div>
+        </Show>
+
+        <Suspense fallback=move || {
+          view! { <p class="text-sm text-slate-600 dark:text-slate-400">"Loading prices..."</p> }
+        }>
+          {move || {
+            prices
+              .get()
+              .map(|result| match result {
+                Ok(list) if list.is_empty() => {
+                  view! { <p class="text-sm text-slate-500 dark:text-slate-400">"No prices recorded yet."</p> }
+                    .into_any()
+                }
+                Ok(list) => {
+                  let handle_delete = handle_delete.clone();
+                  view! {
+                    <div>
+                      <PriceChart prices=list.clone() />
+                      <ul class="mt-3 max-h-40 overflow-y-auto divide-y divide-slate-200 dark:divide-slate-600 text-sm">
+                        {list
+                          .into_iter()
+                          .rev()
+                          .map(|p| {
+                            let handle_delete = handle_delete.clone();
+                            let price_id = p.id;
+                            let per_kg = p
+                              .price_per_kg()
+                              .map(|v| format!(" (${:.2}/kg)", v))
+                              .unwrap_or_default();
+                            view! {
+                              <li class="flex items-center justify-between gap-2 py-1 text-slate-700 dark:text-slate-300">
+                                <span>
+                                  {format!(
+                                    "{} · {} · ${:.2} for {}g{}",
+                                    p.observed_on,
+                                    p.store_label(),
+                                    p.price,
+                                    p.package_size_g,
+                                    per_kg,
+                                  )}
+                                </span>
+                                <button
+                                  class="text-xs text-red-600 hover:text-red-800 dark:text-red-400 disabled:opacity-50"
+                                  disabled=move || saving.get()
+                                  on:click=move |_| {
+                                    if let Some(id) = price_id {
+                                      handle_delete(id);
+                                    }
+                                  }
+                                >
+                                  "Remove"
+                                </button>
+                              </li>
+                            }
+                          })
+                          .collect_view()}
+                      </ul>
+                    </div>
+                  }
+                    .into_any()
+                }
+                Err(e) => {
+                  view! { <p class="text-sm text-red-600">{format!("Failed to load prices: {}", e)}</p> }
+                    .into_any()
+                }
+              })
+          }}
+        </Suspense>
+
+        <div class="mt-3 grid grid-cols-2 gap-3 sm:grid-cols-4">
+          <div>
+            <label class=LABEL_CLASS>"Price ($)"</label>
+            <input
+              type="number"
+              step="0.01"
+              class=INPUT_CLASS
+              prop:value=move || price.get()
+              on:input=move |ev| price.set(event_target_value(&ev))
+            />
+          </div>
+          <div>
+            <label class=LABEL_CLASS>"Package (g)"</label>
+            <input
+              type="number"
+              step="0.1"
+              class=INPUT_CLASS
+              prop:value=move || package_size.get()
+              on:input=move |ev| package_size.set(event_target_value(&ev))
+            />
+          </div>
+          <div>
+            <label class=LABEL_CLASS>"Store"</label>
+            <input
+              type="text"
+              class=INPUT_CLASS
+              prop:value=move || store.get()
+              on:input=move |ev| store.set(event_target_value(&ev))
+            />
+          </div>
+          <div>
+            <label class=LABEL_CLASS>"Observed on"</label>
+            <input
+              type="date"
+              class=INPUT_CLASS
+              prop:value=move || observed_on.get()
+              on:input=move |ev| observed_on.set(event_target_value(&ev))
+            />
+          </div>
+        </div>
+        <div class="mt-2 flex justify-end">
+          <button
+            class="rounded bg-blue-600 px-3 py-1 text-sm font-medium text-white hover:bg-blue-700 disabled:bg-blue-300 dark:disabled:bg-blue-800"
+            disabled=move || saving.get()
+            on:click={
+              let handle_add = handle_add.clone();
+              move |_| handle_add()
+            }
+          >
+            "Add Price"
+          </button>
+        </div>
+      </div>
+    }
+}
@@ -10,7 +10,11 @@ use wasm_bindgen::JsCast;
 use crate::auth::AdminAuth;
 #[cfg(not(feature = "ssr"))]
 use crate::cache::{get_cache, set_cache, INGREDIENTS_CACHE_KEY};
-use crate::components::{CloseIcon, EditIcon, PlusIcon, UploadIcon, INPUT_CLASS, LABEL_CLASS};
+use crate::components::{
+    CloseIcon, EditIcon, MergeIcon, PlusIcon, UploadIcon, INPUT_CLASS, LABEL_CLASS,
+};
+use crate::ingredient_merge::IngredientMergeModal;
+use crate::ingredient_prices::{IngredientPrice, IngredientPriceHistory};
 
 // ============================================================================
 // Data Types
@@ -34,6 +38,8 @@ pub struct Ingredient {
     // Package info
     pub package_size_g: f32, // grams
     pub package_price: f32,  // price in local currency
+    #[serde(default)]
+    pub store: String, // where the current price was seen
 }
 
 impl Ingredient {
@@ -62,6 +68,7 @@ impl Ingredient {
             salt: 0.0,
             package_size_g: 0.0,
             package_price: 0.0,
+            store: String::new(),
         }
     }
 }
@@ -149,6 +156,115 @@ impl SendD1Database {
 // Server Functions
 // ============================================================================
 
+/// Build an Ingredient from a D1 row with a comma-separated `labels` column
+#[cfg(feature = "ssr")]
+pub(crate) fn ingredient_from_row(row: &serde_json::Value) -> Ingredient {
+    // Parse comma-separated labels, handling NULL/empty case
+    let labels: Vec<String> = row
+        .get("labels")
+        .and_then(|v| v.as_str())
+        .map(|s| s.split(',').map(|l| l.to_string()).collect())
+        .unwrap_or_default();
+
+    Ingredient {
+        id: row.get("id").and_then(|v| v.as_i64()),
+        name: row
+            .get("name")
+            .and_then(|v| v.as_str())
+            .unwrap_or("")
+            .to_string(),
+        labels,
+        calories: row.get("calories").and_then(|v| v.as_f64()).unwrap_or(0.0) as f32,
+        protein: row.get("protein").and_then(|v| v.as_f64()).unwrap_or(0.0) as f32,
+        fat: row.get("fat").and_then(|v| v.as_f64()).unwrap_or(0.0) as f32,
+        saturated_fat: row
+            .get("saturated_fat")
+            .and_then(|v| v.as_f64())
+            .unwrap_or(0.0) as f32,
+        carbs: row.get("carbs").and_then(|v| v.as_f64()).unwrap_or(0.0) as f32,
+        sugar: row.get("sugar").and_then(|v| v.as_f64()).unwrap_or(0.0) as f32,
+        fiber: row.get("fiber").and_then(|v| v.as_f64()).unwrap_or(0.0) as f32,
+        salt: row.get("salt").and_then(|v| v.as_f64()).unwrap_or(0.0) as f32,
+        package_size_g: row
+            .get("package_size_g")
+            .and_then(|v| v.as_f64())
+            .unwrap_or(0.0) as f32,
+        package_price: row
+            .get("package_price")
+            .and_then(|v| v.as_f64())
+            .unwrap_or(0.0) as f32,
+        store: row
+            .get("store")
+            .and_then(|v| v.as_str())
+            .unwrap_or("")
+            .to_string(),
+    }
+}
+
+/// Fail with a readable message if another ingredient already uses `name`.
+/// Trashed ingredients keep their name until purged, so those point at the trash.
+#[cfg(feature = "ssr")]
+async fn ensure_name_available(
+    db: &SendD1Database,
+    name: &str,
+    id: Option<i64>,
+) -> Result<(), ServerFnError> {
+    use send_wrapper::SendWrapper;
+
+    let existing = SendWrapper::new(async {
+        let stmt = db
+            .inner()
+            .prepare("SELECT id, deleted_at FROM ingredients WHERE name = ?");
+        let stmt = stmt.bind(&[name.into()])?;
+        stmt.first::<serde_json::Value>(None).await
+    })
+    .await
+    .map_err(|e| ServerFnError::new(format!("D1 query error: {:?}", e)))?;
+
+    let Some(row) = existing else {
+        return Ok(());
+    };
+    if id.is_some() && row.get("id").and_then(|v| v.as_i64()) == id {
+        return Ok(());
+    }
+    if row.get("deleted_at").is_some_and(|v| !v.is_null()) {
+        Err(ServerFnError::new(format!(
+            "An ingredient named \"{}\" is in the trash. Restore it from the trash instead.",
+            name
+        )))
+    } else {
+        Err(ServerFnError::new(format!(
+            "An ingredient named \"{}\" already exists",
+            name
+        )))
+    }
+}
+
+/// Load a single ingredient with its labels, used to snapshot state for the audit log
+#[cfg(feature = "ssr")]
+pub(crate) async fn fetch_ingredient(
+    db: &SendD1Database,
+    id: i64,
+) -> Result<Option<Ingredient>, ServerFnError> {
+    use send_wrapper::SendWrapper;
+
+    let row = SendWrapper::new(async {
+        let stmt = db.inner().prepare(
+            "SELECT i.id, i.name, i.calories, i.protein, i.fat, i.saturated_fat, i.carbs, i.sugar, i.fiber, i.salt, i.package_size_g, i.package_price, i.store, GROUP_CONCAT(il.label, ',') as labels
+             FROM ingredients i
+             LEFT JOIN ingredient_labels il ON i.id = il.ingredient_id
+             WHERE i.id = ?
+             GROUP BY i.id"
+        );
+        let stmt = stmt.bind(&[(id as f64).into()])?;
+        stmt.first::<serde_json::Value>(None).await
+    })
+    .await
+    .map_err(|e| ServerFnError::new(format!("D1 query error: {:?}", e)))?;
+
+    Ok(row.as_ref().map(ingredient_from_row))
+}
+
 /// Fetch all ingredients from D1 database
 #[server]
 pub async fn get_ingredients() -> Result<Vec<Ingredient>, ServerFnError> {
@@ -160,55 +276,17 @@ pub async fn get_ingredients() -> Result<Vec<Ingredient>, ServerFnError> {
     // This uses a single query instead of N+1 queries
     let ingredients = SendWrapper::new(async {
         let stmt = db.inner().prepare(
-            "SELECT i.id, i.name, i.calories, i.protein, i.fat, i.saturated_fat, i.carbs, i.sugar, i.fiber, i.salt, i.package_size_g, i.package_price, GROUP_CONCAT(il.label, ',') as labels
+            "SELECT i.id, i.name, i.calories, i.protein, i.fat, i.saturated_fat, i.carbs, i.sugar, i.fiber, i.salt, i.package_size_g, i.package_price, i.store, GROUP_CONCAT(il.label, ',') as labels
              FROM ingredients i
              LEFT JOIN ingredient_labels il ON i.id = il.ingredient_id
+             WHERE i.deleted_at IS NULL
              GROUP BY i.id
              ORDER BY i.name"
         );
         let results = stmt.all().await?;
         let rows: Vec<serde_json::Value> = results.results::<serde_json::Value>()?;
 
-        let ingredients: Vec<Ingredient> = rows
-            .into_iter()
-            .map(|row| {
-                // Parse comma-separated labels, handling NULL/empty case
-                let labels: Vec<String> = row
-                    .get("labels")
-                    .and_then(|v| v.as_str())
-                    .map(|s| s.split(',').map(|l| l.to_string()).collect())
-                    .unwrap_or_default();
-
-                Ingredient {
-                    id: row.get("id").and_then(|v| v.as_i64()),
-                    name: row
-                        .get("name")
-                        .and_then(|v| v.as_str())
-                        .unwrap_or("")
-                        .to_string(),
-                    labels,
-                    calories: row.get("calories").and_then(|v| v.as_f64()).unwrap_or(0.0) as f32,
-                    protein: row.get("protein").and_then(|v| v.as_f64()).unwrap_or(0.0) as f32,
-                    fat: row.get("fat").and_then(|v| v.as_f64()).unwrap_or(0.0) as f32,
-                    saturated_fat: row
-                        .get("saturated_fat")
-                        .and_then(|v| v.as_f64())
-                        .unwrap_or(0.0) as f32,
-                    carbs: row.get("carbs").and_then(|v| v.as_f64()).unwrap_or(0.0) as f32,
-                    sugar: row.get("sugar").and_then(|v| v.as_f64()).unwrap_or(0.0) as f32,
-                    fiber: row.get("fiber").and_then(|v| v.as_f64()).unwrap_or(0.0) as f32,
-                    salt: row.get("salt").and_then(|v| v.as_f64()).unwrap_or(0.0) as f32,
-                    package_size_g: row
-                        .get("package_size_g")
-                        .and_then(|v| v.as_f64())
-                        .unwrap_or(0.0) as f32,
-                    package_price: row
-                        .get("package_price")
-                        .and_then(|v| v.as_f64())
-                        .unwrap_or(0.0) as f32,
-                }
-            })
-            .collect();
+        let ingredients: Vec<Ingredient> = rows.iter().map(ingredient_from_row).collect();
 
         Ok::<_, worker::Error>(ingredients)
     })
@@ -220,14 +298,24 @@ pub async fn get_ingredients() -> Result<Vec<Ingredient>, ServerFnError> {
 
 /// Create a new ingredient
 #[server]
-pub async fn create_ingredient(ingredient: Ingredient) -> Result<Ingredient, ServerFnError> {
+pub async fn create_ingredient(
+    ingredient: Ingredient,
+    auth_token: Option<String>,
+) -> Result<Ingredient, ServerFnError> {
     use send_wrapper::SendWrapper;
 
+    use crate::audit::{record_audit, AuditAction, AuditEntity};
+    use crate::auth::resolve_actor;
+    use crate::ingredient_prices::record_price_if_changed;
+
     let db = expect_context::<SendD1Database>();
+    let actor = resolve_actor(auth_token.as_deref()).await;
+
+    ensure_name_available(&db, &ingredient.name, None).await?;
 
     let result = SendWrapper::new(async {
         let stmt = db.inner().prepare(
-            "INSERT INTO ingredients (name, calories, protein, fat, saturated_fat, carbs, sugar, fiber, salt, package_size_g, package_price) VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?) RETURNING id"
+            "INSERT INTO ingredients (name, calories, protein, fat, saturated_fat, carbs, sugar, fiber, salt, package_size_g, package_price, store) VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?) RETURNING id"
         );
 
         let stmt = stmt.bind(&[
@@ -242,6 +330,7 @@ pub async fn create_ingredient(ingredient: Ingredient) -> Result<Ingredient, Ser
             ingredient.salt.into(),
             ingredient.package_size_g.into(),
             ingredient.package_price.into(),
+            ingredient.store.clone().into(),
         ])?;
 
         stmt.first::<serde_json::Value>(None).await
@@ -268,26 +357,54 @@ pub async fn create_ingredient(ingredient: Ingredient) -> Result<Ingredient, Ser
 
     log::info!("Created ingredient: {} (id: {})", ingredient.name, id);
 
-    Ok(Ingredient {
+    let ingredient = Ingredient {
         id: Some(id),
         ..ingredient
-    })
+    };
+
+    record_price_if_changed(&db, id, None, &ingredient).await?;
+
+    record_audit(
+        &db,
+        AuditEntity::Ingredient,
+        id,
+        AuditAction::Create,
+        None,
+        Some(&ingredient),
+        &actor,
+    )
+    .await?;
+
+    Ok(ingredient)
 }
 
 /// Update an existing ingredient
 #[server]
-pub async fn update_ingredient(ingredient: Ingredient) -> Result<(), ServerFnError> {
+pub async fn update_ingredient(
+    ingredient: Ingredient,
+    auth_token: Option<String>,
+) -> Result<(), ServerFnError> {
     use send_wrapper::SendWrapper;
 
+    use crate::audit::{record_audit, AuditAction, AuditEntity};
+    use crate::auth::resolve_actor;
+    use crate::ingredient_prices::record_price_if_changed;
+
     let db = expect_context::<SendD1Database>();
+    let actor = resolve_actor(auth_token.as_deref()).await;
 
     let id = ingredient
         .id
         .ok_or_else(|| ServerFnError::new("Ingredient ID is required for update"))?;
 
+    ensure_name_available(&db, &ingredient.name, Some(id)).await?;
+
+    let before = fetch_ingredient(&db, id).await?;
+    let after = ingredient.clone();
+
     SendWrapper::new(async {
         let stmt = db.inner().prepare(
-            "UPDATE ingredients SET name = ?, calories = ?, protein = ?, fat = ?, saturated_fat = ?, carbs = ?, sugar = ?, fiber = ?, salt = ?, package_size_g = ?, package_price = ?, updated_at = datetime('now') WHERE id = ?"
+            "UPDATE ingredients SET name = ?, calories = ?, protein = ?, fat = ?, saturated_fat = ?, carbs = ?, sugar = ?, fiber = ?, salt = ?, package_size_g = ?, package_price = ?, store = ?, updated_at = datetime('now') WHERE id = ?"
         );
 
         let stmt = stmt.bind(&[
@@ -302,6 +419,7 @@ pub async fn update_ingredient(ingredient: Ingredient) -> Result<(), ServerFnErr
             ingredient.salt.into(),
             ingredient.package_size_g.into(),
             ingredient.package_price.into(),
+            ingredient.store.into(),
             (id as f64).into(),
         ])?;
 
@@ -334,36 +452,78 @@ pub async fn update_ingredient(ingredient: Ingredient) -> Result<(), ServerFnErr
         .map_err(|e| ServerFnError::new(format!("D1 insert label error: {:?}", e)))?;
     }
 
+    record_price_if_changed(&db, id, before.as_ref(), &after).await?;
+
+    record_audit(
+        &db,
+        AuditEntity::Ingredient,
+        id,
+        AuditAction::Update,
+        before.as_ref(),
+        Some(&after),
+        &actor,
+    )
+    .await?;
+
     log::info!("Updated ingredient id: {}", id);
     Ok(())
 }
 
-/// Delete an ingredient
+/// Move an ingredient to the trash. It stays referenced by existing recipes
+/// until it is purged.
 #[server]
-pub async fn delete_ingredient(id: i64) -> Result<(), ServerFnError> {
+pub async fn delete_ingredient(id: i64, auth_token: Option<String>) -> Result<(), ServerFnError> {
     use send_wrapper::SendWrapper;
 
+    use crate::audit::{record_audit, AuditAction, AuditEntity};
+    use crate::auth::resolve_actor;
+
     let db = expect_context::<SendD1Database>();
+    let actor = resolve_actor(auth_token.as_deref()).await;
+
+    let before = fetch_ingredient(&db, id).await?;
 
-    // Labels will be deleted by CASCADE
     SendWrapper::new(async {
-        let stmt = db.inner().prepare("DELETE FROM ingredients WHERE id = ?");
+        let stmt = db.inner().prepare(
+            "UPDATE ingredients SET deleted_at = datetime('now') WHERE id = ? AND deleted_at IS NULL",
+        );
         let stmt = stmt.bind(&[(id as f64).into()])?;
         stmt.run().await
     })
     .await
     .map_err(|e| ServerFnError::new(format!("D1 delete error: {:?}", e)))?;
 
-    log::info!("Deleted ingredient id: {}", id);
+    if before.is_some() {
+        record_audit(
+            &db,
+            AuditEntity::Ingredient,
+            id,
+            AuditAction::Delete,
+            before.as_ref(),
+            None,
+            &actor,
+        )
+        .await?;
+    }
+
+    log::info!("Moved ingredient id: {} to trash", id);
     Ok(())
 }
 
 /// Bulk upsert ingredients - inserts new ingredients or updates existing ones by name
 #[server]
-pub async fn bulk_upsert_ingredients(ingredients: Vec<Ingredient>) -> Result<usize, ServerFnError> {
+pub async fn bulk_upsert_ingredients(
+    ingredients: Vec<Ingredient>,
+    auth_token: Option<String>,
+) -> Result<usize, ServerFnError> {
     use send_wrapper::SendWrapper;
 
+    use crate::audit::{record_audit, AuditAction, AuditEntity};
+    use crate::auth::resolve_actor;
+    use crate::ingredient_prices::record_price_if_changed;
+
     let db = expect_context::<SendD1Database>();
+    let actor = resolve_actor(auth_token.as_deref()).await;
     let mut count = 0;
 
     for ingredient in ingredients {
@@ -379,17 +539,19 @@ pub async fn bulk_upsert_ingredients(ingredients: Vec<Ingredient>) -> Result<usi
         .map_err(|e| ServerFnError::new(format!("D1 query error: {:?}", e)))?;
 
         let ingredient_id: i64;
+        let before: Option<Ingredient>;
 
         if let Some(row) = existing {
-            // Update existing
+            // Update existing (re-importing a trashed ingredient restores it)
             ingredient_id = row
                 .get("id")
                 .and_then(|v| v.as_i64())
                 .ok_or_else(|| ServerFnError::new("Failed to get ID"))?;
+            before = fetch_ingredient(&db, ingredient_id).await?;
 
             SendWrapper::new(async {
                 let stmt = db.inner().prepare(
-                    "UPDATE ingredients SET calories = ?, protein = ?, fat = ?, saturated_fat = ?, carbs = ?, sugar = ?, fiber = ?, salt = ?, package_size_g = ?, package_price = ?, updated_at = datetime('now') WHERE id = ?"
+                    "UPDATE ingredients SET calories = ?, protein = ?, fat = ?, saturated_fat = ?, carbs = ?, sugar = ?, fiber = ?, salt = ?, package_size_g = ?, package_price = ?, deleted_at = NULL, updated_at = datetime('now') WHERE id = ?"
                 );
                 let stmt = stmt.bind(&[
                     ingredient.calories.into(),
@@ -418,7 +580,7 @@ pub async fn bulk_upsert_ingredients(ingredients: Vec<Ingredient>) -> Result<usi
             // Insert new
             let result = SendWrapper::new(async {
                 let stmt = db.inner().prepare(
-                    "INSERT INTO ingredients (name, calories, protein, fat, saturated_fat, carbs, sugar, fiber, salt, package_size_g, package_price) VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?) RETURNING id"
+                    "INSERT INTO ingredients (name, calories, protein, fat, saturated_fat, carbs, sugar, fiber, salt, package_size_g, package_price, store) VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?) RETURNING id"
                 );
                 let stmt = stmt.bind(&[
                     ingredient.name.clone().into(),
@@ -432,6 +594,7 @@ pub async fn bulk_upsert_ingredients(ingredients: Vec<Ingredient>) -> Result<usi
                     ingredient.salt.into(),
                     ingredient.package_size_g.into(),
                     ingredient.package_price.into(),
+                    ingredient.store.clone().into(),
                 ])?;
                 stmt.first::<serde_json::Value>(None).await
             })
@@ -441,6 +604,7 @@ pub async fn bulk_upsert_ingredients(ingredients: Vec<Ingredient>) -> Result<usi
             ingredient_id = result
                 .and_then(|v| v.get("id").and_then(|id| id.as_i64()))
                 .ok_or_else(|| ServerFnError::new("Failed to get inserted ID"))?;
+            before = None;
 
             log::info!("Inserted ingredient: {}", ingredient.name);
         }
@@ -469,6 +633,32 @@ pub async fn bulk_upsert_ingredients(ingredients: Vec<Ingredient>) -> Result<usi
             .map_err(|e| ServerFnError::new(format!("D1 insert label error: {:?}", e)))?;
         }
 
+        let action = if before.is_some() {
+            AuditAction::Update
+        } else {
+            AuditAction::Create
+        };
+        // Imports carry no store, so an updated ingredient keeps the one it had
+        let after = Ingredient {
+            id: Some(ingredient_id),
+            store: before
+                .as_ref()
+                .map(|b| b.store.clone())
+                .unwrap_or(ingredient.store.clone()),
+            ..ingredient
+        };
+        record_price_if_changed(&db, ingredient_id, before.as_ref(), &after).await?;
+        record_audit(
+            &db,
+            AuditEntity::Ingredient,
+            ingredient_id,
+            action,
+            before.as_ref(),
+            Some(&after),
+            &actor,
+        )
+        .await?;
+
         count += 1;
     }
 
@@ -598,6 +788,8 @@ fn IngredientModal(
     editing: RwSignal<Option<Ingredient>>,
     on_save: impl Fn() + Clone + Send + Sync + 'static,
 ) -> impl IntoView {
+    let auth_token = expect_context::<AdminAuth>().token;
+
     // Form fields
     let name = RwSignal::new(String::new());
     let labels = RwSignal::new(Vec::<String>::new());
@@ -612,8 +804,39 @@ fn IngredientModal(
     let salt = RwSignal::new(String::new());
     let package_size = RwSignal::new(String::new());
     let package_price = RwSignal::new(String::new());
+    let store = RwSignal::new(String::new());
     let error = RwSignal::new(Option::<String>::None);
     let saving = RwSignal::new(false);
+    let show_delete_confirm = RwSignal::new(false);
+    let trigger_delete = RwSignal::new(false);
+
+    // Handle delete via Effect
+    {
+        let on_save = on_save.clone();
+        Effect::new(move || {
+            if trigger_delete.get() {
+                trigger_delete.set(false);
+                if let Some(id) = editing.get().and_then(|ing| ing.id) {
+                    saving.set(true);
+                    let on_save = on_save.clone();
+                    let token = auth_token.get_untracked();
+                    wasm_bindgen_futures::spawn_local(async move {
+                        match delete_ingredient(id, token).await {
+                            Ok(()) => {
+                                show.set(false);
+                                editing.set(None);
+                                on_save();
+                            }
+                            Err(e) => {
+                                error.set(Some(format!("Failed to delete: {}", e)));
+                            }
+                        }
+                        saving.set(false);
+                    });
+                }
+            }
+        });
+    }
 
     // Populate form when editing changes
     Effect::new(move || {
@@ -630,6 +853,7 @@ fn IngredientModal(
             salt.set(ing.salt.to_string());
             package_size.set(ing.package_size_g.to_string());
             package_price.set(ing.package_price.to_string());
+            store.set(ing.store.clone());
         } else {
             // Reset form for new ingredient
             name.set(String::new());
@@ -644,9 +868,11 @@ fn IngredientModal(
             salt.set(String::new());
             package_size.set(String::new());
             package_price.set(String::new());
+            store.set(String::new());
         }
         new_label.set(String::new());
         error.set(None);
+        show_delete_confirm.set(false);
     });
 
     let close = move || {
@@ -697,15 +923,17 @@ fn IngredientModal(
                 salt: salt.get().parse().unwrap_or(0.0),
                 package_size_g: package_size.get().parse().unwrap_or(0.0),
                 package_price: package_price.get().parse().unwrap_or(0.0),
+                store: store.get().trim().to_string(),
             };
 
             saving.set(true);
             let on_save = on_save.clone();
+            let token = auth_token.get_untracked();
             wasm_bindgen_futures::spawn_local(async move {
                 let result = if ingredient.id.is_some() {
-                    update_ingredient(ingredient).await.map(|_| ())
+                    update_ingredient(ingredient, token).await.map(|_| ())
                 } else {
-                    create_ingredient(ingredient).await.map(|_| ())
+                    create_ingredient(ingredient, token).await.map(|_| ())
                 };
 
                 saving.set(false);
@@ -924,25 +1152,89 @@ fn IngredientModal(
                   on:input=move |ev| package_price.set(event_target_value(&ev))
                 />
               </div>
+              <div class="col-span-2">
+                <label class=LABEL_CLASS>"Store"</label>
+                <input
+                  type="text"
+                  class=INPUT_CLASS
+                  prop:value=move || store.get()
+                  on:input=move |ev| store.set(event_target_value(&ev))
+                  placeholder="Where this price was seen"
+                />
+              </div>
             </div>
 
-            <div class="mt-6 flex justify-end gap-3">
-              <button
-                class="rounded bg-slate-200 dark:bg-slate-600 px-4 py-2 font-medium text-slate-700 dark:text-slate-200 hover:bg-slate-300 dark:hover:bg-slate-500"
-                on:click=move |_| close()
-              >
-                "Cancel"
-              </button>
-              <button
-                class="rounded bg-blue-600 px-4 py-2 font-medium text-white hover:bg-blue-700 disabled:bg-blue-300 dark:disabled:bg-blue-800"
-                disabled=move || saving.get()
-                on:click={
-                  let handle_save = handle_save.clone();
-                  move |_| handle_save()
-                }
-              >
-                {move || if saving.get() { "Saving..." } else { "Save" }}
-              </button>
+            // Price history is only available once the ingredient is saved
+            {move || {
+              editing
+                .get()
+                .and_then(|ing| ing.id)
+                .map(|id| {
+                  view! {
+                    <IngredientPriceHistory
+                      ingredient_id=id
+                      on_current_change=move |latest: IngredientPrice| {
+                        package_price.set(latest.price.to_string());
+                        package_size.set(latest.package_size_g.to_string());
+                        store.set(latest.store);
+                      }
+                    />
+                  }
+                })
+            }}
+
+            <div class="mt-6 flex justify-between gap-3">
+              <div>
+                <Show when=move || editing.get().and_then(|i| i.id).is_some()>
+                  <Show
+                    when=move || show_delete_confirm.get()
+                    fallback=move || {
+                      view! {
+                        <button
+                          class="rounded bg-red-100 dark:bg-red-900/30 px-4 py-2 font-medium text-red-700 dark:text-red-400 hover:bg-red-200 dark:hover:bg-red-900/50"
+                          on:click=move |_| show_delete_confirm.set(true)
+                        >
+                          "Delete"
+                        </button>
+                      }
+                    }
+                  >
+                    <div class="flex items-center gap-2">
+                      <span class="text-sm text-red-700 dark:text-red-400">"Move to trash?"</span>
+                      <button
+                        class="rounded bg-red-600 px-3 py-1 text-sm font-medium text-white hover:bg-red-700"
+                        on:click=move |_| trigger_delete.set(true)
+                      >
+                        "Yes, delete"
+                      </button>
+                      <button
+                        class="rounded bg-slate-200 dark:bg-slate-600 px-3 py-1 text-sm font-medium text-slate-700 dark:text-slate-200 hover:bg-slate-300 dark:hover:bg-slate-500"
+                        on:click=move |_| show_delete_confirm.set(false)
+                      >
+                        "Cancel"
+                      </button>
+                    </div>
+                  </Show>
+                </Show>
+              </div>
+              <div class="flex gap-3">
+                <button
+                  class="rounded bg-slate-200 dark:bg-slate-600 px-4 py-2 font-medium text-slate-700 dark:text-slate-200 hover:bg-slate-300 dark:hover:bg-slate-500"
+                  on:click=move |_| close()
+                >
+                  "Cancel"
+                </button>
+                <button
+                  class="rounded bg-blue-600 px-4 py-2 font-medium text-white hover:bg-blue-700 disabled:bg-blue-300 dark:disabled:bg-blue-800"
+                  disabled=move || saving.get()
+                  on:click={
+                    let handle_save = handle_save.clone();
+                    move |_| handle_save()
+                  }
+                >
+                  {move || if saving.get() { "Saving..." } else { "Save" }}
+                </button>
+              </div>
             </div>
           </div>
         </div>
@@ -1043,6 +1335,7 @@ fn parse_tsv_ingredients(input: &str) -> Vec<ParsedLine> {
             fiber: parse_f32(parts[8]),
             protein: parse_f32(parts[9]),
             salt: parse_f32(parts[10]),
+            store: String::new(),
         };
 
         results.push(ParsedLine::Ingredient(ingredient));
@@ -1057,6 +1350,7 @@ fn BulkImportModal(
     show: RwSignal<bool>,
     on_save: impl Fn() + Clone + Send + Sync + 'static,
 ) -> impl IntoView {
+    let auth_token = expect_context::<AdminAuth>().token;
     let tsv_input = RwSignal::new(String::new());
     let parsed_results = RwSignal::new(Vec::<ParsedLine>::new());
     let error = RwSignal::new(Option::<String>::None);
@@ -1098,8 +1392,9 @@ fn BulkImportModal(
 
             importing.set(true);
             let on_save = on_save.clone();
+            let token = auth_token.get_untracked();
             wasm_bindgen_futures::spawn_local(async move {
-                let result = bulk_upsert_ingredients(ingredients).await;
+                let result = bulk_upsert_ingredients(ingredients, token).await;
 
                 importing.set(false);
                 match result {
@@ -1525,7 +1820,11 @@ fn IngredientTable(
                       parts.push(format!("Labels: {}", i.labels.join(", ")));
                     }
                     parts.push(format!("Package: {}g", i.package_size_g));
-                    parts.push(format!("Price: ${:.2}", i.package_price));
+                    if i.store.is_empty() {
+                      parts.push(format!("Price: ${:.2}", i.package_price));
+                    } else {
+                      parts.push(format!("Price: ${:.2} at {}", i.package_price, i.store));
+                    }
                     parts.join("\n")
                   });
                 let name = ing.with_value(|i| i.name.clone());
@@ -1685,6 +1984,7 @@ pub fn Ingredients() -> impl IntoView {
     let show_modal = RwSignal::new(false);
     let editing_ingredient = RwSignal::new(Option::<Ingredient>::None);
     let show_bulk_import = RwSignal::new(false);
+    let show_merge = RwSignal::new(false);
 
     // Signal to hold cached data (client-side only, loaded before resource resolves)
     let cached_data = RwSignal::new(Option::<Vec<Ingredient>>::None);
@@ -1752,6 +2052,13 @@ pub fn Ingredients() -> impl IntoView {
                 <UploadIcon />
                 "Bulk Import"
               </button>
+              <button
+                class="flex items-center gap-2 rounded bg-teal-600 px-4 py-2 text-sm font-medium text-white hover:bg-teal-700"
+                on:click=move |_| show_merge.set(true)
+              >
+                <MergeIcon />
+                "Merge Duplicates"
+              </button>
             </Show>
             <div class="flex items-center gap-3 bg-white dark:bg-slate-800 rounded-lg px-4 py-2 shadow-sm">
               <span class="text-sm font-medium text-slate-700 dark:text-slate-300">"View nutrients:"</span>
@@ -1889,6 +2196,7 @@ pub fn Ingredients() -> impl IntoView {
 
         <IngredientModal show=show_modal editing=editing_ingredient on_save=refetch />
         <BulkImportModal show=show_bulk_import on_save=refetch />
+        <IngredientMergeModal show=show_merge on_merge=refetch />
       </div>
     }
 }
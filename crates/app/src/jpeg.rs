@@ -0,0 +1,454 @@
+//! JPEG codec module
+//!
+//! JPEG decoding and encoding for the server-side image pipeline, on top of the
+//! `jpeg-decoder` and `jpeg-encoder` crates. Decoding covers baseline and progressive
+//! files with any chroma subsampling, converts them to RGB and reads the EXIF orientation.
+//! Photos too big to decode whole are scaled down while decoding. Metadata can also be
+//! stripped from any JPEG without re-encoding it.
+
+use std::fmt;
+
+use crate::image_pipeline::{RgbImage, MAX_PIXELS};
+
+// ============================================================================
+// Errors
+// ============================================================================
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum JpegError {
+    /// The data ended before the image did
+    Truncated,
+    /// Bigger than `MAX_PIXELS` even at the decoder's smallest scale
+    TooLarge,
+    /// A valid JPEG using a feature the decoder doesn't implement
+    Unsupported(String),
+    /// Corrupt or malformed data
+    Invalid(String),
+}
+
+impl fmt::Display for JpegError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            JpegError::Truncated => write!(f, "JPEG data is truncated"),
+            JpegError::TooLarge => write!(f, "JPEG is too large to decode"),
+            JpegError::Unsupported(what) => write!(f, "unsupported JPEG: {}", what),
+            JpegError::Invalid(what) => write!(f, "invalid JPEG: {}", what),
+        }
+    }
+}
+
+impl std::error::Error for JpegError {}
+
+impl From<jpeg_decoder::Error> for JpegError {
+    fn from(e: jpeg_decoder::Error) -> Self {
+        match e {
+            jpeg_decoder::Error::Io(e) if e.kind() == std::io::ErrorKind::UnexpectedEof => {
+                JpegError::Truncated
+            }
+            jpeg_decoder::Error::Unsupported(feature) => {
+                JpegError::Unsupported(format!("{:?}", feature))
+            }
+            jpeg_decoder::Error::Format(what) => JpegError::Invalid(what),
+            e => JpegError::Invalid(e.to_string()),
+        }
+    }
+}
+
+// ============================================================================
+// Decoding
+// ============================================================================
+
+/// A decoded JPEG and the EXIF orientation (1-8) it should be displayed with
+pub struct DecodedJpeg {
+    pub image: RgbImage,
+    pub orientation: u8,
+}
+
+/// Decode a JPEG to RGB. One bigger than `MAX_PIXELS` is decoded at a half, quarter or
+/// eighth of its size, whichever is the largest that fits.
+pub fn decode(data: &[u8]) -> Result<DecodedJpeg, JpegError> {
+    let mut decoder = jpeg_decoder::Decoder::new(data);
+    decoder.read_info()?;
+    let info = decoder
+        .info()
+        .ok_or_else(|| JpegError::Invalid("no frame header".to_string()))?;
+
+    // The decoder scales by eighths, rounding up
+    let scaled = |size: u16, eighths: u32| (size as u32 * eighths).div_ceil(8);
+    let eighths = [8, 4, 2, 1]
+        .into_iter()
+        .find(|&eighths| {
+            scaled(info.width, eighths) as usize * scaled(info.height, eighths) as usize
+                <= MAX_PIXELS
+        })
+        .ok_or(JpegError::TooLarge)?;
+    if eighths < 8 {
+        decoder.scale(
+            scaled(info.width, eighths) as u16,
+            scaled(info.height, eighths) as u16,
+        )?;
+    }
+    // Room for the largest pixel format, CMYK, as a guard on the scaling above
+    decoder.set_max_decoding_buffer_size(MAX_PIXELS * 4);
+
+    let pixels = decoder.decode()?;
+    let info = decoder
+        .info()
+        .ok_or_else(|| JpegError::Invalid("no frame header".to_string()))?;
+    let rgb = match info.pixel_format {
+        jpeg_decoder::PixelFormat::RGB24 => pixels,
+        jpeg_decoder::PixelFormat::L8 => pixels.iter().flat_map(|&l| [l, l, l]).collect(),
+        // The decoder gives the inverse of each ink, so white is all 255
+        jpeg_decoder::PixelFormat::CMYK32 => pixels
+            .chunks_exact(4)
+            .flat_map(|p| {
+                let k = p[3] as u32;
+                [0, 1, 2].map(|i| (p[i] as u32 * k / 255) as u8)
+            })
+            .collect(),
+        jpeg_decoder::PixelFormat::L16 => {
+            return Err(JpegError::Unsupported("16-bit samples".to_string()))
+        }
+    };
+    let image = RgbImage {
+        width: info.width as u32,
+        height: info.height as u32,
+        pixels: rgb,
+    };
+    if image.pixels.len() != image.width as usize * image.height as usize * 3 {
+        return Err(JpegError::Invalid("wrong number of pixels".to_string()));
+    }
+
+    let orientation = decoder.exif_data().and_then(exif_orientation).unwrap_or(1);
+    Ok(DecodedJpeg { image, orientation })
+}
+
+/// The orientation tag (0x0112) from IFD0 of an EXIF TIFF block
+pub(crate) fn exif_orientation(tiff: &[u8]) -> Option<u8> {
+    let big_endian = match tiff.get(..2)? {
+        b"MM" => true,
+        b"II" => false,
+        _ => return None,
+    };
+    let u16_at = |pos: usize| -> Option<u16> {
+        let b = tiff.get(pos..pos + 2)?;
+        Some(if big_endian {
+            u16::from_be_bytes([b[0], b[1]])
+        } else {
+            u16::from_le_bytes([b[0], b[1]])
+        })
+    };
+    let u32_at = |pos: usize| -> Option<u32> {
+        let b = tiff.get(pos..pos + 4)?;
+        let bytes = [b[0], b[1], b[2], b[3]];
+        Some(if big_endian {
+            u32::from_be_bytes(bytes)
+        } else {
+            u32::from_le_bytes(bytes)
+        })
+    };
+    if u16_at(2)? != 42 {
+        return None;
+    }
+    let ifd = u32_at(4)? as usize;
+    let entries = u16_at(ifd)? as usize;
+    (0..entries)
+        .map(|i| ifd + 2 + i * 12)
+        .find(|&entry| u16_at(entry) == Some(0x0112))
+        .and_then(|entry| u16_at(entry + 8))
+        .filter(|o| (1..=8).contains(o))
+        .map(|o| o as u8)
+}
+
+// ============================================================================
+// Metadata
+// ============================================================================
+
+/// APP segments kept when stripping metadata: JFIF (APP0), the ICC colour profile (APP2)
+/// and Adobe's colour transform (APP14), which all affect how the image looks
+const KEPT_APP_MARKERS: [u8; 3] = [0xE0, 0xE2, 0xEE];
+
+/// SOFn markers, as opposed to DHT, JPG and DAC in the same range
+fn is_frame_header(marker: u8) -> bool {
+    (0xC0..=0xCF).contains(&marker) && !matches!(marker, 0xC4 | 0xC8 | 0xCC)
+}
+
+/// A marker segment in a JPEG's header
+struct Segment<'a> {
+    marker: u8,
+    /// Position of the marker in the file
+    offset: usize,
+    payload: &'a [u8],
+}
+
+/// The segments up to and including the first scan header
+fn header_segments(data: &[u8]) -> Result<Vec<Segment<'_>>, JpegError> {
+    let invalid = |what: &str| JpegError::Invalid(what.to_string());
+    if !data.starts_with(&[0xFF, 0xD8]) {
+        return Err(invalid("missing start of image marker"));
+    }
+    let mut segments = Vec::new();
+    let mut pos = 2;
+    loop {
+        // Markers may be preceded by any number of 0xFF fill bytes
+        while data.get(pos) == Some(&0xFF) && data.get(pos + 1) == Some(&0xFF) {
+            pos += 1;
+        }
+        if data.get(pos) != Some(&0xFF) {
+            return Err(JpegError::Truncated);
+        }
+        let marker = *data.get(pos + 1).ok_or(JpegError::Truncated)?;
+        if marker == 0xD9 {
+            return Err(invalid("no image data"));
+        }
+        let length = data
+            .get(pos + 2..pos + 4)
+            .map(|b| u16::from_be_bytes([b[0], b[1]]) as usize)
+            .ok_or(JpegError::Truncated)?;
+        if length < 2 {
+            return Err(invalid("bad segment length"));
+        }
+        let payload = data
+            .get(pos + 4..pos + 2 + length)
+            .ok_or(JpegError::Truncated)?;
+        segments.push(Segment {
+            marker,
+            offset: pos,
+            payload,
+        });
+        if marker == 0xDA {
+            if !segments.iter().any(|s| is_frame_header(s.marker)) {
+                return Err(invalid("scan before frame header"));
+            }
+            return Ok(segments);
+        }
+        pos += 2 + length;
+    }
+}
+
+/// The EXIF orientation (1-8) of a JPEG, 1 when it doesn't say
+pub fn read_orientation(data: &[u8]) -> u8 {
+    header_segments(data)
+        .unwrap_or_default()
+        .into_iter()
+        .filter(|s| s.marker == 0xE1)
+        .find_map(|s| exif_orientation(s.payload.strip_prefix(b"Exif\0\0")?))
+        .unwrap_or(1)
+}
+
+/// Copy a JPEG without its EXIF, XMP, IPTC and comment segments, leaving the compressed
+/// image untouched. An `orientation` other than 1 is written back as an EXIF block
+/// holding only that tag.
+pub fn strip_metadata(data: &[u8], orientation: u8) -> Result<Vec<u8>, JpegError> {
+    let segments = header_segments(data)?;
+    // EXIF conventionally directly follows the JFIF header, or the start of image without one
+    let exif_index = usize::from(segments[0].marker == 0xE0);
+    let mut out = Vec::with_capacity(data.len());
+    out.extend_from_slice(&[0xFF, 0xD8]);
+    for (
+        i,
+        &Segment {
+            marker,
+            offset,
+            payload,
+        },
+    ) in segments.iter().enumerate()
+    {
+        if i == exif_index && orientation != 1 {
+            out.extend(orientation_segment(orientation));
+        }
+        if marker == 0xDA {
+            // The scan header, the entropy-coded data and everything after it
+            out.extend_from_slice(&data[offset..]);
+            break;
+        }
+        let is_metadata = marker == 0xFE
+            || ((0xE0..=0xEF).contains(&marker) && !KEPT_APP_MARKERS.contains(&marker));
+        if !is_metadata {
+            out.extend_from_slice(&data[offset..offset + 4 + payload.len()]);
+        }
+    }
+    Ok(out)
+}
+
+/// An APP1 segment with a big-endian EXIF block holding just the orientation tag
+fn orientation_segment(orientation: u8) -> Vec<u8> {
+    let mut segment = vec![0xFF, 0xE1, 0x00, 0x22];
+    segment.extend_from_slice(b"Exif\0\0");
+    // TIFF header, then IFD0 at offset 8 with one SHORT entry and no next IFD
+    segment.extend_from_slice(&[b'M', b'M', 0x00, 0x2A, 0x00, 0x00, 0x00, 0x08, 0x00, 0x01]);
+    segment.extend_from_slice(&[0x01, 0x12, 0x00, 0x03, 0x00, 0x00, 0x00, 0x01]);
+    segment.extend_from_slice(&[0x00, orientation, 0x00, 0x00]);
+    segment.extend_from_slice(&[0x00, 0x00, 0x00, 0x00]);
+    segment
+}
+
+// ============================================================================
+// Encoding
+// ============================================================================
+
+/// Encode an RGB image as a baseline JPEG. Below quality 90 the chroma is subsampled 4:2:0.
+pub fn encode(image: &RgbImage, quality: u8) -> Vec<u8> {
+    let mut out = Vec::new();
+    let encoder = jpeg_encoder::Encoder::new(&mut out, quality);
+    // Writing to memory only fails for images over 65535 pixels on a side, which the
+    // pipeline never produces
+    if let Err(e) = encoder.encode(
+        &image.pixels,
+        image.width as u16,
+        image.height as u16,
+        jpeg_encoder::ColorType::Rgb,
+    ) {
+        log::warn!("Failed to encode JPEG: {}", e);
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A smooth gradient, which survives compression with little loss
+    fn gradient(width: u32, height: u32) -> RgbImage {
+        let mut image = RgbImage::new(width, height);
+        for y in 0..height {
+            for x in 0..width {
+                image.set(
+                    x,
+                    y,
+                    [(x * 255 / width) as u8, (y * 255 / height) as u8, 96],
+                );
+            }
+        }
+        image
+    }
+
+    fn assert_close(expected: &RgbImage, actual: &RgbImage) {
+        assert_eq!(
+            (actual.width, actual.height),
+            (expected.width, expected.height)
+        );
+        for y in 0..expected.height {
+            for x in 0..expected.width {
+                let (e, a) = (expected.get(x, y), actual.get(x, y));
+                for channel in 0..3 {
+                    assert!(
+                        e[channel].abs_diff(a[channel]) <= 8,
+                        "pixel ({}, {}): {:?} decoded as {:?}",
+                        x,
+                        y,
+                        e,
+                        a
+                    );
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn encode_decode_roundtrip() {
+        // Not a whole number of blocks, so the edge blocks are padded and cropped
+        let image = gradient(21, 13);
+        let decoded = decode(&encode(&image, 95)).unwrap();
+        assert_eq!(decoded.orientation, 1);
+        assert_close(&image, &decoded.image);
+    }
+
+    #[test]
+    fn progressive_jpegs_decode() {
+        let image = gradient(40, 24);
+        let mut data = Vec::new();
+        let mut encoder = jpeg_encoder::Encoder::new(&mut data, 95);
+        encoder.set_progressive(true);
+        encoder
+            .encode(&image.pixels, 40, 24, jpeg_encoder::ColorType::Rgb)
+            .unwrap();
+        assert_close(&image, &decode(&data).unwrap().image);
+    }
+
+    #[test]
+    fn truncated_data_is_an_error() {
+        let data = encode(&gradient(32, 32), 90);
+        assert_eq!(
+            decode(&data[..data.len() / 2]).err(),
+            Some(JpegError::Truncated)
+        );
+        assert!(decode(&data[..20]).is_err());
+        assert!(decode(b"not a jpeg").is_err());
+    }
+
+    #[test]
+    fn corrupt_bytes_anywhere_fail_without_panicking() {
+        let data = encode(&gradient(16, 16), 90);
+        let mut seed = 0x9E37_79B9u32;
+        for _ in 0..500 {
+            let mut corrupt = data.clone();
+            for _ in 0..4 {
+                seed = seed.wrapping_mul(1_664_525).wrapping_add(1_013_904_223);
+                let index = (seed >> 8) as usize % corrupt.len();
+                corrupt[index] ^= (seed >> 24) as u8 | 1;
+            }
+            if let Ok(decoded) = decode(&corrupt) {
+                let image = decoded.image;
+                assert_eq!(
+                    image.pixels.len(),
+                    image.width as usize * image.height as usize * 3
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn orientation_survives_metadata_stripping() {
+        let data = encode(&gradient(8, 8), 80);
+        assert_eq!(read_orientation(&data), 1);
+
+        let rotated = strip_metadata(&data, 6).unwrap();
+        assert_eq!(read_orientation(&rotated), 6);
+        assert_eq!(decode(&rotated).unwrap().orientation, 6);
+
+        // Stripping it again without a rotation leaves the encoder's output
+        assert_eq!(strip_metadata(&rotated, 1).unwrap(), data);
+        assert_eq!(read_orientation(b"not a jpeg"), 1);
+    }
+
+    #[test]
+    fn exif_orientation_reads_either_byte_order() {
+        // Big-endian, with just the orientation
+        let mut tiff = b"MM\x00\x2A\x00\x00\x00\x08\x00\x01".to_vec();
+        tiff.extend_from_slice(&[0x01, 0x12, 0x00, 0x03, 0x00, 0x00, 0x00, 0x01, 0, 3, 0, 0]);
+        tiff.extend_from_slice(&[0, 0, 0, 0]);
+        assert_eq!(exif_orientation(&tiff), Some(3));
+
+        // Little-endian, with the orientation after another tag
+        let mut tiff = b"II\x2A\x00\x08\x00\x00\x00\x02\x00".to_vec();
+        tiff.extend_from_slice(&[
+            0x0F, 0x01, 0x02, 0x00, 0x06, 0x00, 0x00, 0x00, 0x26, 0, 0, 0,
+        ]);
+        tiff.extend_from_slice(&[
+            0x12, 0x01, 0x03, 0x00, 0x01, 0x00, 0x00, 0x00, 0x08, 0, 0, 0,
+        ]);
+        tiff.extend_from_slice(&[0, 0, 0, 0]);
+        assert_eq!(exif_orientation(&tiff), Some(8));
+    }
+
+    #[test]
+    fn malformed_exif_is_ignored() {
+        let mut tiff = b"MM\x00\x2A\x00\x00\x00\x08\x00\x01".to_vec();
+        tiff.extend_from_slice(&[0x01, 0x12, 0x00, 0x03, 0x00, 0x00, 0x00, 0x01, 0, 3, 0, 0]);
+        // The IFD says it has an entry that isn't there
+        assert_eq!(exif_orientation(&tiff[..10]), None);
+        // Wrong TIFF magic number
+        let mut bad_magic = tiff.clone();
+        bad_magic[3] = 43;
+        assert_eq!(exif_orientation(&bad_magic), None);
+        // IFD offset past the end
+        let mut bad_offset = tiff.clone();
+        bad_offset[7] = 0xF0;
+        assert_eq!(exif_orientation(&bad_offset), None);
+        // Orientation out of range
+        let mut bad_orientation = tiff;
+        bad_orientation[19] = 9;
+        assert_eq!(exif_orientation(&bad_orientation), None);
+    }
+}
@@ -1,14 +1,36 @@
 #![recursion_limit = "512"]
 
 pub mod about;
+pub mod audit;
 pub mod auth;
 pub mod cache;
 pub mod components;
+pub mod container_labels;
+pub mod food_calendar;
 pub mod food_log;
+pub mod image_gc;
+pub mod image_pipeline;
+pub mod image_signing;
+pub mod image_upload;
+pub mod ingredient_merge;
+pub mod ingredient_prices;
 pub mod ingredients;
+pub mod jpeg;
+pub mod meal_plan;
+pub mod nutrition_label;
 pub mod pages;
+pub mod pantry;
+pub mod png;
+pub mod qr;
+pub mod quick_log;
+pub mod recipe_fit;
+pub mod recipe_history;
 pub mod recipes;
 pub mod settings;
+pub mod solver;
+pub mod stats;
+pub mod trash;
+pub mod what_to_cook;
 
 use leptos::{
     hydration::{AutoReload, HydrationScripts},
@@ -22,13 +44,19 @@ use leptos_router::{
 };
 
 // Re-export public types from modules
+pub use audit::{AuditEntry, GetAuditLog};
 pub use auth::{AdminAuth, AuthState, LoginResult, ValidateResult};
+pub use trash::{GetTrash, PurgeFromTrash, RestoreFromTrash, TrashItem};
 // Re-export server function types for worker registration
 #[cfg(feature = "ssr")]
 pub use auth::SendKvStore;
 pub use auth::{AdminLogin, AdminLogout, AdminValidate};
 
 // Re-export ingredient types for worker registration
+pub use ingredient_merge::{MergeIngredients, MergePreview, PreviewIngredientMerge};
+pub use ingredient_prices::{
+    AddIngredientPrice, DeleteIngredientPrice, GetIngredientPrices, IngredientPrice,
+};
 #[cfg(feature = "ssr")]
 pub use ingredients::SendD1Database;
 pub use ingredients::{
@@ -37,23 +65,53 @@ pub use ingredients::{
 };
 
 // Re-export recipe types for worker registration
-pub use recipes::{CreateRecipe, DeleteRecipe, GetRecipes, Recipe, UpdateRecipe};
+pub use recipe_history::{GetRecipeRevisions, RecipeRevision, RestoreRecipeRevision};
+pub use recipes::{CostMode, CreateRecipe, DeleteRecipe, GetRecipes, Recipe, UpdateRecipe};
 
-// Re-export food log types for worker registration
+// Re-export nutrition label rendering for the worker's label endpoint
+#[cfg(feature = "ssr")]
+pub use nutrition_label::recipe_label_svg;
+
+// Re-export container label rendering for the worker's label sheet endpoint
 #[cfg(feature = "ssr")]
-pub use food_log::SendR2Bucket;
+pub use container_labels::recipe_container_labels_svg;
+
+// Re-export pantry types for worker registration
+pub use pantry::{GetPantry, PantryItem, RemovePantryItem, RestockPantryItems, SavePantryItem};
+
+// Re-export food log types for worker registration
 pub use food_log::{
-    CreateFoodLog, DeleteFoodImage, DeleteFoodLog, FoodLog, GetFoodImage, GetFoodLogs,
-    UpdateFoodLog, UploadFoodImage,
+    CreateFoodLog, DeleteFoodImage, DeleteFoodLog, FoodLog, FoodLogFilter, FoodLogImage,
+    FoodLogPage, GetFoodImage, GetFoodLogs, UpdateFoodLog,
 };
+#[cfg(feature = "ssr")]
+pub use food_log::{SendContext, SendR2Bucket};
+#[cfg(feature = "ssr")]
+pub use image_gc::collect_orphaned_images;
+pub use image_gc::{GetImageGcReport, ImageGcReport};
+pub use image_pipeline::{variant_key, ImageVariant};
+#[cfg(feature = "ssr")]
+pub use image_signing::ImageSigner;
+pub use image_upload::UploadError;
+#[cfg(feature = "ssr")]
+pub use image_upload::{complete_upload, store_upload_part, uploaded_parts};
+
+// Re-export stats types for worker registration
+pub use stats::{DailyNutrition, GetDailyNutrition};
 
 use about::About;
+use audit::AuditLog;
 use auth::PinModal;
 use food_log::FoodLogs;
 use ingredients::Ingredients;
+use meal_plan::MealPlanner;
 use pages::{DarkMode, Navigation};
+use pantry::Pantry;
+use quick_log::QuickLog;
 use recipes::Recipes;
 use settings::Settings;
+use stats::Stats;
+use trash::Trash;
 
 #[component]
 pub fn App() -> impl IntoView {
@@ -76,10 +134,16 @@ pub fn App() -> impl IntoView {
         <main class="min-h-screen bg-slate-100 px-4 dark:bg-slate-900 transition-colors">
           <Routes fallback=|| "Not found">
             <Route path=path!("/") view=FoodLogs />
+            <Route path=path!("/quick-log") view=QuickLog />
             <Route path=path!("/ingredients") view=Ingredients />
             <Route path=path!("/recipes") view=Recipes />
+            <Route path=path!("/pantry") view=Pantry />
+            <Route path=path!("/planner") view=MealPlanner />
+            <Route path=path!("/stats") view=Stats />
             <Route path=path!("/settings") view=Settings />
             <Route path=path!("/about") view=About />
+            <Route path=path!("/audit") view=AuditLog />
+            <Route path=path!("/trash") view=Trash />
           </Routes>
         </main>
       </Router>
@@ -0,0 +1,644 @@
+//! Meal planner module
+//!
+//! Picks whole servings of recipes for a day or a week so that the Settings targets
+//! are met at the lowest ingredient cost. The choice is an integer program solved with
+//! the in-crate solver; the accepted plan is kept in localStorage as the current meal plan.
+
+#[cfg(not(feature = "ssr"))]
+use gloo_storage::{LocalStorage, Storage};
+use leptos::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::recipes::{get_recipes, CostMode, Recipe, RecipeNutrition};
+use crate::settings::{load_settings, SettingsData, SODIUM_MG_PER_G_SALT};
+use crate::solver::{LinearProgram, LpResult, Relation};
+
+// ============================================================================
+// Constants
+// ============================================================================
+
+// Local storage key
+#[cfg(not(feature = "ssr"))]
+const MEAL_PLAN_STORAGE_KEY: &str = "food_meal_plan";
+
+/// Branch-and-bound budget per day
+const MAX_NODES: usize = 2000;
+
+/// Added to every serving's cost so that, between equally cheap plans, fewer servings win
+const SERVING_PENALTY: f64 = 0.01;
+
+// ============================================================================
+// Data Types
+// ============================================================================
+
+/// Servings of one recipe within a day
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+pub struct PlannedMeal {
+    pub recipe_id: i64,
+    pub recipe_name: String,
+    pub servings: u32,
+    /// Cost of all servings
+    pub cost: f32,
+    /// Nutrition of all servings
+    pub nutrition: RecipeNutrition,
+}
+
+/// The meals chosen for one day
+#[derive(Clone, Debug, Default, Serialize, Deserialize, PartialEq)]
+pub struct PlanDay {
+    pub meals: Vec<PlannedMeal>,
+}
+
+impl PlanDay {
+    pub fn cost(&self) -> f32 {
+        self.meals.iter().map(|m| m.cost).sum()
+    }
+
+    pub fn nutrition(&self) -> RecipeNutrition {
+        let mut total = RecipeNutrition::default();
+        for meal in &self.meals {
+            let n = &meal.nutrition;
+            total.calories += n.calories;
+            total.protein += n.protein;
+            total.fat += n.fat;
+            total.saturated_fat += n.saturated_fat;
+            total.carbs += n.carbs;
+            total.sugar += n.sugar;
+            total.fiber += n.fiber;
+            total.salt += n.salt;
+        }
+        total
+    }
+}
+
+/// A day or week of planned meals
+#[derive(Clone, Debug, Default, Serialize, Deserialize, PartialEq)]
+pub struct MealPlan {
+    pub days: Vec<PlanDay>,
+}
+
+impl MealPlan {
+    pub fn cost(&self) -> f32 {
+        self.days.iter().map(PlanDay::cost).sum()
+    }
+}
+
+/// Daily nutrition targets the planner has to meet
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct NutritionTargets {
+    pub calories: f64,
+    pub protein_g: f64,
+    pub carbs_g: f64,
+    pub fat_g: f64,
+    pub max_sodium_mg: f64,
+    pub max_sat_fat_g: f64,
+    pub min_fiber_g: f64,
+}
+
+impl NutritionTargets {
+    pub(crate) fn from_settings(settings: &SettingsData) -> Self {
+        Self {
+            calories: settings.daily_calories as f64,
+            protein_g: settings.protein_grams(),
+            carbs_g: settings.carbs_grams(),
+            fat_g: settings.fat_grams(),
+            max_sodium_mg: settings.sodium_mg as f64,
+            max_sat_fat_g: settings.sat_fat_grams as f64,
+            min_fiber_g: settings.fiber_min as f64,
+        }
+    }
+}
+
+/// How much freedom the planner has
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct PlannerOptions {
+    pub days: u32,
+    /// Allowed deviation from the calorie target, in percent
+    pub calorie_tolerance_pct: f64,
+    /// Allowed deviation from each macro target, in percent
+    pub macro_tolerance_pct: f64,
+    pub max_servings_per_day: u32,
+    /// Only applies when planning more than one day
+    pub max_servings_per_week: u32,
+}
+
+impl Default for PlannerOptions {
+    fn default() -> Self {
+        Self {
+            days: 1,
+            calorie_tolerance_pct: 10.0,
+            macro_tolerance_pct: 25.0,
+            max_servings_per_day: 2,
+            max_servings_per_week: 3,
+        }
+    }
+}
+
+/// Why no plan could be made
+#[derive(Clone, Debug, PartialEq)]
+pub enum PlanError {
+    NoRecipes,
+    Infeasible { day: u32 },
+}
+
+impl std::fmt::Display for PlanError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            PlanError::NoRecipes => write!(f, "No recipes with nutrition data to plan with."),
+            PlanError::Infeasible { day } => write!(
+                f,
+                "No combination of recipes meets the targets for day {}. Try widening the tolerances, allowing more servings or adding recipes.",
+                day
+            ),
+        }
+    }
+}
+
+// ============================================================================
+// Planning
+// ============================================================================
+
+/// Choose servings per recipe for each day, minimising cost while meeting the targets.
+/// Days are solved one after another, each using what is left of the weekly serving caps.
+pub fn plan_meals(
+    recipes: &[Recipe],
+    targets: &NutritionTargets,
+    options: &PlannerOptions,
+) -> Result<MealPlan, PlanError> {
+    let pool: Vec<&Recipe> = recipes
+        .iter()
+        .filter(|r| r.id.is_some() && r.nutrition_per_serving().calories > 0.0)
+        .collect();
+    if pool.is_empty() {
+        return Err(PlanError::NoRecipes);
+    }
+
+    let per_serving: Vec<RecipeNutrition> =
+        pool.iter().map(|r| r.nutrition_per_serving()).collect();
+    let cost_per_serving: Vec<f64> = pool
+        .iter()
+        .map(|r| r.cost(CostMode::Current) as f64 / r.servings.max(1) as f64)
+        .collect();
+
+    let mut servings_left = vec![
+        if options.days > 1 {
+            options.max_servings_per_week
+        } else {
+            options.max_servings_per_day
+        };
+        pool.len()
+    ];
+    let mut plan = MealPlan::default();
+
+    for day in 1..=options.days.max(1) {
+        let caps: Vec<u32> = servings_left
+            .iter()
+            .map(|left| (*left).min(options.max_servings_per_day))
+            .collect();
+        let servings = plan_day(&per_serving, &cost_per_serving, &caps, targets, options)
+            .ok_or(PlanError::Infeasible { day })?;
+
+        let mut meals = Vec::new();
+        for (i, &count) in servings.iter().enumerate() {
+            if count == 0 {
+                continue;
+            }
+            servings_left[i] -= count;
+            meals.push(PlannedMeal {
+                recipe_id: pool[i].id.unwrap_or_default(),
+                recipe_name: pool[i].name.clone(),
+                servings: count,
+                cost: cost_per_serving[i] as f32 * count as f32,
+                nutrition: per_serving[i].scaled(count as f32),
+            });
+        }
+        plan.days.push(PlanDay { meals });
+    }
+
+    Ok(plan)
+}
+
+/// Solve a single day. Returns the number of servings per recipe.
+fn plan_day(
+    per_serving: &[RecipeNutrition],
+    cost_per_serving: &[f64],
+    caps: &[u32],
+    targets: &NutritionTargets,
+    options: &PlannerOptions,
+) -> Option<Vec<u32>> {
+    let objective = cost_per_serving
+        .iter()
+        .map(|c| c + SERVING_PENALTY)
+        .collect();
+    let mut lp = LinearProgram::new(objective);
+    for (var, cap) in caps.iter().enumerate() {
+        lp.set_upper_bound(var, *cap as f64);
+    }
+
+    let column = |f: fn(&RecipeNutrition) -> f32| -> Vec<f64> {
+        per_serving.iter().map(|n| f(n) as f64).collect()
+    };
+    let mut add_range = |coeffs: Vec<f64>, target: f64, tolerance_pct: f64| {
+        let slack = target * tolerance_pct / 100.0;
+        lp.add_constraint(coeffs.clone(), Relation::GreaterOrEqual, target - slack);
+        lp.add_constraint(coeffs, Relation::LessOrEqual, target + slack);
+    };
+
+    add_range(
+        column(|n| n.calories),
+        targets.calories,
+        options.calorie_tolerance_pct,
+    );
+    add_range(
+        column(|n| n.protein),
+        targets.protein_g,
+        options.macro_tolerance_pct,
+    );
+    add_range(
+        column(|n| n.carbs),
+        targets.carbs_g,
+        options.macro_tolerance_pct,
+    );
+    add_range(
+        column(|n| n.fat),
+        targets.fat_g,
+        options.macro_tolerance_pct,
+    );
+
+    let sodium = per_serving
+        .iter()
+        .map(|n| n.salt as f64 * SODIUM_MG_PER_G_SALT)
+        .collect();
+    lp.add_constraint(sodium, Relation::LessOrEqual, targets.max_sodium_mg);
+    lp.add_constraint(
+        column(|n| n.saturated_fat),
+        Relation::LessOrEqual,
+        targets.max_sat_fat_g,
+    );
+    lp.add_constraint(
+        column(|n| n.fiber),
+        Relation::GreaterOrEqual,
+        targets.min_fiber_g,
+    );
+
+    match lp.solve_integer(MAX_NODES) {
+        LpResult::Optimal { x, .. } => Some(x.iter().map(|v| v.round().max(0.0) as u32).collect()),
+        LpResult::Infeasible | LpResult::Unbounded => None,
+    }
+}
+
+// ============================================================================
+// Storage
+// ============================================================================
+
+/// The saved meal plan, if any
+pub fn load_meal_plan() -> Option<MealPlan> {
+    #[cfg(feature = "ssr")]
+    {
+        None
+    }
+    #[cfg(not(feature = "ssr"))]
+    {
+        LocalStorage::get(MEAL_PLAN_STORAGE_KEY).ok()
+    }
+}
+
+pub fn save_meal_plan(plan: &MealPlan) {
+    #[cfg(not(feature = "ssr"))]
+    {
+        if let Err(e) = LocalStorage::set(MEAL_PLAN_STORAGE_KEY, plan) {
+            log::warn!("Failed to save meal plan: {:?}", e);
+        }
+    }
+    #[cfg(feature = "ssr")]
+    {
+        let _ = plan;
+    }
+}
+
+pub fn clear_meal_plan() {
+    #[cfg(not(feature = "ssr"))]
+    {
+        LocalStorage::delete(MEAL_PLAN_STORAGE_KEY);
+    }
+}
+
+// ============================================================================
+// Components
+// ============================================================================
+
+/// Day-by-day breakdown of a plan against the targets
+#[component]
+fn PlanView(plan: MealPlan, targets: NutritionTargets) -> impl IntoView {
+    let total_cost = plan.cost();
+    let multi_day = plan.days.len() > 1;
+
+    view! {
+      <div class="space-y-4">
+        {plan
+          .days
+          .into_iter()
+          .enumerate()
+          .map(|(i, day)| {
+            let n = day.nutrition();
+            let cost = day.cost();
+            let sodium_mg = n.salt as f64 * SODIUM_MG_PER_G_SALT;
+            view! {
+              <div class="rounded border border-slate-200 dark:border-slate-700 p-4">
+                <div class="mb-2 flex items-center justify-between">
+                  <h4 class="font-semibold text-slate-900 dark:text-slate-100">
+                    {if multi_day { format!("Day {}", i + 1) } else { "Today".to_string() }}
+                  </h4>
+                  <span class="text-sm font-medium text-slate-700 dark:text-slate-300">{format!("${:.2}", cost)}</span>
+                </div>
+                <ul class="mb-3 space-y-1">
+                  {day
+                    .meals
+                    .into_iter()
+                    .map(|meal| {
+                      view! {
+                        <li class="flex justify-between text-sm text-slate-700 dark:text-slate-300">
+                          <span>{format!("{} × {}", meal.servings, meal.recipe_name)}</span>
+                          <span class="text-slate-500 dark:text-slate-400">
+                            {format!("{:.0} kcal, ${:.2}", meal.nutrition.calories, meal.cost)}
+                          </span>
+                        </li>
+                      }
+                    })
+                    .collect_view()}
+                </ul>
+                <div class="grid grid-cols-2 gap-x-4 gap-y-1 text-xs text-slate-600 dark:text-slate-400 sm:grid-cols-4">
+                  <span>{format!("Calories: {:.0} / {:.0}", n.calories, targets.calories)}</span>
+                  <span>{format!("Protein: {:.0}g / {:.0}g", n.protein, targets.protein_g)}</span>
+                  <span>{format!("Carbs: {:.0}g / {:.0}g", n.carbs, targets.carbs_g)}</span>
+                  <span>{format!("Fat: {:.0}g / {:.0}g", n.fat, targets.fat_g)}</span>
+                  <span>{format!("Sodium: {:.0}mg ≤ {:.0}mg", sodium_mg, targets.max_sodium_mg)}</span>
+                  <span>{format!("Sat. fat: {:.1}g ≤ {:.0}g", n.saturated_fat, targets.max_sat_fat_g)}</span>
+                  <span>{format!("Fiber: {:.1}g ≥ {:.0}g", n.fiber, targets.min_fiber_g)}</span>
+                </div>
+              </div>
+            }
+          })
+          .collect_view()}
+        <p class="text-right font-semibold text-slate-900 dark:text-slate-100">
+          {format!("Total cost: ${:.2}", total_cost)}
+        </p>
+      </div>
+    }
+}
+
+#[component]
+pub fn MealPlanner() -> impl IntoView {
+    let recipes = Resource::new(|| (), |_| get_recipes());
+    let targets = RwSignal::new(NutritionTargets::from_settings(&SettingsData::default()));
+    let saved_plan = RwSignal::new(None::<MealPlan>);
+    let excluded = RwSignal::new(Vec::<i64>::new());
+    let options = RwSignal::new(PlannerOptions::default());
+    let result = RwSignal::new(None::<Result<MealPlan, PlanError>>);
+
+    // Settings and the saved plan live in localStorage, so read them after hydration
+    Effect::new(move || {
+        targets.set(NutritionTargets::from_settings(&load_settings()));
+        saved_plan.set(load_meal_plan());
+    });
+
+    let toggle_excluded = move |recipe_id: i64| {
+        excluded.update(|ids| {
+            if let Some(pos) = ids.iter().position(|id| *id == recipe_id) {
+                ids.remove(pos);
+            } else {
+                ids.push(recipe_id);
+            }
+        });
+    };
+
+    let generate = move |_| {
+        let Some(Ok(list)) = recipes.get_untracked() else {
+            return;
+        };
+        let skip = excluded.get_untracked();
+        let pool: Vec<Recipe> = list
+            .into_iter()
+            .filter(|r| r.id.is_some_and(|id| !skip.contains(&id)))
+            .collect();
+        result.set(Some(plan_meals(
+            &pool,
+            &targets.get_untracked(),
+            &options.get_untracked(),
+        )));
+    };
+
+    let save_plan = move |_| {
+        if let Some(Ok(plan)) = result.get_untracked() {
+            save_meal_plan(&plan);
+            saved_plan.set(Some(plan));
+            result.set(None);
+        }
+    };
+
+    let clear_plan = move |_| {
+        clear_meal_plan();
+        saved_plan.set(None);
+    };
+
+    let days_button = move |days: u32, label: &'static str| {
+        view! {
+          <button
+            class=move || {
+              if options.get().days == days {
+                "px-3 py-1 bg-blue-600 text-white"
+              } else {
+                "px-3 py-1 bg-white dark:bg-slate-800 text-slate-700 dark:text-slate-300 hover:bg-slate-100 dark:hover:bg-slate-700"
+              }
+            }
+            on:click=move |_| options.update(|o| o.days = days)
+          >
+            {label}
+          </button>
+        }
+    };
+
+    let number_input = move |label: &'static str,
+                             get: fn(&PlannerOptions) -> f64,
+                             set: fn(&mut PlannerOptions, f64)| {
+        view! {
+          <label class="block">
+            <span class="mb-1 block text-sm font-medium text-slate-700 dark:text-slate-300">{label}</span>
+            <input
+              type="number"
+              min="0"
+              class="w-full rounded border border-slate-300 dark:border-slate-600 bg-white dark:bg-slate-700 px-3 py-2 text-slate-900 dark:text-slate-100"
+              prop:value=move || get(&options.get())
+              on:input=move |ev| {
+                if let Ok(val) = event_target_value(&ev).parse::<f64>() {
+                  options.update(|o| set(o, val.max(0.0)));
+                }
+              }
+            />
+          </label>
+        }
+    };
+
+    view! {
+      <div class="mx-auto max-w-7xl py-6">
+        <div class="mb-6 flex items-center justify-between flex-wrap gap-4">
+          <h2 class="text-3xl font-bold text-slate-900 dark:text-slate-100">"Meal Planner"</h2>
+          <div class="flex rounded border border-slate-300 dark:border-slate-600 overflow-hidden text-sm">
+            {days_button(1, "Day")}
+            {days_button(7, "Week")}
+          </div>
+        </div>
+
+        <div class="mb-6 rounded-lg bg-white dark:bg-slate-800 p-6 shadow-md">
+          <h3 class="mb-2 text-lg font-bold text-slate-900 dark:text-slate-100">"Daily Targets"</h3>
+          <p class="mb-4 text-sm text-slate-600 dark:text-slate-400">
+            {move || {
+              let t = targets.get();
+              format!(
+                "{:.0} kcal · {:.0}g protein · {:.0}g carbs · {:.0}g fat · sodium ≤ {:.0}mg · sat. fat ≤ {:.0}g · fiber ≥ {:.0}g",
+                t.calories,
+                t.protein_g,
+                t.carbs_g,
+                t.fat_g,
+                t.max_sodium_mg,
+                t.max_sat_fat_g,
+                t.min_fiber_g,
+              )
+            }} " " <a href="/settings" class="text-blue-600 hover:underline dark:text-blue-400">
+              "Change in Settings"
+            </a>
+          </p>
+          <div class="grid grid-cols-2 gap-4 md:grid-cols-4">
+            {number_input("Calorie tolerance (%)", |o| o.calorie_tolerance_pct, |o, v| o.calorie_tolerance_pct = v)}
+            {number_input("Macro tolerance (%)", |o| o.macro_tolerance_pct, |o, v| o.macro_tolerance_pct = v)}
+            {number_input(
+              "Max servings per recipe/day",
+              |o| o.max_servings_per_day as f64,
+              |o, v| o.max_servings_per_day = v as u32,
+            )}
+            <Show when=move || { options.get().days > 1 }>
+              {number_input(
+                "Max servings per recipe/week",
+                |o| o.max_servings_per_week as f64,
+                |o, v| o.max_servings_per_week = v as u32,
+              )}
+            </Show>
+          </div>
+        </div>
+
+        <div class="mb-6 rounded-lg bg-white dark:bg-slate-800 p-6 shadow-md">
+          <h3 class="mb-4 text-lg font-bold text-slate-900 dark:text-slate-100">"Recipe Pool"</h3>
+          <Suspense fallback=move || {
+            view! { <p class="text-sm text-slate-600 dark:text-slate-400">"Loading recipes..."</p> }
+          }>
+            {move || {
+              recipes
+                .get()
+                .map(|result| match result {
+                  Ok(list) if list.is_empty() => {
+                    view! {
+                      <p class="text-sm text-slate-600 dark:text-slate-400">"No recipes yet. Add some to plan meals."</p>
+                    }
+                      .into_any()
+                  }
+                  Ok(list) => {
+                    view! {
+                      <div class="grid max-h-64 grid-cols-1 gap-1 overflow-y-auto sm:grid-cols-2 lg:grid-cols-3">
+                        {list
+                          .into_iter()
+                          .filter_map(|recipe| {
+                            let id = recipe.id?;
+                            let per_serving = recipe.nutrition_per_serving();
+                            let cost = recipe.cost(CostMode::Current) / recipe.servings.max(1) as f32;
+                            Some(
+                              view! {
+                                <label class="flex items-center gap-2 text-sm text-slate-700 dark:text-slate-300">
+                                  <input
+                                    type="checkbox"
+                                    class="h-4 w-4"
+                                    prop:checked=move || !excluded.get().contains(&id)
+                                    on:change=move |_| toggle_excluded(id)
+                                  />
+                                  <span>{recipe.name}</span>
+                                  <span class="text-xs text-slate-500 dark:text-slate-400">
+                                    {format!("{:.0} kcal, ${:.2}/serving", per_serving.calories, cost)}
+                                  </span>
+                                </label>
+                              },
+                            )
+                          })
+                          .collect_view()}
+                      </div>
+                    }
+                      .into_any()
+                  }
+                  Err(e) => {
+                    view! { <p class="text-sm text-red-600">{format!("Failed to load recipes: {}", e)}</p> }.into_any()
+                  }
+                })
+            }}
+          </Suspense>
+          <div class="mt-4 flex justify-end">
+            <button
+              class="rounded bg-blue-600 px-4 py-2 text-sm font-medium text-white hover:bg-blue-700"
+              on:click=generate
+            >
+              "Generate Plan"
+            </button>
+          </div>
+        </div>
+
+        {move || {
+          result
+            .get()
+            .map(|outcome| match outcome {
+              Ok(plan) => {
+                view! {
+                  <div class="mb-6 rounded-lg bg-white dark:bg-slate-800 p-6 shadow-md">
+                    <div class="mb-4 flex items-center justify-between">
+                      <h3 class="text-lg font-bold text-slate-900 dark:text-slate-100">"Suggested Plan"</h3>
+                      <button
+                        class="rounded bg-green-600 px-4 py-2 text-sm font-medium text-white hover:bg-green-700"
+                        on:click=save_plan
+                      >
+                        "Save as Meal Plan"
+                      </button>
+                    </div>
+                    <PlanView plan=plan targets=targets.get_untracked() />
+                  </div>
+                }
+                  .into_any()
+              }
+              Err(e) => {
+                view! { <div class="mb-6 rounded bg-red-100 px-4 py-3 text-red-700">{e.to_string()}</div> }.into_any()
+              }
+            })
+        }}
+
+        <div class="rounded-lg bg-white dark:bg-slate-800 p-6 shadow-md">
+          <div class="mb-4 flex items-center justify-between">
+            <h3 class="text-lg font-bold text-slate-900 dark:text-slate-100">"Current Meal Plan"</h3>
+            <Show when=move || saved_plan.get().is_some()>
+              <button
+                class="rounded border border-slate-300 dark:border-slate-600 px-3 py-1 text-sm text-slate-700 dark:text-slate-300 hover:bg-slate-100 dark:hover:bg-slate-700"
+                on:click=clear_plan
+              >
+                "Clear"
+              </button>
+            </Show>
+          </div>
+          {move || match saved_plan.get() {
+            Some(plan) => view! { <PlanView plan=plan targets=targets.get() /> }.into_any(),
+            None => {
+              view! {
+                <p class="text-sm text-slate-600 dark:text-slate-400">
+                  "No meal plan saved. Generate one above and save it."
+                </p>
+              }
+                .into_any()
+            }
+          }}
+        </div>
+      </div>
+    }
+}
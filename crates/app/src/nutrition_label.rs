@@ -0,0 +1,699 @@
+//! Nutrition label module
+//!
+//! Renders printable nutrition facts panels for recipes as SVG: a US FDA-style panel per
+//! serving and an EU-style nutrition declaration per 100g. Percent daily values use the
+//! goals from Settings. The same SVG backs the recipe card preview and the worker's
+//! `/api/recipe-label/{id}` endpoint.
+
+use std::fmt::Write;
+
+use leptos::prelude::*;
+use wasm_bindgen::JsCast;
+
+use crate::recipes::Recipe;
+use crate::settings::{load_settings, SettingsData, SODIUM_MG_PER_G_SALT};
+
+#[cfg(feature = "ssr")]
+use crate::ingredients::SendD1Database;
+#[cfg(feature = "ssr")]
+use server_fn::ServerFnError;
+
+// ============================================================================
+// Constants
+// ============================================================================
+
+const FONT_FAMILY: &str = "Helvetica, Arial, sans-serif";
+const PAD: f32 = 8.0;
+const FDA_WIDTH: f32 = 280.0;
+const EU_WIDTH: f32 = 340.0;
+
+// 1 kcal = 4.184 kJ
+const KJ_PER_KCAL: f32 = 4.184;
+
+/// Exported PNGs are drawn at this multiple of the SVG size
+const PNG_SCALE: u32 = 3;
+
+// ============================================================================
+// Data Types
+// ============================================================================
+
+/// Which label layout to render
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Default)]
+pub enum LabelStyle {
+    /// US Nutrition Facts panel, per serving
+    #[default]
+    Fda,
+    /// EU nutrition declaration, per 100g and per serving
+    Eu,
+}
+
+impl LabelStyle {
+    pub fn label(&self) -> &'static str {
+        match self {
+            LabelStyle::Fda => "US (FDA)",
+            LabelStyle::Eu => "EU",
+        }
+    }
+
+    /// Value used in the `style` query parameter
+    pub fn param(&self) -> &'static str {
+        match self {
+            LabelStyle::Fda => "fda",
+            LabelStyle::Eu => "eu",
+        }
+    }
+
+    pub fn from_param(value: &str) -> Self {
+        match value {
+            "eu" => LabelStyle::Eu,
+            _ => LabelStyle::Fda,
+        }
+    }
+}
+
+/// Daily amounts that percentages on the label are computed against
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct DailyValues {
+    pub calories: f32,
+    pub fat_g: f32,
+    pub sat_fat_g: f32,
+    pub sodium_mg: f32,
+    pub carbs_g: f32,
+    pub fiber_g: f32,
+    pub protein_g: f32,
+}
+
+impl DailyValues {
+    pub(crate) fn from_settings(settings: &SettingsData) -> Self {
+        Self {
+            calories: settings.daily_calories as f32,
+            fat_g: settings.fat_grams() as f32,
+            sat_fat_g: settings.sat_fat_grams as f32,
+            sodium_mg: settings.sodium_mg as f32,
+            carbs_g: settings.carbs_grams() as f32,
+            fiber_g: settings.fiber_min as f32,
+            protein_g: settings.protein_grams() as f32,
+        }
+    }
+
+    pub fn salt_g(&self) -> f32 {
+        self.sodium_mg / SODIUM_MG_PER_G_SALT as f32
+    }
+
+    /// Encode as query parameters for the label endpoint
+    pub fn to_query(&self) -> String {
+        format!(
+            "kcal={:.0}&fat={:.0}&sat_fat={:.0}&sodium={:.0}&carbs={:.0}&fiber={:.0}&protein={:.0}",
+            self.calories,
+            self.fat_g,
+            self.sat_fat_g,
+            self.sodium_mg,
+            self.carbs_g,
+            self.fiber_g,
+            self.protein_g,
+        )
+    }
+
+    /// Decode from query parameters, using the default goals for anything missing
+    pub fn from_query(query: &str) -> Self {
+        let defaults = Self::default();
+        let get = |key: &str, default: f32| {
+            query_param(query, key)
+                .and_then(|v| v.parse::<f32>().ok())
+                .filter(|v| *v > 0.0)
+                .unwrap_or(default)
+        };
+        Self {
+            calories: get("kcal", defaults.calories),
+            fat_g: get("fat", defaults.fat_g),
+            sat_fat_g: get("sat_fat", defaults.sat_fat_g),
+            sodium_mg: get("sodium", defaults.sodium_mg),
+            carbs_g: get("carbs", defaults.carbs_g),
+            fiber_g: get("fiber", defaults.fiber_g),
+            protein_g: get("protein", defaults.protein_g),
+        }
+    }
+}
+
+impl Default for DailyValues {
+    fn default() -> Self {
+        Self::from_settings(&SettingsData::default())
+    }
+}
+
+/// Look up a raw `key=value` pair in a URL query string
+pub fn query_param<'a>(query: &'a str, key: &str) -> Option<&'a str> {
+    query
+        .split('&')
+        .filter_map(|pair| pair.split_once('='))
+        .find(|(k, _)| *k == key)
+        .map(|(_, v)| v)
+}
+
+/// Percentage of a daily value, if there is one
+fn percent_dv(amount: f32, daily: f32) -> Option<f32> {
+    (daily > 0.0).then(|| (amount / daily * 100.0).round())
+}
+
+// ============================================================================
+// SVG Rendering
+// ============================================================================
+
+pub(crate) fn escape_xml(value: &str) -> String {
+    value
+        .replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+fn svg_text(out: &mut String, x: f32, y: f32, size: f32, bold: bool, anchor: &str, content: &str) {
+    let _ = write!(
+        out,
+        r#"<text x="{x}" y="{y}" font-size="{size}" font-weight="{}" text-anchor="{anchor}">{content}</text>"#,
+        if bold { "bold" } else { "normal" },
+    );
+}
+
+fn svg_bar(out: &mut String, width: f32, y: f32, height: f32) {
+    let _ = write!(
+        out,
+        r#"<rect x="{PAD}" y="{y}" width="{}" height="{height}" fill="black"/>"#,
+        width - 2.0 * PAD,
+    );
+}
+
+fn wrap_svg(width: f32, height: f32, body: &str) -> String {
+    format!(
+        r#"<svg xmlns="http://www.w3.org/2000/svg" width="{width}" height="{height}" viewBox="0 0 {width} {height}" font-family="{FONT_FAMILY}"><rect x="0.5" y="0.5" width="{}" height="{}" fill="white" stroke="black"/>{body}</svg>"#,
+        width - 1.0,
+        height - 1.0,
+    )
+}
+
+/// US Nutrition Facts panel for one serving
+pub fn fda_label_svg(recipe: &Recipe, dv: &DailyValues) -> String {
+    let n = recipe.nutrition_per_serving();
+    let servings = recipe.servings.max(1);
+    let serving_g = recipe.total_grams() / servings as f32;
+    let right = FDA_WIDTH - PAD;
+    let mut body = String::new();
+    let mut y = PAD;
+
+    svg_text(
+        &mut body,
+        PAD,
+        y + 11.0,
+        11.0,
+        false,
+        "start",
+        &escape_xml(&recipe.name),
+    );
+    y += 14.0;
+    svg_text(
+        &mut body,
+        PAD,
+        y + 26.0,
+        28.0,
+        true,
+        "start",
+        "Nutrition Facts",
+    );
+    y += 32.0;
+    svg_bar(&mut body, FDA_WIDTH, y, 1.0);
+    y += 4.0;
+    let per_recipe = if servings == 1 {
+        "1 serving per recipe".to_string()
+    } else {
+        format!("{} servings per recipe", servings)
+    };
+    svg_text(&mut body, PAD, y + 12.0, 11.0, false, "start", &per_recipe);
+    y += 16.0;
+    svg_text(
+        &mut body,
+        PAD,
+        y + 14.0,
+        13.0,
+        true,
+        "start",
+        "Serving size",
+    );
+    svg_text(
+        &mut body,
+        right,
+        y + 14.0,
+        13.0,
+        true,
+        "end",
+        &format!("{:.0}g", serving_g),
+    );
+    y += 18.0;
+    svg_bar(&mut body, FDA_WIDTH, y, 8.0);
+    y += 10.0;
+    svg_text(
+        &mut body,
+        PAD,
+        y + 10.0,
+        10.0,
+        true,
+        "start",
+        "Amount per serving",
+    );
+    y += 12.0;
+    svg_text(&mut body, PAD, y + 22.0, 22.0, true, "start", "Calories");
+    svg_text(
+        &mut body,
+        right,
+        y + 24.0,
+        26.0,
+        true,
+        "end",
+        &format!("{:.0}", n.calories),
+    );
+    y += 28.0;
+    svg_bar(&mut body, FDA_WIDTH, y, 4.0);
+    y += 6.0;
+    svg_text(
+        &mut body,
+        right,
+        y + 10.0,
+        10.0,
+        true,
+        "end",
+        "% Daily Value*",
+    );
+    y += 14.0;
+
+    // (label, amount, % daily value, indented)
+    let rows: [(&str, String, Option<f32>, bool); 7] = [
+        (
+            "Total Fat",
+            format!("{:.1}g", n.fat),
+            percent_dv(n.fat, dv.fat_g),
+            false,
+        ),
+        (
+            "Saturated Fat",
+            format!("{:.1}g", n.saturated_fat),
+            percent_dv(n.saturated_fat, dv.sat_fat_g),
+            true,
+        ),
+        (
+            "Sodium",
+            format!("{:.0}mg", n.salt * SODIUM_MG_PER_G_SALT as f32),
+            percent_dv(n.salt * SODIUM_MG_PER_G_SALT as f32, dv.sodium_mg),
+            false,
+        ),
+        (
+            "Total Carbohydrate",
+            format!("{:.1}g", n.carbs),
+            percent_dv(n.carbs, dv.carbs_g),
+            false,
+        ),
+        (
+            "Dietary Fiber",
+            format!("{:.1}g", n.fiber),
+            percent_dv(n.fiber, dv.fiber_g),
+            true,
+        ),
+        ("Total Sugars", format!("{:.1}g", n.sugar), None, true),
+        (
+            "Protein",
+            format!("{:.1}g", n.protein),
+            percent_dv(n.protein, dv.protein_g),
+            false,
+        ),
+    ];
+    for (label, amount, percent, indented) in rows {
+        let x = if indented { PAD + 12.0 } else { PAD };
+        let _ = write!(
+            body,
+            r#"<line x1="{x}" y1="{y}" x2="{right}" y2="{y}" stroke="black" stroke-width="0.5"/>"#
+        );
+        let _ = write!(
+            body,
+            r#"<text x="{x}" y="{}" font-size="11"><tspan font-weight="{}">{label}</tspan> {amount}</text>"#,
+            y + 13.0,
+            if indented { "normal" } else { "bold" },
+        );
+        if let Some(percent) = percent {
+            svg_text(
+                &mut body,
+                right,
+                y + 13.0,
+                11.0,
+                true,
+                "end",
+                &format!("{:.0}%", percent),
+            );
+        }
+        y += 18.0;
+    }
+
+    svg_bar(&mut body, FDA_WIDTH, y, 8.0);
+    y += 10.0;
+    for line in [
+        "* The % Daily Value (DV) tells you how much a nutrient".to_string(),
+        "in a serving of food contributes to a daily diet. Daily".to_string(),
+        format!(
+            "values are based on your goal of {:.0} calories a day.",
+            dv.calories
+        ),
+    ] {
+        svg_text(&mut body, PAD, y + 9.0, 8.5, false, "start", &line);
+        y += 11.0;
+    }
+
+    wrap_svg(FDA_WIDTH, y + PAD, &body)
+}
+
+/// EU nutrition declaration per 100g and per serving, with %RI per serving
+pub fn eu_label_svg(recipe: &Recipe, dv: &DailyValues) -> String {
+    let total = recipe.nutrition();
+    let total_g = recipe.total_grams();
+    let per_100g = if total_g > 0.0 {
+        total.scaled(100.0 / total_g)
+    } else {
+        Default::default()
+    };
+    let per_serving = recipe.nutrition_per_serving();
+    let serving_g = total_g / recipe.servings.max(1) as f32;
+
+    let col_100g = 180.0;
+    let col_serving = 260.0;
+    let col_ri = EU_WIDTH - PAD;
+    let mut body = String::new();
+    let mut y = PAD;
+
+    svg_text(
+        &mut body,
+        PAD,
+        y + 11.0,
+        11.0,
+        false,
+        "start",
+        &escape_xml(&recipe.name),
+    );
+    y += 14.0;
+    svg_text(
+        &mut body,
+        PAD,
+        y + 18.0,
+        18.0,
+        true,
+        "start",
+        "Nutrition declaration",
+    );
+    y += 24.0;
+    svg_text(&mut body, col_100g, y + 11.0, 10.0, true, "end", "Per 100g");
+    svg_text(
+        &mut body,
+        col_serving,
+        y + 11.0,
+        10.0,
+        true,
+        "end",
+        &format!("Per {:.0}g", serving_g),
+    );
+    svg_text(&mut body, col_ri, y + 11.0, 10.0, true, "end", "%RI*");
+    y += 14.0;
+    svg_bar(&mut body, EU_WIDTH, y, 2.0);
+    y += 2.0;
+
+    // (label, per 100g, per serving, %RI, indented)
+    let rows: [(&str, String, String, Option<f32>, bool); 9] = [
+        (
+            "Energy",
+            format!("{:.0} kJ", per_100g.calories * KJ_PER_KCAL),
+            format!("{:.0} kJ", per_serving.calories * KJ_PER_KCAL),
+            percent_dv(per_serving.calories, dv.calories),
+            false,
+        ),
+        (
+            "",
+            format!("{:.0} kcal", per_100g.calories),
+            format!("{:.0} kcal", per_serving.calories),
+            None,
+            false,
+        ),
+        (
+            "Fat",
+            format!("{:.1} g", per_100g.fat),
+            format!("{:.1} g", per_serving.fat),
+            percent_dv(per_serving.fat, dv.fat_g),
+            false,
+        ),
+        (
+            "of which saturates",
+            format!("{:.1} g", per_100g.saturated_fat),
+            format!("{:.1} g", per_serving.saturated_fat),
+            percent_dv(per_serving.saturated_fat, dv.sat_fat_g),
+            true,
+        ),
+        (
+            "Carbohydrate",
+            format!("{:.1} g", per_100g.carbs),
+            format!("{:.1} g", per_serving.carbs),
+            percent_dv(per_serving.carbs, dv.carbs_g),
+            false,
+        ),
+        (
+            "of which sugars",
+            format!("{:.1} g", per_100g.sugar),
+            format!("{:.1} g", per_serving.sugar),
+            None,
+            true,
+        ),
+        (
+            "Fibre",
+            format!("{:.1} g", per_100g.fiber),
+            format!("{:.1} g", per_serving.fiber),
+            percent_dv(per_serving.fiber, dv.fiber_g),
+            false,
+        ),
+        (
+            "Protein",
+            format!("{:.1} g", per_100g.protein),
+            format!("{:.1} g", per_serving.protein),
+            percent_dv(per_serving.protein, dv.protein_g),
+            false,
+        ),
+        (
+            "Salt",
+            format!("{:.2} g", per_100g.salt),
+            format!("{:.2} g", per_serving.salt),
+            percent_dv(per_serving.salt, dv.salt_g()),
+            false,
+        ),
+    ];
+    for (label, amount_100g, amount_serving, percent, indented) in rows {
+        // The kcal line belongs to the energy row above it
+        if !label.is_empty() {
+            let _ = write!(
+                body,
+                r#"<line x1="{PAD}" y1="{y}" x2="{col_ri}" y2="{y}" stroke="black" stroke-width="0.5"/>"#
+            );
+        }
+        let x = if indented { PAD + 10.0 } else { PAD };
+        svg_text(&mut body, x, y + 13.0, 11.0, false, "start", label);
+        svg_text(
+            &mut body,
+            col_100g,
+            y + 13.0,
+            11.0,
+            false,
+            "end",
+            &amount_100g,
+        );
+        svg_text(
+            &mut body,
+            col_serving,
+            y + 13.0,
+            11.0,
+            false,
+            "end",
+            &amount_serving,
+        );
+        if let Some(percent) = percent {
+            svg_text(
+                &mut body,
+                col_ri,
+                y + 13.0,
+                11.0,
+                false,
+                "end",
+                &format!("{:.0}%", percent),
+            );
+        }
+        y += 18.0;
+    }
+
+    svg_bar(&mut body, EU_WIDTH, y, 2.0);
+    y += 4.0;
+    svg_text(
+        &mut body,
+        PAD,
+        y + 9.0,
+        8.5,
+        false,
+        "start",
+        &format!(
+            "*Reference intake based on your daily goals ({:.0} kcal).",
+            dv.calories
+        ),
+    );
+    y += 11.0;
+
+    wrap_svg(EU_WIDTH, y + PAD, &body)
+}
+
+pub fn label_svg(recipe: &Recipe, style: LabelStyle, dv: &DailyValues) -> String {
+    match style {
+        LabelStyle::Fda => fda_label_svg(recipe, dv),
+        LabelStyle::Eu => eu_label_svg(recipe, dv),
+    }
+}
+
+/// Render the label for a stored recipe, for the worker's label endpoint.
+/// `query` carries `style` and the daily goals; returns `None` if there is no such recipe.
+#[cfg(feature = "ssr")]
+pub async fn recipe_label_svg(
+    db: &SendD1Database,
+    recipe_id: i64,
+    query: &str,
+) -> Result<Option<String>, ServerFnError> {
+    let Some(recipe) = crate::recipes::fetch_recipe(db, recipe_id).await? else {
+        return Ok(None);
+    };
+    let style = LabelStyle::from_param(query_param(query, "style").unwrap_or_default());
+    Ok(Some(label_svg(
+        &recipe,
+        style,
+        &DailyValues::from_query(query),
+    )))
+}
+
+// ============================================================================
+// PNG Export
+// ============================================================================
+
+/// Rasterise an SVG label in the browser and download it as a PNG
+fn download_png(svg: &str, file_name: String) {
+    let Ok(image) = web_sys::HtmlImageElement::new() else {
+        return;
+    };
+    let image_clone = image.clone();
+    let onload = wasm_bindgen::closure::Closure::wrap(Box::new(move |_: web_sys::Event| {
+        if draw_png(&image_clone, &file_name).is_none() {
+            log::warn!("Failed to export nutrition label as PNG");
+        }
+    }) as Box<dyn FnMut(_)>);
+    image.set_onload(Some(onload.as_ref().unchecked_ref()));
+    onload.forget();
+    image.set_src(&format!(
+        "data:image/svg+xml;charset=utf-8,{}",
+        js_sys::encode_uri_component(svg)
+    ));
+}
+
+fn draw_png(image: &web_sys::HtmlImageElement, file_name: &str) -> Option<()> {
+    let document = web_sys::window()?.document()?;
+    let canvas: web_sys::HtmlCanvasElement =
+        document.create_element("canvas").ok()?.dyn_into().ok()?;
+    canvas.set_width(image.natural_width() * PNG_SCALE);
+    canvas.set_height(image.natural_height() * PNG_SCALE);
+    let ctx: web_sys::CanvasRenderingContext2d = canvas.get_context("2d").ok()??.dyn_into().ok()?;
+    ctx.scale(PNG_SCALE as f64, PNG_SCALE as f64).ok()?;
+    ctx.draw_image_with_html_image_element(image, 0.0, 0.0)
+        .ok()?;
+
+    let link: web_sys::HtmlAnchorElement = document.create_element("a").ok()?.dyn_into().ok()?;
+    link.set_href(&canvas.to_data_url_with_type("image/png").ok()?);
+    link.set_download(file_name);
+    link.click();
+    Some(())
+}
+
+// ============================================================================
+// Components
+// ============================================================================
+
+/// Nutrition label preview with style switch, printable SVG link and PNG download
+#[component]
+pub fn NutritionLabel(recipe: Recipe) -> impl IntoView {
+    let style = RwSignal::new(LabelStyle::Fda);
+    let daily_values = RwSignal::new(DailyValues::default());
+
+    // Goals live in localStorage, so read them after hydration
+    Effect::new(move || {
+        daily_values.set(DailyValues::from_settings(&load_settings()));
+    });
+
+    let recipe_id = recipe.id;
+    let file_stem = recipe
+        .name
+        .chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() {
+                c.to_ascii_lowercase()
+            } else {
+                '-'
+            }
+        })
+        .collect::<String>();
+    let svg = move || label_svg(&recipe, style.get(), &daily_values.get());
+    let svg = StoredValue::new(svg);
+
+    let style_button = move |value: LabelStyle| {
+        view! {
+          <button
+            class=move || {
+              if style.get() == value {
+                "px-3 py-1 bg-blue-600 text-white"
+              } else {
+                "px-3 py-1 bg-white dark:bg-slate-800 text-slate-700 dark:text-slate-300 hover:bg-slate-100 dark:hover:bg-slate-700"
+              }
+            }
+            on:click=move |_| style.set(value)
+          >
+            {value.label()}
+          </button>
+        }
+    };
+
+    view! {
+      <div class="mt-3 space-y-3">
+        <div class="flex items-center justify-between flex-wrap gap-2">
+          <div class="flex rounded border border-slate-300 dark:border-slate-600 overflow-hidden text-sm">
+            {style_button(LabelStyle::Fda)} {style_button(LabelStyle::Eu)}
+          </div>
+          <div class="flex gap-3 text-sm">
+            {recipe_id
+              .map(|id| {
+                view! {
+                  <a
+                    class="text-blue-600 hover:underline dark:text-blue-400"
+                    target="_blank"
+                    href=move || {
+                      format!("/api/recipe-label/{}?style={}&{}", id, style.get().param(), daily_values.get().to_query())
+                    }
+                  >
+                    "Open SVG"
+                  </a>
+                }
+              })}
+            <button
+              class="text-blue-600 hover:underline dark:text-blue-400"
+              on:click=move |_| {
+                let svg = svg.with_value(|svg| svg());
+                download_png(&svg, format!("{}-{}.png", file_stem, style.get_untracked().param()));
+              }
+            >
+              "Download PNG"
+            </button>
+          </div>
+        </div>
+        <div class="overflow-x-auto" inner_html=move || svg.with_value(|svg| svg()) />
+      </div>
+    }
+}
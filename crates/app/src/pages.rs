@@ -4,7 +4,7 @@
 
 use leptos::prelude::*;
 
-use crate::auth::AdminAuthButton;
+use crate::auth::{AdminAuth, AdminAuthButton};
 use crate::components::NavLink;
 
 // ============================================================================
@@ -159,13 +159,23 @@ pub fn DarkModeToggle() -> impl IntoView {
 #[component]
 pub fn Navigation() -> impl IntoView {
     let (menu_open, set_menu_open) = signal(false);
-    let links: [(&str, &str); 5] = [
+    let links: [(&str, &str); 8] = [
         ("/", "Food Log"),
         ("/ingredients", "Ingredients"),
         ("/recipes", "Recipes"),
+        ("/pantry", "Pantry"),
+        ("/planner", "Planner"),
+        ("/stats", "Stats"),
         ("/settings", "Settings"),
         ("/about", "About"),
     ];
+    // Only shown while unlocked as admin
+    let admin_links: [(&str, &str); 3] = [
+        ("/quick-log", "Quick Log"),
+        ("/trash", "Trash"),
+        ("/audit", "Audit Log"),
+    ];
+    let is_admin = expect_context::<AdminAuth>().is_authenticated;
 
     view! {
       <nav class="bg-slate-800 text-white shadow-md dark:bg-slate-950">
@@ -180,6 +190,14 @@ pub fn Navigation() -> impl IntoView {
                     view! { <NavLink href=href label=label /> }
                   })
                   .collect_view()}
+                <Show when=move || is_admin.get()>
+                  {admin_links
+                    .iter()
+                    .map(|&(href, label)| {
+                      view! { <NavLink href=href label=label /> }
+                    })
+                    .collect_view()}
+                </Show>
               </div>
             </div>
 
@@ -245,6 +263,14 @@ pub fn Navigation() -> impl IntoView {
                     view! { <NavLink href=href label=label mobile=true /> }
                   })
                   .collect_view()}
+                <Show when=move || is_admin.get()>
+                  {admin_links
+                    .iter()
+                    .map(|&(href, label)| {
+                      view! { <NavLink href=href label=label mobile=true /> }
+                    })
+                    .collect_view()}
+                </Show>
               </div>
               <div class="border-t border-slate-700 dark:border-slate-800 pt-3">
                 <AdminAuthButton />
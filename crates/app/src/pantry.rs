@@ -0,0 +1,742 @@
+//! Pantry module
+//!
+//! Tracks how many grams of each ingredient are at home. Stock goes up when a low-stock
+//! item is checked off as bought and goes down when a food log for a recipe is created.
+//! The grams each food log took are recorded, so editing, trashing or restoring the log
+//! puts back exactly what it took before taking out what it uses now.
+//! The page also suggests recipes that can be cooked from what is in stock.
+
+use leptos::prelude::*;
+use serde::{Deserialize, Serialize};
+use server_fn::ServerFnError;
+
+use crate::auth::AdminAuth;
+use crate::components::{
+    is_backdrop_click, CloseIcon, EditIcon, ModalError, ModalFooter, PlusIcon, INPUT_CLASS,
+    LABEL_CLASS,
+};
+use crate::ingredients::get_ingredients;
+use crate::what_to_cook::WhatCanICook;
+
+// ============================================================================
+// Data Types
+// ============================================================================
+
+/// Stock of one ingredient in the pantry
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+pub struct PantryItem {
+    pub id: Option<i64>,
+    pub ingredient_id: i64,
+    pub ingredient_name: String,
+    pub grams_on_hand: f32,
+    /// The item is listed as low stock at or below this amount
+    pub low_stock_g: f32,
+    /// Optional expiry date, YYYY-MM-DD
+    pub expires_on: Option<String>,
+    // From the ingredient, used when restocking and for costs
+    pub package_size_g: f32,
+    pub package_price: f32,
+}
+
+impl PantryItem {
+    /// Whether the item should be bought again
+    pub fn is_low(&self) -> bool {
+        self.grams_on_hand <= self.low_stock_g
+    }
+
+    /// Whether the item expires on or before `date` (YYYY-MM-DD)
+    pub fn expires_by(&self, date: &str) -> bool {
+        self.expires_on
+            .as_deref()
+            .is_some_and(|expires| !expires.is_empty() && expires <= date)
+    }
+}
+
+// ============================================================================
+// Server Functions
+// ============================================================================
+
+#[cfg(feature = "ssr")]
+use crate::ingredients::SendD1Database;
+
+#[cfg(feature = "ssr")]
+fn pantry_item_from_row(row: &serde_json::Value) -> Option<PantryItem> {
+    Some(PantryItem {
+        id: row.get("id")?.as_i64(),
+        ingredient_id: row.get("ingredient_id")?.as_i64()?,
+        ingredient_name: row.get("name")?.as_str()?.to_string(),
+        grams_on_hand: row.get("grams_on_hand")?.as_f64()? as f32,
+        low_stock_g: row
+            .get("low_stock_g")
+            .and_then(|v| v.as_f64())
+            .unwrap_or(0.0) as f32,
+        expires_on: row
+            .get("expires_on")
+            .and_then(|v| v.as_str())
+            .map(|s| s.to_string()),
+        package_size_g: row
+            .get("package_size_g")
+            .and_then(|v| v.as_f64())
+            .unwrap_or(0.0) as f32,
+        package_price: row
+            .get("package_price")
+            .and_then(|v| v.as_f64())
+            .unwrap_or(0.0) as f32,
+    })
+}
+
+/// Bring the pantry in line with a food log. Whatever the entry took out before is
+/// put back, then `usage` (recipe id and servings eaten) is taken out again, or
+/// nothing when the entry no longer counts, e.g. after it was trashed.
+/// Stock never goes below zero: only the grams actually on hand are taken, and
+/// those are recorded so that they can be returned exactly.
+/// Ingredients that are not tracked in the pantry are left alone.
+#[cfg(feature = "ssr")]
+pub(crate) async fn sync_pantry_for_log(
+    db: &SendD1Database,
+    food_log_id: i64,
+    usage: Option<(i64, f32)>,
+) -> Result<(), ServerFnError> {
+    use send_wrapper::SendWrapper;
+
+    use crate::recipes::fetch_recipe;
+
+    let recipe = match usage {
+        Some((recipe_id, _)) => fetch_recipe(db, recipe_id).await?,
+        None => None,
+    };
+
+    SendWrapper::new(async {
+        let db = db.inner();
+        let log_id = food_log_id as f64;
+
+        let stmt = db.prepare(
+            "UPDATE pantry_items SET
+                 grams_on_hand = grams_on_hand + (
+                     SELECT SUM(c.grams) FROM pantry_consumption c
+                     WHERE c.food_log_id = ? AND c.ingredient_id = pantry_items.ingredient_id
+                 ),
+                 updated_at = datetime('now')
+             WHERE ingredient_id IN (SELECT ingredient_id FROM pantry_consumption WHERE food_log_id = ?)",
+        );
+        let mut statements = vec![stmt.bind(&[log_id.into(), log_id.into()])?];
+        let stmt = db.prepare("DELETE FROM pantry_consumption WHERE food_log_id = ?");
+        statements.push(stmt.bind(&[log_id.into()])?);
+
+        if let (Some(recipe), Some((_, servings))) = (&recipe, usage) {
+            let per_serving = recipe.servings.max(1) as f32;
+            for ing in &recipe.ingredients {
+                let grams = (ing.effective_grams() / per_serving * servings) as f64;
+                let ingredient_id = ing.ingredient_id as f64;
+
+                // Record what is taken before taking it, while the stock is still known
+                let stmt = db.prepare(
+                    "INSERT INTO pantry_consumption (food_log_id, ingredient_id, grams)
+                     SELECT ?, ingredient_id, MIN(grams_on_hand, ?) FROM pantry_items
+                     WHERE ingredient_id = ? AND grams_on_hand > 0",
+                );
+                statements.push(stmt.bind(&[log_id.into(), grams.into(), ingredient_id.into()])?);
+                let stmt = db.prepare(
+                    "UPDATE pantry_items SET grams_on_hand = MAX(0, grams_on_hand - ?), updated_at = datetime('now') WHERE ingredient_id = ?",
+                );
+                statements.push(stmt.bind(&[grams.into(), ingredient_id.into()])?);
+            }
+        }
+
+        db.batch(statements).await
+    })
+    .await
+    .map_err(|e| ServerFnError::new(format!("D1 pantry update error: {:?}", e)))?;
+
+    Ok(())
+}
+
+/// Fetch everything in the pantry
+#[server]
+pub async fn get_pantry() -> Result<Vec<PantryItem>, ServerFnError> {
+    use send_wrapper::SendWrapper;
+
+    let db = expect_context::<SendD1Database>();
+
+    let rows = SendWrapper::new(async {
+        let stmt = db.inner().prepare(
+            "SELECT p.id, p.ingredient_id, p.grams_on_hand, p.low_stock_g, p.expires_on,
+                    i.name, i.package_size_g, i.package_price
+             FROM pantry_items p
+             JOIN ingredients i ON p.ingredient_id = i.id
+             WHERE i.deleted_at IS NULL
+             ORDER BY i.name",
+        );
+        let results = stmt.all().await?;
+        results.results::<serde_json::Value>()
+    })
+    .await
+    .map_err(|e| ServerFnError::new(format!("D1 query error: {:?}", e)))?;
+
+    Ok(rows.iter().filter_map(pantry_item_from_row).collect())
+}
+
+/// Add an ingredient to the pantry or update its stock (admin only)
+#[server]
+pub async fn save_pantry_item(
+    item: PantryItem,
+    auth_token: Option<String>,
+) -> Result<(), ServerFnError> {
+    use send_wrapper::SendWrapper;
+    use wasm_bindgen::JsValue;
+
+    use crate::auth::require_admin;
+
+    require_admin(auth_token.as_deref()).await?;
+
+    let db = expect_context::<SendD1Database>();
+
+    let expires_on: JsValue = item
+        .expires_on
+        .as_deref()
+        .filter(|d| !d.is_empty())
+        .map(JsValue::from)
+        .unwrap_or(JsValue::NULL);
+
+    SendWrapper::new(async {
+        let stmt = db.inner().prepare(
+            "INSERT INTO pantry_items (ingredient_id, grams_on_hand, low_stock_g, expires_on) VALUES (?, ?, ?, ?)
+             ON CONFLICT(ingredient_id) DO UPDATE SET
+               grams_on_hand = excluded.grams_on_hand,
+               low_stock_g = excluded.low_stock_g,
+               expires_on = excluded.expires_on,
+               updated_at = datetime('now')",
+        );
+        let stmt = stmt.bind(&[
+            (item.ingredient_id as f64).into(),
+            item.grams_on_hand.max(0.0).into(),
+            item.low_stock_g.max(0.0).into(),
+            expires_on,
+        ])?;
+        stmt.run().await
+    })
+    .await
+    .map_err(|e| ServerFnError::new(format!("D1 pantry save error: {:?}", e)))?;
+
+    log::info!(
+        "Saved pantry item for ingredient id: {}",
+        item.ingredient_id
+    );
+    Ok(())
+}
+
+/// Stop tracking an ingredient in the pantry (admin only)
+#[server]
+pub async fn remove_pantry_item(
+    ingredient_id: i64,
+    auth_token: Option<String>,
+) -> Result<(), ServerFnError> {
+    use send_wrapper::SendWrapper;
+
+    use crate::auth::require_admin;
+
+    require_admin(auth_token.as_deref()).await?;
+
+    let db = expect_context::<SendD1Database>();
+
+    SendWrapper::new(async {
+        let stmt = db
+            .inner()
+            .prepare("DELETE FROM pantry_items WHERE ingredient_id = ?");
+        let stmt = stmt.bind(&[(ingredient_id as f64).into()])?;
+        stmt.run().await
+    })
+    .await
+    .map_err(|e| ServerFnError::new(format!("D1 pantry delete error: {:?}", e)))?;
+
+    log::info!("Removed ingredient id: {} from the pantry", ingredient_id);
+    Ok(())
+}
+
+/// Check off bought items: each ingredient gets one package added to its stock (admin only)
+#[server]
+pub async fn restock_pantry_items(
+    ingredient_ids: Vec<i64>,
+    auth_token: Option<String>,
+) -> Result<(), ServerFnError> {
+    use send_wrapper::SendWrapper;
+
+    use crate::auth::require_admin;
+
+    require_admin(auth_token.as_deref()).await?;
+
+    let db = expect_context::<SendD1Database>();
+
+    for ingredient_id in &ingredient_ids {
+        SendWrapper::new(async {
+            let stmt = db.inner().prepare(
+                "INSERT INTO pantry_items (ingredient_id, grams_on_hand)
+                 SELECT id, package_size_g FROM ingredients WHERE id = ?
+                 ON CONFLICT(ingredient_id) DO UPDATE SET
+                   grams_on_hand = grams_on_hand + excluded.grams_on_hand,
+                   updated_at = datetime('now')",
+            );
+            let stmt = stmt.bind(&[(*ingredient_id as f64).into()])?;
+            stmt.run().await
+        })
+        .await
+        .map_err(|e| ServerFnError::new(format!("D1 pantry restock error: {:?}", e)))?;
+    }
+
+    log::info!("Restocked {} pantry items", ingredient_ids.len());
+    Ok(())
+}
+
+// ============================================================================
+// Components
+// ============================================================================
+
+/// Local date `days` from today as YYYY-MM-DD
+pub(crate) fn local_date_in_days(days: u32) -> String {
+    let date = js_sys::Date::new_0();
+    date.set_date(date.get_date() + days);
+    format!(
+        "{:04}-{:02}-{:02}",
+        date.get_full_year(),
+        date.get_month() + 1,
+        date.get_date()
+    )
+}
+
+/// Modal for adding an ingredient to the pantry or editing its stock
+#[component]
+fn PantryItemModal(
+    show: RwSignal<bool>,
+    editing: RwSignal<Option<PantryItem>>,
+    on_save: impl Fn() + Clone + Send + Sync + 'static,
+) -> impl IntoView {
+    let auth_token = expect_context::<AdminAuth>().token;
+    let ingredients = Resource::new(move || show.get(), |_| get_ingredients());
+
+    let ingredient_id = RwSignal::new(Option::<i64>::None);
+    let grams_on_hand = RwSignal::new(String::new());
+    let low_stock = RwSignal::new(String::new());
+    let expires_on = RwSignal::new(String::new());
+    let error = RwSignal::new(Option::<String>::None);
+    let (saving, set_saving) = signal(false);
+
+    // Populate form when editing changes
+    Effect::new(move || {
+        if let Some(item) = editing.get() {
+            ingredient_id.set(Some(item.ingredient_id));
+            grams_on_hand.set(item.grams_on_hand.to_string());
+            low_stock.set(item.low_stock_g.to_string());
+            expires_on.set(item.expires_on.unwrap_or_default());
+        } else {
+            ingredient_id.set(None);
+            grams_on_hand.set(String::new());
+            low_stock.set(String::new());
+            expires_on.set(String::new());
+        }
+        error.set(None);
+    });
+
+    let close = move || {
+        show.set(false);
+        editing.set(None);
+    };
+
+    let handle_save = move || {
+        let Some(id) = ingredient_id.get() else {
+            error.set(Some("Choose an ingredient".to_string()));
+            return;
+        };
+        let expires = expires_on.get();
+        let item = PantryItem {
+            id: None,
+            ingredient_id: id,
+            ingredient_name: String::new(),
+            grams_on_hand: grams_on_hand.get().parse().unwrap_or(0.0),
+            low_stock_g: low_stock.get().parse().unwrap_or(0.0),
+            expires_on: (!expires.is_empty()).then_some(expires),
+            package_size_g: 0.0,
+            package_price: 0.0,
+        };
+
+        set_saving.set(true);
+        let on_save = on_save.clone();
+        let token = auth_token.get_untracked();
+        wasm_bindgen_futures::spawn_local(async move {
+            match save_pantry_item(item, token).await {
+                Ok(()) => {
+                    show.set(false);
+                    editing.set(None);
+                    on_save();
+                }
+                Err(e) => {
+                    error.set(Some(format!("Failed to save: {}", e)));
+                }
+            }
+            set_saving.set(false);
+        });
+    };
+
+    view! {
+      <Show when=move || show.get()>
+        <div
+          id="pantry-modal-backdrop"
+          class="fixed inset-0 z-50 flex items-center justify-center bg-black/50 overflow-y-auto py-4"
+          on:click=move |ev: web_sys::MouseEvent| {
+            if is_backdrop_click(&ev, "pantry-modal-backdrop") {
+              close();
+            }
+          }
+        >
+          <div class="w-full max-w-lg rounded-lg bg-white dark:bg-slate-800 p-6 shadow-xl mx-4">
+            <div class="mb-4 flex items-center justify-between">
+              <h2 class="text-xl font-bold text-slate-900 dark:text-slate-100">
+                {move || if editing.get().is_some() { "Edit Pantry Item" } else { "Add to Pantry" }}
+              </h2>
+              <button
+                class="text-slate-500 hover:text-slate-700 dark:text-slate-400 dark:hover:text-slate-200"
+                on:click=move |_| close()
+              >
+                <CloseIcon />
+              </button>
+            </div>
+
+            <ModalError error=error />
+
+            <div class="grid grid-cols-2 gap-4">
+              <div class="col-span-2">
+                <label class=LABEL_CLASS>"Ingredient"</label>
+                {move || match editing.get() {
+                  Some(item) => {
+                    view! { <p class="py-2 text-sm font-medium text-slate-900 dark:text-slate-100">{item.ingredient_name}</p> }
+                      .into_any()
+                  }
+                  None => {
+                    view! {
+                      <Suspense fallback=move || {
+                        view! { <p class="text-sm text-slate-600 dark:text-slate-400">"Loading ingredients..."</p> }
+                      }>
+                        {move || {
+                          ingredients
+                            .get()
+                            .map(|result| {
+                              let list = result.unwrap_or_default();
+                              view! {
+                                <select
+                                  class=INPUT_CLASS
+                                  on:change=move |ev| ingredient_id.set(event_target_value(&ev).parse().ok())
+                                >
+                                  <option value="" selected=move || ingredient_id.get().is_none()>
+                                    "Choose an ingredient..."
+                                  </option>
+                                  {list
+                                    .into_iter()
+                                    .filter_map(|ing| {
+                                      let id = ing.id?;
+                                      Some(
+                                        view! {
+                                          <option value=id.to_string() selected=move || ingredient_id.get() == Some(id)>
+                                            {ing.name}
+                                          </option>
+                                        },
+                                      )
+                                    })
+                                    .collect_view()}
+                                </select>
+                              }
+                            })
+                        }}
+                      </Suspense>
+                    }
+                      .into_any()
+                  }
+                }}
+              </div>
+              <div>
+                <label class=LABEL_CLASS>"On hand (g)"</label>
+                <input
+                  type="number"
+                  step="1"
+                  class=INPUT_CLASS
+                  prop:value=move || grams_on_hand.get()
+                  on:input=move |ev| grams_on_hand.set(event_target_value(&ev))
+                />
+              </div>
+              <div>
+                <label class=LABEL_CLASS>"Low stock at (g)"</label>
+                <input
+                  type="number"
+                  step="1"
+                  class=INPUT_CLASS
+                  prop:value=move || low_stock.get()
+                  on:input=move |ev| low_stock.set(event_target_value(&ev))
+                />
+              </div>
+              <div class="col-span-2">
+                <label class=LABEL_CLASS>"Expires on (optional)"</label>
+                <input
+                  type="date"
+                  class=INPUT_CLASS
+                  prop:value=move || expires_on.get()
+                  on:input=move |ev| expires_on.set(event_target_value(&ev))
+                />
+              </div>
+            </div>
+
+            <ModalFooter saving=saving on_cancel=close on_save=handle_save.clone() />
+          </div>
+        </div>
+      </Show>
+    }
+}
+
+/// Pantry page with stock levels, a low-stock shopping list and expiry warnings
+#[component]
+pub fn Pantry() -> impl IntoView {
+    let auth = expect_context::<AdminAuth>();
+    let is_authenticated = auth.is_authenticated;
+    let auth_token = auth.token;
+
+    let pantry = Resource::new(|| (), |_| get_pantry());
+    let show_modal = RwSignal::new(false);
+    let editing = RwSignal::new(Option::<PantryItem>::None);
+    // Ingredients ticked off on the low-stock list
+    let bought = RwSignal::new(Vec::<i64>::new());
+    let error = RwSignal::new(Option::<String>::None);
+    let busy = RwSignal::new(false);
+
+    let refetch = move || pantry.refetch();
+
+    let handle_restock = move |_| {
+        let ids = bought.get_untracked();
+        if ids.is_empty() {
+            return;
+        }
+        busy.set(true);
+        error.set(None);
+        let token = auth_token.get_untracked();
+        wasm_bindgen_futures::spawn_local(async move {
+            match restock_pantry_items(ids, token).await {
+                Ok(()) => {
+                    bought.set(Vec::new());
+                    pantry.refetch();
+                }
+                Err(e) => error.set(Some(format!("Failed to restock: {}", e))),
+            }
+            busy.set(false);
+        });
+    };
+
+    let handle_remove = move |ingredient_id: i64| {
+        busy.set(true);
+        error.set(None);
+        let token = auth_token.get_untracked();
+        wasm_bindgen_futures::spawn_local(async move {
+            match remove_pantry_item(ingredient_id, token).await {
+                Ok(()) => pantry.refetch(),
+                Err(e) => error.set(Some(format!("Failed to remove: {}", e))),
+            }
+            busy.set(false);
+        });
+    };
+
+    let toggle_bought = move |ingredient_id: i64| {
+        bought.update(|ids| {
+            if let Some(pos) = ids.iter().position(|id| *id == ingredient_id) {
+                ids.remove(pos);
+            } else {
+                ids.push(ingredient_id);
+            }
+        });
+    };
+
+    view! {
+      <div class="mx-auto max-w-7xl py-6">
+        <div class="mb-6 flex items-center justify-between flex-wrap gap-4">
+          <h2 class="text-3xl font-bold text-slate-900 dark:text-slate-100">"Pantry"</h2>
+          <Show when=move || is_authenticated.get()>
+            <button
+              class="flex items-center gap-2 rounded bg-green-600 px-4 py-2 text-sm font-medium text-white hover:bg-green-700"
+              on:click=move |_| {
+                editing.set(None);
+                show_modal.set(true);
+              }
+            >
+              <PlusIcon />
+              "Add Item"
+            </button>
+          </Show>
+        </div>
+
+        <Show when=move || error.get().is_some()>
+          <div class="mb-4 rounded bg-red-100 px-4 py-3 text-red-700">{move || error.get().unwrap_or_default()}</div>
+        </Show>
+
+        <Suspense fallback=move || {
+          view! { <p class="text-slate-600 dark:text-slate-400">"Loading pantry..."</p> }
+        }>
+          {move || {
+            pantry
+              .get()
+              .map(|result| match result {
+                Ok(items) if items.is_empty() => {
+                  view! {
+                    <p class="text-slate-600 dark:text-slate-400">
+                      "Nothing in the pantry yet. Add ingredients to track what's at home."
+                    </p>
+                  }
+                    .into_any()
+                }
+                Ok(items) => {
+                  let today = local_date_in_days(0);
+                  let soon = local_date_in_days(7);
+                  let low: Vec<PantryItem> = items.iter().filter(|i| i.is_low()).cloned().collect();
+                  let has_low = !low.is_empty();
+                  view! {
+                    <Show when=move || has_low>
+                      <div class="mb-6 rounded-lg bg-white dark:bg-slate-800 p-6 shadow-md">
+                        <div class="mb-3 flex items-center justify-between flex-wrap gap-2">
+                          <div>
+                            <h3 class="text-lg font-bold text-slate-900 dark:text-slate-100">"Low Stock"</h3>
+                            <p class="text-xs text-slate-500 dark:text-slate-400">
+                              "Tick what you bought. Each item gets one package added."
+                            </p>
+                          </div>
+                          <Show when=move || is_authenticated.get()>
+                            <button
+                              class="rounded bg-blue-600 px-3 py-1 text-sm font-medium text-white hover:bg-blue-700 disabled:bg-blue-300 dark:disabled:bg-blue-800"
+                              disabled=move || busy.get() || bought.get().is_empty()
+                              on:click=handle_restock
+                            >
+                              "Mark as Bought"
+                            </button>
+                          </Show>
+                        </div>
+                        <ul class="space-y-1">
+                          {low
+                            .clone()
+                            .into_iter()
+                            .map(|item| {
+                              let ingredient_id = item.ingredient_id;
+                              view! {
+                                <li class="flex items-center gap-2 text-sm text-slate-700 dark:text-slate-300">
+                                  <input
+                                    type="checkbox"
+                                    class="h-4 w-4"
+                                    disabled=move || !is_authenticated.get()
+                                    prop:checked=move || bought.get().contains(&ingredient_id)
+                                    on:change=move |_| toggle_bought(ingredient_id)
+                                  />
+                                  <span class="font-medium">{item.ingredient_name.clone()}</span>
+                                  <span class="text-slate-500 dark:text-slate-400">
+                                    {format!(
+                                      "{:.0}g left, +{:.0}g per package{}",
+                                      item.grams_on_hand,
+                                      item.package_size_g,
+                                      if item.package_price > 0.0 {
+                                        format!(" (${:.2})", item.package_price)
+                                      } else {
+                                        String::new()
+                                      },
+                                    )}
+                                  </span>
+                                </li>
+                              }
+                            })
+                            .collect_view()}
+                        </ul>
+                      </div>
+                    </Show>
+
+                    <div class="rounded-lg bg-white shadow-md overflow-x-auto dark:bg-slate-800">
+                      <table class="min-w-full divide-y divide-slate-200 dark:divide-slate-700 text-sm">
+                        <thead class="bg-slate-50 dark:bg-slate-700">
+                          <tr class="text-left text-xs font-medium uppercase tracking-wider text-slate-500 dark:text-slate-400">
+                            <th class="px-4 py-3">"Ingredient"</th>
+                            <th class="px-4 py-3">"On hand"</th>
+                            <th class="px-4 py-3">"Low at"</th>
+                            <th class="px-4 py-3">"Expires"</th>
+                            <th class="px-4 py-3"></th>
+                          </tr>
+                        </thead>
+                        <tbody class="divide-y divide-slate-200 dark:divide-slate-700 text-slate-900 dark:text-slate-100">
+                          {items
+                            .into_iter()
+                            .map(|item| {
+                              let ingredient_id = item.ingredient_id;
+                              let stock_class = if item.is_low() {
+                                "px-4 py-2 font-medium text-amber-700 dark:text-amber-400"
+                              } else {
+                                "px-4 py-2"
+                              };
+                              let expiry_class = if item.expires_by(&today) {
+                                "px-4 py-2 font-medium text-red-600 dark:text-red-400"
+                              } else if item.expires_by(&soon) {
+                                "px-4 py-2 text-amber-700 dark:text-amber-400"
+                              } else {
+                                "px-4 py-2 text-slate-600 dark:text-slate-400"
+                              };
+                              let expiry = item.expires_on.clone().unwrap_or_else(|| "-".to_string());
+                              let item_for_edit = item.clone();
+                              view! {
+                                <tr class="hover:bg-slate-50 dark:hover:bg-slate-700">
+                                  <td class="px-4 py-2 font-medium">{item.ingredient_name.clone()}</td>
+                                  <td class=stock_class>{format!("{:.0}g", item.grams_on_hand)}</td>
+                                  <td class="px-4 py-2 text-slate-600 dark:text-slate-400">
+                                    {format!("{:.0}g", item.low_stock_g)}
+                                  </td>
+                                  <td class=expiry_class>{expiry}</td>
+                                  <td class="px-4 py-2 text-right">
+                                    <Show when=move || is_authenticated.get()>
+                                      <div class="flex items-center justify-end gap-2">
+                                        <button
+                                          class="text-blue-600 hover:text-blue-800 p-1"
+                                          title="Edit stock"
+                                          on:click={
+                                            let item_for_edit = item_for_edit.clone();
+                                            move |_| {
+                                              editing.set(Some(item_for_edit.clone()));
+                                              show_modal.set(true);
+                                            }
+                                          }
+                                        >
+                                          <EditIcon />
+                                        </button>
+                                        <button
+                                          class="text-xs text-red-600 hover:text-red-800 dark:text-red-400 disabled:opacity-50"
+                                          disabled=move || busy.get()
+                                          on:click=move |_| handle_remove(ingredient_id)
+                                        >
+                                          "Remove"
+                                        </button>
+                                      </div>
+                                    </Show>
+                                  </td>
+                                </tr>
+                              }
+                            })
+                            .collect_view()}
+                        </tbody>
+                      </table>
+                    </div>
+                  }
+                    .into_any()
+                }
+                Err(e) => {
+                  view! {
+                    <div class="rounded bg-red-100 px-4 py-3 text-red-700">
+                      <p class="font-medium">"Failed to load pantry"</p>
+                      <p class="text-sm">{e.to_string()}</p>
+                    </div>
+                  }
+                    .into_any()
+                }
+              })
+          }}
+        </Suspense>
+
+        <WhatCanICook pantry=Signal::derive(move || pantry.get().and_then(Result::ok).unwrap_or_default()) />
+
+        <PantryItemModal show=show_modal editing=editing on_save=refetch />
+      </div>
+    }
+}
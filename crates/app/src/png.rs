@@ -0,0 +1,265 @@
+//! PNG decoder module
+//!
+//! Decodes PNGs of every colour type and bit depth to RGB for the server-side image
+//! pipeline, on top of the `png` crate, compositing any transparency over white, and
+//! strips metadata chunks from uploads.
+
+use std::fmt;
+use std::io::Cursor;
+
+use crate::image_pipeline::{RgbImage, MAX_PIXELS};
+
+// ============================================================================
+// Errors
+// ============================================================================
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum PngError {
+    /// The data ended before the image did
+    Truncated,
+    /// More than `MAX_PIXELS`
+    TooLarge,
+    /// Corrupt or malformed data
+    Invalid(String),
+}
+
+impl fmt::Display for PngError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PngError::Truncated => write!(f, "PNG data is truncated"),
+            PngError::TooLarge => write!(f, "PNG is too large to decode"),
+            PngError::Invalid(what) => write!(f, "invalid PNG: {}", what),
+        }
+    }
+}
+
+impl std::error::Error for PngError {}
+
+impl From<png::DecodingError> for PngError {
+    fn from(e: png::DecodingError) -> Self {
+        match e {
+            png::DecodingError::IoError(e) if e.kind() == std::io::ErrorKind::UnexpectedEof => {
+                PngError::Truncated
+            }
+            png::DecodingError::LimitsExceeded => PngError::TooLarge,
+            e => PngError::Invalid(e.to_string()),
+        }
+    }
+}
+
+// ============================================================================
+// Decoding
+// ============================================================================
+
+/// Decode a PNG to RGB
+pub fn decode(data: &[u8]) -> Result<RgbImage, PngError> {
+    let mut decoder = png::Decoder::new(Cursor::new(data));
+    // Palettes, transparency and low bit depths expanded, 16-bit samples cut to 8
+    decoder.set_transformations(png::Transformations::normalize_to_color8());
+    let mut reader = decoder.read_info()?;
+    let (width, height) = (reader.info().width, reader.info().height);
+    if width as usize * height as usize > MAX_PIXELS {
+        return Err(PngError::TooLarge);
+    }
+
+    let size = reader.output_buffer_size().ok_or(PngError::TooLarge)?;
+    let mut buffer = vec![0; size];
+    let frame = reader.next_frame(&mut buffer)?;
+    let samples = &buffer[..frame.buffer_size()];
+
+    let mut image = RgbImage::new(frame.width, frame.height);
+    let channels = frame.color_type.samples();
+    for (pixel, out) in samples
+        .chunks_exact(channels)
+        .zip(image.pixels.chunks_exact_mut(3))
+    {
+        let (rgb, alpha) = match *pixel {
+            [l] => ([l; 3], 255),
+            [l, a] => ([l; 3], a),
+            [r, g, b] => ([r, g, b], 255),
+            [r, g, b, a] => ([r, g, b], a),
+            _ => return Err(PngError::Invalid("unexpected colour type".to_string())),
+        };
+        for (out, value) in out.iter_mut().zip(rgb) {
+            *out = over_white(value, alpha);
+        }
+    }
+    Ok(image)
+}
+
+/// A sample composited over white
+fn over_white(value: u8, alpha: u8) -> u8 {
+    let (value, alpha) = (value as u32, alpha as u32);
+    ((value * alpha + 255 * (255 - alpha) + 127) / 255) as u8
+}
+
+// ============================================================================
+// Metadata
+// ============================================================================
+
+const SIGNATURE: [u8; 8] = [0x89, b'P', b'N', b'G', 0x0D, 0x0A, 0x1A, 0x0A];
+
+/// Ancillary chunks that only carry metadata: text, modification time and EXIF
+const METADATA_CHUNKS: [&[u8; 4]; 5] = [b"tEXt", b"zTXt", b"iTXt", b"tIME", b"eXIf"];
+
+struct Chunk<'a> {
+    kind: &'a [u8],
+    /// Position of the chunk's length field in the file
+    offset: usize,
+    body: &'a [u8],
+}
+
+impl Chunk<'_> {
+    /// The whole chunk as stored: length, type, body and CRC
+    fn bytes<'d>(&self, data: &'d [u8]) -> &'d [u8] {
+        &data[self.offset..self.offset + 12 + self.body.len()]
+    }
+}
+
+/// Every chunk up to IEND, checking lengths and CRCs
+fn chunks(data: &[u8]) -> Result<Vec<Chunk<'_>>, PngError> {
+    let invalid = |what: &str| PngError::Invalid(what.to_string());
+    if !data.starts_with(&SIGNATURE) {
+        return Err(invalid("missing signature"));
+    }
+    let mut chunks = Vec::new();
+    let mut pos = SIGNATURE.len();
+    loop {
+        let length = data
+            .get(pos..pos + 4)
+            .map(|b| u32::from_be_bytes([b[0], b[1], b[2], b[3]]) as usize)
+            .ok_or(PngError::Truncated)?;
+        let typed = data
+            .get(pos + 4..pos + 8 + length)
+            .ok_or(PngError::Truncated)?;
+        let crc = data
+            .get(pos + 8 + length..pos + 12 + length)
+            .map(|b| u32::from_be_bytes([b[0], b[1], b[2], b[3]]))
+            .ok_or(PngError::Truncated)?;
+        if crc32fast::hash(typed) != crc {
+            return Err(invalid("chunk checksum mismatch"));
+        }
+        let (kind, body) = typed.split_at(4);
+        if chunks.is_empty() && kind != b"IHDR" {
+            return Err(invalid("missing header"));
+        }
+        chunks.push(Chunk {
+            kind,
+            offset: pos,
+            body,
+        });
+        if kind == b"IEND" {
+            return Ok(chunks);
+        }
+        pos += 12 + length;
+    }
+}
+
+/// The EXIF orientation (1-8) of a PNG, 1 when it doesn't say
+pub fn read_orientation(data: &[u8]) -> u8 {
+    chunks(data)
+        .unwrap_or_default()
+        .into_iter()
+        .find(|chunk| chunk.kind == b"eXIf")
+        .and_then(|chunk| crate::jpeg::exif_orientation(chunk.body))
+        .unwrap_or(1)
+}
+
+/// Copy a PNG without its text, time and EXIF chunks, checking its structure on the way
+pub fn strip_metadata(data: &[u8]) -> Result<Vec<u8>, PngError> {
+    let chunks = chunks(data)?;
+    // The decoder checks the header
+    png::Decoder::new(Cursor::new(data)).read_info()?;
+    if !chunks.iter().any(|chunk| chunk.kind == b"IDAT") {
+        return Err(PngError::Invalid("no image data".to_string()));
+    }
+    let mut out = Vec::with_capacity(data.len());
+    out.extend_from_slice(&SIGNATURE);
+    for chunk in &chunks {
+        if !METADATA_CHUNKS.iter().any(|&m| m == chunk.kind) {
+            out.extend_from_slice(chunk.bytes(data));
+        }
+    }
+    Ok(out)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn encode(width: u32, height: u32, color: png::ColorType, samples: &[u8]) -> Vec<u8> {
+        let mut out = Vec::new();
+        let mut encoder = png::Encoder::new(&mut out, width, height);
+        encoder.set_color(color);
+        encoder.set_depth(png::BitDepth::Eight);
+        let mut writer = encoder.write_header().unwrap();
+        writer.write_image_data(samples).unwrap();
+        writer.finish().unwrap();
+        out
+    }
+
+    fn chunk(kind: &[u8; 4], body: &[u8]) -> Vec<u8> {
+        let mut out = (body.len() as u32).to_be_bytes().to_vec();
+        out.extend_from_slice(kind);
+        out.extend_from_slice(body);
+        out.extend_from_slice(&crc32fast::hash(&out[4..]).to_be_bytes());
+        out
+    }
+
+    #[test]
+    fn decodes_and_composites_over_white() {
+        let data = encode(2, 1, png::ColorType::Rgba, &[255, 0, 0, 255, 0, 0, 255, 0]);
+        let image = decode(&data).unwrap();
+        assert_eq!((image.width, image.height), (2, 1));
+        assert_eq!(image.get(0, 0), [255, 0, 0]);
+        assert_eq!(image.get(1, 0), [255, 255, 255]);
+    }
+
+    #[test]
+    fn decodes_greyscale() {
+        let data = encode(2, 2, png::ColorType::Grayscale, &[0, 64, 128, 255]);
+        let image = decode(&data).unwrap();
+        assert_eq!(image.get(1, 0), [64, 64, 64]);
+        assert_eq!(image.get(1, 1), [255, 255, 255]);
+    }
+
+    #[test]
+    fn truncated_or_corrupt_data_is_an_error() {
+        let data = encode(4, 4, png::ColorType::Rgb, &[90; 48]);
+        assert!(decode(&data[..data.len() - 20]).is_err());
+        assert!(decode(&data[..10]).is_err());
+
+        let mut corrupt = data.clone();
+        let last = corrupt.len() - 16;
+        corrupt[last] ^= 0xFF;
+        assert!(decode(&corrupt).is_err());
+        assert!(decode(b"not a png").is_err());
+    }
+
+    #[test]
+    fn strips_metadata_chunks() {
+        let exif = [
+            b'M', b'M', 0, 42, 0, 0, 0, 8, 0, 1, 0x01, 0x12, 0, 3, 0, 0, 0, 1, 0, 6, 0, 0, 0, 0, 0,
+            0,
+        ];
+        let plain = encode(2, 2, png::ColorType::Rgb, &[90; 12]);
+        // The metadata goes after the signature and header
+        let mut tagged = plain[..33].to_vec();
+        tagged.extend(chunk(b"tEXt", b"Comment\0hello"));
+        tagged.extend(chunk(b"eXIf", &exif));
+        tagged.extend_from_slice(&plain[33..]);
+        assert_eq!(read_orientation(&tagged), 6);
+
+        let stripped = strip_metadata(&tagged).unwrap();
+        assert_eq!(stripped, plain);
+        assert_eq!(read_orientation(&stripped), 1);
+
+        let mut corrupt = tagged.clone();
+        let last = corrupt.len() - 1;
+        corrupt[last] ^= 1;
+        assert_eq!(
+            strip_metadata(&corrupt),
+            Err(PngError::Invalid("chunk checksum mismatch".to_string()))
+        );
+    }
+}
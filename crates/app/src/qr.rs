@@ -0,0 +1,616 @@
+//! QR code module
+//!
+//! A minimal QR code encoder for the short URLs printed on container labels: byte mode,
+//! error correction level M, versions 1 to 10 (up to 213 bytes). Follows the structure of
+//! the ISO/IEC 18004 reference algorithm, including automatic mask selection.
+
+use std::fmt::Write;
+
+const MAX_VERSION: usize = 10;
+
+// Error correction codewords per block and number of blocks for level M, indexed by version
+const ECC_CODEWORDS_PER_BLOCK: [usize; MAX_VERSION + 1] =
+    [0, 10, 16, 26, 18, 24, 16, 18, 22, 22, 26];
+const NUM_ECC_BLOCKS: [usize; MAX_VERSION + 1] = [0, 1, 1, 1, 2, 2, 4, 4, 4, 5, 5];
+
+// Format information bits for error correction level M
+const ECC_LEVEL_M_BITS: u32 = 0;
+
+// Penalty weights used when choosing a mask
+const PENALTY_N1: i32 = 3;
+const PENALTY_N2: i32 = 3;
+const PENALTY_N3: i32 = 40;
+const PENALTY_N4: i32 = 10;
+
+/// An encoded QR code as a square grid of dark/light modules
+#[derive(Clone, Debug, PartialEq)]
+pub struct QrCode {
+    size: usize,
+    modules: Vec<bool>,
+    is_function: Vec<bool>,
+}
+
+impl QrCode {
+    /// Encode `data` in the smallest version that fits. Returns `None` if it is too long.
+    pub fn encode(data: &[u8]) -> Option<Self> {
+        let version = (1..=MAX_VERSION).find(|&v| {
+            let count_bits = if v <= 9 { 8 } else { 16 };
+            4 + count_bits + data.len() * 8 <= num_data_codewords(v) * 8
+        })?;
+
+        // Segment: byte mode indicator, character count, data
+        let mut bits = BitBuffer::default();
+        bits.append(0b0100, 4);
+        bits.append(data.len() as u32, if version <= 9 { 8 } else { 16 });
+        for &b in data {
+            bits.append(b as u32, 8);
+        }
+
+        // Terminator, byte alignment and alternating pad bytes
+        let capacity = num_data_codewords(version) * 8;
+        bits.append(0, (capacity - bits.len()).min(4));
+        bits.append(0, (8 - bits.len() % 8) % 8);
+        for pad in [0xEC, 0x11].into_iter().cycle() {
+            if bits.len() >= capacity {
+                break;
+            }
+            bits.append(pad, 8);
+        }
+        let codewords = add_ecc_and_interleave(&bits.to_bytes(), version);
+
+        let size = version * 4 + 17;
+        let mut qr = Self {
+            size,
+            modules: vec![false; size * size],
+            is_function: vec![false; size * size],
+        };
+        qr.draw_function_patterns(version);
+        qr.draw_codewords(&codewords);
+
+        let best_mask = (0..8)
+            .min_by_key(|&mask| {
+                qr.apply_mask(mask);
+                qr.draw_format_bits(mask);
+                let penalty = qr.penalty_score();
+                qr.apply_mask(mask);
+                penalty
+            })
+            .unwrap_or(0);
+        qr.apply_mask(best_mask);
+        qr.draw_format_bits(best_mask);
+        Some(qr)
+    }
+
+    pub fn size(&self) -> usize {
+        self.size
+    }
+
+    /// Whether the module at column `x`, row `y` is dark
+    pub fn get(&self, x: usize, y: usize) -> bool {
+        self.modules[y * self.size + x]
+    }
+
+    /// SVG path data drawing every dark module as a unit square, offset by a quiet zone of `border` modules
+    pub fn svg_path(&self, border: usize) -> String {
+        let mut path = String::new();
+        for y in 0..self.size {
+            for x in 0..self.size {
+                if self.get(x, y) {
+                    let _ = write!(path, "M{},{}h1v1h-1z", x + border, y + border);
+                }
+            }
+        }
+        path
+    }
+
+    // ------------------------------------------------------------------------
+    // Function patterns
+    // ------------------------------------------------------------------------
+
+    fn set_function(&mut self, x: usize, y: usize, dark: bool) {
+        let i = y * self.size + x;
+        self.modules[i] = dark;
+        self.is_function[i] = true;
+    }
+
+    fn draw_function_patterns(&mut self, version: usize) {
+        let size = self.size;
+
+        // Timing patterns
+        for i in 0..size {
+            self.set_function(6, i, i % 2 == 0);
+            self.set_function(i, 6, i % 2 == 0);
+        }
+
+        // Finder patterns in three corners
+        self.draw_finder(3, 3);
+        self.draw_finder(size - 4, 3);
+        self.draw_finder(3, size - 4);
+
+        // Alignment patterns, except where they would overlap the finders
+        let positions = alignment_positions(version);
+        let n = positions.len();
+        for (i, &x) in positions.iter().enumerate() {
+            for (j, &y) in positions.iter().enumerate() {
+                let on_finder = (i == 0 && (j == 0 || j == n - 1)) || (i == n - 1 && j == 0);
+                if !on_finder {
+                    self.draw_alignment(x, y);
+                }
+            }
+        }
+
+        // Reserve the format areas; the real bits are drawn once the mask is known
+        self.draw_format_bits(0);
+        self.draw_version(version);
+    }
+
+    fn draw_finder(&mut self, cx: usize, cy: usize) {
+        for dy in -4i32..=4 {
+            for dx in -4i32..=4 {
+                let x = cx as i32 + dx;
+                let y = cy as i32 + dy;
+                if (0..self.size as i32).contains(&x) && (0..self.size as i32).contains(&y) {
+                    let dist = dx.abs().max(dy.abs());
+                    self.set_function(x as usize, y as usize, dist != 2 && dist != 4);
+                }
+            }
+        }
+    }
+
+    fn draw_alignment(&mut self, cx: usize, cy: usize) {
+        for dy in -2i32..=2 {
+            for dx in -2i32..=2 {
+                let dark = dx.abs().max(dy.abs()) != 1;
+                self.set_function((cx as i32 + dx) as usize, (cy as i32 + dy) as usize, dark);
+            }
+        }
+    }
+
+    fn draw_format_bits(&mut self, mask: u32) {
+        let data = (ECC_LEVEL_M_BITS << 3) | mask;
+        let mut rem = data;
+        for _ in 0..10 {
+            rem = (rem << 1) ^ ((rem >> 9) * 0x537);
+        }
+        let bits = ((data << 10) | rem) ^ 0x5412;
+        let bit = |i: u32| (bits >> i) & 1 != 0;
+        let size = self.size;
+
+        // First copy, around the top-left finder
+        for i in 0..=5 {
+            self.set_function(8, i, bit(i as u32));
+        }
+        self.set_function(8, 7, bit(6));
+        self.set_function(8, 8, bit(7));
+        self.set_function(7, 8, bit(8));
+        for i in 9..15 {
+            self.set_function(14 - i, 8, bit(i as u32));
+        }
+
+        // Second copy, split between the other two finders
+        for i in 0..8 {
+            self.set_function(size - 1 - i, 8, bit(i as u32));
+        }
+        for i in 8..15 {
+            self.set_function(8, size - 15 + i, bit(i as u32));
+        }
+        self.set_function(8, size - 8, true);
+    }
+
+    fn draw_version(&mut self, version: usize) {
+        if version < 7 {
+            return;
+        }
+        let mut rem = version as u32;
+        for _ in 0..12 {
+            rem = (rem << 1) ^ ((rem >> 11) * 0x1F25);
+        }
+        let bits = ((version as u32) << 12) | rem;
+        for i in 0..18 {
+            let dark = (bits >> i) & 1 != 0;
+            let a = self.size - 11 + i % 3;
+            let b = i / 3;
+            self.set_function(a, b, dark);
+            self.set_function(b, a, dark);
+        }
+    }
+
+    // ------------------------------------------------------------------------
+    // Data and masking
+    // ------------------------------------------------------------------------
+
+    /// Place the codeword bits in the zigzag order, skipping function modules
+    fn draw_codewords(&mut self, data: &[u8]) {
+        let size = self.size;
+        let mut i = 0;
+        let mut right = size - 1;
+        loop {
+            // The vertical timing pattern shifts the column pairs left by one
+            if right == 6 {
+                right = 5;
+            }
+            let upward = (right + 1) & 2 == 0;
+            for vert in 0..size {
+                for j in 0..2 {
+                    let x = right - j;
+                    let y = if upward { size - 1 - vert } else { vert };
+                    let index = y * size + x;
+                    if !self.is_function[index] && i < data.len() * 8 {
+                        self.modules[index] = (data[i >> 3] >> (7 - (i & 7))) & 1 != 0;
+                        i += 1;
+                    }
+                }
+            }
+            if right < 3 {
+                break;
+            }
+            right -= 2;
+        }
+    }
+
+    /// XOR a mask pattern over the data modules. Applying the same mask twice undoes it.
+    fn apply_mask(&mut self, mask: u32) {
+        for y in 0..self.size {
+            for x in 0..self.size {
+                let invert = match mask {
+                    0 => (x + y) % 2 == 0,
+                    1 => y % 2 == 0,
+                    2 => x % 3 == 0,
+                    3 => (x + y) % 3 == 0,
+                    4 => (x / 3 + y / 2) % 2 == 0,
+                    5 => x * y % 2 + x * y % 3 == 0,
+                    6 => (x * y % 2 + x * y % 3) % 2 == 0,
+                    _ => ((x + y) % 2 + x * y % 3) % 2 == 0,
+                };
+                let index = y * self.size + x;
+                if invert && !self.is_function[index] {
+                    self.modules[index] = !self.modules[index];
+                }
+            }
+        }
+    }
+
+    /// Penalty for hard-to-scan features: long runs, 2x2 blocks, finder look-alikes and imbalance
+    fn penalty_score(&self) -> i32 {
+        let size = self.size;
+        let mut penalty = 0;
+
+        let lines = (0..size)
+            .map(|y| (0..size).map(|x| self.get(x, y)).collect::<Vec<_>>())
+            .chain((0..size).map(|x| (0..size).map(|y| self.get(x, y)).collect::<Vec<_>>()));
+        for line in lines {
+            // Runs of five or more modules of one colour
+            let mut run = 1;
+            for i in 1..=line.len() {
+                if i < line.len() && line[i] == line[i - 1] {
+                    run += 1;
+                } else {
+                    if run >= 5 {
+                        penalty += PENALTY_N1 + (run - 5);
+                    }
+                    run = 1;
+                }
+            }
+
+            // 1:1:3:1:1 finder-like patterns with four light modules on one side
+            let mut padded = vec![false; 4];
+            padded.extend_from_slice(&line);
+            padded.extend_from_slice(&[false; 4]);
+            const FINDER: [bool; 7] = [true, false, true, true, true, false, true];
+            for window in padded.windows(11) {
+                let light_before = window[..4].iter().all(|m| !m) && window[4..] == FINDER;
+                let light_after = window[..7] == FINDER && window[7..].iter().all(|m| !m);
+                if light_before || light_after {
+                    penalty += PENALTY_N3;
+                }
+            }
+        }
+
+        // 2x2 blocks of one colour
+        for y in 0..size - 1 {
+            for x in 0..size - 1 {
+                let c = self.get(x, y);
+                if c == self.get(x + 1, y) && c == self.get(x, y + 1) && c == self.get(x + 1, y + 1)
+                {
+                    penalty += PENALTY_N2;
+                }
+            }
+        }
+
+        // Balance of dark and light modules
+        let dark = self.modules.iter().filter(|m| **m).count() as i32;
+        let total = (size * size) as i32;
+        let k = ((dark * 20 - total * 10).abs() + total - 1) / total - 1;
+        penalty + k * PENALTY_N4
+    }
+}
+
+// ============================================================================
+// Codewords
+// ============================================================================
+
+#[derive(Default)]
+struct BitBuffer(Vec<bool>);
+
+impl BitBuffer {
+    fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    fn append(&mut self, value: u32, count: usize) {
+        for i in (0..count).rev() {
+            self.0.push((value >> i) & 1 != 0);
+        }
+    }
+
+    fn to_bytes(&self) -> Vec<u8> {
+        self.0
+            .chunks(8)
+            .map(|chunk| chunk.iter().fold(0u8, |acc, &bit| (acc << 1) | bit as u8))
+            .collect()
+    }
+}
+
+/// Number of modules available for codewords once function patterns are placed
+fn num_raw_data_modules(version: usize) -> usize {
+    let mut result = (16 * version + 128) * version + 64;
+    if version >= 2 {
+        let num_align = version / 7 + 2;
+        result -= (25 * num_align - 10) * num_align - 55;
+        if version >= 7 {
+            result -= 36;
+        }
+    }
+    result
+}
+
+fn num_data_codewords(version: usize) -> usize {
+    num_raw_data_modules(version) / 8 - ECC_CODEWORDS_PER_BLOCK[version] * NUM_ECC_BLOCKS[version]
+}
+
+fn alignment_positions(version: usize) -> Vec<usize> {
+    if version == 1 {
+        return Vec::new();
+    }
+    let size = version * 4 + 17;
+    let num_align = version / 7 + 2;
+    let step = (version * 4 + num_align * 2 + 1) / (num_align * 2 - 2) * 2;
+    let mut positions: Vec<usize> = (0..num_align - 1).map(|i| size - 7 - i * step).collect();
+    positions.push(6);
+    positions.reverse();
+    positions
+}
+
+/// Split the data into blocks, append Reed-Solomon codewords to each and interleave them
+fn add_ecc_and_interleave(data: &[u8], version: usize) -> Vec<u8> {
+    let num_blocks = NUM_ECC_BLOCKS[version];
+    let ecc_len = ECC_CODEWORDS_PER_BLOCK[version];
+    let raw_codewords = num_raw_data_modules(version) / 8;
+    let num_short_blocks = num_blocks - raw_codewords % num_blocks;
+    let short_block_len = raw_codewords / num_blocks;
+
+    let divisor = reed_solomon_divisor(ecc_len);
+    let mut blocks = Vec::with_capacity(num_blocks);
+    let mut offset = 0;
+    for i in 0..num_blocks {
+        let data_len = short_block_len - ecc_len + usize::from(i >= num_short_blocks);
+        let block_data = &data[offset..offset + data_len];
+        offset += data_len;
+        let mut block = block_data.to_vec();
+        if i < num_short_blocks {
+            // Placeholder so every block has the same length; skipped when interleaving
+            block.push(0);
+        }
+        block.extend(reed_solomon_remainder(block_data, &divisor));
+        blocks.push(block);
+    }
+
+    let mut result = Vec::with_capacity(raw_codewords);
+    for i in 0..=short_block_len {
+        for (j, block) in blocks.iter().enumerate() {
+            if i != short_block_len - ecc_len || j >= num_short_blocks {
+                result.push(block[i]);
+            }
+        }
+    }
+    result
+}
+
+fn reed_solomon_divisor(degree: usize) -> Vec<u8> {
+    let mut result = vec![0u8; degree];
+    result[degree - 1] = 1;
+    let mut root = 1u8;
+    for _ in 0..degree {
+        for j in 0..degree {
+            result[j] = gf_multiply(result[j], root);
+            if j + 1 < degree {
+                result[j] ^= result[j + 1];
+            }
+        }
+        root = gf_multiply(root, 0x02);
+    }
+    result
+}
+
+fn reed_solomon_remainder(data: &[u8], divisor: &[u8]) -> Vec<u8> {
+    let mut result = vec![0u8; divisor.len()];
+    for &b in data {
+        let factor = b ^ result.remove(0);
+        result.push(0);
+        for (r, &d) in result.iter_mut().zip(divisor) {
+            *r ^= gf_multiply(d, factor);
+        }
+    }
+    result
+}
+
+/// Multiplication in GF(2^8) modulo x^8 + x^4 + x^3 + x^2 + 1
+fn gf_multiply(x: u8, y: u8) -> u8 {
+    let mut z: u32 = 0;
+    for i in (0..8).rev() {
+        z = (z << 1) ^ ((z >> 7) * 0x11D);
+        z ^= ((y as u32 >> i) & 1) * x as u32;
+    }
+    z as u8
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Version of a code from its size
+    fn version_of(qr: &QrCode) -> usize {
+        (qr.size - 17) / 4
+    }
+
+    /// Read the 15 format bits from the copy around the top-left finder
+    fn format_bits(qr: &QrCode) -> u32 {
+        let mut coords: Vec<(usize, usize)> = (0..=5).map(|i| (8, i)).collect();
+        coords.extend([(8, 7), (8, 8), (7, 8)]);
+        coords.extend((9..15).map(|i| (14 - i, 8)));
+        coords
+            .iter()
+            .enumerate()
+            .map(|(i, &(x, y))| u32::from(qr.get(x, y)) << i)
+            .sum()
+    }
+
+    /// Undo the mask and read the codewords back in zigzag order
+    fn read_codewords(qr: &QrCode, mask: u32) -> Vec<u8> {
+        let mut unmasked = qr.clone();
+        unmasked.apply_mask(mask);
+        let size = qr.size;
+        let mut bits = Vec::new();
+        let mut right = size - 1;
+        loop {
+            if right == 6 {
+                right = 5;
+            }
+            let upward = (right + 1) & 2 == 0;
+            for vert in 0..size {
+                for j in 0..2 {
+                    let x = right - j;
+                    let y = if upward { size - 1 - vert } else { vert };
+                    if !unmasked.is_function[y * size + x] {
+                        bits.push(unmasked.get(x, y));
+                    }
+                }
+            }
+            if right < 3 {
+                break;
+            }
+            right -= 2;
+        }
+        bits.chunks_exact(8)
+            .map(|byte| byte.iter().fold(0u8, |acc, &b| (acc << 1) | u8::from(b)))
+            .collect()
+    }
+
+    /// Split interleaved codewords back into (data, ecc) per block
+    fn deinterleave(codewords: &[u8], version: usize) -> Vec<(Vec<u8>, Vec<u8>)> {
+        let num_blocks = NUM_ECC_BLOCKS[version];
+        let ecc_len = ECC_CODEWORDS_PER_BLOCK[version];
+        let raw_codewords = num_raw_data_modules(version) / 8;
+        let num_short_blocks = num_blocks - raw_codewords % num_blocks;
+        let short_block_len = raw_codewords / num_blocks;
+
+        let mut blocks = vec![Vec::new(); num_blocks];
+        let mut next = codewords.iter();
+        for i in 0..=short_block_len {
+            for (j, block) in blocks.iter_mut().enumerate() {
+                if i != short_block_len - ecc_len || j >= num_short_blocks {
+                    block.push(*next.next().unwrap());
+                }
+            }
+        }
+        blocks
+            .into_iter()
+            .map(|block| {
+                let split = block.len() - ecc_len;
+                (block[..split].to_vec(), block[split..].to_vec())
+            })
+            .collect()
+    }
+
+    /// Decode a byte-mode payload, checking the format bits and error correction on the way
+    fn decode(qr: &QrCode) -> Vec<u8> {
+        let version = version_of(qr);
+        let format = format_bits(qr) ^ 0x5412;
+        assert_eq!(format >> 13, ECC_LEVEL_M_BITS, "error correction level");
+        let mask = (format >> 10) & 7;
+        let mut rem = format >> 10;
+        for _ in 0..10 {
+            rem = (rem << 1) ^ ((rem >> 9) * 0x537);
+        }
+        assert_eq!(format & 0x3FF, rem, "format BCH code");
+
+        let divisor = reed_solomon_divisor(ECC_CODEWORDS_PER_BLOCK[version]);
+        let mut data = Vec::new();
+        for (block, ecc) in deinterleave(&read_codewords(qr, mask), version) {
+            assert_eq!(reed_solomon_remainder(&block, &divisor), ecc);
+            data.extend(block);
+        }
+
+        let mut bits = data
+            .iter()
+            .flat_map(|byte| (0..8).rev().map(move |i| u32::from(byte >> i & 1)));
+        let mut take = |n: usize| (0..n).fold(0u32, |acc, _| (acc << 1) | bits.next().unwrap());
+        assert_eq!(take(4), 0b0100, "byte mode");
+        let len = take(if version <= 9 { 8 } else { 16 }) as usize;
+        (0..len).map(|_| take(8) as u8).collect()
+    }
+
+    #[test]
+    fn reed_solomon_matches_the_reference_example() {
+        // "HELLO WORLD" at 1-M from the ISO/IEC 18004 worked example
+        let data = [
+            32, 91, 11, 120, 209, 114, 220, 77, 67, 64, 236, 17, 236, 17, 236, 17,
+        ];
+        let ecc = reed_solomon_remainder(&data, &reed_solomon_divisor(10));
+        assert_eq!(ecc, vec![196, 35, 39, 119, 235, 215, 231, 226, 93, 23]);
+    }
+
+    #[test]
+    fn picks_the_smallest_version_that_fits() {
+        assert_eq!(QrCode::encode(&[b'a'; 14]).unwrap().size(), 21);
+        assert_eq!(QrCode::encode(&[b'a'; 15]).unwrap().size(), 25);
+        assert_eq!(QrCode::encode(&[b'a'; 213]).unwrap().size(), 57);
+        assert!(QrCode::encode(&[b'a'; 214]).is_none());
+    }
+
+    #[test]
+    fn draws_finder_and_timing_patterns() {
+        let qr = QrCode::encode(b"https://example.com/log?recipe=1").unwrap();
+        let size = qr.size();
+        for (cx, cy) in [(3, 3), (size - 4, 3), (3, size - 4)] {
+            assert!(qr.get(cx, cy), "finder centre");
+            assert!(!qr.get(cx - 2, cy), "finder light ring");
+            assert!(qr.get(cx - 3, cy - 3), "finder corner");
+        }
+        for i in 8..size - 8 {
+            assert_eq!(qr.get(i, 6), i % 2 == 0);
+            assert_eq!(qr.get(6, i), i % 2 == 0);
+        }
+        assert!(qr.get(8, size - 8), "dark module");
+    }
+
+    #[test]
+    fn decodes_back_to_the_input() {
+        for len in [0, 1, 14, 15, 40, 100, 150, 213] {
+            let data: Vec<u8> = (0..len).map(|i| (i * 37 % 251) as u8).collect();
+            let qr = QrCode::encode(&data).unwrap();
+            assert_eq!(decode(&qr), data, "{} bytes", len);
+        }
+    }
+
+    #[test]
+    fn svg_path_has_one_square_per_dark_module() {
+        let qr = QrCode::encode(b"label").unwrap();
+        let dark = (0..qr.size())
+            .flat_map(|y| (0..qr.size()).map(move |x| (x, y)))
+            .filter(|&(x, y)| qr.get(x, y))
+            .count();
+        assert_eq!(qr.svg_path(4).matches('M').count(), dark);
+        assert!(qr.svg_path(4).starts_with("M4,4h1v1h-1z"));
+    }
+}
@@ -0,0 +1,426 @@
+//! Quick Log module
+//!
+//! A phone-sized page for logging a meal photo first: it opens the camera straight
+//! away, fills in the date, time, meal and the recipe most likely being eaten, and
+//! saves in one tap. The photo keeps the default crop until the entry is edited on the
+//! Food Log page.
+
+use leptos::prelude::*;
+use leptos_router::hooks::use_navigate;
+use leptos_router::NavigateOptions;
+
+use crate::auth::AdminAuth;
+#[cfg(not(feature = "ssr"))]
+use crate::cache::{get_cache, FOOD_LOGS_CACHE_KEY, RECIPES_CACHE_KEY};
+use crate::components::{ImageIcon, INPUT_CLASS, LABEL_CLASS};
+use crate::food_log::{
+    create_food_log, days_from_date, get_food_logs, local_now, FoodLog, FoodLogFilter,
+    FoodLogImage, MealType,
+};
+use crate::image_upload::{
+    check_upload_size, prepare_photo, random_key, upload_photo, ImageFormat, UploadError,
+};
+use crate::recipes::{get_recipes, Recipe};
+use crate::settings::load_settings;
+
+// ============================================================================
+// Recipe Prediction
+// ============================================================================
+
+/// Minutes since midnight of a time in `HH:MM` form
+fn minutes_of_day(time: &str) -> Option<i64> {
+    let (hours, minutes) = time.split_once(':')?;
+    Some(hours.parse::<i64>().ok()? * 60 + minutes.parse::<i64>().ok()?)
+}
+
+/// The recipe most likely being eaten as `meal` at `logged_at` (`YYYY-MM-DDTHH:MM`),
+/// judging by earlier entries. Each entry votes for its recipe, counting for more the
+/// closer its time of day and the more recent it is, and double for the same meal.
+pub fn likely_recipe(history: &[FoodLog], logged_at: &str, meal: MealType) -> Option<i64> {
+    let today = days_from_date(logged_at)?;
+    let now = logged_at.get(11..16).and_then(minutes_of_day);
+
+    let mut scores: Vec<(i64, f64)> = Vec::new();
+    for log in history {
+        let (Some(recipe_id), Some(day)) = (log.recipe_id, days_from_date(&log.logged_at)) else {
+            continue;
+        };
+        // A week ago counts half as much as today
+        let recency = 1.0 / (1.0 + (today - day).max(0) as f64 / 7.0);
+        // From 1 at the same time of day down to 0 twelve hours away, wrapping at midnight
+        let closeness = match (now, log.time().and_then(minutes_of_day)) {
+            (Some(now), Some(then)) => {
+                let apart = (now - then).rem_euclid(24 * 60);
+                1.0 - apart.min(24 * 60 - apart) as f64 / (12.0 * 60.0)
+            }
+            _ => 0.5,
+        };
+        let same_meal = if log.meal_type == meal { 2.0 } else { 1.0 };
+        let score = recency * (0.25 + closeness) * same_meal;
+        match scores.iter_mut().find(|(id, _)| *id == recipe_id) {
+            Some((_, total)) => *total += score,
+            None => scores.push((recipe_id, score)),
+        }
+    }
+
+    scores
+        .into_iter()
+        .max_by(|a, b| a.1.total_cmp(&b.1))
+        .map(|(id, _)| id)
+}
+
+// ============================================================================
+// Page Component
+// ============================================================================
+
+/// The photo taken for the entry, waiting to be uploaded
+#[derive(Clone)]
+struct QuickPhoto {
+    /// Local object URL the photo is previewed from
+    src: String,
+    blob: web_sys::Blob,
+    /// Kept across save attempts so a failed upload resumes
+    upload_id: String,
+    /// Whether the photo has already been shrunk for upload
+    prepared: bool,
+}
+
+#[component]
+pub fn QuickLog() -> impl IntoView {
+    let auth = expect_context::<AdminAuth>();
+    let auth_token = auth.token;
+    let navigate = use_navigate();
+
+    let photo = RwSignal::new_local(Option::<QuickPhoto>::None);
+    let logged_date = RwSignal::new(String::new());
+    let logged_time = RwSignal::new(String::new());
+    let meal_type = RwSignal::new(MealType::default());
+    // The recipe picked by hand; until then the predicted one is used
+    let chosen_recipe = RwSignal::new(Option::<Option<i64>>::None);
+    let upload_progress = RwSignal::new(Option::<f64>::None);
+    let error = RwSignal::new(Option::<String>::None);
+    let saving = RwSignal::new(false);
+
+    let history = RwSignal::new(Vec::<FoodLog>::new());
+    let recipes = RwSignal::new(Vec::<Recipe>::new());
+
+    // Predict from the cached lists straight away, then from fresh ones
+    #[cfg(not(feature = "ssr"))]
+    {
+        if let Some(cached) = get_cache::<Vec<FoodLog>>(FOOD_LOGS_CACHE_KEY) {
+            history.set(cached);
+        }
+        if let Some(cached) = get_cache::<Vec<Recipe>>(RECIPES_CACHE_KEY) {
+            recipes.set(cached);
+        }
+    }
+
+    let history_resource = Resource::new(|| (), |_| get_food_logs(FoodLogFilter::default(), None));
+    let recipes_resource = Resource::new(|| (), |_| get_recipes());
+
+    Effect::new(move || {
+        if let Some(Ok(page)) = history_resource.get() {
+            history.set(page.logs);
+        }
+    });
+
+    Effect::new(move || {
+        if let Some(Ok(list)) = recipes_resource.get() {
+            recipes.set(list);
+        }
+    });
+
+    Effect::new(move || {
+        let now = FoodLog {
+            logged_at: local_now(),
+            ..FoodLog::new_empty()
+        };
+        logged_date.set(now.date().to_string());
+        logged_time.set(now.time().unwrap_or_default().to_string());
+        meal_type.set(MealType::for_time(now.time().unwrap_or_default()));
+    });
+
+    // Only recipes that still exist can be predicted
+    let predicted_recipe = Memo::new(move |_| {
+        let available: Vec<FoodLog> = recipes.with(|recipes| {
+            history.with(|history| {
+                history
+                    .iter()
+                    .filter(|log| {
+                        log.recipe_id
+                            .is_some_and(|id| recipes.iter().any(|r| r.id == Some(id)))
+                    })
+                    .cloned()
+                    .collect()
+            })
+        });
+        let logged_at = format!("{}T{}", logged_date.get(), logged_time.get());
+        likely_recipe(&available, &logged_at, meal_type.get())
+    });
+    let recipe_id = move || {
+        chosen_recipe
+            .get()
+            .unwrap_or_else(|| predicted_recipe.get())
+    };
+
+    let file_input_ref = NodeRef::<leptos::html::Input>::new();
+
+    // Open the camera as soon as the page is shown. Browsers only allow this shortly
+    // after a tap, such as the one that opened the page; otherwise the big button does it.
+    let camera_opened = RwSignal::new(false);
+    Effect::new(move || {
+        if let Some(input) = file_input_ref.get() {
+            if !camera_opened.get_untracked() {
+                camera_opened.set(true);
+                input.click();
+            }
+        }
+    });
+
+    let handle_file_select = move |_| {
+        let Some(input) = file_input_ref.get() else {
+            return;
+        };
+        let Some(file) = input.files().and_then(|files| files.get(0)) else {
+            return;
+        };
+        // Let the same photo be chosen again
+        input.set_value("");
+
+        // The server checks the photo properly; this catches the obvious cases before
+        // the upload. Some browsers don't report a type, so an empty one is let through.
+        let content_type = file.type_();
+        let check =
+            if content_type.is_empty() || ImageFormat::from_content_type(&content_type).is_some() {
+                check_upload_size(file.size() as usize)
+            } else {
+                Err(UploadError::UnsupportedFormat)
+            };
+        if let Err(e) = check {
+            error.set(Some(format!("{}: {}", file.name(), e)));
+            return;
+        }
+        let Ok(src) = web_sys::Url::create_object_url_with_blob(&file) else {
+            return;
+        };
+        error.set(None);
+        photo.set(Some(QuickPhoto {
+            src,
+            blob: file.into(),
+            upload_id: random_key(),
+            prepared: false,
+        }));
+    };
+
+    let open_camera = move |_| {
+        if let Some(input) = file_input_ref.get() {
+            input.click();
+        }
+    };
+
+    let handle_save = move |_| {
+        let Some(mut taken) = photo.get_untracked() else {
+            return;
+        };
+        if logged_date.get_untracked().trim().is_empty() {
+            error.set(Some("Date is required".to_string()));
+            return;
+        }
+
+        saving.set(true);
+        error.set(None);
+        let token = auth_token.get_untracked();
+        let navigate = navigate.clone();
+
+        wasm_bindgen_futures::spawn_local(async move {
+            // Shrink the photo for upload. Animated GIFs would lose their animation on a
+            // canvas, and a failure just means uploading the original more slowly.
+            if !taken.prepared {
+                taken.prepared = true;
+                if taken.blob.type_() != ImageFormat::Gif.content_type() {
+                    match prepare_photo(&taken.src, 0, &load_settings().photo).await {
+                        Ok(blob) if blob.size() < taken.blob.size() => taken.blob = blob,
+                        Ok(_) => {}
+                        Err(e) => log::warn!("Failed to shrink photo before upload: {}", e),
+                    }
+                }
+                photo.set(Some(taken.clone()));
+            }
+
+            upload_progress.set(Some(0.0));
+            let uploaded = upload_photo(&taken.blob, &taken.upload_id, |fraction| {
+                upload_progress.set(Some(fraction))
+            })
+            .await;
+            upload_progress.set(None);
+            let image_key = match uploaded {
+                Ok(key) => key,
+                Err(message) => {
+                    error.set(Some(format!("Failed to upload photo: {}", message)));
+                    saving.set(false);
+                    return;
+                }
+            };
+
+            let log = FoodLog {
+                recipe_id: recipe_id(),
+                images: vec![FoodLogImage::new(image_key)],
+                logged_at: match logged_time.get_untracked() {
+                    time if time.is_empty() => logged_date.get_untracked(),
+                    time => format!("{}T{}", logged_date.get_untracked(), time),
+                },
+                meal_type: meal_type.get_untracked(),
+                ..FoodLog::new_empty()
+            };
+
+            match create_food_log(log, token).await {
+                Ok(_) => navigate("/", NavigateOptions::default()),
+                Err(e) => {
+                    error.set(Some(format!("Failed to save: {}", e)));
+                    saving.set(false);
+                }
+            }
+        });
+    };
+
+    view! {
+      <div class="mx-auto max-w-md py-6">
+        <h2 class="mb-4 text-3xl font-bold text-slate-900 dark:text-slate-100">"Quick Log"</h2>
+
+        <Show
+          when=move || auth.is_authenticated.get()
+          fallback=|| {
+            view! { <p class="text-slate-600 dark:text-slate-400">"Unlock admin access to log meals."</p> }
+          }
+        >
+          <input
+            node_ref=file_input_ref
+            type="file"
+            accept="image/*"
+            capture="environment"
+            class="hidden"
+            on:change=handle_file_select
+          />
+
+          <Show when=move || error.get().is_some()>
+            <div class="mb-4 rounded bg-red-100 px-4 py-3 text-red-700">{move || error.get().unwrap_or_default()}</div>
+          </Show>
+
+          <button
+            type="button"
+            class="mb-4 block w-full overflow-hidden rounded-lg border-2 border-dashed border-slate-300 dark:border-slate-600 text-slate-500 dark:text-slate-400 hover:border-blue-500 hover:text-blue-500 transition-colors disabled:opacity-50"
+            disabled=move || saving.get()
+            on:click=open_camera
+          >
+            {move || match photo.get() {
+              Some(taken) => {
+                view! { <img src=taken.src alt="Meal photo" class="aspect-[4/3] w-full object-cover" /> }
+                  .into_any()
+              }
+              None => {
+                view! {
+                  <div class="py-16 text-center">
+                    <ImageIcon class="mx-auto mb-2" />
+                    <span class="text-lg">"Take a photo"</span>
+                  </div>
+                }
+                  .into_any()
+              }
+            }}
+          </button>
+
+          <div class="space-y-4">
+            <div>
+              <label class=LABEL_CLASS>"Recipe"</label>
+              <select
+                class=INPUT_CLASS
+                on:change=move |ev| {
+                  let value = event_target_value(&ev);
+                  chosen_recipe.set(Some(value.parse::<i64>().ok()));
+                }
+              >
+                <option value="" selected=move || recipe_id().is_none()>
+                  "-- No recipe --"
+                </option>
+                <For
+                  each=move || recipes.get()
+                  key=|r| r.id.unwrap_or(0)
+                  children=move |r: Recipe| {
+                    let rid = r.id.unwrap_or(0);
+                    view! {
+                      <option value=rid.to_string() selected=move || recipe_id() == Some(rid)>
+                        {r.name}
+                      </option>
+                    }
+                  }
+                />
+              </select>
+            </div>
+
+            <div>
+              <label class=LABEL_CLASS>"Meal"</label>
+              <div class="grid grid-cols-4 rounded border border-slate-300 dark:border-slate-600 overflow-hidden text-sm">
+                {MealType::ALL
+                  .into_iter()
+                  .map(|meal| {
+                    view! {
+                      <button
+                        type="button"
+                        class=move || {
+                          if meal_type.get() == meal {
+                            "py-2 bg-blue-600 text-white"
+                          } else {
+                            "py-2 bg-white dark:bg-slate-800 text-slate-700 dark:text-slate-300 hover:bg-slate-100 dark:hover:bg-slate-700"
+                          }
+                        }
+                        on:click=move |_| meal_type.set(meal)
+                      >
+                        {meal.label()}
+                      </button>
+                    }
+                  })
+                  .collect_view()}
+              </div>
+            </div>
+
+            <div class="grid grid-cols-2 gap-3">
+              <div>
+                <label class=LABEL_CLASS>"Date"</label>
+                <input
+                  type="date"
+                  class=INPUT_CLASS
+                  prop:value=move || logged_date.get()
+                  on:input=move |ev| logged_date.set(event_target_value(&ev))
+                />
+              </div>
+              <div>
+                <label class=LABEL_CLASS>"Time"</label>
+                <input
+                  type="time"
+                  class=INPUT_CLASS
+                  prop:value=move || logged_time.get()
+                  on:input=move |ev| logged_time.set(event_target_value(&ev))
+                />
+              </div>
+            </div>
+
+            <button
+              type="button"
+              class="w-full rounded-lg bg-green-600 px-4 py-4 text-lg font-medium text-white hover:bg-green-700 disabled:opacity-50"
+              disabled=move || saving.get() || photo.with(|p| p.is_none())
+              on:click=handle_save.clone()
+            >
+              {move || match upload_progress.get() {
+                Some(fraction) => format!("Uploading {:.0}%...", fraction * 100.0),
+                None if saving.get() => "Saving...".to_string(),
+                None => "Save".to_string(),
+              }}
+            </button>
+            <p class="text-center text-xs text-slate-500 dark:text-slate-400">
+              "Crop the photo and add a rating or notes later from the Food Log."
+            </p>
+          </div>
+        </Show>
+      </div>
+    }
+}
@@ -0,0 +1,493 @@
+//! Recipe fit module
+//!
+//! "Fit to targets" for the recipe editor: given per-serving calorie and macro targets
+//! and a gram range per ingredient, proposes ingredient amounts with a bounded
+//! least-squares fit.
+
+use leptos::prelude::*;
+
+use crate::components::{INPUT_CLASS, LABEL_CLASS};
+use crate::recipes::{RecipeIngredient, RecipeNutrition};
+use crate::solver::bounded_least_squares;
+
+/// Upper bound used when an ingredient has no maximum set
+const DEFAULT_MAX_GRAMS: f32 = 1000.0;
+
+// ============================================================================
+// Data Types
+// ============================================================================
+
+/// Per-serving targets. Unset targets are left out of the fit.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct MacroTargets {
+    pub calories: Option<f32>,
+    pub protein: Option<f32>,
+    pub carbs: Option<f32>,
+    pub fat: Option<f32>,
+}
+
+impl MacroTargets {
+    pub fn is_empty(&self) -> bool {
+        self.calories.is_none()
+            && self.protein.is_none()
+            && self.carbs.is_none()
+            && self.fat.is_none()
+    }
+}
+
+/// How much of a nutrient 100 g of an ingredient has
+type NutrientPer100g = fn(&RecipeIngredient) -> f32;
+
+/// Allowed range for one ingredient's amount
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct AmountBounds {
+    pub ingredient_id: i64,
+    pub min_g: f32,
+    pub max_g: f32,
+}
+
+/// Propose amounts for the recipe's ingredients so that the per-serving nutrition is as
+/// close as possible to the targets. Each target counts relative to its size, so a few
+/// grams of protein weigh as much as a few percent of calories. Whole-package ingredients
+/// keep their amount.
+pub fn fit_amounts(
+    ingredients: &[RecipeIngredient],
+    servings: i32,
+    targets: &MacroTargets,
+    bounds: &[AmountBounds],
+) -> Vec<RecipeIngredient> {
+    let servings = servings.max(1) as f64;
+    let variables: Vec<usize> = ingredients
+        .iter()
+        .enumerate()
+        .filter(|(_, ing)| !ing.use_whole_package)
+        .map(|(i, _)| i)
+        .collect();
+
+    let nutrients: [(Option<f32>, NutrientPer100g); 4] = [
+        (targets.calories, |ing| ing.calories_per_100g),
+        (targets.protein, |ing| ing.protein_per_100g),
+        (targets.carbs, |ing| ing.carbs_per_100g),
+        (targets.fat, |ing| ing.fat_per_100g),
+    ];
+
+    let mut rows = Vec::new();
+    let mut b = Vec::new();
+    for (target, per_100g) in nutrients {
+        let Some(target) = target.filter(|t| *t > 0.0) else {
+            continue;
+        };
+        let target = target as f64;
+        let fixed: f64 = ingredients
+            .iter()
+            .filter(|ing| ing.use_whole_package)
+            .map(|ing| per_100g(ing) as f64 * ing.package_size_g as f64 / 100.0 / servings)
+            .sum();
+        rows.push(
+            variables
+                .iter()
+                .map(|&i| per_100g(&ingredients[i]) as f64 / 100.0 / servings / target)
+                .collect::<Vec<f64>>(),
+        );
+        b.push((target - fixed) / target);
+    }
+
+    let bounds_for = |ing: &RecipeIngredient| {
+        bounds
+            .iter()
+            .find(|bound| bound.ingredient_id == ing.ingredient_id)
+            .map(|bound| (bound.min_g, bound.max_g))
+            .unwrap_or((0.0, DEFAULT_MAX_GRAMS))
+    };
+    let lower: Vec<f64> = variables
+        .iter()
+        .map(|&i| bounds_for(&ingredients[i]).0 as f64)
+        .collect();
+    let upper: Vec<f64> = variables
+        .iter()
+        .map(|&i| {
+            let (min_g, max_g) = bounds_for(&ingredients[i]);
+            max_g.max(min_g) as f64
+        })
+        .collect();
+    let start: Vec<f64> = variables
+        .iter()
+        .map(|&i| ingredients[i].amount_grams as f64)
+        .collect();
+
+    let amounts = bounded_least_squares(&rows, &b, &lower, &upper, &start);
+
+    let mut proposed = ingredients.to_vec();
+    for (&i, grams) in variables.iter().zip(amounts) {
+        proposed[i].amount_grams = grams.round() as f32;
+    }
+    proposed
+}
+
+// ============================================================================
+// Components
+// ============================================================================
+
+/// Text inputs for one ingredient's gram range
+#[derive(Clone, Debug, PartialEq)]
+struct BoundsInput {
+    ingredient_id: i64,
+    min_g: String,
+    max_g: String,
+}
+
+fn parse_target(value: &str) -> Option<f32> {
+    value.trim().parse::<f32>().ok().filter(|v| *v > 0.0)
+}
+
+/// Target inputs, per-ingredient ranges and the proposed amounts for the recipe editor
+#[component]
+pub fn FitToTargets(
+    selected_ingredients: RwSignal<Vec<RecipeIngredient>>,
+    servings: RwSignal<String>,
+) -> impl IntoView {
+    let target_calories = RwSignal::new(String::new());
+    let target_protein = RwSignal::new(String::new());
+    let target_carbs = RwSignal::new(String::new());
+    let target_fat = RwSignal::new(String::new());
+    let bounds = RwSignal::new(Vec::<BoundsInput>::new());
+    let proposal = RwSignal::new(None::<Vec<RecipeIngredient>>);
+    let error = RwSignal::new(None::<String>);
+
+    let targets = move || MacroTargets {
+        calories: parse_target(&target_calories.get()),
+        protein: parse_target(&target_protein.get()),
+        carbs: parse_target(&target_carbs.get()),
+        fat: parse_target(&target_fat.get()),
+    };
+    let servings_count = move || servings.get().parse::<i32>().unwrap_or(1).max(1);
+
+    // A proposal is only valid for the ingredient list it was computed from
+    Effect::new(move || {
+        selected_ingredients.track();
+        proposal.set(None);
+    });
+
+    let bound_value = move |ingredient_id: i64, max: bool| {
+        bounds
+            .get()
+            .iter()
+            .find(|b| b.ingredient_id == ingredient_id)
+            .map(|b| {
+                if max {
+                    b.max_g.clone()
+                } else {
+                    b.min_g.clone()
+                }
+            })
+            .unwrap_or_default()
+    };
+    let set_bound = move |ingredient_id: i64, max: bool, value: String| {
+        bounds.update(|list| {
+            let pos = match list.iter().position(|b| b.ingredient_id == ingredient_id) {
+                Some(pos) => pos,
+                None => {
+                    list.push(BoundsInput {
+                        ingredient_id,
+                        min_g: String::new(),
+                        max_g: String::new(),
+                    });
+                    list.len() - 1
+                }
+            };
+            if max {
+                list[pos].max_g = value;
+            } else {
+                list[pos].min_g = value;
+            }
+        });
+        proposal.set(None);
+    };
+
+    let solve = move |_| {
+        let targets = targets();
+        if targets.is_empty() {
+            error.set(Some("Set at least one target".to_string()));
+            return;
+        }
+        let amount_bounds: Vec<AmountBounds> = bounds
+            .get_untracked()
+            .iter()
+            .map(|b| AmountBounds {
+                ingredient_id: b.ingredient_id,
+                min_g: b.min_g.trim().parse().unwrap_or(0.0_f32).max(0.0),
+                max_g: b.max_g.trim().parse().unwrap_or(DEFAULT_MAX_GRAMS).max(0.0),
+            })
+            .collect();
+        error.set(None);
+        proposal.set(Some(fit_amounts(
+            &selected_ingredients.get_untracked(),
+            servings_count(),
+            &targets,
+            &amount_bounds,
+        )));
+    };
+
+    let apply = move |_| {
+        if let Some(proposed) = proposal.get_untracked() {
+            selected_ingredients.set(proposed);
+            proposal.set(None);
+        }
+    };
+
+    let target_input = move |label: &'static str, value: RwSignal<String>| {
+        view! {
+          <div>
+            <label class=LABEL_CLASS>{label}</label>
+            <input
+              type="number"
+              min="0"
+              class=INPUT_CLASS
+              placeholder="any"
+              prop:value=move || value.get()
+              on:input=move |ev| {
+                value.set(event_target_value(&ev));
+                proposal.set(None);
+              }
+            />
+          </div>
+        }
+    };
+
+    let compare =
+        move |label: &'static str, unit: &'static str, actual: f32, target: Option<f32>| {
+            let text = match target {
+                Some(target) => format!("{:.0}{} / {:.0}{}", actual, unit, target, unit),
+                None => format!("{:.0}{}", actual, unit),
+            };
+            view! {
+              <div class="bg-slate-50 dark:bg-slate-700 rounded p-2 text-center">
+                <div class="font-semibold text-slate-900 dark:text-slate-100">{text}</div>
+                <div class="text-slate-600 dark:text-slate-400">{label}</div>
+              </div>
+            }
+        };
+
+    view! {
+      <div class="mt-4 rounded border border-blue-200 dark:border-blue-800 bg-blue-50/50 dark:bg-blue-900/10 p-4 space-y-4">
+        <div>
+          <h4 class="font-semibold text-slate-800 dark:text-slate-200">"Targets per serving"</h4>
+          <p class="text-xs text-slate-500 dark:text-slate-400">
+            "Leave a target empty to ignore it. Whole-package ingredients keep their amount."
+          </p>
+        </div>
+        <div class="grid grid-cols-2 sm:grid-cols-4 gap-2">
+          {target_input("kcal", target_calories)} {target_input("Protein (g)", target_protein)}
+          {target_input("Carbs (g)", target_carbs)} {target_input("Fat (g)", target_fat)}
+        </div>
+
+        <table class="w-full text-sm">
+          <thead>
+            <tr class="text-left text-slate-600 dark:text-slate-400">
+              <th class="py-1">"Ingredient"</th>
+              <th class="py-1 w-24">"Min (g)"</th>
+              <th class="py-1 w-24">"Max (g)"</th>
+            </tr>
+          </thead>
+          <tbody>
+            {move || {
+              selected_ingredients
+                .get()
+                .into_iter()
+                .filter(|ing| !ing.use_whole_package)
+                .map(|ing| {
+                  let id = ing.ingredient_id;
+                  view! {
+                    <tr>
+                      <td class="py-1 pr-2 text-slate-800 dark:text-slate-200">{ing.ingredient_name}</td>
+                      <td class="py-1 pr-2">
+                        <input
+                          type="number"
+                          min="0"
+                          class=INPUT_CLASS
+                          placeholder="0"
+                          prop:value=move || bound_value(id, false)
+                          on:input=move |ev| set_bound(id, false, event_target_value(&ev))
+                        />
+                      </td>
+                      <td class="py-1">
+                        <input
+                          type="number"
+                          min="0"
+                          class=INPUT_CLASS
+                          placeholder=format!("{:.0}", DEFAULT_MAX_GRAMS)
+                          prop:value=move || bound_value(id, true)
+                          on:input=move |ev| set_bound(id, true, event_target_value(&ev))
+                        />
+                      </td>
+                    </tr>
+                  }
+                })
+                .collect_view()
+            }}
+          </tbody>
+        </table>
+
+        <Show when=move || error.get().is_some()>
+          <p class="text-sm text-red-600">{move || error.get().unwrap_or_default()}</p>
+        </Show>
+
+        <div class="flex justify-end">
+          <button class="rounded bg-blue-600 px-4 py-2 text-sm font-medium text-white hover:bg-blue-700" on:click=solve>
+            "Propose Amounts"
+          </button>
+        </div>
+
+        {move || {
+          proposal
+            .get()
+            .map(|proposed| {
+              let current = selected_ingredients.get();
+              let nutrition = RecipeNutrition::from_ingredients(&proposed).per_serving(servings_count());
+              let t = targets();
+              view! {
+                <div class="border-t border-blue-200 dark:border-blue-800 pt-4 space-y-3">
+                  <h4 class="font-semibold text-slate-800 dark:text-slate-200">"Proposed amounts"</h4>
+                  <ul class="text-sm space-y-1">
+                    {proposed
+                      .iter()
+                      .map(|ing| {
+                        let before = current
+                          .iter()
+                          .find(|c| c.ingredient_id == ing.ingredient_id)
+                          .map(|c| c.effective_grams())
+                          .unwrap_or_default();
+                        view! {
+                          <li class="flex justify-between text-slate-700 dark:text-slate-300">
+                            <span>{ing.ingredient_name.clone()}</span>
+                            <span>{format!("{:.0}g → {:.0}g", before, ing.effective_grams())}</span>
+                          </li>
+                        }
+                      })
+                      .collect_view()}
+                  </ul>
+                  <div class="grid grid-cols-4 gap-2 text-sm">
+                    {compare("kcal", "", nutrition.calories, t.calories)}
+                    {compare("protein", "g", nutrition.protein, t.protein)}
+                    {compare("carbs", "g", nutrition.carbs, t.carbs)} {compare("fat", "g", nutrition.fat, t.fat)}
+                  </div>
+                  <div class="flex justify-end">
+                    <button
+                      class="rounded bg-green-600 px-4 py-2 text-sm font-medium text-white hover:bg-green-700"
+                      on:click=apply
+                    >
+                      "Apply Amounts"
+                    </button>
+                  </div>
+                </div>
+              }
+            })
+        }}
+      </div>
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ingredient(
+        id: i64,
+        grams: f32,
+        calories: f32,
+        protein: f32,
+        carbs: f32,
+    ) -> RecipeIngredient {
+        RecipeIngredient {
+            id: None,
+            ingredient_id: id,
+            ingredient_name: format!("Ingredient {}", id),
+            amount_grams: grams,
+            use_whole_package: false,
+            package_size_g: 0.0,
+            calories_per_100g: calories,
+            protein_per_100g: protein,
+            fat_per_100g: 0.0,
+            saturated_fat_per_100g: 0.0,
+            carbs_per_100g: carbs,
+            sugar_per_100g: 0.0,
+            fiber_per_100g: 0.0,
+            salt_per_100g: 0.0,
+            package_price: 0.0,
+            cheapest_price_per_g: None,
+            cheapest_store: None,
+        }
+    }
+
+    fn amounts(ingredients: &[RecipeIngredient]) -> Vec<f32> {
+        ingredients.iter().map(|ing| ing.amount_grams).collect()
+    }
+
+    fn protein_and_carbs(protein: f32, carbs: f32) -> MacroTargets {
+        MacroTargets {
+            protein: Some(protein),
+            carbs: Some(carbs),
+            ..MacroTargets::default()
+        }
+    }
+
+    #[test]
+    fn hits_reachable_targets() {
+        // 20g protein/100g and 7g protein + 78g carbs/100g:
+        // 165g + 100g gives 40g protein and 78g carbs
+        let ingredients = [
+            ingredient(1, 100.0, 0.0, 20.0, 0.0),
+            ingredient(2, 50.0, 0.0, 7.0, 78.0),
+        ];
+        let fitted = fit_amounts(&ingredients, 1, &protein_and_carbs(40.0, 78.0), &[]);
+        assert_eq!(amounts(&fitted), vec![165.0, 100.0]);
+    }
+
+    #[test]
+    fn targets_are_per_serving() {
+        let ingredients = [
+            ingredient(1, 100.0, 0.0, 20.0, 0.0),
+            ingredient(2, 50.0, 0.0, 7.0, 78.0),
+        ];
+        let fitted = fit_amounts(&ingredients, 2, &protein_and_carbs(40.0, 78.0), &[]);
+        assert_eq!(amounts(&fitted), vec![330.0, 200.0]);
+    }
+
+    #[test]
+    fn keeps_amounts_within_bounds() {
+        let ingredients = [ingredient(1, 100.0, 0.0, 20.0, 0.0)];
+        let bounds = [AmountBounds {
+            ingredient_id: 1,
+            min_g: 10.0,
+            max_g: 120.0,
+        }];
+        let targets = MacroTargets {
+            protein: Some(40.0),
+            ..MacroTargets::default()
+        };
+        let fitted = fit_amounts(&ingredients, 1, &targets, &bounds);
+        assert_eq!(amounts(&fitted), vec![120.0]);
+    }
+
+    #[test]
+    fn whole_packages_keep_their_amount_and_count_towards_targets() {
+        let mut package = ingredient(1, 200.0, 0.0, 10.0, 0.0);
+        package.use_whole_package = true;
+        package.package_size_g = 200.0;
+        let ingredients = [package, ingredient(2, 10.0, 0.0, 20.0, 0.0)];
+        let targets = MacroTargets {
+            protein: Some(30.0),
+            ..MacroTargets::default()
+        };
+        // The package already gives 20g protein, so 50g of the other ingredient adds the rest
+        let fitted = fit_amounts(&ingredients, 1, &targets, &[]);
+        assert_eq!(amounts(&fitted), vec![200.0, 50.0]);
+    }
+
+    #[test]
+    fn without_targets_nothing_changes() {
+        let ingredients = [ingredient(1, 100.0, 150.0, 20.0, 0.0)];
+        let fitted = fit_amounts(&ingredients, 1, &MacroTargets::default(), &[]);
+        assert_eq!(fitted, ingredients.to_vec());
+    }
+}
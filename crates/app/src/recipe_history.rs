@@ -0,0 +1,696 @@
+//! Recipe history module
+//!
+//! Contains the append-only recipe revision log, diffing between revisions,
+//! and the history modal used to inspect and restore previous versions.
+
+use leptos::prelude::*;
+use serde::{Deserialize, Serialize};
+use server_fn::ServerFnError;
+
+use crate::auth::AdminAuth;
+use crate::components::{is_backdrop_click, CloseIcon};
+use crate::recipes::{Recipe, RecipeNutrition};
+
+// ============================================================================
+// Data Types
+// ============================================================================
+
+/// A saved snapshot of a recipe
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+pub struct RecipeRevision {
+    pub id: i64,
+    pub recipe_id: i64,
+    pub created_at: String,
+    pub recipe: Recipe,
+}
+
+/// A single ingredient-level change between two recipe versions
+#[derive(Clone, Debug, PartialEq)]
+pub enum IngredientChange {
+    Added { name: String, grams: f32 },
+    Removed { name: String, grams: f32 },
+    Amount { name: String, from: f32, to: f32 },
+}
+
+impl IngredientChange {
+    pub fn describe(&self) -> String {
+        match self {
+            IngredientChange::Added { name, grams } => format!("+ {} ({}g)", name, grams),
+            IngredientChange::Removed { name, grams } => format!("- {} ({}g)", name, grams),
+            IngredientChange::Amount { name, from, to } => {
+                format!("~ {}: {}g \u{2192} {}g", name, from, to)
+            }
+        }
+    }
+}
+
+/// Differences between two versions of a recipe
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct RecipeDiff {
+    /// Human-readable changes to the basic recipe fields
+    pub field_changes: Vec<String>,
+    pub ingredient_changes: Vec<IngredientChange>,
+    /// Per-serving nutrition of the newer version minus the older one
+    pub nutrition_delta: RecipeNutrition,
+}
+
+impl RecipeDiff {
+    /// Compute the changes going from `old` to `new`
+    pub fn between(old: &Recipe, new: &Recipe) -> Self {
+        let mut field_changes = Vec::new();
+        if old.name != new.name {
+            field_changes.push(format!("Name: {} \u{2192} {}", old.name, new.name));
+        }
+        if old.description != new.description {
+            field_changes.push("Description changed".to_string());
+        }
+        if old.servings != new.servings {
+            field_changes.push(format!(
+                "Servings: {} \u{2192} {}",
+                old.servings, new.servings
+            ));
+        }
+        if old.prep_time_minutes != new.prep_time_minutes
+            || old.cook_time_minutes != new.cook_time_minutes
+        {
+            field_changes.push(format!(
+                "Time: {} \u{2192} {}",
+                old.total_time(),
+                new.total_time()
+            ));
+        }
+        if old.instructions != new.instructions {
+            field_changes.push("Instructions changed".to_string());
+        }
+
+        let mut ingredient_changes = Vec::new();
+        for ing in &new.ingredients {
+            match old
+                .ingredients
+                .iter()
+                .find(|o| o.ingredient_id == ing.ingredient_id)
+            {
+                None => ingredient_changes.push(IngredientChange::Added {
+                    name: ing.ingredient_name.clone(),
+                    grams: ing.effective_grams(),
+                }),
+                Some(prev) if (prev.effective_grams() - ing.effective_grams()).abs() > 0.01 => {
+                    ingredient_changes.push(IngredientChange::Amount {
+                        name: ing.ingredient_name.clone(),
+                        from: prev.effective_grams(),
+                        to: ing.effective_grams(),
+                    })
+                }
+                Some(_) => {}
+            }
+        }
+        for prev in &old.ingredients {
+            if !new
+                .ingredients
+                .iter()
+                .any(|n| n.ingredient_id == prev.ingredient_id)
+            {
+                ingredient_changes.push(IngredientChange::Removed {
+                    name: prev.ingredient_name.clone(),
+                    grams: prev.effective_grams(),
+                });
+            }
+        }
+
+        let before = old.nutrition_per_serving();
+        let after = new.nutrition_per_serving();
+        let nutrition_delta = RecipeNutrition {
+            calories: after.calories - before.calories,
+            protein: after.protein - before.protein,
+            fat: after.fat - before.fat,
+            saturated_fat: after.saturated_fat - before.saturated_fat,
+            carbs: after.carbs - before.carbs,
+            sugar: after.sugar - before.sugar,
+            fiber: after.fiber - before.fiber,
+            salt: after.salt - before.salt,
+        };
+
+        Self {
+            field_changes,
+            ingredient_changes,
+            nutrition_delta,
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.field_changes.is_empty() && self.ingredient_changes.is_empty()
+    }
+}
+
+// ============================================================================
+// Server Functions
+// ============================================================================
+
+#[cfg(feature = "ssr")]
+use crate::ingredients::SendD1Database;
+
+/// Build the insert appending a snapshot of a saved recipe to the revision log
+#[cfg(feature = "ssr")]
+pub(crate) fn revision_statement(
+    db: &worker::d1::D1Database,
+    recipe: &Recipe,
+) -> Result<worker::d1::D1PreparedStatement, worker::Error> {
+    let recipe_id = recipe
+        .id
+        .ok_or_else(|| worker::Error::RustError("Recipe ID is required for a revision".into()))?;
+    let snapshot = serde_json::to_string(recipe)?;
+
+    let stmt = db.prepare("INSERT INTO recipe_revisions (recipe_id, snapshot) VALUES (?, ?)");
+    stmt.bind(&[(recipe_id as f64).into(), snapshot.into()])
+}
+
+/// Build the insert recording a recipe as its first revision if it has none yet.
+/// Recipes created before revisions were tracked get a baseline this way, so the
+/// first edit still has a "before" to diff against and restore.
+#[cfg(feature = "ssr")]
+pub(crate) fn baseline_revision_statement(
+    db: &worker::d1::D1Database,
+    recipe: &Recipe,
+) -> Result<worker::d1::D1PreparedStatement, worker::Error> {
+    let recipe_id = recipe
+        .id
+        .ok_or_else(|| worker::Error::RustError("Recipe ID is required for a revision".into()))?;
+    let snapshot = serde_json::to_string(recipe)?;
+
+    let stmt = db.prepare(
+        "INSERT INTO recipe_revisions (recipe_id, snapshot)
+         SELECT ?, ? WHERE NOT EXISTS (SELECT 1 FROM recipe_revisions WHERE recipe_id = ?)",
+    );
+    stmt.bind(&[
+        (recipe_id as f64).into(),
+        snapshot.into(),
+        (recipe_id as f64).into(),
+    ])
+}
+
+/// Append a snapshot of a saved recipe to the revision log
+#[cfg(feature = "ssr")]
+pub(crate) async fn record_recipe_revision(
+    db: &SendD1Database,
+    recipe: &Recipe,
+) -> Result<(), ServerFnError> {
+    use send_wrapper::SendWrapper;
+
+    SendWrapper::new(async { revision_statement(db.inner(), recipe)?.run().await })
+        .await
+        .map_err(|e| ServerFnError::new(format!("D1 insert revision error: {:?}", e)))?;
+
+    Ok(())
+}
+
+/// Record the current state of a recipe as its first revision if it has none yet
+#[cfg(feature = "ssr")]
+pub(crate) async fn ensure_baseline_revision(
+    db: &SendD1Database,
+    recipe: &Recipe,
+) -> Result<(), ServerFnError> {
+    use send_wrapper::SendWrapper;
+
+    SendWrapper::new(async { baseline_revision_statement(db.inner(), recipe)?.run().await })
+        .await
+        .map_err(|e| ServerFnError::new(format!("D1 insert revision error: {:?}", e)))?;
+
+    Ok(())
+}
+
+/// Fetch all revisions of a recipe, newest first
+#[server]
+pub async fn get_recipe_revisions(recipe_id: i64) -> Result<Vec<RecipeRevision>, ServerFnError> {
+    use send_wrapper::SendWrapper;
+
+    let db = expect_context::<SendD1Database>();
+
+    let rows = SendWrapper::new(async {
+        let stmt = db.inner().prepare(
+            "SELECT id, recipe_id, snapshot, created_at FROM recipe_revisions WHERE recipe_id = ? ORDER BY id DESC",
+        );
+        let stmt = stmt.bind(&[(recipe_id as f64).into()])?;
+        let results = stmt.all().await?;
+        results.results::<serde_json::Value>()
+    })
+    .await
+    .map_err(|e| ServerFnError::new(format!("D1 query error: {:?}", e)))?;
+
+    let revisions = rows
+        .into_iter()
+        .filter_map(|row| {
+            let snapshot = row.get("snapshot")?.as_str()?;
+            let recipe = match serde_json::from_str::<Recipe>(snapshot) {
+                Ok(recipe) => recipe,
+                Err(e) => {
+                    log::warn!("Skipping unreadable recipe revision: {}", e);
+                    return None;
+                }
+            };
+            Some(RecipeRevision {
+                id: row.get("id")?.as_i64()?,
+                recipe_id: row.get("recipe_id")?.as_i64()?,
+                created_at: row
+                    .get("created_at")
+                    .and_then(|v| v.as_str())
+                    .unwrap_or("")
+                    .to_string(),
+                recipe,
+            })
+        })
+        .collect();
+
+    Ok(revisions)
+}
+
+/// Restore a recipe to the state captured in a revision.
+/// The restore itself is saved as a new revision, so history stays append-only.
+#[server]
+pub async fn restore_recipe_revision(
+    revision_id: i64,
+    auth_token: Option<String>,
+) -> Result<Recipe, ServerFnError> {
+    use send_wrapper::SendWrapper;
+
+    use crate::recipes::update_recipe;
+
+    let db = expect_context::<SendD1Database>();
+
+    let row = SendWrapper::new(async {
+        let stmt = db
+            .inner()
+            .prepare("SELECT recipe_id, snapshot FROM recipe_revisions WHERE id = ?");
+        let stmt = stmt.bind(&[(revision_id as f64).into()])?;
+        stmt.first::<serde_json::Value>(None).await
+    })
+    .await
+    .map_err(|e| ServerFnError::new(format!("D1 query error: {:?}", e)))?
+    .ok_or_else(|| ServerFnError::new("Revision not found"))?;
+
+    let recipe_id = row
+        .get("recipe_id")
+        .and_then(|v| v.as_i64())
+        .ok_or_else(|| ServerFnError::new("Revision has no recipe ID"))?;
+    let snapshot = row
+        .get("snapshot")
+        .and_then(|v| v.as_str())
+        .ok_or_else(|| ServerFnError::new("Revision has no snapshot"))?;
+    let mut recipe: Recipe = serde_json::from_str(snapshot)
+        .map_err(|e| ServerFnError::new(format!("Failed to read revision: {}", e)))?;
+    recipe.id = Some(recipe_id);
+
+    // The recipe and its ingredients must still exist for the snapshot to be written back
+    let (recipe_exists, ingredient_ids) = SendWrapper::new(async {
+        let stmt = db
+            .inner()
+            .prepare("SELECT id FROM recipes WHERE id = ? AND deleted_at IS NULL");
+        let stmt = stmt.bind(&[(recipe_id as f64).into()])?;
+        let recipe_exists = stmt.first::<serde_json::Value>(None).await?.is_some();
+
+        let stmt = db
+            .inner()
+            .prepare("SELECT id FROM ingredients WHERE deleted_at IS NULL");
+        let rows = stmt.all().await?.results::<serde_json::Value>()?;
+        let ids: Vec<i64> = rows
+            .iter()
+            .filter_map(|r| r.get("id").and_then(|v| v.as_i64()))
+            .collect();
+        Ok::<_, worker::Error>((recipe_exists, ids))
+    })
+    .await
+    .map_err(|e| ServerFnError::new(format!("D1 query error: {:?}", e)))?;
+
+    if !recipe_exists {
+        return Err(ServerFnError::new("Recipe no longer exists"));
+    }
+
+    let missing: Vec<&str> = recipe
+        .ingredients
+        .iter()
+        .filter(|ing| !ingredient_ids.contains(&ing.ingredient_id))
+        .map(|ing| ing.ingredient_name.as_str())
+        .collect();
+    if !missing.is_empty() {
+        return Err(ServerFnError::new(format!(
+            "Cannot restore: ingredients no longer exist: {}",
+            missing.join(", ")
+        )));
+    }
+
+    update_recipe(recipe.clone(), auth_token).await?;
+
+    log::info!(
+        "Restored recipe id: {} to revision id: {}",
+        recipe_id,
+        revision_id
+    );
+    Ok(recipe)
+}
+
+// ============================================================================
+// Components
+// ============================================================================
+
+/// Format a signed nutrient delta, e.g. "+12.5g protein"
+fn format_delta(value: f32, unit: &str, label: &str, precision: usize) -> String {
+    format!("{:+.*}{} {}", precision, value, unit, label)
+}
+
+/// Summary of what changed in one revision compared to the one before it
+#[component]
+pub(crate) fn RevisionDiff(diff: RecipeDiff) -> impl IntoView {
+    let delta = diff.nutrition_delta.clone();
+    let has_changes = !diff.is_empty();
+    let field_changes = diff.field_changes.clone();
+    let ingredient_changes = diff.ingredient_changes.clone();
+
+    view! {
+      <Show
+        when=move || has_changes
+        fallback=|| view! { <p class="text-sm text-slate-500 dark:text-slate-400">"No changes"</p> }
+      >
+        <ul class="space-y-0.5 text-sm text-slate-700 dark:text-slate-300">
+          {field_changes.iter().map(|change| view! { <li>{change.clone()}</li> }).collect_view()}
+          {ingredient_changes
+            .iter()
+            .map(|change| {
+              let class = match change {
+                IngredientChange::Added { .. } => "text-green-700 dark:text-green-400",
+                IngredientChange::Removed { .. } => "text-red-700 dark:text-red-400",
+                IngredientChange::Amount { .. } => "text-amber-700 dark:text-amber-400",
+              };
+              view! { <li class=format!("font-mono {}", class)>{change.describe()}</li> }
+            })
+            .collect_view()}
+        </ul>
+        <p class="mt-1 text-xs text-slate-500 dark:text-slate-400">
+          {format!(
+            "Per serving: {} | {} | {} | {}",
+            format_delta(delta.calories, "", "kcal", 0),
+            format_delta(delta.protein, "g", "protein", 1),
+            format_delta(delta.carbs, "g", "carbs", 1),
+            format_delta(delta.fat, "g", "fat", 1),
+          )}
+        </p>
+      </Show>
+    }
+}
+
+/// Modal listing the saved versions of a recipe with diffs and restore actions
+#[component]
+pub fn RecipeHistoryModal(
+    recipe: RwSignal<Option<Recipe>>,
+    on_restore: impl Fn() + Clone + Send + Sync + 'static,
+) -> impl IntoView {
+    let auth_token = expect_context::<AdminAuth>().token;
+    let error = RwSignal::new(Option::<String>::None);
+    let restoring = RwSignal::new(false);
+
+    let revisions = Resource::new(
+        move || recipe.get().and_then(|r| r.id),
+        |recipe_id| async move {
+            match recipe_id {
+                Some(id) => get_recipe_revisions(id).await,
+                None => Ok(Vec::new()),
+            }
+        },
+    );
+
+    let close = move || {
+        recipe.set(None);
+        error.set(None);
+    };
+
+    // Keep the callback in a StoredValue so the handler below is Copy and can be
+    // used from the re-rendered <Show>/<Suspense> children
+    let on_restore = StoredValue::new(on_restore);
+    let handle_restore = move |revision_id: i64| {
+        restoring.set(true);
+        error.set(None);
+        let token = auth_token.get_untracked();
+        wasm_bindgen_futures::spawn_local(async move {
+            match restore_recipe_revision(revision_id, token).await {
+                Ok(_) => {
+                    revisions.refetch();
+                    on_restore.with_value(|on_restore| on_restore());
+                    recipe.set(None);
+                }
+                Err(e) => {
+                    error.set(Some(format!("Failed to restore: {}", e)));
+                }
+            }
+            restoring.set(false);
+        });
+    };
+
+    view! {
+      <Show when=move || recipe.get().is_some()>
+        <div
+          id="recipe-history-backdrop"
+          class="fixed inset-0 z-50 flex items-start justify-center bg-black/50 overflow-y-auto py-4"
+          on:click=move |ev: web_sys::MouseEvent| {
+            if is_backdrop_click(&ev, "recipe-history-backdrop") {
+              close();
+            }
+          }
+        >
+          <div class="w-full max-w-2xl rounded-lg bg-white dark:bg-slate-800 p-6 shadow-xl mx-4 my-auto">
+            <div class="mb-4 flex items-center justify-between">
+              <h2 class="text-xl font-bold text-slate-900 dark:text-slate-100">
+                {move || format!("History: {}", recipe.get().map(|r| r.name).unwrap_or_default())}
+              </h2>
+              <button
+                class="text-slate-500 hover:text-slate-700 dark:text-slate-400 dark:hover:text-slate-200"
+                on:click=move |_| close()
+              >
+                <CloseIcon />
+              </button>
+            </div>
+
+            <Show when=move || error.get().is_some()>
+              <div class="mb-4 rounded bg-red-100 dark:bg-red-900/30 px-4 py-2 text-sm text-red-700 dark:text-red-400">
+                {move || error.get().unwrap_or_default()}
+              </div>
+            </Show>
+
+            <div class="max-h-[70vh] overflow-y-auto pr-2">
+              <Suspense fallback=move || {
+                view! { <p class="text-slate-600 dark:text-slate-400">"Loading history..."</p> }
+              }>
+                {move || {
+                  revisions
+                    .get()
+                    .map(|result| match result {
+                      Ok(list) if list.is_empty() => {
+                        view! {
+                          <p class="text-slate-600 dark:text-slate-400">"No saved versions yet."</p>
+                        }
+                          .into_any()
+                      }
+                      Ok(list) => {
+                        view! {
+                          <ol class="divide-y divide-slate-200 dark:divide-slate-600">
+                            {list
+                              .iter()
+                              .enumerate()
+                              .map(|(idx, rev)| {
+                                let revision_id = rev.id;
+                                let is_current = idx == 0;
+                                let diff = list.get(idx + 1).map(|prev| RecipeDiff::between(&prev.recipe, &rev.recipe));
+                                let kcal = rev.recipe.nutrition_per_serving().calories;
+                                view! {
+                                  <li class="py-3">
+                                    <div class="mb-1 flex items-center justify-between gap-3">
+                                      <div>
+                                        <span class="font-medium text-slate-900 dark:text-slate-100">
+                                          {format!("{} UTC", rev.created_at)}
+                                        </span>
+                                        <span class="ml-2 text-xs text-slate-500 dark:text-slate-400">
+                                          {format!("{:.0} kcal/serving", kcal)}
+                                        </span>
+                                      </div>
+                                      <Show
+                                        when=move || !is_current
+                                        fallback=|| {
+                                          view! {
+                                            <span class="text-xs font-medium text-green-700 dark:text-green-400">
+                                              "Current"
+                                            </span>
+                                          }
+                                        }
+                                      >
+                                        <button
+                                          class="rounded bg-slate-200 dark:bg-slate-600 px-3 py-1 text-sm font-medium text-slate-700 dark:text-slate-200 hover:bg-slate-300 dark:hover:bg-slate-500 disabled:opacity-50"
+                                          disabled=move || restoring.get()
+                                          on:click=move |_| handle_restore(revision_id)
+                                        >
+                                          "Restore this version"
+                                        </button>
+                                      </Show>
+                                    </div>
+                                    {match diff {
+                                      Some(diff) => view! { <RevisionDiff diff=diff /> }.into_any(),
+                                      None => {
+                                        view! {
+                                          <p class="text-sm text-slate-500 dark:text-slate-400">"Initial version"</p>
+                                        }
+                                          .into_any()
+                                      }
+                                    }}
+                                  </li>
+                                }
+                              })
+                              .collect_view()}
+                          </ol>
+                        }
+                          .into_any()
+                      }
+                      Err(e) => {
+                        view! {
+                          <div class="rounded bg-red-100 px-4 py-3 text-red-700">
+                            <p class="font-medium">"Failed to load history"</p>
+                            <p class="text-sm">{e.to_string()}</p>
+                          </div>
+                        }
+                          .into_any()
+                      }
+                    })
+                }}
+              </Suspense>
+            </div>
+          </div>
+        </div>
+      </Show>
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::recipes::RecipeIngredient;
+
+    fn ingredient(id: i64, name: &str, grams: f32, protein: f32) -> RecipeIngredient {
+        RecipeIngredient {
+            id: None,
+            ingredient_id: id,
+            ingredient_name: name.to_string(),
+            amount_grams: grams,
+            use_whole_package: false,
+            package_size_g: 500.0,
+            calories_per_100g: 100.0,
+            protein_per_100g: protein,
+            fat_per_100g: 0.0,
+            saturated_fat_per_100g: 0.0,
+            carbs_per_100g: 0.0,
+            sugar_per_100g: 0.0,
+            fiber_per_100g: 0.0,
+            salt_per_100g: 0.0,
+            package_price: 0.0,
+            cheapest_price_per_g: None,
+            cheapest_store: None,
+        }
+    }
+
+    fn recipe(ingredients: Vec<RecipeIngredient>) -> Recipe {
+        Recipe {
+            id: Some(1),
+            name: "Porridge".to_string(),
+            servings: 2,
+            ingredients,
+            ..Recipe::new_empty()
+        }
+    }
+
+    #[test]
+    fn identical_recipes_have_no_changes() {
+        let old = recipe(vec![ingredient(1, "Oats", 80.0, 13.0)]);
+        let diff = RecipeDiff::between(&old, &old.clone());
+        assert!(diff.is_empty());
+        assert_eq!(diff.nutrition_delta, RecipeNutrition::default());
+    }
+
+    #[test]
+    fn reports_field_changes() {
+        let old = recipe(Vec::new());
+        let new = Recipe {
+            name: "Overnight oats".to_string(),
+            servings: 4,
+            cook_time_minutes: 90,
+            instructions: vec!["Soak".to_string()],
+            ..old.clone()
+        };
+        let diff = RecipeDiff::between(&old, &new);
+        assert_eq!(
+            diff.field_changes,
+            vec![
+                "Name: Porridge \u{2192} Overnight oats".to_string(),
+                "Servings: 2 \u{2192} 4".to_string(),
+                "Time: 0min \u{2192} 1h 30min".to_string(),
+                "Instructions changed".to_string(),
+            ]
+        );
+        assert!(diff.ingredient_changes.is_empty());
+    }
+
+    #[test]
+    fn reports_added_removed_and_changed_ingredients() {
+        let old = recipe(vec![
+            ingredient(1, "Oats", 80.0, 13.0),
+            ingredient(2, "Milk", 200.0, 3.5),
+        ]);
+        let new = recipe(vec![
+            ingredient(1, "Oats", 100.0, 13.0),
+            ingredient(3, "Banana", 120.0, 1.0),
+        ]);
+        let diff = RecipeDiff::between(&old, &new);
+        assert_eq!(
+            diff.ingredient_changes,
+            vec![
+                IngredientChange::Amount {
+                    name: "Oats".to_string(),
+                    from: 80.0,
+                    to: 100.0,
+                },
+                IngredientChange::Added {
+                    name: "Banana".to_string(),
+                    grams: 120.0,
+                },
+                IngredientChange::Removed {
+                    name: "Milk".to_string(),
+                    grams: 200.0,
+                },
+            ]
+        );
+        assert_eq!(
+            diff.ingredient_changes[0].describe(),
+            "~ Oats: 80g \u{2192} 100g"
+        );
+    }
+
+    #[test]
+    fn whole_package_changes_count_as_amount_changes() {
+        let old = recipe(vec![ingredient(1, "Oats", 80.0, 13.0)]);
+        let mut whole = ingredient(1, "Oats", 80.0, 13.0);
+        whole.use_whole_package = true;
+        let diff = RecipeDiff::between(&old, &recipe(vec![whole]));
+        assert_eq!(
+            diff.ingredient_changes,
+            vec![IngredientChange::Amount {
+                name: "Oats".to_string(),
+                from: 80.0,
+                to: 500.0,
+            }]
+        );
+    }
+
+    #[test]
+    fn nutrition_delta_is_per_serving() {
+        // 100g more oats over 2 servings: +50 kcal and +6.5g protein each
+        let old = recipe(vec![ingredient(1, "Oats", 100.0, 13.0)]);
+        let new = recipe(vec![ingredient(1, "Oats", 200.0, 13.0)]);
+        let delta = RecipeDiff::between(&old, &new).nutrition_delta;
+        assert!((delta.calories - 50.0).abs() < 1e-3);
+        assert!((delta.protein - 6.5).abs() < 1e-3);
+        assert_eq!(delta.fat, 0.0);
+    }
+}
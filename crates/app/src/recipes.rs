@@ -10,8 +10,12 @@ use wasm_bindgen::JsCast;
 use crate::auth::AdminAuth;
 #[cfg(not(feature = "ssr"))]
 use crate::cache::{get_cache, set_cache, INGREDIENTS_CACHE_KEY, RECIPES_CACHE_KEY};
-use crate::components::{CloseIcon, EditIcon, PlusIcon, INPUT_CLASS, LABEL_CLASS};
+use crate::components::{CloseIcon, EditIcon, HistoryIcon, PlusIcon, INPUT_CLASS, LABEL_CLASS};
+use crate::container_labels::ContainerLabelsForm;
 use crate::ingredients::{get_ingredients, Ingredient};
+use crate::nutrition_label::NutritionLabel;
+use crate::recipe_fit::FitToTargets;
+use crate::recipe_history::RecipeHistoryModal;
 
 // ============================================================================
 // Data Types
@@ -35,6 +39,33 @@ pub struct RecipeIngredient {
     pub sugar_per_100g: f32,
     pub fiber_per_100g: f32,
     pub salt_per_100g: f32,
+    // Price info from the ingredient, used for recipe costs
+    #[serde(default)]
+    pub package_price: f32,
+    /// Lowest price per gram seen in the last `RECENT_PRICE_DAYS` days
+    #[serde(default)]
+    pub cheapest_price_per_g: Option<f32>,
+    #[serde(default)]
+    pub cheapest_store: Option<String>,
+}
+
+/// Which prices to use when computing what a recipe costs
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Default)]
+pub enum CostMode {
+    /// The latest price of each ingredient
+    #[default]
+    Current,
+    /// The cheapest price seen at any store recently
+    CheapestRecent,
+}
+
+impl CostMode {
+    pub fn label(&self) -> &'static str {
+        match self {
+            CostMode::Current => "Current prices",
+            CostMode::CheapestRecent => "Cheapest recent store",
+        }
+    }
 }
 
 /// Macro to generate nutrient calculation methods for RecipeIngredient.
@@ -65,6 +96,25 @@ impl RecipeIngredient {
     nutrient_method!(sugar, sugar_per_100g);
     nutrient_method!(fiber, fiber_per_100g);
     nutrient_method!(salt, salt_per_100g);
+
+    /// Current price per gram, if the ingredient has a priced package
+    pub fn price_per_g(&self) -> Option<f32> {
+        if self.package_price > 0.0 && self.package_size_g > 0.0 {
+            Some(self.package_price / self.package_size_g)
+        } else {
+            None
+        }
+    }
+
+    /// Cost of the amount used. Cheapest mode falls back to the current price
+    /// when there is no recent observation.
+    pub fn cost(&self, mode: CostMode) -> Option<f32> {
+        let per_g = match mode {
+            CostMode::Current => self.price_per_g(),
+            CostMode::CheapestRecent => self.cheapest_price_per_g.or_else(|| self.price_per_g()),
+        };
+        per_g.map(|p| p * self.effective_grams())
+    }
 }
 
 /// Computed nutrition values for a recipe
@@ -99,16 +149,20 @@ impl RecipeNutrition {
 
     /// Get nutrition per serving
     pub fn per_serving(&self, servings: i32) -> Self {
-        let s = servings.max(1) as f32;
+        self.scaled(1.0 / servings.max(1) as f32)
+    }
+
+    /// Multiply every value by `factor`
+    pub fn scaled(&self, factor: f32) -> Self {
         Self {
-            calories: self.calories / s,
-            protein: self.protein / s,
-            fat: self.fat / s,
-            saturated_fat: self.saturated_fat / s,
-            carbs: self.carbs / s,
-            sugar: self.sugar / s,
-            fiber: self.fiber / s,
-            salt: self.salt / s,
+            calories: self.calories * factor,
+            protein: self.protein * factor,
+            fat: self.fat * factor,
+            saturated_fat: self.saturated_fat * factor,
+            carbs: self.carbs * factor,
+            sugar: self.sugar * factor,
+            fiber: self.fiber * factor,
+            salt: self.salt * factor,
         }
     }
 }
@@ -151,6 +205,30 @@ impl Recipe {
         self.nutrition().per_serving(self.servings)
     }
 
+    /// Total weight of all ingredients in grams
+    pub fn total_grams(&self) -> f32 {
+        self.ingredients
+            .iter()
+            .map(|ing| ing.effective_grams())
+            .sum()
+    }
+
+    /// Total cost of the priced ingredients
+    pub fn cost(&self, mode: CostMode) -> f32 {
+        self.ingredients
+            .iter()
+            .filter_map(|ing| ing.cost(mode))
+            .sum()
+    }
+
+    /// Number of ingredients without a known price
+    pub fn unpriced_ingredients(&self) -> usize {
+        self.ingredients
+            .iter()
+            .filter(|ing| ing.price_per_g().is_none() && ing.cheapest_price_per_g.is_none())
+            .count()
+    }
+
     /// Format total time
     pub fn total_time(&self) -> String {
         let total = self.prep_time_minutes + self.cook_time_minutes;
@@ -172,8 +250,138 @@ impl Default for Recipe {
 // Server Functions
 // ============================================================================
 
+#[cfg(feature = "ssr")]
+use crate::ingredient_prices::RECENT_PRICE_DAYS;
 #[cfg(feature = "ssr")]
 use crate::ingredients::SendD1Database;
+#[cfg(feature = "ssr")]
+use crate::recipe_history::{ensure_baseline_revision, record_recipe_revision};
+
+/// Build a RecipeIngredient from a joined recipe_ingredients/ingredients row
+#[cfg(feature = "ssr")]
+fn recipe_ingredient_from_row(r: &serde_json::Value) -> Option<RecipeIngredient> {
+    Some(RecipeIngredient {
+        id: r.get("id")?.as_i64(),
+        ingredient_id: r.get("ingredient_id")?.as_i64()?,
+        ingredient_name: r.get("name")?.as_str()?.to_string(),
+        amount_grams: r.get("amount_grams")?.as_f64()? as f32,
+        use_whole_package: r.get("use_whole_package")?.as_i64()? == 1,
+        package_size_g: r.get("package_size_g")?.as_f64()? as f32,
+        calories_per_100g: r.get("calories")?.as_f64()? as f32,
+        protein_per_100g: r.get("protein")?.as_f64()? as f32,
+        fat_per_100g: r.get("fat")?.as_f64()? as f32,
+        saturated_fat_per_100g: r.get("saturated_fat")?.as_f64()? as f32,
+        carbs_per_100g: r.get("carbs")?.as_f64()? as f32,
+        sugar_per_100g: r.get("sugar")?.as_f64()? as f32,
+        fiber_per_100g: r.get("fiber")?.as_f64()? as f32,
+        salt_per_100g: r.get("salt")?.as_f64()? as f32,
+        package_price: r
+            .get("package_price")
+            .and_then(|v| v.as_f64())
+            .unwrap_or(0.0) as f32,
+        cheapest_price_per_g: r
+            .get("cheapest_price_per_g")
+            .and_then(|v| v.as_f64())
+            .map(|v| v as f32),
+        cheapest_store: r
+            .get("cheapest_store")
+            .and_then(|v| v.as_str())
+            .map(|s| s.to_string()),
+    })
+}
+
+/// Build a Recipe from a recipes row and its already-loaded ingredients
+#[cfg(feature = "ssr")]
+fn recipe_from_row(row: &serde_json::Value, ingredients: Vec<RecipeIngredient>) -> Recipe {
+    let instructions_json = row
+        .get("instructions")
+        .and_then(|v| v.as_str())
+        .unwrap_or("[]");
+    let instructions: Vec<String> = serde_json::from_str(instructions_json).unwrap_or_default();
+
+    Recipe {
+        id: row.get("id").and_then(|v| v.as_i64()),
+        name: row
+            .get("name")
+            .and_then(|v| v.as_str())
+            .unwrap_or("")
+            .to_string(),
+        description: row
+            .get("description")
+            .and_then(|v| v.as_str())
+            .unwrap_or("")
+            .to_string(),
+        servings: row.get("servings").and_then(|v| v.as_i64()).unwrap_or(1) as i32,
+        prep_time_minutes: row
+            .get("prep_time_minutes")
+            .and_then(|v| v.as_i64())
+            .unwrap_or(0) as i32,
+        cook_time_minutes: row
+            .get("cook_time_minutes")
+            .and_then(|v| v.as_i64())
+            .unwrap_or(0) as i32,
+        instructions,
+        ingredients,
+    }
+}
+
+/// Fetch the ingredients of a recipe, joined with their nutrition data
+#[cfg(feature = "ssr")]
+async fn load_recipe_ingredients(
+    db: &SendD1Database,
+    recipe_id: i64,
+) -> Result<Vec<RecipeIngredient>, worker::Error> {
+    // The cheapest recent price per gram and its store come from the price history
+    let ing_stmt = db.inner().prepare(format!(
+        "SELECT ri.id, ri.ingredient_id, ri.amount_grams, ri.use_whole_package,
+                i.name, i.calories, i.protein, i.fat, i.saturated_fat, 
+                i.carbs, i.sugar, i.fiber, i.salt, i.package_size_g, i.package_price,
+                (SELECT MIN(p.price / p.package_size_g) FROM ingredient_prices p
+                 WHERE p.ingredient_id = i.id AND p.package_size_g > 0
+                   AND p.observed_on >= date('now', '-{days} days')) as cheapest_price_per_g,
+                (SELECT p.store FROM ingredient_prices p
+                 WHERE p.ingredient_id = i.id AND p.package_size_g > 0
+                   AND p.observed_on >= date('now', '-{days} days')
+                 ORDER BY p.price / p.package_size_g LIMIT 1) as cheapest_store
+         FROM recipe_ingredients ri
+         JOIN ingredients i ON ri.ingredient_id = i.id
+         WHERE ri.recipe_id = ?
+         ORDER BY ri.id",
+        days = RECENT_PRICE_DAYS,
+    ));
+    let ing_stmt = ing_stmt.bind(&[(recipe_id as f64).into()])?;
+    let ing_results = ing_stmt.all().await?;
+
+    let ing_rows: Vec<serde_json::Value> = ing_results.results::<serde_json::Value>()?;
+
+    Ok(ing_rows
+        .iter()
+        .filter_map(recipe_ingredient_from_row)
+        .collect())
+}
+
+/// Load a single recipe with its ingredients as currently stored
+#[cfg(feature = "ssr")]
+pub(crate) async fn fetch_recipe(
+    db: &SendD1Database,
+    id: i64,
+) -> Result<Option<Recipe>, ServerFnError> {
+    use send_wrapper::SendWrapper;
+
+    SendWrapper::new(async {
+        let stmt = db.inner().prepare(
+            "SELECT id, name, description, servings, prep_time_minutes, cook_time_minutes, instructions FROM recipes WHERE id = ?"
+        );
+        let stmt = stmt.bind(&[(id as f64).into()])?;
+        let Some(row) = stmt.first::<serde_json::Value>(None).await? else {
+            return Ok(None);
+        };
+        let ingredients = load_recipe_ingredients(db, id).await?;
+        Ok::<_, worker::Error>(Some(recipe_from_row(&row, ingredients)))
+    })
+    .await
+    .map_err(|e| ServerFnError::new(format!("D1 query error: {:?}", e)))
+}
 
 /// Fetch all recipes from D1 database
 #[server]
@@ -185,7 +393,7 @@ pub async fn get_recipes() -> Result<Vec<Recipe>, ServerFnError> {
     // Fetch all recipes and their ingredients in a single SendWrapper block
     let recipes = SendWrapper::new(async {
         let stmt = db.inner().prepare(
-            "SELECT id, name, description, servings, prep_time_minutes, cook_time_minutes, instructions FROM recipes ORDER BY name"
+            "SELECT id, name, description, servings, prep_time_minutes, cook_time_minutes, instructions FROM recipes WHERE deleted_at IS NULL ORDER BY name"
         );
         let recipe_results = stmt.all().await?;
 
@@ -199,74 +407,10 @@ pub async fn get_recipes() -> Result<Vec<Recipe>, ServerFnError> {
                 .and_then(|v| v.as_i64())
                 .unwrap_or(0);
 
-            let instructions_json = row
-                .get("instructions")
-                .and_then(|v| v.as_str())
-                .unwrap_or("[]");
-            let instructions: Vec<String> =
-                serde_json::from_str(instructions_json).unwrap_or_default();
-
             // Fetch ingredients for this recipe
-            let ing_stmt = db.inner().prepare(
-                "SELECT ri.id, ri.ingredient_id, ri.amount_grams, ri.use_whole_package,
-                        i.name, i.calories, i.protein, i.fat, i.saturated_fat, 
-                        i.carbs, i.sugar, i.fiber, i.salt, i.package_size_g
-                 FROM recipe_ingredients ri
-                 JOIN ingredients i ON ri.ingredient_id = i.id
-                 WHERE ri.recipe_id = ?
-                 ORDER BY ri.id",
-            );
-            let ing_stmt = ing_stmt.bind(&[(recipe_id as f64).into()])?;
-            let ing_results = ing_stmt.all().await?;
+            let ingredients = load_recipe_ingredients(&db, recipe_id).await?;
 
-            let ing_rows: Vec<serde_json::Value> = ing_results.results::<serde_json::Value>()?;
-
-            let ingredients: Vec<RecipeIngredient> = ing_rows
-                .into_iter()
-                .filter_map(|r| {
-                    Some(RecipeIngredient {
-                        id: r.get("id")?.as_i64(),
-                        ingredient_id: r.get("ingredient_id")?.as_i64()?,
-                        ingredient_name: r.get("name")?.as_str()?.to_string(),
-                        amount_grams: r.get("amount_grams")?.as_f64()? as f32,
-                        use_whole_package: r.get("use_whole_package")?.as_i64()? == 1,
-                        package_size_g: r.get("package_size_g")?.as_f64()? as f32,
-                        calories_per_100g: r.get("calories")?.as_f64()? as f32,
-                        protein_per_100g: r.get("protein")?.as_f64()? as f32,
-                        fat_per_100g: r.get("fat")?.as_f64()? as f32,
-                        saturated_fat_per_100g: r.get("saturated_fat")?.as_f64()? as f32,
-                        carbs_per_100g: r.get("carbs")?.as_f64()? as f32,
-                        sugar_per_100g: r.get("sugar")?.as_f64()? as f32,
-                        fiber_per_100g: r.get("fiber")?.as_f64()? as f32,
-                        salt_per_100g: r.get("salt")?.as_f64()? as f32,
-                    })
-                })
-                .collect();
-
-            recipes.push(Recipe {
-                id: Some(recipe_id),
-                name: row
-                    .get("name")
-                    .and_then(|v| v.as_str())
-                    .unwrap_or("")
-                    .to_string(),
-                description: row
-                    .get("description")
-                    .and_then(|v| v.as_str())
-                    .unwrap_or("")
-                    .to_string(),
-                servings: row.get("servings").and_then(|v| v.as_i64()).unwrap_or(1) as i32,
-                prep_time_minutes: row
-                    .get("prep_time_minutes")
-                    .and_then(|v| v.as_i64())
-                    .unwrap_or(0) as i32,
-                cook_time_minutes: row
-                    .get("cook_time_minutes")
-                    .and_then(|v| v.as_i64())
-                    .unwrap_or(0) as i32,
-                instructions,
-                ingredients,
-            });
+            recipes.push(recipe_from_row(&row, ingredients));
         }
 
         Ok::<_, worker::Error>(recipes)
@@ -279,10 +423,17 @@ pub async fn get_recipes() -> Result<Vec<Recipe>, ServerFnError> {
 
 /// Create a new recipe
 #[server]
-pub async fn create_recipe(recipe: Recipe) -> Result<Recipe, ServerFnError> {
+pub async fn create_recipe(
+    recipe: Recipe,
+    auth_token: Option<String>,
+) -> Result<Recipe, ServerFnError> {
     use send_wrapper::SendWrapper;
 
+    use crate::audit::{record_audit, AuditAction, AuditEntity};
+    use crate::auth::resolve_actor;
+
     let db = expect_context::<SendD1Database>();
+    let actor = resolve_actor(auth_token.as_deref()).await;
 
     let instructions_json = serde_json::to_string(&recipe.instructions)
         .map_err(|e| ServerFnError::new(e.to_string()))?;
@@ -327,20 +478,39 @@ pub async fn create_recipe(recipe: Recipe) -> Result<Recipe, ServerFnError> {
         .map_err(|e| ServerFnError::new(format!("D1 insert ingredient error: {:?}", e)))?;
     }
 
+    let recipe = fetch_recipe(&db, recipe_id)
+        .await?
+        .ok_or_else(|| ServerFnError::new("Created recipe not found"))?;
+    record_recipe_revision(&db, &recipe).await?;
+    record_audit(
+        &db,
+        AuditEntity::Recipe,
+        recipe_id,
+        AuditAction::Create,
+        None,
+        Some(&recipe),
+        &actor,
+    )
+    .await?;
+
     log::info!("Created recipe: {} (id: {})", recipe.name, recipe_id);
 
-    Ok(Recipe {
-        id: Some(recipe_id),
-        ..recipe
-    })
+    Ok(recipe)
 }
 
 /// Update an existing recipe
 #[server]
-pub async fn update_recipe(recipe: Recipe) -> Result<(), ServerFnError> {
+pub async fn update_recipe(
+    recipe: Recipe,
+    auth_token: Option<String>,
+) -> Result<(), ServerFnError> {
     use send_wrapper::SendWrapper;
 
+    use crate::audit::{record_audit, AuditAction, AuditEntity};
+    use crate::auth::resolve_actor;
+
     let db = expect_context::<SendD1Database>();
+    let actor = resolve_actor(auth_token.as_deref()).await;
 
     let recipe_id = recipe
         .id
@@ -348,6 +518,10 @@ pub async fn update_recipe(recipe: Recipe) -> Result<(), ServerFnError> {
 
     let instructions_json = serde_json::to_string(&recipe.instructions)
         .map_err(|e| ServerFnError::new(e.to_string()))?;
+    let before = fetch_recipe(&db, recipe_id).await?;
+    if let Some(before) = &before {
+        ensure_baseline_revision(&db, before).await?;
+    }
 
     // Update the recipe
     SendWrapper::new(async {
@@ -397,38 +571,65 @@ pub async fn update_recipe(recipe: Recipe) -> Result<(), ServerFnError> {
         .map_err(|e| ServerFnError::new(format!("D1 insert ingredient error: {:?}", e)))?;
     }
 
+    // Snapshot what was stored rather than the payload, so ingredient names and
+    // nutrition come from the database
+    let after = fetch_recipe(&db, recipe_id)
+        .await?
+        .ok_or_else(|| ServerFnError::new("Updated recipe not found"))?;
+    record_recipe_revision(&db, &after).await?;
+    record_audit(
+        &db,
+        AuditEntity::Recipe,
+        recipe_id,
+        AuditAction::Update,
+        before.as_ref(),
+        Some(&after),
+        &actor,
+    )
+    .await?;
+
     log::info!("Updated recipe id: {}", recipe_id);
     Ok(())
 }
 
-/// Delete a recipe
+/// Move a recipe to the trash
 #[server]
-pub async fn delete_recipe(id: i64) -> Result<(), ServerFnError> {
+pub async fn delete_recipe(id: i64, auth_token: Option<String>) -> Result<(), ServerFnError> {
     use send_wrapper::SendWrapper;
 
+    use crate::audit::{record_audit, AuditAction, AuditEntity};
+    use crate::auth::resolve_actor;
+
     let db = expect_context::<SendD1Database>();
+    let actor = resolve_actor(auth_token.as_deref()).await;
 
-    // Delete recipe ingredients first (cascade should handle this, but be explicit)
-    SendWrapper::new(async {
-        let stmt = db
-            .inner()
-            .prepare("DELETE FROM recipe_ingredients WHERE recipe_id = ?");
-        let stmt = stmt.bind(&[(id as f64).into()])?;
-        stmt.run().await
-    })
-    .await
-    .map_err(|e| ServerFnError::new(format!("D1 delete ingredients error: {:?}", e)))?;
+    let before = fetch_recipe(&db, id).await?;
 
-    // Delete the recipe
+    // Ingredients are kept so the recipe can be restored intact
     SendWrapper::new(async {
-        let stmt = db.inner().prepare("DELETE FROM recipes WHERE id = ?");
+        let stmt = db.inner().prepare(
+            "UPDATE recipes SET deleted_at = datetime('now') WHERE id = ? AND deleted_at IS NULL",
+        );
         let stmt = stmt.bind(&[(id as f64).into()])?;
         stmt.run().await
     })
     .await
     .map_err(|e| ServerFnError::new(format!("D1 delete error: {:?}", e)))?;
 
-    log::info!("Deleted recipe id: {}", id);
+    if before.is_some() {
+        record_audit(
+            &db,
+            AuditEntity::Recipe,
+            id,
+            AuditAction::Delete,
+            before.as_ref(),
+            None,
+            &actor,
+        )
+        .await?;
+    }
+
+    log::info!("Moved recipe id: {} to trash", id);
     Ok(())
 }
 
@@ -466,6 +667,9 @@ fn IngredientSelector(
                 sugar_per_100g: ing.sugar,
                 fiber_per_100g: ing.fiber,
                 salt_per_100g: ing.salt,
+                package_price: ing.package_price,
+                cheapest_price_per_g: None,
+                cheapest_store: None,
             });
         });
     };
@@ -676,6 +880,8 @@ fn RecipeModal(
     on_save: impl Fn() + Clone + Send + Sync + 'static,
     on_delete: impl Fn(i64) + Clone + Send + Sync + 'static,
 ) -> impl IntoView {
+    let auth_token = expect_context::<AdminAuth>().token;
+
     // Form fields
     let name = RwSignal::new(String::new());
     let description = RwSignal::new(String::new());
@@ -687,6 +893,7 @@ fn RecipeModal(
     let error = RwSignal::new(Option::<String>::None);
     let saving = RwSignal::new(false);
     let show_delete_confirm = RwSignal::new(false);
+    let show_fit = RwSignal::new(false);
     let trigger_delete = RwSignal::new(false);
 
     // Handle delete via Effect
@@ -699,8 +906,9 @@ fn RecipeModal(
                     if let Some(id) = recipe.id {
                         saving.set(true);
                         let on_delete = on_delete.clone();
+                        let token = auth_token.get_untracked();
                         wasm_bindgen_futures::spawn_local(async move {
-                            match delete_recipe(id).await {
+                            match delete_recipe(id, token).await {
                                 Ok(()) => {
                                     show.set(false);
                                     editing.set(None);
@@ -748,6 +956,7 @@ fn RecipeModal(
         }
         error.set(None);
         show_delete_confirm.set(false);
+        show_fit.set(false);
     });
 
     let close = move || {
@@ -777,11 +986,12 @@ fn RecipeModal(
 
             saving.set(true);
             let on_save = on_save.clone();
+            let token = auth_token.get_untracked();
             wasm_bindgen_futures::spawn_local(async move {
                 let result = if recipe.id.is_some() {
-                    update_recipe(recipe).await.map(|_| ())
+                    update_recipe(recipe, token).await.map(|_| ())
                 } else {
-                    create_recipe(recipe).await.map(|_| ())
+                    create_recipe(recipe, token).await.map(|_| ())
                 };
 
                 saving.set(false);
@@ -892,11 +1102,24 @@ fn RecipeModal(
 
               // Ingredients section
               <div class="border-t border-slate-200 dark:border-slate-600 pt-4">
-                <h3 class="text-lg font-semibold text-slate-800 dark:text-slate-200 mb-3">"Ingredients"</h3>
+                <div class="mb-3 flex items-center justify-between">
+                  <h3 class="text-lg font-semibold text-slate-800 dark:text-slate-200">"Ingredients"</h3>
+                  <Show when=move || !selected_ingredients.get().is_empty()>
+                    <button
+                      class="text-sm text-blue-600 hover:text-blue-800 dark:text-blue-400"
+                      on:click=move |_| show_fit.update(|v| *v = !*v)
+                    >
+                      {move || if show_fit.get() { "Hide fit to targets" } else { "Fit to targets" }}
+                    </button>
+                  </Show>
+                </div>
                 <IngredientSelector
                   available_ingredients=available_ingredients
                   selected_ingredients=selected_ingredients
                 />
+                <Show when=move || show_fit.get() && !selected_ingredients.get().is_empty()>
+                  <FitToTargets selected_ingredients=selected_ingredients servings=servings />
+                </Show>
               </div>
 
               // Instructions section
@@ -1011,10 +1234,18 @@ fn RecipeModal(
 fn RecipeCard(
     recipe: Recipe,
     on_edit: impl Fn(Recipe) + Clone + Send + Sync + 'static,
+    on_history: impl Fn(Recipe) + Clone + Send + Sync + 'static,
     is_authenticated: ReadSignal<bool>,
+    cost_mode: ReadSignal<CostMode>,
 ) -> impl IntoView {
     let nutrition = recipe.nutrition_per_serving();
+    let recipe_for_cost = recipe.clone();
     let recipe_for_edit = recipe.clone();
+    let recipe_for_history = recipe.clone();
+    let recipe_for_label = recipe.clone();
+    let show_label = RwSignal::new(false);
+    let recipe_for_containers = recipe.clone();
+    let show_containers = RwSignal::new(false);
     let recipe_name = recipe.name.clone();
     let recipe_desc = recipe.description.clone();
     let has_description = !recipe.description.is_empty();
@@ -1037,17 +1268,30 @@ fn RecipeCard(
             </Show>
           </div>
           <Show when=move || is_authenticated.get()>
-            <button
-              class="text-blue-600 hover:text-blue-800 p-1"
-              title="Edit recipe"
-              on:click={
-                let recipe_for_edit = recipe_for_edit.clone();
-                let on_edit = on_edit.clone();
-                move |_| on_edit(recipe_for_edit.clone())
-              }
-            >
-              <EditIcon />
-            </button>
+            <div class="flex items-center gap-1">
+              <button
+                class="text-slate-500 hover:text-slate-700 dark:text-slate-400 dark:hover:text-slate-200 p-1"
+                title="Version history"
+                on:click={
+                  let recipe_for_history = recipe_for_history.clone();
+                  let on_history = on_history.clone();
+                  move |_| on_history(recipe_for_history.clone())
+                }
+              >
+                <HistoryIcon />
+              </button>
+              <button
+                class="text-blue-600 hover:text-blue-800 p-1"
+                title="Edit recipe"
+                on:click={
+                  let recipe_for_edit = recipe_for_edit.clone();
+                  let on_edit = on_edit.clone();
+                  move |_| on_edit(recipe_for_edit.clone())
+                }
+              >
+                <EditIcon />
+              </button>
+            </div>
           </Show>
         </div>
 
@@ -1062,6 +1306,37 @@ fn RecipeCard(
           <Show when=move || { prep > 0 || cook > 0 }>
             <span class="font-medium">{format!("Total: {}", total.clone())}</span>
           </Show>
+          {move || {
+            let mode = cost_mode.get();
+            let cost = recipe_for_cost.cost(mode);
+            (cost > 0.0)
+              .then(|| {
+                let unpriced = recipe_for_cost.unpriced_ingredients();
+                let title = recipe_for_cost
+                  .ingredients
+                  .iter()
+                  .filter_map(|ing| {
+                    let ing_cost = ing.cost(mode)?;
+                    let store = match (mode, &ing.cheapest_store) {
+                      (CostMode::CheapestRecent, Some(store)) if !store.is_empty() => format!(" at {}", store),
+                      _ => String::new(),
+                    };
+                    Some(format!("{}: ${:.2}{}", ing.ingredient_name, ing_cost, store))
+                  })
+                  .collect::<Vec<_>>()
+                  .join("\n");
+                view! {
+                  <span class="cursor-help" title=title>
+                    {format!(
+                      "Cost: ${:.2} (${:.2}/serving){}",
+                      cost,
+                      cost / servings.max(1) as f32,
+                      if unpriced > 0 { format!(", {} unpriced", unpriced) } else { String::new() },
+                    )}
+                  </span>
+                }
+              })
+          }}
         </div>
 
         <Show when=move || has_ingredients>
@@ -1111,6 +1386,30 @@ fn RecipeCard(
               nutrition.salt,
             )}
           </p>
+          <Show when=move || has_ingredients>
+            <div class="mt-2 flex gap-4">
+              <button
+                class="text-sm text-blue-600 hover:underline dark:text-blue-400"
+                on:click=move |_| show_label.update(|v| *v = !*v)
+              >
+                {move || if show_label.get() { "Hide nutrition label" } else { "Nutrition label" }}
+              </button>
+              <button
+                class="text-sm text-blue-600 hover:underline dark:text-blue-400"
+                on:click=move |_| show_containers.update(|v| *v = !*v)
+              >
+                {move || {
+                  if show_containers.get() { "Hide container labels" } else { "Container labels" }
+                }}
+              </button>
+            </div>
+          </Show>
+          <Show when=move || show_label.get()>
+            <NutritionLabel recipe=recipe_for_label.clone() />
+          </Show>
+          <Show when=move || show_containers.get()>
+            <ContainerLabelsForm recipe=recipe_for_containers.clone() />
+          </Show>
         </div>
       </div>
     }
@@ -1124,6 +1423,7 @@ pub fn Recipes() -> impl IntoView {
     // Modal state
     let show_modal = RwSignal::new(false);
     let editing_recipe = RwSignal::new(Option::<Recipe>::None);
+    let history_recipe = RwSignal::new(Option::<Recipe>::None);
 
     // Signals to hold cached data (client-side only, loaded before resources resolve)
     let cached_recipes = RwSignal::new(Option::<Vec<Recipe>>::None);
@@ -1178,19 +1478,48 @@ pub fn Recipes() -> impl IntoView {
         show_modal.set(true);
     };
 
+    let handle_history = move |recipe: Recipe| {
+        history_recipe.set(Some(recipe));
+    };
+
+    let cost_mode = RwSignal::new(CostMode::Current);
+
     view! {
       <div class="mx-auto max-w-7xl py-6">
         <div class="mb-6 flex items-center justify-between flex-wrap gap-4">
           <h2 class="text-3xl font-bold text-slate-900 dark:text-slate-100">"Recipes"</h2>
-          <Show when=move || auth.is_authenticated.get()>
-            <button
-              class="flex items-center gap-2 rounded bg-green-600 px-4 py-2 text-sm font-medium text-white hover:bg-green-700"
-              on:click=handle_new
-            >
-              <PlusIcon />
-              "New Recipe"
-            </button>
-          </Show>
+          <div class="flex items-center gap-3">
+            <div class="flex rounded border border-slate-300 dark:border-slate-600 overflow-hidden text-sm">
+              {[CostMode::Current, CostMode::CheapestRecent]
+                .into_iter()
+                .map(|mode| {
+                  view! {
+                    <button
+                      class=move || {
+                        if cost_mode.get() == mode {
+                          "px-3 py-2 bg-blue-600 text-white"
+                        } else {
+                          "px-3 py-2 bg-white dark:bg-slate-800 text-slate-700 dark:text-slate-300 hover:bg-slate-100 dark:hover:bg-slate-700"
+                        }
+                      }
+                      on:click=move |_| cost_mode.set(mode)
+                    >
+                      {mode.label()}
+                    </button>
+                  }
+                })
+                .collect_view()}
+            </div>
+            <Show when=move || auth.is_authenticated.get()>
+              <button
+                class="flex items-center gap-2 rounded bg-green-600 px-4 py-2 text-sm font-medium text-white hover:bg-green-700"
+                on:click=handle_new
+              >
+                <PlusIcon />
+                "New Recipe"
+              </button>
+            </Show>
+          </div>
         </div>
 
         // Show cached data while loading, with Suspense for SSR support
@@ -1207,7 +1536,15 @@ pub fn Recipes() -> impl IntoView {
                     key=|recipe| recipe.id.unwrap_or(0)
                     children=move |recipe: Recipe| {
                       let is_auth_signal = is_auth.read_only();
-                      view! { <RecipeCard recipe=recipe on_edit=handle_edit is_authenticated=is_auth_signal /> }
+                      view! {
+                        <RecipeCard
+                          recipe=recipe
+                          on_edit=handle_edit
+                          on_history=handle_history
+                          is_authenticated=is_auth_signal
+                          cost_mode=cost_mode.read_only()
+                        />
+                      }
                     }
                   />
                 </div>
@@ -1259,7 +1596,15 @@ pub fn Recipes() -> impl IntoView {
                           key=|recipe| recipe.id.unwrap_or(0)
                           children=move |recipe: Recipe| {
                             let is_auth_signal = is_auth.read_only();
-                            view! { <RecipeCard recipe=recipe on_edit=handle_edit is_authenticated=is_auth_signal /> }
+                            view! {
+                              <RecipeCard
+                                recipe=recipe
+                                on_edit=handle_edit
+                                on_history=handle_history
+                                is_authenticated=is_auth_signal
+                                cost_mode=cost_mode.read_only()
+                              />
+                            }
                           }
                         />
                       </div>
@@ -1290,6 +1635,8 @@ pub fn Recipes() -> impl IntoView {
             }
           }}
         </Suspense>
+
+        <RecipeHistoryModal recipe=history_recipe on_restore=refetch />
       </div>
     }
 }
@@ -6,20 +6,27 @@
 //! - MacroDistribution: Protein/Carbs/Fat sliders with pie chart
 //! - DailyLimits: Salt/sodium and saturated fat limits
 //! - DailyMinimums: Fiber minimum
+//! - PhotoUploads: How food photos are shrunk before upload
 
 #[cfg(not(feature = "ssr"))]
 use gloo_storage::{LocalStorage, Storage};
 use leptos::prelude::*;
 use serde::{Deserialize, Serialize};
 
+use crate::components::{INPUT_CLASS, LABEL_CLASS};
+use crate::image_upload::{PhotoFormat, PhotoSettings};
+
 // ============================================================================
 // Constants
 // ============================================================================
 
 // Calories per gram for macros
-const CALORIES_PER_GRAM_PROTEIN: f64 = 4.0;
-const CALORIES_PER_GRAM_CARBS: f64 = 4.0;
-const CALORIES_PER_GRAM_FAT: f64 = 9.0;
+pub(crate) const CALORIES_PER_GRAM_PROTEIN: f64 = 4.0;
+pub(crate) const CALORIES_PER_GRAM_CARBS: f64 = 4.0;
+pub(crate) const CALORIES_PER_GRAM_FAT: f64 = 9.0;
+
+// 1g salt = 393.4mg sodium
+pub(crate) const SODIUM_MG_PER_G_SALT: f64 = 393.4;
 
 // Default values
 const DEFAULT_DAILY_CALORIES: i32 = 2000;
@@ -39,14 +46,33 @@ const SETTINGS_STORAGE_KEY: &str = "food_settings";
 // ============================================================================
 
 #[derive(Clone, Serialize, Deserialize)]
-struct SettingsData {
-    daily_calories: i32,
-    protein_pct: i32,
-    carbs_pct: i32,
-    fat_pct: i32,
-    sodium_mg: i32,
-    sat_fat_grams: i32,
-    fiber_min: i32,
+pub(crate) struct SettingsData {
+    pub(crate) daily_calories: i32,
+    pub(crate) protein_pct: i32,
+    pub(crate) carbs_pct: i32,
+    pub(crate) fat_pct: i32,
+    pub(crate) sodium_mg: i32,
+    pub(crate) sat_fat_grams: i32,
+    pub(crate) fiber_min: i32,
+    #[serde(default)]
+    pub(crate) photo: PhotoSettings,
+}
+
+impl SettingsData {
+    /// Daily protein target in grams
+    pub(crate) fn protein_grams(&self) -> f64 {
+        self.daily_calories as f64 * self.protein_pct as f64 / 100.0 / CALORIES_PER_GRAM_PROTEIN
+    }
+
+    /// Daily carbohydrate target in grams
+    pub(crate) fn carbs_grams(&self) -> f64 {
+        self.daily_calories as f64 * self.carbs_pct as f64 / 100.0 / CALORIES_PER_GRAM_CARBS
+    }
+
+    /// Daily fat target in grams
+    pub(crate) fn fat_grams(&self) -> f64 {
+        self.daily_calories as f64 * self.fat_pct as f64 / 100.0 / CALORIES_PER_GRAM_FAT
+    }
 }
 
 impl Default for SettingsData {
@@ -62,11 +88,12 @@ impl Default for SettingsData {
             sodium_mg: DEFAULT_SODIUM_MG,
             sat_fat_grams,
             fiber_min: DEFAULT_FIBER_MIN,
+            photo: PhotoSettings::default(),
         }
     }
 }
 
-fn load_settings() -> SettingsData {
+pub(crate) fn load_settings() -> SettingsData {
     #[cfg(feature = "ssr")]
     {
         SettingsData::default()
@@ -621,8 +648,8 @@ fn DailyLimits(
 ) -> impl IntoView {
     // Computed salt in grams (linked to sodium)
     let salt_grams = Memo::new(move |_| {
-        // 1g salt = 393.4mg sodium; salt_g = sodium_mg / 393.4
-        (sodium_mg.get() as f64 / 393.4 * 10.0).round() / 10.0
+        // salt_g = sodium_mg / 393.4
+        (sodium_mg.get() as f64 / SODIUM_MG_PER_G_SALT * 10.0).round() / 10.0
     });
 
     // Computed saturated fat percentage
@@ -664,7 +691,7 @@ fn DailyLimits(
                   prop:value=move || salt_grams.get()
                   on:input=move |ev| {
                     if let Ok(val) = event_target_value(&ev).parse::<f64>() {
-                      let sodium = (val * 393.4).round() as i32;
+                      let sodium = (val * SODIUM_MG_PER_G_SALT).round() as i32;
                       set_sodium_mg.set(sodium.max(0));
                     }
                   }
@@ -750,6 +777,81 @@ fn DailyMinimums(fiber_min: ReadSignal<i32>, set_fiber_min: WriteSignal<i32>) ->
     }
 }
 
+/// Photo upload section (downscaling and re-encoding in the browser)
+#[component]
+fn PhotoUploads(
+    photo: ReadSignal<PhotoSettings>,
+    set_photo: WriteSignal<PhotoSettings>,
+) -> impl IntoView {
+    view! {
+      <div class="rounded-lg bg-white dark:bg-slate-800 p-6 shadow-md">
+        <h3 class="mb-4 text-xl font-semibold text-slate-900 dark:text-slate-100">"Photo Uploads"</h3>
+        <div class="space-y-4">
+          <div class="grid grid-cols-2 gap-4">
+            <div>
+              <label class=LABEL_CLASS>"Longest Side (px)"</label>
+              <input
+                type="number"
+                min="320"
+                step="64"
+                prop:value=move || photo.get().max_side
+                on:input=move |ev| {
+                  if let Ok(val) = event_target_value(&ev).parse::<u32>() {
+                    set_photo.update(|p| p.max_side = val.max(320));
+                  }
+                }
+                class=INPUT_CLASS
+              />
+            </div>
+            <div>
+              <label class=LABEL_CLASS>"Format"</label>
+              <select
+                class=INPUT_CLASS
+                prop:value=move || photo.get().format.as_str()
+                on:change=move |ev| {
+                  if let Some(format) = PhotoFormat::parse(&event_target_value(&ev)) {
+                    set_photo.update(|p| p.format = format);
+                  }
+                }
+              >
+                {PhotoFormat::ALL
+                  .into_iter()
+                  .map(|format| view! { <option value=format.as_str()>{format.label()}</option> })
+                  .collect_view()}
+              </select>
+            </div>
+          </div>
+          <div>
+            <label class=LABEL_CLASS>{move || format!("Quality: {}%", photo.get().quality)}</label>
+            <input
+              type="range"
+              min="50"
+              max="100"
+              prop:value=move || photo.get().quality
+              on:input=move |ev| {
+                if let Ok(val) = event_target_value(&ev).parse::<u8>() {
+                  set_photo.update(|p| p.quality = val.clamp(1, 100));
+                }
+              }
+              class="w-full"
+            />
+          </div>
+          <label class="flex items-center gap-2 text-sm text-slate-700 dark:text-slate-300">
+            <input
+              type="checkbox"
+              prop:checked=move || photo.get().bake_rotation
+              on:change=move |ev| set_photo.update(|p| p.bake_rotation = event_target_checked(&ev))
+            />
+            "Turn photos by their crop rotation before uploading"
+          </label>
+          <p class="text-xs text-slate-500 dark:text-slate-400">
+            "Photos are shrunk in the browser before upload. WebP files are smaller, but are always shown at full size."
+          </p>
+        </div>
+      </div>
+    }
+}
+
 // ============================================================================
 // Main Component
 // ============================================================================
@@ -776,6 +878,9 @@ pub fn Settings() -> impl IntoView {
     // Fiber minimum
     let (fiber_min, set_fiber_min) = signal(initial_settings.fiber_min);
 
+    // Photo uploads
+    let (photo, set_photo) = signal(initial_settings.photo);
+
     // Auto-save settings whenever any value changes
     Effect::new(move |_| {
         let settings = SettingsData {
@@ -786,6 +891,7 @@ pub fn Settings() -> impl IntoView {
             sodium_mg: sodium_mg.get(),
             sat_fat_grams: sat_fat_grams.get(),
             fiber_min: fiber_min.get(),
+            photo: photo.get(),
         };
         save_settings(&settings);
     });
@@ -826,6 +932,8 @@ pub fn Settings() -> impl IntoView {
           />
 
           <DailyMinimums fiber_min=fiber_min set_fiber_min=set_fiber_min />
+
+          <PhotoUploads photo=photo set_photo=set_photo />
         </div>
       </div>
     }
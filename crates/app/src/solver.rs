@@ -0,0 +1,504 @@
+//! Solver module
+//!
+//! A small dense linear programming solver (two-phase simplex) with branch-and-bound
+//! for integer solutions, plus a box-constrained least-squares fit. Problems here have
+//! tens of variables, so plain dense methods are plenty.
+
+const EPS: f64 = 1e-9;
+const MAX_PIVOTS: usize = 10_000;
+const MAX_SWEEPS: usize = 10_000;
+
+// ============================================================================
+// Problem Definition
+// ============================================================================
+
+/// How a constraint's left-hand side relates to its right-hand side
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Relation {
+    LessOrEqual,
+    GreaterOrEqual,
+    Equal,
+}
+
+/// A linear constraint `coeffs · x (relation) rhs`
+#[derive(Clone, Debug, PartialEq)]
+pub struct Constraint {
+    pub coeffs: Vec<f64>,
+    pub relation: Relation,
+    pub rhs: f64,
+}
+
+/// Minimise `objective · x` subject to the constraints, with `0 <= x <= upper_bounds`
+#[derive(Clone, Debug, PartialEq)]
+pub struct LinearProgram {
+    pub objective: Vec<f64>,
+    pub constraints: Vec<Constraint>,
+    pub upper_bounds: Vec<Option<f64>>,
+}
+
+/// Outcome of solving a linear program
+#[derive(Clone, Debug, PartialEq)]
+pub enum LpResult {
+    Optimal { x: Vec<f64>, value: f64 },
+    Infeasible,
+    Unbounded,
+}
+
+impl LinearProgram {
+    /// Create a program minimising `objective` with no constraints yet
+    pub fn new(objective: Vec<f64>) -> Self {
+        let n = objective.len();
+        Self {
+            objective,
+            constraints: Vec::new(),
+            upper_bounds: vec![None; n],
+        }
+    }
+
+    pub fn num_vars(&self) -> usize {
+        self.objective.len()
+    }
+
+    pub fn add_constraint(&mut self, coeffs: Vec<f64>, relation: Relation, rhs: f64) {
+        debug_assert_eq!(coeffs.len(), self.num_vars());
+        self.constraints.push(Constraint {
+            coeffs,
+            relation,
+            rhs,
+        });
+    }
+
+    pub fn set_upper_bound(&mut self, var: usize, bound: f64) {
+        self.upper_bounds[var] = Some(bound);
+    }
+
+    /// Solve the continuous problem
+    pub fn solve(&self) -> LpResult {
+        let n = self.num_vars();
+
+        // Collect all rows, turning upper bounds into constraints and making every rhs non-negative
+        let mut rows: Vec<Constraint> = self.constraints.clone();
+        for (var, bound) in self.upper_bounds.iter().enumerate() {
+            if let Some(bound) = bound {
+                let mut coeffs = vec![0.0; n];
+                coeffs[var] = 1.0;
+                rows.push(Constraint {
+                    coeffs,
+                    relation: Relation::LessOrEqual,
+                    rhs: *bound,
+                });
+            }
+        }
+        for row in &mut rows {
+            if row.rhs < 0.0 {
+                row.coeffs.iter_mut().for_each(|c| *c = -*c);
+                row.rhs = -row.rhs;
+                row.relation = match row.relation {
+                    Relation::LessOrEqual => Relation::GreaterOrEqual,
+                    Relation::GreaterOrEqual => Relation::LessOrEqual,
+                    Relation::Equal => Relation::Equal,
+                };
+            }
+        }
+
+        let m = rows.len();
+        let num_slack = rows
+            .iter()
+            .filter(|r| r.relation != Relation::Equal)
+            .count();
+        let num_artificial = rows
+            .iter()
+            .filter(|r| r.relation != Relation::LessOrEqual)
+            .count();
+        let cols = n + num_slack + num_artificial;
+        let rhs_col = cols;
+
+        // Build the tableau with an initial basis of slacks and artificials
+        let mut tableau = vec![vec![0.0; cols + 1]; m];
+        let mut basis = vec![0; m];
+        let mut is_artificial = vec![false; cols];
+        let mut next_slack = n;
+        let mut next_artificial = n + num_slack;
+        for (i, row) in rows.iter().enumerate() {
+            tableau[i][..n].copy_from_slice(&row.coeffs);
+            tableau[i][rhs_col] = row.rhs;
+            match row.relation {
+                Relation::LessOrEqual => {
+                    tableau[i][next_slack] = 1.0;
+                    basis[i] = next_slack;
+                    next_slack += 1;
+                }
+                Relation::GreaterOrEqual => {
+                    tableau[i][next_slack] = -1.0;
+                    next_slack += 1;
+                    tableau[i][next_artificial] = 1.0;
+                    is_artificial[next_artificial] = true;
+                    basis[i] = next_artificial;
+                    next_artificial += 1;
+                }
+                Relation::Equal => {
+                    tableau[i][next_artificial] = 1.0;
+                    is_artificial[next_artificial] = true;
+                    basis[i] = next_artificial;
+                    next_artificial += 1;
+                }
+            }
+        }
+
+        // Phase 1: minimise the sum of artificial variables
+        if num_artificial > 0 {
+            let phase1_cost: Vec<f64> = is_artificial
+                .iter()
+                .map(|&a| if a { 1.0 } else { 0.0 })
+                .collect();
+            let allowed = vec![true; cols];
+            if run_simplex(&mut tableau, &mut basis, &phase1_cost, &allowed).is_err() {
+                return LpResult::Infeasible;
+            }
+            let infeasibility: f64 = (0..m)
+                .filter(|&i| is_artificial[basis[i]])
+                .map(|i| tableau[i][rhs_col])
+                .sum();
+            if infeasibility > 1e-7 {
+                return LpResult::Infeasible;
+            }
+
+            // Drive remaining (zero-valued) artificials out of the basis where possible
+            let stuck: Vec<usize> = (0..m).filter(|&i| is_artificial[basis[i]]).collect();
+            for i in stuck {
+                if let Some(j) = (0..cols).find(|&j| !is_artificial[j] && tableau[i][j].abs() > EPS)
+                {
+                    pivot(&mut tableau, &mut basis, i, j);
+                }
+            }
+        }
+
+        // Phase 2: minimise the real objective without letting artificials back in
+        let mut cost = vec![0.0; cols];
+        cost[..n].copy_from_slice(&self.objective);
+        let allowed: Vec<bool> = is_artificial.iter().map(|a| !a).collect();
+        if run_simplex(&mut tableau, &mut basis, &cost, &allowed).is_err() {
+            return LpResult::Unbounded;
+        }
+
+        let mut x = vec![0.0; n];
+        for (i, &b) in basis.iter().enumerate() {
+            if b < n {
+                x[b] = tableau[i][rhs_col];
+            }
+        }
+        let value = x.iter().zip(&self.objective).map(|(xi, ci)| xi * ci).sum();
+        LpResult::Optimal { x, value }
+    }
+
+    /// Solve with every variable restricted to whole numbers, using branch-and-bound.
+    /// Returns the best solution found within `max_nodes` relaxations.
+    pub fn solve_integer(&self, max_nodes: usize) -> LpResult {
+        let mut best: Option<(Vec<f64>, f64)> = None;
+        let mut stack = vec![self.clone()];
+        let mut nodes = 0;
+
+        while let Some(problem) = stack.pop() {
+            if nodes >= max_nodes {
+                break;
+            }
+            nodes += 1;
+
+            let (x, value) = match problem.solve() {
+                LpResult::Optimal { x, value } => (x, value),
+                LpResult::Unbounded => return LpResult::Unbounded,
+                LpResult::Infeasible => continue,
+            };
+            if best
+                .as_ref()
+                .is_some_and(|(_, best_value)| value >= best_value - 1e-7)
+            {
+                continue;
+            }
+
+            // Branch on the most fractional variable
+            let branch = x
+                .iter()
+                .enumerate()
+                .map(|(j, xj)| (j, (xj - xj.round()).abs()))
+                .filter(|(_, frac)| *frac > 1e-6)
+                .max_by(|a, b| a.1.total_cmp(&b.1));
+
+            match branch {
+                None => {
+                    let rounded: Vec<f64> = x.iter().map(|xj| xj.round()).collect();
+                    best = Some((rounded, value));
+                }
+                Some((j, _)) => {
+                    let mut unit = vec![0.0; problem.num_vars()];
+                    unit[j] = 1.0;
+
+                    let mut down = problem.clone();
+                    down.add_constraint(unit.clone(), Relation::LessOrEqual, x[j].floor());
+                    let mut up = problem;
+                    up.add_constraint(unit, Relation::GreaterOrEqual, x[j].ceil());
+
+                    // Explore rounding up first; it tends to reach feasible plans quickly
+                    stack.push(down);
+                    stack.push(up);
+                }
+            }
+        }
+
+        match best {
+            Some((x, value)) => LpResult::Optimal { x, value },
+            None => LpResult::Infeasible,
+        }
+    }
+}
+
+// ============================================================================
+// Simplex Internals
+// ============================================================================
+
+/// Pivot the tableau so that column `col` becomes basic in row `row`
+fn pivot(tableau: &mut [Vec<f64>], basis: &mut [usize], row: usize, col: usize) {
+    let pivot_value = tableau[row][col];
+    tableau[row].iter_mut().for_each(|v| *v /= pivot_value);
+    let pivot_row = tableau[row].clone();
+    for (i, r) in tableau.iter_mut().enumerate() {
+        if i == row {
+            continue;
+        }
+        let factor = r[col];
+        if factor.abs() > EPS {
+            r.iter_mut()
+                .zip(&pivot_row)
+                .for_each(|(v, p)| *v -= factor * p);
+        }
+    }
+    basis[row] = col;
+}
+
+/// Run primal simplex iterations with Bland's rule. Errors if the problem is unbounded.
+fn run_simplex(
+    tableau: &mut [Vec<f64>],
+    basis: &mut [usize],
+    cost: &[f64],
+    allowed: &[bool],
+) -> Result<(), ()> {
+    let cols = cost.len();
+    let rhs_col = cols;
+
+    for _ in 0..MAX_PIVOTS {
+        // Entering column: lowest index with a negative reduced cost
+        let entering = (0..cols).find(|&j| {
+            if !allowed[j] || basis.contains(&j) {
+                return false;
+            }
+            let reduced: f64 = cost[j]
+                - tableau
+                    .iter()
+                    .zip(basis.iter())
+                    .map(|(row, &b)| cost[b] * row[j])
+                    .sum::<f64>();
+            reduced < -EPS
+        });
+        let Some(col) = entering else {
+            return Ok(());
+        };
+
+        // Leaving row: minimum ratio, ties broken by lowest basis index
+        let mut leaving: Option<(usize, f64)> = None;
+        for (i, row) in tableau.iter().enumerate() {
+            if row[col] <= EPS {
+                continue;
+            }
+            let ratio = row[rhs_col] / row[col];
+            let better = match leaving {
+                None => true,
+                Some((l, best)) => {
+                    ratio < best - EPS || (ratio <= best + EPS && basis[i] < basis[l])
+                }
+            };
+            if better {
+                leaving = Some((i, ratio));
+            }
+        }
+        let Some((row, _)) = leaving else {
+            return Err(());
+        };
+
+        pivot(tableau, basis, row, col);
+    }
+
+    log::warn!("Simplex stopped after {} pivots", MAX_PIVOTS);
+    Ok(())
+}
+
+// ============================================================================
+// Least Squares
+// ============================================================================
+
+/// Minimise `|A x - b|²` subject to `lower <= x <= upper` by cyclic coordinate descent,
+/// starting from `start`. `rows` holds the rows of `A`. When the fit is not unique the
+/// result stays close to the starting point.
+pub fn bounded_least_squares(
+    rows: &[Vec<f64>],
+    b: &[f64],
+    lower: &[f64],
+    upper: &[f64],
+    start: &[f64],
+) -> Vec<f64> {
+    let n = start.len();
+    let mut x: Vec<f64> = start
+        .iter()
+        .zip(lower.iter().zip(upper))
+        .map(|(s, (lo, hi))| s.clamp(*lo, *hi))
+        .collect();
+    let mut residual: Vec<f64> = rows
+        .iter()
+        .zip(b)
+        .map(|(row, bi)| bi - row.iter().zip(&x).map(|(a, xj)| a * xj).sum::<f64>())
+        .collect();
+    let col_norms: Vec<f64> = (0..n)
+        .map(|j| rows.iter().map(|row| row[j] * row[j]).sum())
+        .collect();
+
+    for _ in 0..MAX_SWEEPS {
+        let mut max_step: f64 = 0.0;
+        for (j, &norm) in col_norms.iter().enumerate() {
+            if norm <= EPS {
+                continue;
+            }
+            let gradient: f64 = rows.iter().zip(&residual).map(|(row, r)| row[j] * r).sum();
+            let next = (x[j] + gradient / norm).clamp(lower[j], upper[j]);
+            let step = next - x[j];
+            if step.abs() > 0.0 {
+                for (row, r) in rows.iter().zip(residual.iter_mut()) {
+                    *r -= row[j] * step;
+                }
+                x[j] = next;
+                max_step = max_step.max(step.abs());
+            }
+        }
+        if max_step < 1e-6 {
+            break;
+        }
+    }
+    x
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn assert_optimal(result: LpResult, expected_x: &[f64], expected_value: f64) {
+        let LpResult::Optimal { x, value } = result else {
+            panic!("expected an optimal solution, got {:?}", result);
+        };
+        assert_eq!(x.len(), expected_x.len());
+        for (got, want) in x.iter().zip(expected_x) {
+            assert!(
+                (got - want).abs() < 1e-6,
+                "x = {:?}, expected {:?}",
+                x,
+                expected_x
+            );
+        }
+        assert!(
+            (value - expected_value).abs() < 1e-6,
+            "value = {}, expected {}",
+            value,
+            expected_value
+        );
+    }
+
+    #[test]
+    fn solves_a_bounded_maximisation() {
+        // Maximise x + y with x + 2y <= 4 and 3x + y <= 6
+        let mut lp = LinearProgram::new(vec![-1.0, -1.0]);
+        lp.add_constraint(vec![1.0, 2.0], Relation::LessOrEqual, 4.0);
+        lp.add_constraint(vec![3.0, 1.0], Relation::LessOrEqual, 6.0);
+        assert_optimal(lp.solve(), &[1.6, 1.2], -2.8);
+    }
+
+    #[test]
+    fn handles_greater_or_equal_and_equal_rows() {
+        // Minimise x + 2y with x + y >= 3 and x - y = 1
+        let mut lp = LinearProgram::new(vec![1.0, 2.0]);
+        lp.add_constraint(vec![1.0, 1.0], Relation::GreaterOrEqual, 3.0);
+        lp.add_constraint(vec![1.0, -1.0], Relation::Equal, 1.0);
+        assert_optimal(lp.solve(), &[2.0, 1.0], 4.0);
+    }
+
+    #[test]
+    fn flips_rows_with_negative_rhs() {
+        // y - x >= 1 written as x - y <= -1
+        let mut lp = LinearProgram::new(vec![0.0, 1.0]);
+        lp.add_constraint(vec![1.0, -1.0], Relation::LessOrEqual, -1.0);
+        assert_optimal(lp.solve(), &[0.0, 1.0], 1.0);
+    }
+
+    #[test]
+    fn respects_upper_bounds() {
+        let mut lp = LinearProgram::new(vec![-1.0, -2.0]);
+        lp.set_upper_bound(0, 3.0);
+        lp.set_upper_bound(1, 0.5);
+        assert_optimal(lp.solve(), &[3.0, 0.5], -4.0);
+    }
+
+    #[test]
+    fn reports_infeasible_and_unbounded_programs() {
+        let mut infeasible = LinearProgram::new(vec![1.0]);
+        infeasible.add_constraint(vec![1.0], Relation::LessOrEqual, 1.0);
+        infeasible.add_constraint(vec![1.0], Relation::GreaterOrEqual, 2.0);
+        assert_eq!(infeasible.solve(), LpResult::Infeasible);
+
+        let unbounded = LinearProgram::new(vec![-1.0]);
+        assert_eq!(unbounded.solve(), LpResult::Unbounded);
+    }
+
+    #[test]
+    fn branch_and_bound_finds_the_integer_optimum() {
+        // Maximise 5x + 4y with 6x + 4y <= 24 and x + 2y <= 6.
+        // The relaxation peaks at (3, 1.5); the best whole-number plan is (4, 0).
+        let mut lp = LinearProgram::new(vec![-5.0, -4.0]);
+        lp.add_constraint(vec![6.0, 4.0], Relation::LessOrEqual, 24.0);
+        lp.add_constraint(vec![1.0, 2.0], Relation::LessOrEqual, 6.0);
+        assert_optimal(lp.solve(), &[3.0, 1.5], -21.0);
+        assert_optimal(lp.solve_integer(1000), &[4.0, 0.0], -20.0);
+    }
+
+    #[test]
+    fn branch_and_bound_reports_integer_infeasibility() {
+        // 2x = 1 has only a fractional solution
+        let mut lp = LinearProgram::new(vec![1.0]);
+        lp.add_constraint(vec![2.0], Relation::Equal, 1.0);
+        assert_eq!(lp.solve_integer(100), LpResult::Infeasible);
+    }
+
+    fn assert_close(got: &[f64], want: &[f64]) {
+        for (g, w) in got.iter().zip(want) {
+            assert!((g - w).abs() < 1e-4, "got {:?}, expected {:?}", got, want);
+        }
+    }
+
+    #[test]
+    fn least_squares_solves_a_square_system() {
+        // x + y = 3, x - y = 1
+        let rows = vec![vec![1.0, 1.0], vec![1.0, -1.0]];
+        let x = bounded_least_squares(&rows, &[3.0, 1.0], &[0.0, 0.0], &[10.0, 10.0], &[0.0, 0.0]);
+        assert_close(&x, &[2.0, 1.0]);
+    }
+
+    #[test]
+    fn least_squares_clamps_to_bounds() {
+        let rows = vec![vec![1.0, 0.0], vec![0.0, 1.0]];
+        let x = bounded_least_squares(&rows, &[5.0, -2.0], &[0.0, 0.0], &[3.0, 10.0], &[1.0, 1.0]);
+        assert_close(&x, &[3.0, 0.0]);
+    }
+
+    #[test]
+    fn least_squares_stays_at_an_optimal_start() {
+        // Any x + y = 2 is a perfect fit, so the start is kept
+        let rows = vec![vec![1.0, 1.0]];
+        let x = bounded_least_squares(&rows, &[2.0], &[0.0, 0.0], &[10.0, 10.0], &[0.5, 1.5]);
+        assert_close(&x, &[0.5, 1.5]);
+    }
+}
@@ -0,0 +1,634 @@
+//! Nutrition stats module
+//!
+//! Trends in what we actually eat: the food log is aggregated per day on the server from
+//! each entry's recipe nutrition and portion, then charted per day or per week against
+//! the goals from Settings, with rolling averages to smooth out single days.
+
+use leptos::prelude::*;
+use serde::{Deserialize, Serialize};
+use server_fn::ServerFnError;
+
+use crate::food_log::{date_from_days, days_from_date, local_now};
+use crate::recipes::RecipeNutrition;
+use crate::settings::{
+    load_settings, SettingsData, CALORIES_PER_GRAM_CARBS, CALORIES_PER_GRAM_FAT,
+    CALORIES_PER_GRAM_PROTEIN, SODIUM_MG_PER_G_SALT,
+};
+
+#[cfg(feature = "ssr")]
+use crate::ingredients::SendD1Database;
+
+// ============================================================================
+// Constants
+// ============================================================================
+
+// Chart geometry in SVG user units
+const CHART_WIDTH: f64 = 600.0;
+const CHART_HEIGHT: f64 = 200.0;
+const AXIS_WIDTH: f64 = 44.0;
+const LABEL_HEIGHT: f64 = 18.0;
+
+/// Time spans the page can show, in days
+const RANGES: [(i64, &str); 3] = [(30, "30 days"), (90, "90 days"), (365, "Year")];
+
+// ============================================================================
+// Data Types
+// ============================================================================
+
+/// Nutrition eaten on one day, summed over that day's food log entries
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+pub struct DailyNutrition {
+    /// Local date, `YYYY-MM-DD`
+    pub date: String,
+    /// Food log entries with a recipe on that day
+    pub entries: u32,
+    pub nutrition: RecipeNutrition,
+}
+
+/// One bar on a chart: a day, or a week averaged over the days that were logged
+#[derive(Clone, Debug, PartialEq)]
+pub struct StatsBucket {
+    pub label: String,
+    /// Day number of the first day in the bucket
+    pub start_day: i64,
+    pub days_logged: u32,
+    /// Average per logged day
+    pub nutrition: RecipeNutrition,
+}
+
+/// Whether a goal is a target, a ceiling or a floor
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum GoalKind {
+    Target,
+    Max,
+    Min,
+}
+
+impl GoalKind {
+    fn label(&self) -> &'static str {
+        match self {
+            GoalKind::Target => "Goal",
+            GoalKind::Max => "Limit",
+            GoalKind::Min => "Minimum",
+        }
+    }
+}
+
+/// Group daily totals into per-day or per-week (Monday to Sunday) buckets
+pub fn bucket_days(days: &[DailyNutrition], weekly: bool) -> Vec<StatsBucket> {
+    let mut buckets: Vec<(StatsBucket, RecipeNutrition)> = Vec::new();
+    for day in days {
+        let Some(day_number) = days_from_date(&day.date) else {
+            continue;
+        };
+        // 1970-01-01 was a Thursday, so Mondays are 3 days before multiples of 7 after it
+        let start_day = if weekly {
+            day_number - (day_number + 3).rem_euclid(7)
+        } else {
+            day_number
+        };
+        match buckets.last_mut() {
+            Some((bucket, total)) if bucket.start_day == start_day => {
+                bucket.days_logged += 1;
+                *total = add_nutrition(total, &day.nutrition);
+            }
+            _ => {
+                let label = date_from_days(start_day)[5..].to_string();
+                buckets.push((
+                    StatsBucket {
+                        label,
+                        start_day,
+                        days_logged: 1,
+                        nutrition: RecipeNutrition::default(),
+                    },
+                    day.nutrition.clone(),
+                ));
+            }
+        }
+    }
+    buckets
+        .into_iter()
+        .map(|(mut bucket, total)| {
+            bucket.nutrition = total.scaled(1.0 / bucket.days_logged as f32);
+            bucket
+        })
+        .collect()
+}
+
+fn add_nutrition(a: &RecipeNutrition, b: &RecipeNutrition) -> RecipeNutrition {
+    RecipeNutrition {
+        calories: a.calories + b.calories,
+        protein: a.protein + b.protein,
+        fat: a.fat + b.fat,
+        saturated_fat: a.saturated_fat + b.saturated_fat,
+        carbs: a.carbs + b.carbs,
+        sugar: a.sugar + b.sugar,
+        fiber: a.fiber + b.fiber,
+        salt: a.salt + b.salt,
+    }
+}
+
+/// Trailing average over the buckets starting within `window_days` of each one,
+/// weighted by how many days each bucket logged
+pub fn rolling_average(
+    buckets: &[StatsBucket],
+    value: impl Fn(&RecipeNutrition) -> f32,
+    window_days: i64,
+) -> Vec<f32> {
+    buckets
+        .iter()
+        .map(|current| {
+            let (sum, days) = buckets
+                .iter()
+                .filter(|b| {
+                    b.start_day <= current.start_day
+                        && b.start_day > current.start_day - window_days
+                })
+                .fold((0.0, 0), |(sum, days), b| {
+                    (
+                        sum + value(&b.nutrition) * b.days_logged as f32,
+                        days + b.days_logged,
+                    )
+                });
+            if days == 0 {
+                0.0
+            } else {
+                sum / days as f32
+            }
+        })
+        .collect()
+}
+
+// ============================================================================
+// Server Functions
+// ============================================================================
+
+/// Total nutrition per local day between two dates (inclusive), from each food log
+/// entry's recipe and portion. Days without entries are left out.
+#[server]
+pub async fn get_daily_nutrition(
+    from: String,
+    to: String,
+) -> Result<Vec<DailyNutrition>, ServerFnError> {
+    use send_wrapper::SendWrapper;
+
+    let db = expect_context::<SendD1Database>();
+
+    // Each ingredient row contributes its share of one serving, times the portion eaten
+    let rows = SendWrapper::new(async {
+        let stmt = db.inner().prepare(
+            "SELECT day, COUNT(DISTINCT log_id) as entries,
+                    SUM(scale * calories) as calories, SUM(scale * protein) as protein,
+                    SUM(scale * fat) as fat, SUM(scale * saturated_fat) as saturated_fat,
+                    SUM(scale * carbs) as carbs, SUM(scale * sugar) as sugar,
+                    SUM(scale * fiber) as fiber, SUM(scale * salt) as salt
+             FROM (
+                 SELECT substr(fl.logged_at, 1, 10) as day, fl.id as log_id,
+                        fl.portion * (CASE WHEN ri.use_whole_package = 1 THEN i.package_size_g ELSE ri.amount_grams END)
+                            / 100.0 / MAX(r.servings, 1) as scale,
+                        i.calories, i.protein, i.fat, i.saturated_fat, i.carbs, i.sugar, i.fiber, i.salt
+                 FROM food_logs fl
+                 JOIN recipes r ON fl.recipe_id = r.id
+                 JOIN recipe_ingredients ri ON ri.recipe_id = r.id
+                 JOIN ingredients i ON ri.ingredient_id = i.id
+                 WHERE fl.deleted_at IS NULL AND fl.logged_at >= ? AND substr(fl.logged_at, 1, 10) <= ?
+             )
+             GROUP BY day
+             ORDER BY day",
+        );
+        let stmt = stmt.bind(&[from.into(), to.into()])?;
+        let results = stmt.all().await?;
+        results.results::<serde_json::Value>()
+    })
+    .await
+    .map_err(|e| ServerFnError::new(format!("D1 query error: {:?}", e)))?;
+
+    let value = |row: &serde_json::Value, key: &str| {
+        row.get(key).and_then(|v| v.as_f64()).unwrap_or(0.0) as f32
+    };
+    let days = rows
+        .iter()
+        .filter_map(|row| {
+            Some(DailyNutrition {
+                date: row.get("day")?.as_str()?.to_string(),
+                entries: row.get("entries").and_then(|v| v.as_i64()).unwrap_or(0) as u32,
+                nutrition: RecipeNutrition {
+                    calories: value(row, "calories"),
+                    protein: value(row, "protein"),
+                    fat: value(row, "fat"),
+                    saturated_fat: value(row, "saturated_fat"),
+                    carbs: value(row, "carbs"),
+                    sugar: value(row, "sugar"),
+                    fiber: value(row, "fiber"),
+                    salt: value(row, "salt"),
+                },
+            })
+        })
+        .collect();
+
+    Ok(days)
+}
+
+// ============================================================================
+// Components
+// ============================================================================
+
+/// Bar chart of one nutrient with its rolling average and goal line
+#[component]
+fn TrendChart(
+    title: &'static str,
+    unit: &'static str,
+    /// Tailwind fill class for the bars
+    bar_class: &'static str,
+    buckets: Vec<StatsBucket>,
+    value: fn(&RecipeNutrition) -> f32,
+    window_days: i64,
+    goal: Option<(f32, GoalKind)>,
+) -> impl IntoView {
+    let values: Vec<f32> = buckets.iter().map(|b| value(&b.nutrition)).collect();
+    let rolling = rolling_average(&buckets, value, window_days);
+    let max_value = values
+        .iter()
+        .chain(rolling.iter())
+        .copied()
+        .chain(goal.map(|(g, _)| g))
+        .fold(0.0_f32, f32::max)
+        .max(1.0) as f64
+        * 1.1;
+
+    let plot_width = CHART_WIDTH - AXIS_WIDTH;
+    let plot_height = CHART_HEIGHT - LABEL_HEIGHT;
+    let slot = plot_width / buckets.len().max(1) as f64;
+    let y_for = move |v: f64| plot_height - v / max_value * plot_height;
+
+    let bars = values
+        .iter()
+        .enumerate()
+        .map(|(i, v)| {
+            let height = *v as f64 / max_value * plot_height;
+            view! {
+              <rect
+                class=bar_class
+                x={AXIS_WIDTH + i as f64 * slot + slot * 0.15}
+                y={plot_height - height}
+                width={slot * 0.7}
+                height=height
+              />
+            }
+        })
+        .collect_view();
+
+    let rolling_points = rolling
+        .iter()
+        .enumerate()
+        .map(|(i, v)| {
+            format!(
+                "{:.1},{:.1}",
+                AXIS_WIDTH + (i as f64 + 0.5) * slot,
+                y_for(*v as f64)
+            )
+        })
+        .collect::<Vec<_>>()
+        .join(" ");
+
+    // Label the first, middle and last buckets so the axis stays readable
+    let label_indices: Vec<usize> = match buckets.len() {
+        0 => Vec::new(),
+        n => {
+            let mut indices = vec![0, n / 2, n - 1];
+            indices.dedup();
+            indices
+        }
+    };
+    let x_labels = label_indices
+        .into_iter()
+        .map(|i| {
+            view! {
+              <text
+                x={AXIS_WIDTH + (i as f64 + 0.5) * slot}
+                y={CHART_HEIGHT - 4.0}
+                text-anchor="middle"
+                font-size="11"
+                class="fill-slate-500 dark:fill-slate-400"
+              >
+                {buckets[i].label.clone()}
+              </text>
+            }
+        })
+        .collect_view();
+
+    let average = if values.is_empty() {
+        0.0
+    } else {
+        values.iter().sum::<f32>() / values.len() as f32
+    };
+
+    view! {
+      <div class="rounded-lg bg-white dark:bg-slate-800 p-6 shadow-md">
+        <div class="mb-2 flex items-baseline justify-between">
+          <h3 class="text-lg font-semibold text-slate-900 dark:text-slate-100">{title}</h3>
+          <span class="text-sm text-slate-600 dark:text-slate-400">
+            {format!("Average {:.1} {}", average, unit)}
+            {goal.map(|(g, kind)| format!(" · {} {:.0} {}", kind.label(), g, unit))}
+          </span>
+        </div>
+        <svg class="w-full" viewBox=format!("0 0 {} {}", CHART_WIDTH, CHART_HEIGHT)>
+          <text x={AXIS_WIDTH - 6.0} y=12.0 text-anchor="end" font-size="11" class="fill-slate-500 dark:fill-slate-400">
+            {format!("{:.0}", max_value)}
+          </text>
+          <text x={AXIS_WIDTH - 6.0} y=plot_height text-anchor="end" font-size="11" class="fill-slate-500 dark:fill-slate-400">
+            "0"
+          </text>
+          <line
+            x1=AXIS_WIDTH
+            y1=plot_height
+            x2=CHART_WIDTH
+            y2=plot_height
+            class="stroke-slate-300 dark:stroke-slate-600"
+          />
+          {bars}
+          <polyline points=rolling_points fill="none" stroke-width="2" class="stroke-slate-800 dark:stroke-slate-200" />
+          {goal
+            .map(|(g, _)| {
+              view! {
+                <line
+                  x1=AXIS_WIDTH
+                  y1=y_for(g as f64)
+                  x2=CHART_WIDTH
+                  y2=y_for(g as f64)
+                  stroke-dasharray="6 4"
+                  stroke-width="1.5"
+                  class="stroke-red-500"
+                />
+              }
+            })}
+          {x_labels}
+        </svg>
+        <div class="mt-2 flex gap-4 text-xs text-slate-600 dark:text-slate-400">
+          <span class="flex items-center gap-1">
+            <span class="inline-block h-0.5 w-4 bg-slate-800 dark:bg-slate-200"></span>
+            {format!("{}-day rolling average", window_days)}
+          </span>
+          <Show when=move || goal.is_some()>
+            <span class="flex items-center gap-1">
+              <span class="inline-block h-0.5 w-4 bg-red-500"></span>
+              {goal.map(|(_, kind)| kind.label()).unwrap_or_default()}
+            </span>
+          </Show>
+        </div>
+      </div>
+    }
+}
+
+/// Stacked bars showing the share of calories from protein, carbs and fat
+#[component]
+fn MacroSplitChart(buckets: Vec<StatsBucket>, settings: SettingsData) -> impl IntoView {
+    let plot_width = CHART_WIDTH - AXIS_WIDTH;
+    let plot_height = CHART_HEIGHT - LABEL_HEIGHT;
+    let slot = plot_width / buckets.len().max(1) as f64;
+
+    let bars = buckets
+        .iter()
+        .enumerate()
+        .map(|(i, bucket)| {
+            let n = &bucket.nutrition;
+            let parts = [
+                (n.protein as f64 * CALORIES_PER_GRAM_PROTEIN, "fill-blue-600"),
+                (n.carbs as f64 * CALORIES_PER_GRAM_CARBS, "fill-green-600"),
+                (n.fat as f64 * CALORIES_PER_GRAM_FAT, "fill-orange-600"),
+            ];
+            let total: f64 = parts.iter().map(|(c, _)| c).sum();
+            let mut y = plot_height;
+            parts
+                .into_iter()
+                .map(|(calories, class)| {
+                    let height = if total > 0.0 { calories / total * plot_height } else { 0.0 };
+                    y -= height;
+                    view! {
+                      <rect class=class x={AXIS_WIDTH + i as f64 * slot + slot * 0.15} y=y width={slot * 0.7} height=height />
+                    }
+                })
+                .collect_view()
+        })
+        .collect_view();
+
+    // Goal split as dashed lines at the protein and protein + carbs boundaries
+    let protein_goal = settings.protein_pct as f64 / 100.0;
+    let carbs_goal = protein_goal + settings.carbs_pct as f64 / 100.0;
+    let goal_lines = [protein_goal, carbs_goal]
+        .into_iter()
+        .map(|share| {
+            let y = plot_height - share * plot_height;
+            view! {
+              <line x1=AXIS_WIDTH y1=y x2=CHART_WIDTH y2=y stroke-dasharray="6 4" stroke-width="1.5" class="stroke-red-500" />
+            }
+        })
+        .collect_view();
+
+    view! {
+      <div class="rounded-lg bg-white dark:bg-slate-800 p-6 shadow-md">
+        <div class="mb-2 flex items-baseline justify-between">
+          <h3 class="text-lg font-semibold text-slate-900 dark:text-slate-100">"Macro Split"</h3>
+          <span class="text-sm text-slate-600 dark:text-slate-400">
+            {format!(
+              "Goal {}% protein · {}% carbs · {}% fat",
+              settings.protein_pct,
+              settings.carbs_pct,
+              settings.fat_pct,
+            )}
+          </span>
+        </div>
+        <svg class="w-full" viewBox=format!("0 0 {} {}", CHART_WIDTH, CHART_HEIGHT)>
+          <text x={AXIS_WIDTH - 6.0} y=12.0 text-anchor="end" font-size="11" class="fill-slate-500 dark:fill-slate-400">
+            "100%"
+          </text>
+          <text x={AXIS_WIDTH - 6.0} y=plot_height text-anchor="end" font-size="11" class="fill-slate-500 dark:fill-slate-400">
+            "0%"
+          </text>
+          {bars}
+          {goal_lines}
+        </svg>
+        <div class="mt-2 flex gap-3 text-xs">
+          <div class="flex items-center gap-1">
+            <div class="h-3 w-3 rounded-sm bg-blue-600"></div>
+            <span>"Protein"</span>
+          </div>
+          <div class="flex items-center gap-1">
+            <div class="h-3 w-3 rounded-sm bg-green-600"></div>
+            <span>"Carbs"</span>
+          </div>
+          <div class="flex items-center gap-1">
+            <div class="h-3 w-3 rounded-sm bg-orange-600"></div>
+            <span>"Fat"</span>
+          </div>
+        </div>
+      </div>
+    }
+}
+
+/// Nutrition trends page
+#[component]
+pub fn Stats() -> impl IntoView {
+    let range_days = RwSignal::new(30_i64);
+    let weekly = RwSignal::new(false);
+    let today = RwSignal::new(Option::<i64>::None);
+    let settings = RwSignal::new(SettingsData::default());
+
+    // Today and the goals come from the browser, so read them after hydration
+    Effect::new(move || {
+        today.set(days_from_date(&local_now()));
+        settings.set(load_settings());
+    });
+
+    let days_resource = Resource::new(
+        move || (today.get(), range_days.get()),
+        |(today, range)| async move {
+            match today {
+                Some(today) => {
+                    get_daily_nutrition(date_from_days(today - range + 1), date_from_days(today))
+                        .await
+                }
+                None => Ok(Vec::new()),
+            }
+        },
+    );
+
+    let toggle_class = move |active: bool| {
+        if active {
+            "px-3 py-1 bg-blue-600 text-white"
+        } else {
+            "px-3 py-1 bg-white dark:bg-slate-800 text-slate-700 dark:text-slate-300 hover:bg-slate-100 dark:hover:bg-slate-700"
+        }
+    };
+
+    view! {
+      <div class="mx-auto max-w-7xl py-6">
+        <div class="mb-6 flex items-center justify-between flex-wrap gap-4">
+          <h2 class="text-3xl font-bold text-slate-900 dark:text-slate-100">"Nutrition Trends"</h2>
+          <div class="flex items-center gap-3">
+            <div class="flex rounded border border-slate-300 dark:border-slate-600 overflow-hidden text-sm">
+              {RANGES
+                .into_iter()
+                .map(|(days, label)| {
+                  view! {
+                    <button class=move || toggle_class(range_days.get() == days) on:click=move |_| range_days.set(days)>
+                      {label}
+                    </button>
+                  }
+                })
+                .collect_view()}
+            </div>
+            <div class="flex rounded border border-slate-300 dark:border-slate-600 overflow-hidden text-sm">
+              <button class=move || toggle_class(!weekly.get()) on:click=move |_| weekly.set(false)>
+                "Daily"
+              </button>
+              <button class=move || toggle_class(weekly.get()) on:click=move |_| weekly.set(true)>
+                "Weekly"
+              </button>
+            </div>
+          </div>
+        </div>
+
+        <Suspense fallback=move || {
+          view! { <p class="text-slate-600 dark:text-slate-400">"Loading nutrition trends..."</p> }
+        }>
+          {move || {
+            days_resource
+              .get()
+              .map(|result| match result {
+                Ok(days) if days.is_empty() => {
+                  view! {
+                    <p class="text-slate-600 dark:text-slate-400">
+                      "No food log entries with recipes in this period."
+                    </p>
+                  }
+                    .into_any()
+                }
+                Ok(days) => {
+                  let weekly = weekly.get();
+                  let settings = settings.get();
+                  let buckets = bucket_days(&days, weekly);
+                  // Rolling averages cover a week of days, or four weeks of weeks
+                  let window_days = if weekly { 28 } else { 7 };
+                  let logged = days.len();
+                  let average = |value: fn(&RecipeNutrition) -> f32| {
+                    days.iter().map(|d| value(&d.nutrition)).sum::<f32>() / logged as f32
+                  };
+                  let summary = [
+                    ("Days logged", logged.to_string()),
+                    ("Calories / day", format!("{:.0}", average(|n| n.calories))),
+                    ("Protein / day", format!("{:.0}g", average(|n| n.protein))),
+                    ("Carbs / day", format!("{:.0}g", average(|n| n.carbs))),
+                    ("Fat / day", format!("{:.0}g", average(|n| n.fat))),
+                  ];
+                  view! {
+                    <div class="mb-6 grid grid-cols-2 gap-4 md:grid-cols-5">
+                      {summary
+                        .into_iter()
+                        .map(|(label, value)| {
+                          view! {
+                            <div class="rounded-lg bg-white dark:bg-slate-800 p-4 shadow-md">
+                              <p class="text-xs uppercase tracking-wide text-slate-500 dark:text-slate-400">{label}</p>
+                              <p class="mt-1 text-2xl font-bold text-slate-900 dark:text-slate-100">{value}</p>
+                            </div>
+                          }
+                        })
+                        .collect_view()}
+                    </div>
+                    <div class="grid gap-6 lg:grid-cols-2">
+                      <TrendChart
+                        title="Calories"
+                        unit="kcal"
+                        bar_class="fill-blue-400 dark:fill-blue-500"
+                        buckets=buckets.clone()
+                        value=|n| n.calories
+                        window_days=window_days
+                        goal=Some((settings.daily_calories as f32, GoalKind::Target))
+                      />
+                      <MacroSplitChart buckets=buckets.clone() settings=settings.clone() />
+                      <TrendChart
+                        title="Fiber"
+                        unit="g"
+                        bar_class="fill-green-400 dark:fill-green-500"
+                        buckets=buckets.clone()
+                        value=|n| n.fiber
+                        window_days=window_days
+                        goal=Some((settings.fiber_min as f32, GoalKind::Min))
+                      />
+                      <TrendChart
+                        title="Salt"
+                        unit="g"
+                        bar_class="fill-amber-400 dark:fill-amber-500"
+                        buckets=buckets.clone()
+                        value=|n| n.salt
+                        window_days=window_days
+                        goal=Some(((settings.sodium_mg as f64 / SODIUM_MG_PER_G_SALT) as f32, GoalKind::Max))
+                      />
+                      <TrendChart
+                        title="Saturated Fat"
+                        unit="g"
+                        bar_class="fill-orange-400 dark:fill-orange-500"
+                        buckets=buckets
+                        value=|n| n.saturated_fat
+                        window_days=window_days
+                        goal=Some((settings.sat_fat_grams as f32, GoalKind::Max))
+                      />
+                    </div>
+                  }
+                    .into_any()
+                }
+                Err(e) => {
+                  view! {
+                    <div class="rounded bg-red-100 px-4 py-3 text-red-700">
+                      <p class="font-medium">"Failed to load nutrition trends"</p>
+                      <p class="text-sm">{e.to_string()}</p>
+                    </div>
+                  }
+                    .into_any()
+                }
+              })
+          }}
+        </Suspense>
+      </div>
+    }
+}
@@ -0,0 +1,605 @@
+//! Trash module
+//!
+//! Contains the server functions for listing, restoring and permanently purging
+//! soft-deleted ingredients, recipes and food logs, and the admin Trash page.
+
+use leptos::prelude::*;
+use serde::{Deserialize, Serialize};
+use server_fn::ServerFnError;
+
+use crate::audit::AuditEntity;
+use crate::auth::AdminAuth;
+
+// ============================================================================
+// Data Types
+// ============================================================================
+
+/// A soft-deleted record waiting in the trash
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+pub struct TrashItem {
+    pub entity: AuditEntity,
+    pub id: i64,
+    /// Display name (for food logs: date and recipe)
+    pub name: String,
+    pub deleted_at: String,
+    /// Recipes (including trashed ones) still using this ingredient.
+    /// The ingredient can't be purged until this is empty.
+    pub used_by: Vec<String>,
+}
+
+impl TrashItem {
+    pub fn can_purge(&self) -> bool {
+        self.used_by.is_empty()
+    }
+}
+
+// ============================================================================
+// Server Functions
+// ============================================================================
+
+#[cfg(feature = "ssr")]
+use crate::ingredients::SendD1Database;
+
+#[cfg(feature = "ssr")]
+fn table_name(entity: AuditEntity) -> &'static str {
+    match entity {
+        AuditEntity::Ingredient => "ingredients",
+        AuditEntity::Recipe => "recipes",
+        AuditEntity::FoodLog => "food_logs",
+    }
+}
+
+/// Load a record (deleted or not) as JSON for the audit log
+#[cfg(feature = "ssr")]
+async fn snapshot(
+    db: &SendD1Database,
+    entity: AuditEntity,
+    id: i64,
+) -> Result<Option<serde_json::Value>, ServerFnError> {
+    let value = match entity {
+        AuditEntity::Ingredient => crate::ingredients::fetch_ingredient(db, id)
+            .await?
+            .map(serde_json::to_value),
+        AuditEntity::Recipe => crate::recipes::fetch_recipe(db, id)
+            .await?
+            .map(serde_json::to_value),
+        AuditEntity::FoodLog => crate::food_log::fetch_food_log(db, id)
+            .await?
+            .map(serde_json::to_value),
+    };
+    value
+        .transpose()
+        .map_err(|e| ServerFnError::new(e.to_string()))
+}
+
+/// Fail unless the record exists and is currently in the trash
+#[cfg(feature = "ssr")]
+async fn ensure_in_trash(
+    db: &SendD1Database,
+    entity: AuditEntity,
+    id: i64,
+) -> Result<(), ServerFnError> {
+    use send_wrapper::SendWrapper;
+
+    let row = SendWrapper::new(async {
+        let stmt = db.inner().prepare(format!(
+            "SELECT id FROM {} WHERE id = ? AND deleted_at IS NOT NULL",
+            table_name(entity)
+        ));
+        let stmt = stmt.bind(&[(id as f64).into()])?;
+        stmt.first::<serde_json::Value>(None).await
+    })
+    .await
+    .map_err(|e| ServerFnError::new(format!("D1 query error: {:?}", e)))?;
+
+    match row {
+        Some(_) => Ok(()),
+        None => Err(ServerFnError::new(format!(
+            "{} #{} is not in the trash",
+            entity.label(),
+            id
+        ))),
+    }
+}
+
+/// Names of all recipes (active or trashed) that reference an ingredient
+#[cfg(feature = "ssr")]
+async fn recipes_using_ingredient(
+    db: &SendD1Database,
+    ingredient_id: i64,
+) -> Result<Vec<String>, ServerFnError> {
+    use send_wrapper::SendWrapper;
+
+    let rows = SendWrapper::new(async {
+        let stmt = db.inner().prepare(
+            "SELECT DISTINCT r.id, r.name, r.deleted_at
+             FROM recipe_ingredients ri
+             JOIN recipes r ON ri.recipe_id = r.id
+             WHERE ri.ingredient_id = ?
+             ORDER BY r.name",
+        );
+        let stmt = stmt.bind(&[(ingredient_id as f64).into()])?;
+        stmt.all().await?.results::<serde_json::Value>()
+    })
+    .await
+    .map_err(|e| ServerFnError::new(format!("D1 query error: {:?}", e)))?;
+
+    Ok(rows.iter().map(recipe_usage_name).collect())
+}
+
+/// Recipe name as shown in "used by" lists, marking recipes that are themselves trashed
+#[cfg(feature = "ssr")]
+fn recipe_usage_name(row: &serde_json::Value) -> String {
+    let name = row
+        .get("name")
+        .and_then(|v| v.as_str())
+        .unwrap_or("")
+        .to_string();
+    if row.get("deleted_at").and_then(|v| v.as_str()).is_some() {
+        format!("{} (in trash)", name)
+    } else {
+        name
+    }
+}
+
+/// List everything in the trash, most recently deleted first (admin only)
+#[server]
+pub async fn get_trash(auth_token: Option<String>) -> Result<Vec<TrashItem>, ServerFnError> {
+    use send_wrapper::SendWrapper;
+
+    use crate::auth::require_admin;
+
+    require_admin(auth_token.as_deref()).await?;
+
+    let db = expect_context::<SendD1Database>();
+
+    let (ingredient_rows, usage_rows, recipe_rows, food_log_rows) = SendWrapper::new(async {
+        let stmt = db
+            .inner()
+            .prepare("SELECT id, name, deleted_at FROM ingredients WHERE deleted_at IS NOT NULL");
+        let ingredients = stmt.all().await?.results::<serde_json::Value>()?;
+
+        let stmt = db.inner().prepare(
+            "SELECT DISTINCT ri.ingredient_id, r.id, r.name, r.deleted_at
+             FROM recipe_ingredients ri
+             JOIN recipes r ON ri.recipe_id = r.id
+             JOIN ingredients i ON ri.ingredient_id = i.id
+             WHERE i.deleted_at IS NOT NULL
+             ORDER BY r.name",
+        );
+        let usage = stmt.all().await?.results::<serde_json::Value>()?;
+
+        let stmt = db
+            .inner()
+            .prepare("SELECT id, name, deleted_at FROM recipes WHERE deleted_at IS NOT NULL");
+        let recipes = stmt.all().await?.results::<serde_json::Value>()?;
+
+        let stmt = db.inner().prepare(
+            "SELECT fl.id, fl.logged_at, fl.deleted_at, r.name as recipe_name
+             FROM food_logs fl
+             LEFT JOIN recipes r ON fl.recipe_id = r.id
+             WHERE fl.deleted_at IS NOT NULL",
+        );
+        let food_logs = stmt.all().await?.results::<serde_json::Value>()?;
+
+        Ok::<_, worker::Error>((ingredients, usage, recipes, food_logs))
+    })
+    .await
+    .map_err(|e| ServerFnError::new(format!("D1 query error: {:?}", e)))?;
+
+    let str_field = |row: &serde_json::Value, field: &str| -> String {
+        row.get(field)
+            .and_then(|v| v.as_str())
+            .unwrap_or("")
+            .to_string()
+    };
+
+    let mut items = Vec::new();
+
+    for row in &ingredient_rows {
+        let Some(id) = row.get("id").and_then(|v| v.as_i64()) else {
+            continue;
+        };
+        let used_by = usage_rows
+            .iter()
+            .filter(|u| u.get("ingredient_id").and_then(|v| v.as_i64()) == Some(id))
+            .map(recipe_usage_name)
+            .collect();
+        items.push(TrashItem {
+            entity: AuditEntity::Ingredient,
+            id,
+            name: str_field(row, "name"),
+            deleted_at: str_field(row, "deleted_at"),
+            used_by,
+        });
+    }
+
+    for row in &recipe_rows {
+        let Some(id) = row.get("id").and_then(|v| v.as_i64()) else {
+            continue;
+        };
+        items.push(TrashItem {
+            entity: AuditEntity::Recipe,
+            id,
+            name: str_field(row, "name"),
+            deleted_at: str_field(row, "deleted_at"),
+            used_by: Vec::new(),
+        });
+    }
+
+    for row in &food_log_rows {
+        let Some(id) = row.get("id").and_then(|v| v.as_i64()) else {
+            continue;
+        };
+        let recipe_name = row
+            .get("recipe_name")
+            .and_then(|v| v.as_str())
+            .unwrap_or("No recipe");
+        items.push(TrashItem {
+            entity: AuditEntity::FoodLog,
+            id,
+            name: format!(
+                "{} – {}",
+                str_field(row, "logged_at").replace('T', " "),
+                recipe_name
+            ),
+            deleted_at: str_field(row, "deleted_at"),
+            used_by: Vec::new(),
+        });
+    }
+
+    // SQLite datetime strings sort chronologically
+    items.sort_by(|a, b| b.deleted_at.cmp(&a.deleted_at));
+
+    Ok(items)
+}
+
+/// Take a record back out of the trash (admin only)
+#[server]
+pub async fn restore_from_trash(
+    entity: AuditEntity,
+    id: i64,
+    auth_token: Option<String>,
+) -> Result<(), ServerFnError> {
+    use send_wrapper::SendWrapper;
+
+    use crate::audit::{record_audit, AuditAction};
+    use crate::auth::{require_admin, resolve_actor};
+    use crate::food_log::{fetch_food_log, FoodLog};
+    use crate::pantry::sync_pantry_for_log;
+
+    require_admin(auth_token.as_deref()).await?;
+    let actor = resolve_actor(auth_token.as_deref()).await;
+
+    let db = expect_context::<SendD1Database>();
+
+    ensure_in_trash(&db, entity, id).await?;
+    let before = snapshot(&db, entity, id).await?;
+
+    SendWrapper::new(async {
+        let stmt = db.inner().prepare(format!(
+            "UPDATE {} SET deleted_at = NULL WHERE id = ?",
+            table_name(entity)
+        ));
+        let stmt = stmt.bind(&[(id as f64).into()])?;
+        stmt.run().await
+    })
+    .await
+    .map_err(|e| ServerFnError::new(format!("D1 update error: {:?}", e)))?;
+
+    // A restored food log counts as eaten again
+    if entity == AuditEntity::FoodLog {
+        let usage = fetch_food_log(&db, id)
+            .await?
+            .as_ref()
+            .and_then(FoodLog::pantry_usage);
+        if let Err(e) = sync_pantry_for_log(&db, id, usage).await {
+            log::warn!("Failed to update pantry for food log {}: {}", id, e);
+        }
+    }
+
+    record_audit(
+        &db,
+        entity,
+        id,
+        AuditAction::Restore,
+        None,
+        before.as_ref(),
+        &actor,
+    )
+    .await?;
+
+    log::info!("Restored {} id: {} from trash", entity.as_str(), id);
+    Ok(())
+}
+
+/// Permanently delete a record from the trash (admin only).
+/// Ingredients still referenced by any recipe are refused with the list of those recipes.
+#[server]
+pub async fn purge_from_trash(
+    entity: AuditEntity,
+    id: i64,
+    auth_token: Option<String>,
+) -> Result<(), ServerFnError> {
+    use send_wrapper::SendWrapper;
+
+    use crate::audit::{record_audit, AuditAction};
+    use crate::auth::{require_admin, resolve_actor};
+    use crate::food_log::SendR2Bucket;
+
+    require_admin(auth_token.as_deref()).await?;
+    let actor = resolve_actor(auth_token.as_deref()).await;
+
+    let db = expect_context::<SendD1Database>();
+
+    ensure_in_trash(&db, entity, id).await?;
+    let before = snapshot(&db, entity, id).await?;
+
+    match entity {
+        AuditEntity::Ingredient => {
+            // recipe_ingredients uses ON DELETE RESTRICT, so report the blockers up front
+            let used_by = recipes_using_ingredient(&db, id).await?;
+            if !used_by.is_empty() {
+                let name = before
+                    .as_ref()
+                    .and_then(|v| v.get("name"))
+                    .and_then(|v| v.as_str())
+                    .unwrap_or("Ingredient");
+                return Err(ServerFnError::new(format!(
+                    "Cannot permanently delete \"{}\": it is still used by {}. Remove it from these recipes or purge them first.",
+                    name,
+                    used_by.join(", ")
+                )));
+            }
+
+            // Labels will be deleted by CASCADE
+            SendWrapper::new(async {
+                let stmt = db.inner().prepare("DELETE FROM ingredients WHERE id = ?");
+                let stmt = stmt.bind(&[(id as f64).into()])?;
+                stmt.run().await
+            })
+            .await
+            .map_err(|e| ServerFnError::new(format!("D1 delete error: {:?}", e)))?;
+        }
+        AuditEntity::Recipe => {
+            // Delete recipe ingredients and revisions first, then the recipe itself
+            SendWrapper::new(async {
+                let stmt = db
+                    .inner()
+                    .prepare("DELETE FROM recipe_ingredients WHERE recipe_id = ?");
+                let stmt = stmt.bind(&[(id as f64).into()])?;
+                stmt.run().await?;
+
+                let stmt = db
+                    .inner()
+                    .prepare("DELETE FROM recipe_revisions WHERE recipe_id = ?");
+                let stmt = stmt.bind(&[(id as f64).into()])?;
+                stmt.run().await?;
+
+                let stmt = db.inner().prepare("DELETE FROM recipes WHERE id = ?");
+                let stmt = stmt.bind(&[(id as f64).into()])?;
+                stmt.run().await
+            })
+            .await
+            .map_err(|e| ServerFnError::new(format!("D1 delete error: {:?}", e)))?;
+        }
+        AuditEntity::FoodLog => {
+            let bucket = expect_context::<SendR2Bucket>();
+
+            // Delete the entry's photos from R2
+            let image_keys: Vec<String> = before
+                .as_ref()
+                .and_then(|v| v.get("images"))
+                .and_then(|v| v.as_array())
+                .into_iter()
+                .flatten()
+                .filter_map(|image| Some(image.get("image_key")?.as_str()?.to_string()))
+                .collect();
+            for key in &image_keys {
+                let _ = SendWrapper::new(bucket.inner().delete(key)).await;
+                crate::image_pipeline::delete_variants(&bucket, key, &[]).await;
+            }
+
+            // Delete the image rows first, then the entry itself
+            SendWrapper::new(async {
+                let stmt = db
+                    .inner()
+                    .prepare("DELETE FROM food_log_images WHERE food_log_id = ?");
+                let stmt = stmt.bind(&[(id as f64).into()])?;
+                stmt.run().await?;
+
+                let stmt = db.inner().prepare("DELETE FROM food_logs WHERE id = ?");
+                let stmt = stmt.bind(&[(id as f64).into()])?;
+                stmt.run().await
+            })
+            .await
+            .map_err(|e| ServerFnError::new(format!("D1 delete error: {:?}", e)))?;
+        }
+    }
+
+    record_audit(
+        &db,
+        entity,
+        id,
+        AuditAction::Purge,
+        before.as_ref(),
+        None,
+        &actor,
+    )
+    .await?;
+
+    log::info!("Purged {} id: {}", entity.as_str(), id);
+    Ok(())
+}
+
+// ============================================================================
+// Components
+// ============================================================================
+
+/// Admin page listing soft-deleted records with restore and purge actions
+#[component]
+pub fn Trash() -> impl IntoView {
+    let auth = expect_context::<AdminAuth>();
+    let is_authenticated = auth.is_authenticated;
+    let auth_token = auth.token;
+
+    let error = RwSignal::new(Option::<String>::None);
+    let busy = RwSignal::new(false);
+    let confirm_purge = RwSignal::new(Option::<(AuditEntity, i64)>::None);
+
+    let trash = Resource::new(
+        move || auth_token.get(),
+        |token| async move {
+            if token.is_none() {
+                return Ok(Vec::new());
+            }
+            get_trash(token).await
+        },
+    );
+
+    let run_action = move |entity: AuditEntity, id: i64, purge: bool| {
+        busy.set(true);
+        error.set(None);
+        let token = auth_token.get_untracked();
+        wasm_bindgen_futures::spawn_local(async move {
+            let result = if purge {
+                purge_from_trash(entity, id, token).await
+            } else {
+                restore_from_trash(entity, id, token).await
+            };
+            match result {
+                Ok(()) => {
+                    confirm_purge.set(None);
+                    trash.refetch();
+                }
+                Err(e) => {
+                    error.set(Some(e.to_string()));
+                }
+            }
+            busy.set(false);
+        });
+    };
+
+    view! {
+      <div class="mx-auto max-w-7xl py-6">
+        <h2 class="mb-2 text-3xl font-bold text-slate-900 dark:text-slate-100">"Trash"</h2>
+        <p class="mb-6 text-sm text-slate-600 dark:text-slate-400">
+          "Deleted ingredients, recipes and food logs are kept here until they are restored or deleted forever."
+        </p>
+
+        <Show
+          when=move || is_authenticated.get()
+          fallback=|| {
+            view! { <p class="text-slate-600 dark:text-slate-400">"Unlock admin access to view the trash."</p> }
+          }
+        >
+          <Show when=move || error.get().is_some()>
+            <div class="mb-4 rounded bg-red-100 px-4 py-3 text-red-700">{move || error.get().unwrap_or_default()}</div>
+          </Show>
+
+          <Suspense fallback=move || {
+            view! { <p class="text-slate-600 dark:text-slate-400">"Loading trash..."</p> }
+          }>
+            {move || {
+              trash
+                .get()
+                .map(|result| match result {
+                  Ok(items) if items.is_empty() => {
+                    view! { <p class="text-slate-600 dark:text-slate-400">"The trash is empty."</p> }.into_any()
+                  }
+                  Ok(items) => {
+                    view! {
+                      <div class="rounded-lg bg-white shadow-md overflow-hidden dark:bg-slate-800">
+                        <ul class="divide-y divide-slate-200 dark:divide-slate-700">
+                          {items
+                            .into_iter()
+                            .map(|item| {
+                              let entity = item.entity;
+                              let id = item.id;
+                              let can_purge = item.can_purge();
+                              let used_by = item.used_by.join(", ");
+                              let is_confirming = move || confirm_purge.get() == Some((entity, id));
+                              view! {
+                                <li class="flex flex-col gap-2 px-4 py-3 sm:flex-row sm:items-center sm:justify-between">
+                                  <div>
+                                    <p class="font-medium text-slate-900 dark:text-slate-100">
+                                      <span class="mr-2 inline-flex rounded-full bg-slate-100 dark:bg-slate-700 px-2 py-0.5 text-xs font-medium text-slate-700 dark:text-slate-300">
+                                        {entity.label()}
+                                      </span>
+                                      {item.name.clone()}
+                                    </p>
+                                    <p class="text-xs text-slate-500 dark:text-slate-400">
+                                      {format!("Deleted {} UTC", item.deleted_at)}
+                                    </p>
+                                    <Show when=move || !can_purge>
+                                      <p class="text-xs text-amber-700 dark:text-amber-400">
+                                        {format!("Still used by: {}", used_by)}
+                                      </p>
+                                    </Show>
+                                  </div>
+                                  <div class="flex items-center gap-2">
+                                    <Show
+                                      when=is_confirming
+                                      fallback=move || {
+                                        view! {
+                                          <button
+                                            class="rounded bg-blue-600 px-3 py-1 text-sm font-medium text-white hover:bg-blue-700 disabled:bg-blue-300 dark:disabled:bg-blue-800"
+                                            disabled=move || busy.get()
+                                            on:click=move |_| run_action(entity, id, false)
+                                          >
+                                            "Restore"
+                                          </button>
+                                          <button
+                                            class="rounded bg-red-100 dark:bg-red-900/30 px-3 py-1 text-sm font-medium text-red-700 dark:text-red-400 hover:bg-red-200 dark:hover:bg-red-900/50 disabled:opacity-50"
+                                            disabled=move || busy.get() || !can_purge
+                                            title=move || {
+                                              if can_purge { "" } else { "Remove it from the recipes using it first" }
+                                            }
+                                            on:click=move |_| confirm_purge.set(Some((entity, id)))
+                                          >
+                                            "Delete forever"
+                                          </button>
+                                        }
+                                      }
+                                    >
+                                      <span class="text-sm text-red-700 dark:text-red-400">"This cannot be undone."</span>
+                                      <button
+                                        class="rounded bg-red-600 px-3 py-1 text-sm font-medium text-white hover:bg-red-700 disabled:bg-red-300"
+                                        disabled=move || busy.get()
+                                        on:click=move |_| run_action(entity, id, true)
+                                      >
+                                        "Yes, delete forever"
+                                      </button>
+                                      <button
+                                        class="rounded bg-slate-200 dark:bg-slate-600 px-3 py-1 text-sm font-medium text-slate-700 dark:text-slate-200 hover:bg-slate-300 dark:hover:bg-slate-500"
+                                        on:click=move |_| confirm_purge.set(None)
+                                      >
+                                        "Cancel"
+                                      </button>
+                                    </Show>
+                                  </div>
+                                </li>
+                              }
+                            })
+                            .collect_view()}
+                        </ul>
+                      </div>
+                    }
+                      .into_any()
+                  }
+                  Err(e) => {
+                    view! {
+                      <div class="rounded bg-red-100 px-4 py-3 text-red-700">
+                        <p class="font-medium">"Failed to load trash"</p>
+                        <p class="text-sm">{e.to_string()}</p>
+                      </div>
+                    }
+                      .into_any()
+                  }
+                })
+            }}
+          </Suspense>
+        </Show>
+      </div>
+    }
+}
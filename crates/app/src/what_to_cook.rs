@@ -0,0 +1,308 @@
+//! "What can I cook?" module
+//!
+//! Ranks recipes by how many of their ingredients are at home, either from the
+//! pantry stock or from a manually ticked list of ingredients.
+
+use std::collections::HashMap;
+
+use leptos::prelude::*;
+
+use crate::ingredients::get_ingredients;
+use crate::pantry::PantryItem;
+use crate::recipes::{get_recipes, Recipe};
+
+// ============================================================================
+// Data Types
+// ============================================================================
+
+/// An ingredient a recipe needs more of than is available
+#[derive(Clone, Debug, PartialEq)]
+pub struct MissingIngredient {
+    pub ingredient_id: i64,
+    pub name: String,
+    pub needed_g: f32,
+    pub on_hand_g: f32,
+    /// Cost of the packages needed to cover the shortfall, if the ingredient is priced
+    pub cost: Option<f32>,
+}
+
+/// A recipe with how much of it can be made from what is available
+#[derive(Clone, Debug, PartialEq)]
+pub struct RecipeSuggestion {
+    pub recipe: Recipe,
+    pub available: usize,
+    pub missing: Vec<MissingIngredient>,
+}
+
+impl RecipeSuggestion {
+    pub fn total(&self) -> usize {
+        self.available + self.missing.len()
+    }
+
+    pub fn can_cook(&self) -> bool {
+        self.missing.is_empty()
+    }
+
+    /// Total cost of the missing ingredients that have a price
+    pub fn missing_cost(&self) -> f32 {
+        self.missing.iter().filter_map(|m| m.cost).sum()
+    }
+}
+
+/// Where the list of available ingredients comes from
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Default)]
+pub enum StockSource {
+    #[default]
+    Pantry,
+    Manual,
+}
+
+/// Grams on hand per ingredient id from the pantry
+pub fn stock_from_pantry(items: &[PantryItem]) -> HashMap<i64, f32> {
+    items
+        .iter()
+        .map(|item| (item.ingredient_id, item.grams_on_hand))
+        .collect()
+}
+
+/// Ticked ingredients count as available in any amount
+pub fn stock_from_ticked(ingredient_ids: &[i64]) -> HashMap<i64, f32> {
+    ingredient_ids
+        .iter()
+        .map(|id| (*id, f32::INFINITY))
+        .collect()
+}
+
+/// Rank recipes by the share of their ingredients available in sufficient quantity.
+/// Ties go to the recipe whose missing ingredients are cheapest to buy.
+pub fn suggest_recipes(recipes: &[Recipe], stock: &HashMap<i64, f32>) -> Vec<RecipeSuggestion> {
+    let mut suggestions: Vec<RecipeSuggestion> = recipes
+        .iter()
+        .filter(|recipe| !recipe.ingredients.is_empty())
+        .map(|recipe| {
+            let mut available = 0;
+            let mut missing = Vec::new();
+            for ing in &recipe.ingredients {
+                let needed_g = ing.effective_grams();
+                let on_hand_g = stock.get(&ing.ingredient_id).copied().unwrap_or(0.0);
+                if on_hand_g >= needed_g {
+                    available += 1;
+                    continue;
+                }
+                let shortfall = needed_g - on_hand_g;
+                let cost = (ing.package_price > 0.0).then(|| {
+                    let packages = if ing.package_size_g > 0.0 {
+                        (shortfall / ing.package_size_g).ceil()
+                    } else {
+                        1.0
+                    };
+                    packages * ing.package_price
+                });
+                missing.push(MissingIngredient {
+                    ingredient_id: ing.ingredient_id,
+                    name: ing.ingredient_name.clone(),
+                    needed_g,
+                    on_hand_g,
+                    cost,
+                });
+            }
+            RecipeSuggestion {
+                recipe: recipe.clone(),
+                available,
+                missing,
+            }
+        })
+        .collect();
+
+    suggestions.sort_by(|a, b| {
+        let share_a = a.available as f32 / a.total() as f32;
+        let share_b = b.available as f32 / b.total() as f32;
+        share_b
+            .total_cmp(&share_a)
+            .then(a.missing.len().cmp(&b.missing.len()))
+            .then(a.missing_cost().total_cmp(&b.missing_cost()))
+            .then(a.recipe.name.cmp(&b.recipe.name))
+    });
+    suggestions
+}
+
+// ============================================================================
+// Components
+// ============================================================================
+
+/// Recipe suggestions based on pantry stock or a ticked list of ingredients
+#[component]
+pub fn WhatCanICook(pantry: Signal<Vec<PantryItem>>) -> impl IntoView {
+    let recipes = Resource::new(|| (), |_| get_recipes());
+    let source = RwSignal::new(StockSource::Pantry);
+    let ticked = RwSignal::new(Vec::<i64>::new());
+    let ingredients = Resource::new(
+        move || source.get() == StockSource::Manual,
+        |manual| async move {
+            if manual {
+                get_ingredients().await
+            } else {
+                Ok(Vec::new())
+            }
+        },
+    );
+
+    let toggle_ticked = move |ingredient_id: i64| {
+        ticked.update(|ids| {
+            if let Some(pos) = ids.iter().position(|id| *id == ingredient_id) {
+                ids.remove(pos);
+            } else {
+                ids.push(ingredient_id);
+            }
+        });
+    };
+
+    let source_button = move |value: StockSource, label: &'static str| {
+        view! {
+          <button
+            class=move || {
+              if source.get() == value {
+                "px-3 py-1 bg-blue-600 text-white"
+              } else {
+                "px-3 py-1 bg-white dark:bg-slate-800 text-slate-700 dark:text-slate-300 hover:bg-slate-100 dark:hover:bg-slate-700"
+              }
+            }
+            on:click=move |_| source.set(value)
+          >
+            {label}
+          </button>
+        }
+    };
+
+    view! {
+      <div class="mt-6 rounded-lg bg-white dark:bg-slate-800 p-6 shadow-md">
+        <div class="mb-4 flex items-center justify-between flex-wrap gap-2">
+          <h3 class="text-lg font-bold text-slate-900 dark:text-slate-100">"What can I cook?"</h3>
+          <div class="flex rounded border border-slate-300 dark:border-slate-600 overflow-hidden text-sm">
+            {source_button(StockSource::Pantry, "Pantry stock")}
+            {source_button(StockSource::Manual, "Pick ingredients")}
+          </div>
+        </div>
+
+        <Show when=move || source.get() == StockSource::Manual>
+          <Suspense fallback=move || {
+            view! { <p class="text-sm text-slate-600 dark:text-slate-400">"Loading ingredients..."</p> }
+          }>
+            {move || {
+              ingredients
+                .get()
+                .map(|result| {
+                  let list = result.unwrap_or_default();
+                  view! {
+                    <div class="mb-4 grid max-h-48 grid-cols-2 gap-1 overflow-y-auto rounded border border-slate-200 dark:border-slate-600 p-2 sm:grid-cols-3 lg:grid-cols-4">
+                      {list
+                        .into_iter()
+                        .filter_map(|ing| {
+                          let id = ing.id?;
+                          Some(
+                            view! {
+                              <label class="flex items-center gap-2 text-sm text-slate-700 dark:text-slate-300">
+                                <input
+                                  type="checkbox"
+                                  class="h-4 w-4"
+                                  prop:checked=move || ticked.get().contains(&id)
+                                  on:change=move |_| toggle_ticked(id)
+                                />
+                                {ing.name}
+                              </label>
+                            },
+                          )
+                        })
+                        .collect_view()}
+                    </div>
+                  }
+                })
+            }}
+          </Suspense>
+        </Show>
+
+        <Suspense fallback=move || {
+          view! { <p class="text-sm text-slate-600 dark:text-slate-400">"Loading recipes..."</p> }
+        }>
+          {move || {
+            recipes
+              .get()
+              .map(|result| match result {
+                Ok(list) => {
+                  let stock = match source.get() {
+                    StockSource::Pantry => stock_from_pantry(&pantry.get()),
+                    StockSource::Manual => stock_from_ticked(&ticked.get()),
+                  };
+                  let suggestions = suggest_recipes(&list, &stock);
+                  if suggestions.is_empty() {
+                    return view! {
+                      <p class="text-sm text-slate-600 dark:text-slate-400">"No recipes with ingredients yet."</p>
+                    }
+                      .into_any();
+                  }
+                  view! {
+                    <ul class="divide-y divide-slate-200 dark:divide-slate-700">
+                      {suggestions
+                        .into_iter()
+                        .map(|s| {
+                          let badge_class = if s.can_cook() {
+                            "rounded-full bg-green-100 dark:bg-green-900/30 px-2 py-0.5 text-xs font-medium text-green-800 dark:text-green-300"
+                          } else {
+                            "rounded-full bg-slate-100 dark:bg-slate-700 px-2 py-0.5 text-xs font-medium text-slate-700 dark:text-slate-300"
+                          };
+                          let badge = if s.can_cook() {
+                            "Ready to cook".to_string()
+                          } else {
+                            format!("{}/{} ingredients", s.available, s.total())
+                          };
+                          let missing_cost = s.missing_cost();
+                          let has_missing = !s.missing.is_empty();
+                          let missing_text = s
+                            .missing
+                            .iter()
+                            .map(|m| {
+                              let amount = if m.on_hand_g > 0.0 {
+                                format!("{:.0}g more", m.needed_g - m.on_hand_g)
+                              } else {
+                                format!("{:.0}g", m.needed_g)
+                              };
+                              match m.cost {
+                                Some(cost) => format!("{} ({}, ${:.2})", m.name, amount, cost),
+                                None => format!("{} ({})", m.name, amount),
+                              }
+                            })
+                            .collect::<Vec<_>>()
+                            .join(", ");
+                          view! {
+                            <li class="py-3">
+                              <div class="flex items-center justify-between gap-2">
+                                <span class="font-medium text-slate-900 dark:text-slate-100">{s.recipe.name}</span>
+                                <span class=badge_class>{badge}</span>
+                              </div>
+                              <Show when=move || has_missing>
+                                <p class="mt-1 text-sm text-slate-600 dark:text-slate-400">
+                                  {format!("Missing: {}", missing_text)}
+                                </p>
+                                <Show when=move || { missing_cost > 0.0 }>
+                                  <p class="text-xs text-slate-500 dark:text-slate-400">
+                                    {format!("Shopping cost: ${:.2}", missing_cost)}
+                                  </p>
+                                </Show>
+                              </Show>
+                            </li>
+                          }
+                        })
+                        .collect_view()}
+                    </ul>
+                  }
+                    .into_any()
+                }
+                Err(e) => {
+                  view! { <p class="text-sm text-red-600">{format!("Failed to load recipes: {}", e)}</p> }.into_any()
+                }
+              })
+          }}
+        </Suspense>
+      </div>
+    }
+}
@@ -5,15 +5,19 @@ use axum::{
     extract::Path,
     http::{header, StatusCode},
     response::IntoResponse,
-    routing::{get, post},
+    routing::{get, post, put},
     Router,
 };
-use food_lemmih_com_app::{shell, App, AuthState, SendD1Database, SendKvStore, SendR2Bucket};
+use food_lemmih_com_app::{
+    collect_orphaned_images, complete_upload, recipe_container_labels_svg, recipe_label_svg, shell,
+    store_upload_part, uploaded_parts, variant_key, App, AuthState, ImageSigner, ImageVariant,
+    SendContext, SendD1Database, SendKvStore, SendR2Bucket, UploadError,
+};
 use leptos::prelude::provide_context;
 use leptos_axum::{generate_route_list, handle_server_fns_with_context, LeptosRoutes};
 use leptos_config::LeptosOptions;
 use tower_service::Service;
-use worker::{event, Context, Env, HttpRequest, Result};
+use worker::{event, Context, Env, HttpRequest, Result, ScheduleContext, ScheduledEvent};
 
 // Register server functions at worker start
 #[event(start)]
@@ -27,76 +31,548 @@ fn register() {
     server_fn::axum::register_explicit::<food_lemmih_com_app::CreateIngredient>();
     server_fn::axum::register_explicit::<food_lemmih_com_app::UpdateIngredient>();
     server_fn::axum::register_explicit::<food_lemmih_com_app::DeleteIngredient>();
+    server_fn::axum::register_explicit::<food_lemmih_com_app::PreviewIngredientMerge>();
+    server_fn::axum::register_explicit::<food_lemmih_com_app::MergeIngredients>();
+    server_fn::axum::register_explicit::<food_lemmih_com_app::GetIngredientPrices>();
+    server_fn::axum::register_explicit::<food_lemmih_com_app::AddIngredientPrice>();
+    server_fn::axum::register_explicit::<food_lemmih_com_app::DeleteIngredientPrice>();
     // Recipe server functions
     server_fn::axum::register_explicit::<food_lemmih_com_app::GetRecipes>();
     server_fn::axum::register_explicit::<food_lemmih_com_app::CreateRecipe>();
     server_fn::axum::register_explicit::<food_lemmih_com_app::UpdateRecipe>();
     server_fn::axum::register_explicit::<food_lemmih_com_app::DeleteRecipe>();
+    server_fn::axum::register_explicit::<food_lemmih_com_app::GetRecipeRevisions>();
+    server_fn::axum::register_explicit::<food_lemmih_com_app::RestoreRecipeRevision>();
+    // Pantry server functions
+    server_fn::axum::register_explicit::<food_lemmih_com_app::GetPantry>();
+    server_fn::axum::register_explicit::<food_lemmih_com_app::SavePantryItem>();
+    server_fn::axum::register_explicit::<food_lemmih_com_app::RemovePantryItem>();
+    server_fn::axum::register_explicit::<food_lemmih_com_app::RestockPantryItems>();
     // Food log server functions
     server_fn::axum::register_explicit::<food_lemmih_com_app::GetFoodLogs>();
     server_fn::axum::register_explicit::<food_lemmih_com_app::CreateFoodLog>();
     server_fn::axum::register_explicit::<food_lemmih_com_app::UpdateFoodLog>();
     server_fn::axum::register_explicit::<food_lemmih_com_app::DeleteFoodLog>();
-    server_fn::axum::register_explicit::<food_lemmih_com_app::UploadFoodImage>();
     server_fn::axum::register_explicit::<food_lemmih_com_app::GetFoodImage>();
     server_fn::axum::register_explicit::<food_lemmih_com_app::DeleteFoodImage>();
+    server_fn::axum::register_explicit::<food_lemmih_com_app::GetImageGcReport>();
+    // Stats server functions
+    server_fn::axum::register_explicit::<food_lemmih_com_app::GetDailyNutrition>();
+    // Audit log server functions
+    server_fn::axum::register_explicit::<food_lemmih_com_app::GetAuditLog>();
+    // Trash server functions
+    server_fn::axum::register_explicit::<food_lemmih_com_app::GetTrash>();
+    server_fn::axum::register_explicit::<food_lemmih_com_app::RestoreFromTrash>();
+    server_fn::axum::register_explicit::<food_lemmih_com_app::PurgeFromTrash>();
+}
+
+/// The edge keeps images for a year: stored photos never change under their key, and a
+/// new crop gets a new `crop=` fingerprint in the URL. Browsers are sent a private copy
+/// that lasts only as long as its URL's signature.
+const IMAGE_CACHE_CONTROL: &str = "public, max-age=31536000, immutable";
+
+/// The original sent in place of a variant that hasn't been generated yet must be
+/// revalidated, so the variant replaces it once it's stored
+const FALLBACK_CACHE_CONTROL: &str = "private, no-cache";
+
+/// Metadata of a stored image, as sent in the response headers
+struct StoredImage {
+    content_type: String,
+    /// Quoted, as in the `ETag` header
+    etag: String,
+    last_modified: String,
+    size: u64,
+    /// Whether this is the original standing in for a missing variant
+    fallback: bool,
+}
+
+impl StoredImage {
+    fn from_object(object: &worker::Object, fallback: bool) -> Self {
+        Self {
+            content_type: object
+                .http_metadata()
+                .content_type
+                .unwrap_or_else(|| "image/jpeg".to_string()),
+            etag: object.http_etag(),
+            last_modified: http_date(object.uploaded().as_millis()),
+            size: object.size(),
+            fallback,
+        }
+    }
+
+    /// Read back the metadata of a response stored in the edge cache
+    fn from_cached(response: &worker::Response) -> Option<Self> {
+        let headers = response.headers();
+        Some(Self {
+            content_type: headers.get("content-type").ok()??,
+            etag: headers.get("etag").ok()??,
+            last_modified: headers.get("last-modified").ok()??,
+            size: headers.get("content-length").ok()??.parse().ok()?,
+            fallback: false,
+        })
+    }
+
+    /// Headers for storing the whole image in the edge cache
+    fn cache_headers(&self) -> worker::Result<worker::Headers> {
+        let headers = worker::Headers::new();
+        headers.set("content-type", &self.content_type)?;
+        headers.set("content-length", &self.size.to_string())?;
+        headers.set("etag", &self.etag)?;
+        headers.set("last-modified", &self.last_modified)?;
+        headers.set("cache-control", IMAGE_CACHE_CONTROL)?;
+        Ok(headers)
+    }
+
+    /// Whether an `If-None-Match` header names this image's current version
+    fn matches(&self, if_none_match: &str) -> bool {
+        if_none_match.trim() == "*"
+            || if_none_match
+                .split(',')
+                .any(|tag| tag.trim().trim_start_matches("W/") == self.etag)
+    }
+
+    /// Start a response about this image with the headers every reply carries. The browser
+    /// may keep it for `max_age` seconds, until the signature of the requested URL expires.
+    fn response(&self, status: StatusCode, max_age: u64) -> axum::http::response::Builder {
+        axum::http::Response::builder()
+            .status(status)
+            .header(header::ETAG, &self.etag)
+            .header(header::LAST_MODIFIED, &self.last_modified)
+            .header(
+                header::CACHE_CONTROL,
+                if self.fallback {
+                    FALLBACK_CACHE_CONTROL.to_string()
+                } else {
+                    format!("private, max-age={}", max_age)
+                },
+            )
+            .header(header::ACCEPT_RANGES, "bytes")
+    }
+}
+
+/// A single byte range from a `Range` header
+#[derive(Clone, Copy)]
+enum ByteRange {
+    /// `bytes=start-` or `bytes=start-end`
+    From { start: u64, end: Option<u64> },
+    /// `bytes=-length`, the last `length` bytes
+    Suffix(u64),
+}
+
+impl ByteRange {
+    /// Parse a `Range` header. Multiple ranges aren't supported, so like malformed headers
+    /// they are ignored and the whole image is sent.
+    fn parse(header: &str) -> Option<Self> {
+        let spec = header.trim().strip_prefix("bytes=")?;
+        if spec.contains(',') {
+            return None;
+        }
+        let (start, end) = spec.split_once('-')?;
+        let (start, end) = (start.trim(), end.trim());
+        if start.is_empty() {
+            return Some(ByteRange::Suffix(end.parse().ok()?));
+        }
+        let start = start.parse().ok()?;
+        let end = if end.is_empty() {
+            None
+        } else {
+            Some(end.parse().ok()?)
+        };
+        if end.is_some_and(|end| end < start) {
+            return None;
+        }
+        Some(ByteRange::From { start, end })
+    }
+
+    /// First and last byte of the range in an object of `size` bytes, if it overlaps it
+    fn resolve(self, size: u64) -> Option<(u64, u64)> {
+        match self {
+            ByteRange::From { start, end } if start < size => {
+                Some((start, end.map_or(size - 1, |end| end.min(size - 1))))
+            }
+            ByteRange::Suffix(length) if length > 0 && size > 0 => {
+                Some((size.saturating_sub(length), size - 1))
+            }
+            _ => None,
+        }
+    }
 }
 
-/// Handler to serve images from R2 bucket
+/// Format milliseconds since the Unix epoch as an HTTP date, e.g.
+/// `Sun, 06 Nov 1994 08:49:37 GMT`
+fn http_date(millis: u64) -> String {
+    const WEEKDAYS: [&str; 7] = ["Thu", "Fri", "Sat", "Sun", "Mon", "Tue", "Wed"];
+    const MONTHS: [&str; 12] = [
+        "Jan", "Feb", "Mar", "Apr", "May", "Jun", "Jul", "Aug", "Sep", "Oct", "Nov", "Dec",
+    ];
+
+    let secs = millis / 1000;
+    let days = secs / 86_400;
+    let time = secs % 86_400;
+
+    // Civil date from days since the epoch (Howard Hinnant's algorithm)
+    let z = days + 719_468;
+    let era = z / 146_097;
+    let doe = z % 146_097;
+    let yoe = (doe - doe / 1460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = yoe + era * 400 + u64::from(month <= 2);
+
+    format!(
+        "{}, {:02} {} {} {:02}:{:02}:{:02} GMT",
+        WEEKDAYS[(days % 7) as usize],
+        day,
+        MONTHS[(month - 1) as usize],
+        year,
+        time / 3600,
+        time % 3600 / 60,
+        time % 60
+    )
+}
+
+/// What `serve_food_image` found for a request
+enum ImageReply {
+    NotFound,
+    NotModified(StoredImage),
+    Unsatisfiable(StoredImage),
+    Full(StoredImage, send_wrapper::SendWrapper<worker::ByteStream>),
+    Partial(
+        StoredImage,
+        (u64, u64),
+        send_wrapper::SendWrapper<worker::ByteStream>,
+    ),
+}
+
+/// Handler to serve images from R2 bucket. `?size=thumb|card|full&crop=<fingerprint>`
+/// picks a variant from the image pipeline, falling back to the original upload when there
+/// isn't one yet. The URL must carry a valid signature from `ImageSigner`, and the browser
+/// keeps the image privately until that signature expires.
+///
+/// Whole images are kept in the edge cache, so repeat requests don't reach R2, except for
+/// an original standing in for a variant, which is only cached until revalidated. Responses
+/// carry an `ETag` for `If-None-Match` revalidation, a single `Range` is answered with
+/// just those bytes, and bodies are streamed rather than read into memory.
 async fn serve_food_image(
     Path(key): Path<String>,
+    uri: axum::http::Uri,
+    headers: axum::http::HeaderMap,
     axum::Extension(bucket): axum::Extension<SendR2Bucket>,
+    axum::Extension(signer): axum::Extension<ImageSigner>,
+    axum::Extension(ctx): axum::Extension<SendContext>,
 ) -> impl IntoResponse {
     use send_wrapper::SendWrapper;
 
-    // Fetch the image from R2 - do all R2 work in a single SendWrapper block
-    let result: std::result::Result<Option<(Vec<u8>, String)>, worker::Error> =
-        SendWrapper::new(async {
-            let object = match bucket.inner().get(&key).execute().await? {
-                Some(obj) => obj,
-                None => return Ok(None),
-            };
+    let query = uri.query().unwrap_or_default();
+    let now_secs = worker::Date::now().as_millis() / 1000;
+    let Some(expires) = signer.verify(&key, query, now_secs) else {
+        log::warn!("Rejected unsigned or expired request for image {}", key);
+        return StatusCode::FORBIDDEN.into_response();
+    };
+    let max_age = expires - now_secs;
 
-            let content_type = object
-                .http_metadata()
-                .content_type
-                .unwrap_or_else(|| "image/jpeg".to_string());
+    let param = |name: &str| query.split('&').find_map(|pair| pair.strip_prefix(name));
+    let variant = param("size=")
+        .and_then(ImageVariant::parse)
+        .zip(param("crop="));
+    let header_value = |name: header::HeaderName| {
+        headers
+            .get(name)
+            .and_then(|v| v.to_str().ok())
+            .map(|v| v.to_string())
+    };
+    let if_none_match = header_value(header::IF_NONE_MATCH);
+    let range = header_value(header::RANGE).and_then(|range| ByteRange::parse(&range));
+    // The Cache API needs an absolute URL. The signature is left out, so every signed URL
+    // of the same image and size shares one cache entry.
+    let cache_query: Vec<&str> = query
+        .split('&')
+        .filter(|pair| pair.starts_with("size=") || pair.starts_with("crop="))
+        .collect();
+    let cache_url = header_value(header::HOST)
+        .map(|host| format!("https://{}{}?{}", host, uri.path(), cache_query.join("&")));
+
+    // Do all R2 and cache work in a single SendWrapper block
+    let reply: std::result::Result<ImageReply, worker::Error> = SendWrapper::new(async {
+        let cache = worker::Cache::default();
+        // Ranges are rare enough to always go to R2
+        if let (Some(url), None) = (&cache_url, range) {
+            if let Some(mut hit) = cache.get(url.as_str(), false).await? {
+                if let Some(image) = StoredImage::from_cached(&hit) {
+                    if if_none_match
+                        .as_deref()
+                        .is_some_and(|tags| image.matches(tags))
+                    {
+                        return Ok(ImageReply::NotModified(image));
+                    }
+                    return Ok(ImageReply::Full(image, SendWrapper::new(hit.stream()?)));
+                }
+            }
+        }
+
+        let candidates = variant
+            .map(|(variant, crop)| variant_key(&key, crop, variant))
+            .into_iter()
+            .chain([key.clone()]);
+        for candidate in candidates {
+            let fallback = variant.is_some() && candidate == key;
+            if let Some(range) = range {
+                // The size is needed to check the range before fetching it
+                let Some(head) = bucket.inner().head(&candidate).await? else {
+                    continue;
+                };
+                let image = StoredImage::from_object(&head, fallback);
+                if if_none_match
+                    .as_deref()
+                    .is_some_and(|tags| image.matches(tags))
+                {
+                    return Ok(ImageReply::NotModified(image));
+                }
+                let Some((start, end)) = range.resolve(image.size) else {
+                    return Ok(ImageReply::Unsatisfiable(image));
+                };
+                let object = bucket
+                    .inner()
+                    .get(&candidate)
+                    .range(worker::Range::OffsetWithLength {
+                        offset: start,
+                        length: end - start + 1,
+                    })
+                    .execute()
+                    .await?;
+                let Some(body) = object.as_ref().and_then(|object| object.body()) else {
+                    return Ok(ImageReply::NotFound);
+                };
+                return Ok(ImageReply::Partial(
+                    image,
+                    (start, end),
+                    SendWrapper::new(body.stream()?),
+                ));
+            }
 
-            let body = match object.body() {
-                Some(b) => b,
-                None => return Ok(None),
+            let Some(object) = bucket.inner().get(&candidate).execute().await? else {
+                continue;
+            };
+            let image = StoredImage::from_object(&object, fallback);
+            // The body is dropped unread
+            if if_none_match
+                .as_deref()
+                .is_some_and(|tags| image.matches(tags))
+            {
+                return Ok(ImageReply::NotModified(image));
+            }
+            let Some(body) = object.body() else {
+                return Ok(ImageReply::NotFound);
+            };
+            let Some(url) = cache_url.clone().filter(|_| !fallback) else {
+                return Ok(ImageReply::Full(image, SendWrapper::new(body.stream()?)));
             };
 
-            let bytes = body.bytes().await?;
-            Ok(Some((bytes, content_type)))
-        })
-        .await;
+            // Send one copy of the body to the client and store the other at the edge
+            let mut response =
+                worker::Response::from_stream(body.stream()?)?.with_headers(image.cache_headers()?);
+            let mut for_client = response.cloned()?;
+            ctx.wait_until(async move {
+                if let Err(e) = cache.put(url.as_str(), response).await {
+                    log::warn!("Failed to cache image {}: {:?}", url, e);
+                }
+            });
+            return Ok(ImageReply::Full(
+                image,
+                SendWrapper::new(for_client.stream()?),
+            ));
+        }
+        Ok(ImageReply::NotFound)
+    })
+    .await;
 
-    match result {
-        Ok(Some((bytes, content_type))) => (
+    let response = match reply {
+        Ok(ImageReply::Full(image, body)) => image
+            .response(StatusCode::OK, max_age)
+            .header(header::CONTENT_TYPE, &image.content_type)
+            .header(header::CONTENT_LENGTH, image.size)
+            .body(axum::body::Body::from_stream(body)),
+        Ok(ImageReply::Partial(image, (start, end), body)) => image
+            .response(StatusCode::PARTIAL_CONTENT, max_age)
+            .header(header::CONTENT_TYPE, &image.content_type)
+            .header(header::CONTENT_LENGTH, end - start + 1)
+            .header(
+                header::CONTENT_RANGE,
+                format!("bytes {}-{}/{}", start, end, image.size),
+            )
+            .body(axum::body::Body::from_stream(body)),
+        Ok(ImageReply::NotModified(image)) => image
+            .response(StatusCode::NOT_MODIFIED, max_age)
+            .body(axum::body::Body::empty()),
+        Ok(ImageReply::Unsatisfiable(image)) => image
+            .response(StatusCode::RANGE_NOT_SATISFIABLE, max_age)
+            .header(header::CONTENT_RANGE, format!("bytes */{}", image.size))
+            .body(axum::body::Body::empty()),
+        Ok(ImageReply::NotFound) => {
+            log::warn!("Image not found: {}", key);
+            return StatusCode::NOT_FOUND.into_response();
+        }
+        Err(e) => {
+            log::error!("R2 error fetching image {}: {:?}", key, e);
+            return StatusCode::INTERNAL_SERVER_ERROR.into_response();
+        }
+    };
+    response.unwrap_or_else(|e| {
+        log::error!("Failed to build response for image {}: {}", key, e);
+        StatusCode::INTERNAL_SERVER_ERROR.into_response()
+    })
+}
+
+/// Response for a failed photo upload, with a message the app shows as is
+fn upload_error_response(error: UploadError) -> axum::response::Response {
+    let status = match &error {
+        UploadError::TooLarge { .. } => StatusCode::PAYLOAD_TOO_LARGE,
+        UploadError::UnsupportedFormat => StatusCode::UNSUPPORTED_MEDIA_TYPE,
+        UploadError::Invalid(_) => StatusCode::BAD_REQUEST,
+        UploadError::Incomplete => StatusCode::CONFLICT,
+        UploadError::Storage(detail) => {
+            log::error!("Photo upload storage error: {}", detail);
+            StatusCode::INTERNAL_SERVER_ERROR
+        }
+    };
+    (status, error.to_string()).into_response()
+}
+
+/// Handler listing the parts of a photo upload received so far, comma separated, so an
+/// interrupted upload can resume
+async fn serve_upload_status(
+    Path(upload_id): Path<String>,
+    axum::Extension(bucket): axum::Extension<SendR2Bucket>,
+) -> impl IntoResponse {
+    match uploaded_parts(&bucket, &upload_id).await {
+        Ok(parts) => {
+            let parts: Vec<String> = parts.iter().map(|part| part.to_string()).collect();
+            (
+                StatusCode::OK,
+                [(header::CACHE_CONTROL, "no-store".to_string())],
+                parts.join(","),
+            )
+                .into_response()
+        }
+        Err(e) => upload_error_response(e),
+    }
+}
+
+/// Handler storing one part of a photo upload, sent as the raw request body
+async fn receive_upload_part(
+    Path((upload_id, part)): Path<(String, u32)>,
+    axum::Extension(bucket): axum::Extension<SendR2Bucket>,
+    body: axum::body::Bytes,
+) -> impl IntoResponse {
+    match store_upload_part(&bucket, &upload_id, part, body.to_vec()).await {
+        Ok(()) => StatusCode::NO_CONTENT.into_response(),
+        Err(e) => upload_error_response(e),
+    }
+}
+
+/// Handler finishing a photo upload of `?parts=N` parts, responding with the new image key
+async fn finish_upload(
+    Path(upload_id): Path<String>,
+    uri: axum::http::Uri,
+    axum::Extension(bucket): axum::Extension<SendR2Bucket>,
+) -> impl IntoResponse {
+    let parts = uri
+        .query()
+        .unwrap_or_default()
+        .split('&')
+        .find_map(|pair| pair.strip_prefix("parts="))
+        .and_then(|parts| parts.parse().ok());
+    let Some(parts) = parts else {
+        return upload_error_response(UploadError::Invalid("missing part count".to_string()));
+    };
+    match complete_upload(&bucket, &upload_id, parts).await {
+        Ok(key) => (StatusCode::CREATED, key).into_response(),
+        Err(e) => upload_error_response(e),
+    }
+}
+
+/// Handler to render a recipe's nutrition label as SVG for printing
+async fn serve_recipe_label(
+    Path(id): Path<i64>,
+    uri: axum::http::Uri,
+    axum::Extension(db): axum::Extension<SendD1Database>,
+) -> impl IntoResponse {
+    match recipe_label_svg(&db, id, uri.query().unwrap_or_default()).await {
+        Ok(Some(svg)) => (
             StatusCode::OK,
             [
-                (header::CONTENT_TYPE, content_type),
                 (
-                    header::CACHE_CONTROL,
-                    "public, max-age=31536000".to_string(),
+                    header::CONTENT_TYPE,
+                    "image/svg+xml; charset=utf-8".to_string(),
                 ),
+                (header::CACHE_CONTROL, "no-cache".to_string()),
             ],
-            bytes,
+            svg,
         )
             .into_response(),
         Ok(None) => {
-            log::warn!("Image not found: {}", key);
+            log::warn!("Recipe not found for label: {}", id);
             StatusCode::NOT_FOUND.into_response()
         }
         Err(e) => {
-            log::error!("R2 error fetching image {}: {:?}", key, e);
+            log::error!("Failed to render label for recipe {}: {}", id, e);
+            StatusCode::INTERNAL_SERVER_ERROR.into_response()
+        }
+    }
+}
+
+/// Base URL the request was made against, for links printed on labels
+fn request_origin(uri: &axum::http::Uri, headers: &axum::http::HeaderMap) -> String {
+    if let (Some(scheme), Some(authority)) = (uri.scheme_str(), uri.authority()) {
+        return format!("{}://{}", scheme, authority);
+    }
+    let host = headers
+        .get(header::HOST)
+        .and_then(|h| h.to_str().ok())
+        .unwrap_or("localhost");
+    let scheme = if host.starts_with("localhost") || host.starts_with("127.") {
+        "http"
+    } else {
+        "https"
+    };
+    format!("{}://{}", scheme, host)
+}
+
+/// Handler to render a sheet of meal-prep container labels as SVG for printing
+async fn serve_container_labels(
+    Path(id): Path<i64>,
+    uri: axum::http::Uri,
+    headers: axum::http::HeaderMap,
+    axum::Extension(db): axum::Extension<SendD1Database>,
+) -> impl IntoResponse {
+    let origin = request_origin(&uri, &headers);
+    match recipe_container_labels_svg(&db, id, uri.query().unwrap_or_default(), &origin).await {
+        Ok(Some(svg)) => (
+            StatusCode::OK,
+            [
+                (
+                    header::CONTENT_TYPE,
+                    "image/svg+xml; charset=utf-8".to_string(),
+                ),
+                (header::CACHE_CONTROL, "no-cache".to_string()),
+            ],
+            svg,
+        )
+            .into_response(),
+        Ok(None) => {
+            log::warn!("Recipe not found for container labels: {}", id);
+            StatusCode::NOT_FOUND.into_response()
+        }
+        Err(e) => {
+            log::error!("Failed to render container labels for recipe {}: {}", id, e);
             StatusCode::INTERNAL_SERVER_ERROR.into_response()
         }
     }
 }
 
-fn router(env: Env) -> Router<()> {
+fn router(env: Env, ctx: Context) -> Router<()> {
     let leptos_options = LeptosOptions::builder()
         .output_name("client")
         .site_pkg_dir("pkg")
@@ -129,7 +605,21 @@ fn router(env: Env) -> Router<()> {
         .expect("FOOD_IMAGES R2 bucket not bound");
     let r2_bucket = SendR2Bucket::new(r2_bucket);
 
+    // Get the key signing image URLs (secret first, then var for dev/e2e). Without one no
+    // image can be fetched.
+    let image_signing_key = env
+        .secret("IMAGE_SIGNING_KEY")
+        .ok()
+        .map(|s| s.to_string())
+        .or_else(|| env.var("IMAGE_SIGNING_KEY").ok().map(|s| s.to_string()))
+        .unwrap_or_default();
+    if image_signing_key.is_empty() {
+        log::error!("IMAGE_SIGNING_KEY is not set, so food images can't be served");
+    }
+    let image_signer = ImageSigner::new(&image_signing_key);
+
     let auth_state = AuthState::new(admin_pin);
+    let ctx = SendContext::new(ctx);
 
     // Context provider function for server functions
     let provide_server_context = {
@@ -137,17 +627,31 @@ fn router(env: Env) -> Router<()> {
         let kv_store = kv_store.clone();
         let d1_db = d1_db.clone();
         let r2_bucket = r2_bucket.clone();
+        let ctx = ctx.clone();
+        let image_signer = image_signer.clone();
         move || {
             provide_context(auth_state.clone());
             provide_context(kv_store.clone());
             provide_context(d1_db.clone());
             provide_context(r2_bucket.clone());
+            provide_context(ctx.clone());
+            provide_context(image_signer.clone());
         }
     };
 
     // Build the leptos routes with context provider for server functions
     Router::new()
         .route("/api/food-image/{key}", get(serve_food_image))
+        .route(
+            "/api/food-image-upload/{upload_id}",
+            get(serve_upload_status).post(finish_upload),
+        )
+        .route(
+            "/api/food-image-upload/{upload_id}/{part}",
+            put(receive_upload_part),
+        )
+        .route("/api/recipe-label/{id}", get(serve_recipe_label))
+        .route("/api/recipe-containers/{id}", get(serve_container_labels))
         .route(
             "/api/{*fn_name}",
             post({
@@ -160,6 +664,9 @@ fn router(env: Env) -> Router<()> {
             move || shell(leptos_options.clone())
         })
         .layer(axum::Extension(r2_bucket))
+        .layer(axum::Extension(d1_db))
+        .layer(axum::Extension(image_signer))
+        .layer(axum::Extension(ctx))
         .with_state(leptos_options)
 }
 
@@ -167,7 +674,7 @@ fn router(env: Env) -> Router<()> {
 pub async fn fetch(
     req: HttpRequest,
     env: Env,
-    _ctx: Context,
+    ctx: Context,
 ) -> Result<axum::http::Response<axum::body::Body>> {
     _ = console_log::init_with_level(log::Level::Debug);
     console_error_panic_hook::set_once();
@@ -176,5 +683,29 @@ pub async fn fetch(
     let path = req.uri().path().to_string();
     log::info!("fetch called for {} {}", method, path);
 
-    Ok(router(env).call(req).await?)
+    Ok(router(env, ctx).call(req).await?)
+}
+
+/// Cron trigger deleting food photos that no food log refers to
+#[event(scheduled)]
+pub async fn scheduled(_event: ScheduledEvent, env: Env, _ctx: ScheduleContext) {
+    _ = console_log::init_with_level(log::Level::Debug);
+    console_error_panic_hook::set_once();
+
+    let d1_db = env
+        .d1("INGREDIENTS_DB")
+        .expect("INGREDIENTS_DB D1 database not bound");
+    let r2_bucket = env
+        .bucket("FOOD_IMAGES")
+        .expect("FOOD_IMAGES R2 bucket not bound");
+
+    if let Err(e) = collect_orphaned_images(
+        &SendD1Database::new(d1_db),
+        &SendR2Bucket::new(r2_bucket),
+        false,
+    )
+    .await
+    {
+        log::error!("Image garbage collection failed: {}", e);
+    }
 }
@@ -0,0 +1,11 @@
+-- Append-only history of recipe versions
+-- Every create/update stores a full JSON snapshot of the saved Recipe
+CREATE TABLE IF NOT EXISTS recipe_revisions (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    recipe_id INTEGER NOT NULL,
+    snapshot TEXT NOT NULL, -- JSON-serialized Recipe
+    created_at TEXT NOT NULL DEFAULT (datetime('now'))
+);
+
+-- Index for listing the revisions of a recipe, newest first
+CREATE INDEX IF NOT EXISTS idx_recipe_revisions_recipe ON recipe_revisions(recipe_id, id DESC);
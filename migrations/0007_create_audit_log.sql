@@ -0,0 +1,15 @@
+-- Audit log of all data changes made through the server functions
+CREATE TABLE IF NOT EXISTS audit_log (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    entity TEXT NOT NULL, -- 'ingredient', 'recipe' or 'food_log'
+    entity_id INTEGER NOT NULL,
+    action TEXT NOT NULL, -- 'create', 'update', 'delete', ...
+    before_json TEXT, -- JSON snapshot before the change (NULL for creates)
+    after_json TEXT, -- JSON snapshot after the change (NULL for deletes)
+    actor TEXT NOT NULL DEFAULT '', -- Short identifier of the admin token used
+    created_at TEXT NOT NULL DEFAULT (datetime('now'))
+);
+
+-- Indexes for the admin page filters
+CREATE INDEX IF NOT EXISTS idx_audit_log_created ON audit_log(id DESC);
+CREATE INDEX IF NOT EXISTS idx_audit_log_entity ON audit_log(entity, entity_id);
@@ -0,0 +1,9 @@
+-- Soft delete: deleted rows are kept with a timestamp until purged from the trash
+ALTER TABLE ingredients ADD COLUMN deleted_at TEXT;
+ALTER TABLE recipes ADD COLUMN deleted_at TEXT;
+ALTER TABLE food_logs ADD COLUMN deleted_at TEXT;
+
+-- Indexes for listing the trash
+CREATE INDEX IF NOT EXISTS idx_ingredients_deleted_at ON ingredients(deleted_at);
+CREATE INDEX IF NOT EXISTS idx_recipes_deleted_at ON recipes(deleted_at);
+CREATE INDEX IF NOT EXISTS idx_food_logs_deleted_at ON food_logs(deleted_at);
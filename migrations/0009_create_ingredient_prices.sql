@@ -0,0 +1,24 @@
+-- Every observed price of an ingredient, so history is kept instead of overwritten
+-- The latest observation is mirrored into ingredients.package_price/package_size_g/store
+CREATE TABLE IF NOT EXISTS ingredient_prices (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    ingredient_id INTEGER NOT NULL,
+    price REAL NOT NULL,
+    package_size_g REAL NOT NULL DEFAULT 0,
+    store TEXT NOT NULL DEFAULT '',
+    observed_on TEXT NOT NULL DEFAULT (date('now')), -- YYYY-MM-DD
+    created_at TEXT NOT NULL DEFAULT (datetime('now')),
+    FOREIGN KEY (ingredient_id) REFERENCES ingredients(id) ON DELETE CASCADE
+);
+
+-- Index for listing the price history of an ingredient by date
+CREATE INDEX IF NOT EXISTS idx_ingredient_prices_ingredient ON ingredient_prices(ingredient_id, observed_on);
+
+-- Store of the current price (re-added after migration 0003 dropped it)
+ALTER TABLE ingredients ADD COLUMN store TEXT NOT NULL DEFAULT '';
+
+-- Seed the history with the prices we already know
+INSERT INTO ingredient_prices (ingredient_id, price, package_size_g, observed_on)
+SELECT id, package_price, package_size_g, date(updated_at)
+FROM ingredients
+WHERE package_price > 0;
@@ -0,0 +1,22 @@
+-- Pantry inventory: how much of each ingredient is at home
+CREATE TABLE IF NOT EXISTS pantry_items (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    ingredient_id INTEGER NOT NULL UNIQUE,
+    grams_on_hand REAL NOT NULL DEFAULT 0,
+    low_stock_g REAL NOT NULL DEFAULT 0, -- listed as low stock at or below this amount
+    expires_on TEXT, -- optional YYYY-MM-DD
+    updated_at TEXT NOT NULL DEFAULT (datetime('now')),
+    FOREIGN KEY (ingredient_id) REFERENCES ingredients(id) ON DELETE CASCADE
+);
+
+-- Grams each food log took out of the pantry, so they can be put back exactly
+CREATE TABLE IF NOT EXISTS pantry_consumption (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    food_log_id INTEGER NOT NULL,
+    ingredient_id INTEGER NOT NULL,
+    grams REAL NOT NULL,
+    FOREIGN KEY (food_log_id) REFERENCES food_logs(id) ON DELETE CASCADE
+);
+
+-- Index for returning the stock taken by a food log
+CREATE INDEX IF NOT EXISTS idx_pantry_consumption_food_log ON pantry_consumption(food_log_id);
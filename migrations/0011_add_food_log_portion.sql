@@ -0,0 +1,2 @@
+-- Servings of the recipe eaten in a food log entry
+ALTER TABLE food_logs ADD COLUMN portion REAL NOT NULL DEFAULT 1;
@@ -0,0 +1,4 @@
+-- Meal slot for food log entries. logged_at now holds the local wall-clock time
+-- (YYYY-MM-DDTHH:MM) the meal was eaten; older entries keep their date-only value.
+ALTER TABLE food_logs ADD COLUMN meal_type TEXT NOT NULL DEFAULT 'snack'
+    CHECK (meal_type IN ('breakfast', 'lunch', 'dinner', 'snack'));
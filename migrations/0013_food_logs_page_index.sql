@@ -0,0 +1,4 @@
+-- Keyset pagination orders food logs by (logged_at, id), newest first
+DROP INDEX IF EXISTS idx_food_logs_logged_at;
+CREATE INDEX idx_food_logs_logged_at_id ON food_logs(logged_at DESC, id DESC);
+CREATE INDEX idx_food_logs_recipe_id ON food_logs(recipe_id);
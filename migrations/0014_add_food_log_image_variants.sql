@@ -0,0 +1,3 @@
+-- Whether the image pipeline has stored cropped and resized JPEG variants of the entry's
+-- photo in R2 (under variants/{image_key}/). Existing entries keep showing their original.
+ALTER TABLE food_logs ADD COLUMN image_variants INTEGER NOT NULL DEFAULT 0;
@@ -0,0 +1,31 @@
+-- Photos of a food log entry, in display order. Each photo has its own crop (stored as
+-- percentages 0-100) and records whether the image pipeline has stored its variants.
+CREATE TABLE food_log_images (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    food_log_id INTEGER NOT NULL,
+    image_key TEXT NOT NULL,
+    position INTEGER NOT NULL DEFAULT 0,
+    crop_x REAL NOT NULL DEFAULT 0,
+    crop_y REAL NOT NULL DEFAULT 0,
+    crop_width REAL NOT NULL DEFAULT 100,
+    crop_height REAL NOT NULL DEFAULT 100,
+    crop_rotation INTEGER NOT NULL DEFAULT 0,
+    image_variants INTEGER NOT NULL DEFAULT 0,
+    FOREIGN KEY (food_log_id) REFERENCES food_logs(id) ON DELETE CASCADE
+);
+
+CREATE INDEX idx_food_log_images_food_log ON food_log_images(food_log_id, position);
+
+-- Move each entry's single photo into the new table
+INSERT INTO food_log_images (food_log_id, image_key, position, crop_x, crop_y, crop_width, crop_height, crop_rotation, image_variants)
+SELECT id, image_key, 0, crop_x, crop_y, crop_width, crop_height, crop_rotation, image_variants
+FROM food_logs
+WHERE image_key IS NOT NULL AND image_key != '';
+
+ALTER TABLE food_logs DROP COLUMN image_key;
+ALTER TABLE food_logs DROP COLUMN crop_x;
+ALTER TABLE food_logs DROP COLUMN crop_y;
+ALTER TABLE food_logs DROP COLUMN crop_width;
+ALTER TABLE food_logs DROP COLUMN crop_height;
+ALTER TABLE food_logs DROP COLUMN crop_rotation;
+ALTER TABLE food_logs DROP COLUMN image_variants;
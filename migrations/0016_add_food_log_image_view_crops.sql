@@ -0,0 +1,3 @@
+-- Crops of each photo for views other than the card (the calendar thumbnail and the
+-- detail view), as a JSON array of {view, crop}. Views without one use the card crop.
+ALTER TABLE food_log_images ADD COLUMN view_crops TEXT NOT NULL DEFAULT '[]';
@@ -2,7 +2,7 @@
 // Import the compiled wasm module directly so we can initialize without
 // relying on URL resolution within the Workers runtime.
 
-import init, { fetch as wasmFetch } from './index.js';
+import init, { fetch as wasmFetch, scheduled as wasmScheduled } from './index.js';
 import wasmModule from './index_bg.wasm';
 
 let initPromise;
@@ -19,5 +19,9 @@ export default {
     await ensureInitialized();
     return wasmFetch(request, env, ctx);
   },
+  async scheduled(event, env, ctx) {
+    await ensureInitialized();
+    return wasmScheduled(event, env, ctx);
+  },
 };
 